@@ -1,63 +1,22 @@
-use crate::auth::{CredentialStore, OAuthClient};
+use crate::api::proxy;
+use crate::auth::CredentialStore;
 use crate::config;
 use crate::error::GitAiError;
 use crate::git::repository::{exec_git, parse_git_var_identity};
-use once_cell::sync::Lazy;
-use std::sync::Mutex;
+use std::time::{Duration, Instant};
 use url::Url;
 
-/// Global mutex to prevent multiple threads from refreshing simultaneously.
-/// This provides in-process synchronization to avoid thundering herd issues.
-/// Note: Cross-process races are acceptable - both processes get valid tokens.
-static REFRESH_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+/// Delay before the first retry; doubles on every further attempt
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+/// Cap on the delay between two attempts
+const RETRY_MAX_DELAY: Duration = Duration::from_secs(8);
+/// Upper bound on the time spent across all attempts of a retried request
+const RETRY_TOTAL_DEADLINE: Duration = Duration::from_secs(60);
 
 /// Attempt to load stored credentials and refresh if needed.
 /// Returns None on any failure (not logged in, expired, refresh failed).
-/// Uses in-process Mutex for thread safety during token refresh.
 fn try_load_auth_token() -> Option<String> {
-    let store = CredentialStore::new();
-
-    let creds = match store.load() {
-        Ok(Some(c)) => c,
-        _ => return None,
-    };
-
-    // If refresh token expired, can't authenticate
-    if creds.is_refresh_token_expired() {
-        return None;
-    }
-
-    // Fast path: if access token is valid (with 5 min buffer), use it directly
-    if !creds.is_access_token_expired(300) {
-        return Some(creds.access_token);
-    }
-
-    // Need to refresh - acquire mutex to prevent thundering herd within this process
-    // If mutex is poisoned (previous panic), we return None gracefully
-    let _guard = REFRESH_LOCK.lock().ok()?;
-
-    // Re-check credentials after acquiring lock - another thread may have refreshed
-    let creds = match store.load() {
-        Ok(Some(c)) => c,
-        _ => return None,
-    };
-
-    // Check again if access token is now valid (another thread may have refreshed)
-    if !creds.is_access_token_expired(300) {
-        return Some(creds.access_token);
-    }
-
-    // Still expired - we need to refresh
-    let client = OAuthClient::new();
-    match client.refresh_access_token(&creds.refresh_token) {
-        Ok(new_creds) => {
-            // Store refreshed credentials (ignore errors - we still have the token)
-            let _ = store.store(&new_creds);
-            Some(new_creds.access_token)
-        }
-        Err(_) => None,
-    }
-    // Mutex guard is automatically released when _guard is dropped
+    CredentialStore::new().get_valid_access_token().ok()
 }
 
 /// Resolve the git author identity without requiring a Repository instance.
@@ -91,6 +50,10 @@ pub struct ApiContext {
     pub author_identity: Option<String>,
     /// Request timeout in seconds
     pub timeout_secs: Option<u64>,
+    /// Proxy URL overriding the environment and git's `http.proxy`
+    pub proxy: Option<String>,
+    /// Extra attempts for connection errors and 5xx responses (only set for idempotent requests)
+    pub retries: u32,
 }
 
 impl ApiContext {
@@ -100,25 +63,27 @@ impl ApiContext {
     }
 
     /// Create a GET request with common headers (User-Agent, X-Distinct-ID)
-    /// Use this for all HTTP GET requests to ensure consistent headers.
+    /// Use this for all HTTP GET requests to ensure consistent headers and proxy handling.
     pub fn http_get(url: &str) -> minreq::Request {
-        minreq::get(url)
+        let request = minreq::get(url)
             .with_header(
                 "User-Agent",
                 format!("git-ai/{}", env!("CARGO_PKG_VERSION")),
             )
-            .with_header("X-Distinct-ID", config::get_or_create_distinct_id())
+            .with_header("X-Distinct-ID", config::get_or_create_distinct_id());
+        proxy::apply_proxy(request, url, None)
     }
 
     /// Create a POST request with common headers (User-Agent, X-Distinct-ID)
-    /// Use this for all HTTP POST requests to ensure consistent headers.
+    /// Use this for all HTTP POST requests to ensure consistent headers and proxy handling.
     pub fn http_post(url: &str) -> minreq::Request {
-        minreq::post(url)
+        let request = minreq::post(url)
             .with_header(
                 "User-Agent",
                 format!("git-ai/{}", env!("CARGO_PKG_VERSION")),
             )
-            .with_header("X-Distinct-ID", config::get_or_create_distinct_id())
+            .with_header("X-Distinct-ID", config::get_or_create_distinct_id());
+        proxy::apply_proxy(request, url, None)
     }
 
     /// Create a new API context, automatically using stored credentials if available
@@ -137,6 +102,8 @@ impl ApiContext {
             api_key,
             author_identity,
             timeout_secs: Some(30),
+            proxy: None,
+            retries: 0,
         }
     }
 
@@ -157,6 +124,8 @@ impl ApiContext {
             api_key,
             author_identity,
             timeout_secs: Some(30),
+            proxy: None,
+            retries: 0,
         }
     }
 
@@ -177,6 +146,8 @@ impl ApiContext {
             api_key,
             author_identity,
             timeout_secs: Some(30),
+            proxy: None,
+            retries: 0,
         }
     }
 
@@ -186,6 +157,49 @@ impl ApiContext {
         self
     }
 
+    /// Set an explicit proxy URL, or `None` to resolve it from the environment
+    pub fn with_proxy(mut self, proxy: Option<String>) -> Self {
+        self.proxy = proxy;
+        self
+    }
+
+    /// Retry connection errors and transient statuses (see [`is_retryable_status`]) up to
+    /// `retries` times with exponential backoff. Only use this for idempotent requests.
+    pub fn with_retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Send `request`, retrying connection errors and transient statuses (see
+    /// [`is_retryable_status`]) up to `retries` times.
+    ///
+    /// Attempts are spaced by jittered exponential backoff and all of them together are
+    /// bounded by [`RETRY_TOTAL_DEADLINE`]; other 4xx responses are returned immediately.
+    pub fn send_with_retries(
+        request: minreq::Request,
+        retries: u32,
+        timeout_secs: Option<u64>,
+    ) -> Result<minreq::Response, minreq::Error> {
+        run_with_retries(
+            retries,
+            RETRY_BASE_DELAY,
+            RETRY_TOTAL_DEADLINE,
+            |remaining| {
+                let mut request = request.clone();
+                if retries > 0 || timeout_secs.is_some() {
+                    let remaining_secs = remaining.as_secs_f64().ceil().max(1.0) as u64;
+                    let timeout = timeout_secs.map_or(remaining_secs, |t| t.min(remaining_secs));
+                    request = request.with_timeout(timeout);
+                }
+                request.send()
+            },
+            |result| match result {
+                Ok(response) => is_retryable_status(response.status_code),
+                Err(_) => true,
+            },
+        )
+    }
+
     /// Build the full URL for an endpoint
     fn build_url(&self, endpoint: &str) -> Result<String, GitAiError> {
         let base = Url::parse(&self.base_url)
@@ -219,13 +233,11 @@ impl ApiContext {
             request = request.with_header("Authorization", format!("Bearer {}", token));
         }
 
-        // Set timeout if specified
-        if let Some(timeout) = self.timeout_secs {
-            request = request.with_timeout(timeout);
+        if let Some(proxy_url) = &self.proxy {
+            request = proxy::apply_proxy(request, &url, Some(proxy_url));
         }
 
-        let response = request
-            .send()
+        let response = Self::send_with_retries(request, self.retries, self.timeout_secs)
             .map_err(|e| GitAiError::Generic(format!("HTTP request failed: {}", e)))?;
 
         Ok(response)
@@ -247,19 +259,68 @@ impl ApiContext {
             request = request.with_header("Authorization", format!("Bearer {}", token));
         }
 
-        // Set timeout if specified
-        if let Some(timeout) = self.timeout_secs {
-            request = request.with_timeout(timeout);
+        if let Some(proxy_url) = &self.proxy {
+            request = proxy::apply_proxy(request, &url, Some(proxy_url));
         }
 
-        let response = request
-            .send()
+        let response = Self::send_with_retries(request, self.retries, self.timeout_secs)
             .map_err(|e| GitAiError::Generic(format!("HTTP request failed: {}", e)))?;
 
         Ok(response)
     }
 }
 
+/// Whether a response status is worth retrying: server-side failures (5xx), request timeouts
+/// (408) and rate limiting (429). Any other 4xx means the request itself was refused.
+pub(crate) fn is_retryable_status(status_code: i32) -> bool {
+    status_code >= 500 || status_code == 408 || status_code == 429
+}
+
+/// Backoff before retry number `retry` (0-based): `base * 2^retry`, capped, with the
+/// upper half randomized so concurrent clients don't retry in lockstep.
+fn backoff_delay(base: Duration, retry: u32) -> Duration {
+    let delay = base
+        .saturating_mul(2u32.saturating_pow(retry))
+        .min(RETRY_MAX_DELAY);
+    let half = delay / 2;
+    half + half.mul_f64(jitter_fraction())
+}
+
+/// Random fraction in `[0, 1)`, drawn from the std hasher's per-instance random keys
+fn jitter_fraction() -> f64 {
+    use std::hash::{BuildHasher, Hasher};
+    let bits = std::collections::hash_map::RandomState::new()
+        .build_hasher()
+        .finish();
+    (bits >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// Run `attempt` until it succeeds, `is_retryable` rejects its result, `retries` extra
+/// attempts are spent, or the next backoff would overrun `deadline`. `attempt` receives
+/// the time left before the deadline. Returns the last attempt's result.
+fn run_with_retries<T, E>(
+    retries: u32,
+    base_delay: Duration,
+    deadline: Duration,
+    mut attempt: impl FnMut(Duration) -> Result<T, E>,
+    is_retryable: impl Fn(&Result<T, E>) -> bool,
+) -> Result<T, E> {
+    let start = Instant::now();
+    let mut retry = 0;
+    loop {
+        let result = attempt(deadline.saturating_sub(start.elapsed()));
+        if retry >= retries || !is_retryable(&result) {
+            return result;
+        }
+        let delay = backoff_delay(base_delay, retry);
+        if start.elapsed() + delay >= deadline {
+            return result;
+        }
+        std::thread::sleep(delay);
+        retry += 1;
+    }
+}
+
 /// API client wrapper
 #[derive(Debug, Clone)]
 pub struct ApiClient {
@@ -297,6 +358,7 @@ impl ApiClient {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::auth::credentials::REFRESH_LOCK;
     use std::sync::Arc;
     use std::sync::atomic::{AtomicUsize, Ordering};
 
@@ -332,6 +394,136 @@ mod tests {
         assert_eq!(ctx.timeout_secs, Some(30));
     }
 
+    #[test]
+    fn test_api_context_with_retries() {
+        let ctx = ApiContext::without_auth(Some("https://example.com".to_string()));
+        assert_eq!(ctx.retries, 0);
+        assert_eq!(ctx.with_retries(3).retries, 3);
+    }
+
+    // ============= Retry Tests =============
+
+    fn retry_mock(
+        responses: Vec<Result<i32, &'static str>>,
+        retries: u32,
+        deadline: Duration,
+    ) -> (Result<i32, &'static str>, usize) {
+        let mut responses = responses.into_iter();
+        let mut attempts = 0;
+        let result = run_with_retries(
+            retries,
+            Duration::from_millis(1),
+            deadline,
+            |_| {
+                attempts += 1;
+                responses.next().expect("mock ran out of responses")
+            },
+            |result| match result {
+                Ok(status) => is_retryable_status(*status),
+                Err(_) => true,
+            },
+        );
+        (result, attempts)
+    }
+
+    #[test]
+    fn test_retry_third_attempt_wins_after_two_failures() {
+        let (result, attempts) = retry_mock(
+            vec![Err("connection refused"), Ok(503), Ok(200)],
+            3,
+            Duration::from_secs(10),
+        );
+        assert_eq!(result, Ok(200));
+        assert_eq!(attempts, 3);
+    }
+
+    #[test]
+    fn test_retry_client_error_fails_immediately() {
+        let (result, attempts) = retry_mock(vec![Ok(400), Ok(200)], 3, Duration::from_secs(10));
+        assert_eq!(result, Ok(400));
+        assert_eq!(attempts, 1);
+    }
+
+    #[test]
+    fn test_retry_timeout_and_rate_limit_statuses() {
+        let (result, attempts) =
+            retry_mock(vec![Ok(408), Ok(429), Ok(200)], 3, Duration::from_secs(10));
+        assert_eq!(result, Ok(200));
+        assert_eq!(attempts, 3);
+    }
+
+    #[test]
+    fn test_retry_gives_up_after_retries_exhausted() {
+        let (result, attempts) = retry_mock(
+            vec![Ok(502), Err("timed out"), Ok(503), Ok(200)],
+            2,
+            Duration::from_secs(10),
+        );
+        assert_eq!(result, Ok(503));
+        assert_eq!(attempts, 3);
+    }
+
+    #[test]
+    fn test_retry_stops_at_deadline() {
+        let (result, attempts) = retry_mock(vec![Ok(500), Ok(200)], 5, Duration::ZERO);
+        assert_eq!(result, Ok(500));
+        assert_eq!(attempts, 1);
+    }
+
+    #[test]
+    fn test_backoff_delay_grows_and_is_capped() {
+        let base = Duration::from_millis(100);
+        for retry in 0..4 {
+            let full = base * 2u32.pow(retry);
+            let delay = backoff_delay(base, retry);
+            assert!(
+                delay >= full / 2 && delay <= full,
+                "retry {}: {:?}",
+                retry,
+                delay
+            );
+        }
+        assert!(backoff_delay(base, 30) <= RETRY_MAX_DELAY);
+    }
+
+    #[test]
+    fn test_get_with_retries_against_flaky_server() {
+        use std::io::{Read, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = std::thread::spawn(move || {
+            for status in ["503 Service Unavailable", "502 Bad Gateway", "200 OK"] {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut request = Vec::new();
+                let mut buf = [0u8; 1024];
+                while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                    let n = stream.read(&mut buf).unwrap();
+                    if n == 0 {
+                        break;
+                    }
+                    request.extend_from_slice(&buf[..n]);
+                }
+                let body = if status.starts_with("200") { "ok" } else { "" };
+                write!(
+                    stream,
+                    "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                )
+                .unwrap();
+            }
+        });
+
+        let ctx = ApiContext::without_auth(Some(format!("http://{}", addr))).with_retries(2);
+        let response = ctx.get("/health").unwrap();
+        server.join().unwrap();
+
+        assert_eq!(response.status_code, 200);
+        assert_eq!(response.as_str().unwrap(), "ok");
+    }
+
     // ============= ApiClient Tests =============
 
     #[test]
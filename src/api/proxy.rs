@@ -0,0 +1,285 @@
+//! Proxy selection for git-ai's HTTP requests.
+//!
+//! Follows the curl/git conventions: `https_proxy`/`HTTPS_PROXY` for https URLs,
+//! `http_proxy`/`HTTP_PROXY` for http URLs, then `all_proxy`/`ALL_PROXY`, then
+//! git's `http.proxy` config. Hosts listed in `no_proxy`/`NO_PROXY` connect directly.
+//!
+//! minreq only speaks plain HTTP CONNECT proxies, so `https://` and `socks` proxy URLs
+//! are rejected.
+//!
+//! Known limitation: whenever a request has no proxy set, minreq falls back to the lowercase
+//! `https_proxy` (https URLs) or `http_proxy`/`all_proxy` (http URLs) variables on its own and
+//! offers no way to turn that off per request. A host excluded by `NO_PROXY` is therefore still
+//! proxied when one of those lowercase variables is set; the uppercase forms are only read here
+//! and do honor `NO_PROXY`.
+
+use crate::git::repository::exec_git;
+use crate::utils::debug_log;
+use std::sync::OnceLock;
+use url::Url;
+
+/// Proxy URL to use for a request to `url`, or `None` to connect directly
+pub fn proxy_for_url(url: &str) -> Option<String> {
+    let parsed = Url::parse(url).ok()?;
+    let host = parsed.host_str()?;
+
+    if let Some(no_proxy) = env_var(&["no_proxy", "NO_PROXY"])
+        && no_proxy_matches(&no_proxy, host, parsed.port_or_known_default())
+    {
+        return None;
+    }
+
+    let scheme_vars: &[&str] = if parsed.scheme() == "https" {
+        &["https_proxy", "HTTPS_PROXY"]
+    } else {
+        &["http_proxy", "HTTP_PROXY"]
+    };
+    env_var(scheme_vars)
+        .or_else(|| env_var(&["all_proxy", "ALL_PROXY"]))
+        .or_else(git_http_proxy)
+}
+
+/// Route `request` for `url` through `proxy_override` if given, otherwise through
+/// [`proxy_for_url`]. An unusable proxy URL is logged and the request is left as is.
+pub fn apply_proxy(
+    request: minreq::Request,
+    url: &str,
+    proxy_override: Option<&str>,
+) -> minreq::Request {
+    let Some(proxy) = proxy_override
+        .map(str::to_string)
+        .or_else(|| proxy_for_url(url))
+    else {
+        return request;
+    };
+
+    match parse_proxy_url(&proxy) {
+        Ok(proxy) => request.with_proxy(proxy),
+        Err(e) => {
+            debug_log(&format!("Ignoring proxy '{}': {}", proxy, e));
+            request
+        }
+    }
+}
+
+/// Parse `[http://][user[:password]@]host[:port][/]` into a minreq proxy
+pub fn parse_proxy_url(proxy: &str) -> Result<minreq::Proxy, String> {
+    let proxy = proxy.trim().trim_end_matches('/');
+    if let Some((scheme, _)) = proxy.split_once("://")
+        && !scheme.eq_ignore_ascii_case("http")
+    {
+        return Err(format!(
+            "unsupported proxy scheme '{}', only http:// proxies are supported",
+            scheme
+        ));
+    }
+    let proxy = match proxy.split_once("://") {
+        Some((_, rest)) => format!("http://{}", rest),
+        None => proxy.to_string(),
+    };
+    minreq::Proxy::new(proxy).map_err(|e| format!("invalid proxy URL: {}", e))
+}
+
+/// Whether `host` (on `port`) is excluded from proxying by a `NO_PROXY` list.
+///
+/// Entries are separated by commas or whitespace. `*` matches every host, a domain matches
+/// itself and its subdomains (a leading `.` or `*.` is ignored), and an entry with a `:port`
+/// only matches that port.
+pub fn no_proxy_matches(no_proxy: &str, host: &str, port: Option<u16>) -> bool {
+    let host = host
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .to_ascii_lowercase();
+
+    no_proxy
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|entry| !entry.is_empty())
+        .any(|entry| {
+            if entry == "*" {
+                return true;
+            }
+            let (entry_host, entry_port) = split_no_proxy_port(entry);
+            if entry_port.is_some() && entry_port != port {
+                return false;
+            }
+            let entry_host = entry_host
+                .trim_start_matches("*.")
+                .trim_start_matches('.')
+                .to_ascii_lowercase();
+            !entry_host.is_empty()
+                && (host == entry_host || host.ends_with(&format!(".{}", entry_host)))
+        })
+}
+
+/// Split a `NO_PROXY` entry into host and optional port, leaving bare IPv6 addresses intact
+fn split_no_proxy_port(entry: &str) -> (&str, Option<u16>) {
+    if let Some(rest) = entry.strip_prefix('[') {
+        return match rest.split_once(']') {
+            Some((addr, tail)) => (
+                addr,
+                tail.strip_prefix(':').and_then(|port| port.parse().ok()),
+            ),
+            None => (entry, None),
+        };
+    }
+    match entry.split_once(':') {
+        Some((host, port)) if !port.contains(':') => match port.parse() {
+            Ok(port) => (host, Some(port)),
+            Err(_) => (entry, None),
+        },
+        _ => (entry, None),
+    }
+}
+
+fn env_var(names: &[&str]) -> Option<String> {
+    names.iter().find_map(|name| {
+        std::env::var(name)
+            .ok()
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    })
+}
+
+/// git's `http.proxy`, read once per process
+fn git_http_proxy() -> Option<String> {
+    static GIT_HTTP_PROXY: OnceLock<Option<String>> = OnceLock::new();
+    GIT_HTTP_PROXY
+        .get_or_init(|| {
+            let args = vec![
+                "config".to_string(),
+                "--get".to_string(),
+                "http.proxy".to_string(),
+            ];
+            let output = exec_git(&args).ok()?;
+            let proxy = String::from_utf8(output.stdout).ok()?.trim().to_string();
+            (!proxy.is_empty()).then_some(proxy)
+        })
+        .clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+    use std::io::{Read, Write};
+    use std::net::{Ipv4Addr, TcpListener};
+
+    #[test]
+    fn test_parse_proxy_url_forms() {
+        let expected = minreq::Proxy::new("http://user:pw@proxy.corp:3128").unwrap();
+        assert_eq!(
+            parse_proxy_url("http://user:pw@proxy.corp:3128/").unwrap(),
+            expected
+        );
+        assert_eq!(
+            parse_proxy_url("HTTP://user:pw@proxy.corp:3128").unwrap(),
+            expected
+        );
+        assert_eq!(
+            parse_proxy_url("proxy.corp:3128").unwrap(),
+            minreq::Proxy::new("proxy.corp:3128").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_proxy_url_rejects_unsupported() {
+        assert!(parse_proxy_url("socks5://proxy.corp:1080").is_err());
+        assert!(parse_proxy_url("https://proxy.corp:443").is_err());
+        assert!(parse_proxy_url("http://proxy.corp:notaport").is_err());
+    }
+
+    #[test]
+    fn test_no_proxy_matches_domains_and_subdomains() {
+        let no_proxy = "localhost, .internal.corp,*.example.com other.org";
+        assert!(no_proxy_matches(no_proxy, "localhost", Some(443)));
+        assert!(no_proxy_matches(no_proxy, "api.internal.corp", Some(443)));
+        assert!(no_proxy_matches(no_proxy, "internal.corp", Some(443)));
+        assert!(no_proxy_matches(
+            no_proxy,
+            "deep.api.example.com",
+            Some(443)
+        ));
+        assert!(no_proxy_matches(no_proxy, "OTHER.org", Some(80)));
+        assert!(!no_proxy_matches(no_proxy, "notexample.com", Some(443)));
+        assert!(!no_proxy_matches(no_proxy, "usegitai.com", Some(443)));
+    }
+
+    #[test]
+    fn test_no_proxy_matches_wildcard() {
+        assert!(no_proxy_matches("*", "usegitai.com", Some(443)));
+        assert!(!no_proxy_matches("", "usegitai.com", Some(443)));
+    }
+
+    #[test]
+    fn test_no_proxy_matches_ports() {
+        let no_proxy = "api.corp:8443,[::1]:8080,::1";
+        assert!(no_proxy_matches(no_proxy, "api.corp", Some(8443)));
+        assert!(!no_proxy_matches(no_proxy, "api.corp", Some(443)));
+        assert!(no_proxy_matches("[::1]:8080", "[::1]", Some(8080)));
+        assert!(!no_proxy_matches("[::1]:8080", "[::1]", Some(443)));
+        assert!(no_proxy_matches(no_proxy, "[::1]", Some(443)));
+    }
+
+    #[test]
+    #[serial]
+    fn test_no_proxy_host_connects_directly_despite_uppercase_env_proxy() {
+        let server = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let server_port = server.local_addr().unwrap().port();
+        std::thread::spawn(move || {
+            let (mut stream, _) = server.accept().unwrap();
+            let mut buf = [0u8; 1024];
+            let _ = stream.read(&mut buf);
+            stream
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok")
+                .unwrap();
+        });
+
+        // A proxy that records whether anything connected to it
+        let proxy = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let proxy_url = format!("http://{}", proxy.local_addr().unwrap());
+        proxy.set_nonblocking(true).unwrap();
+
+        let vars = [
+            "https_proxy",
+            "http_proxy",
+            "all_proxy",
+            "HTTPS_PROXY",
+            "HTTP_PROXY",
+            "no_proxy",
+            "NO_PROXY",
+        ];
+        let previous: Vec<_> = vars.iter().map(std::env::var_os).collect();
+        // SAFETY: tests are serialized via #[serial], so mutating process env is safe.
+        unsafe {
+            std::env::remove_var("https_proxy");
+            std::env::remove_var("http_proxy");
+            std::env::remove_var("all_proxy");
+            std::env::set_var("HTTPS_PROXY", &proxy_url);
+            std::env::set_var("HTTP_PROXY", &proxy_url);
+            std::env::remove_var("no_proxy");
+            std::env::set_var("NO_PROXY", "127.0.0.1");
+        }
+
+        let url = format!("http://127.0.0.1:{}/", server_port);
+        assert_eq!(proxy_for_url(&url), None);
+        let response = apply_proxy(minreq::get(&url).with_timeout(10), &url, None).send();
+        let proxy_used = proxy.accept().is_ok();
+
+        // SAFETY: tests are serialized via #[serial], so restoring process env is safe.
+        unsafe {
+            for (name, value) in vars.iter().zip(previous) {
+                match value {
+                    Some(value) => std::env::set_var(name, value),
+                    None => std::env::remove_var(name),
+                }
+            }
+        }
+
+        let response = response.expect("request should connect directly");
+        assert_eq!(response.as_str().unwrap(), "ok");
+        assert!(
+            !proxy_used,
+            "NO_PROXY host was sent through the environment proxy"
+        );
+    }
+}
@@ -1,12 +1,23 @@
 use crate::api::client::ApiContext;
-use crate::auth::types::{DeviceAuthResponse, OAuthError, StoredCredentials, TokenResponse};
+use crate::auth::CredentialStore;
+use crate::auth::types::{
+    DeviceAuthResponse, OAuthError, StoredCredentials, TokenExchangeError, TokenResponse,
+};
 use crate::config;
 use std::thread;
 use std::time::Duration;
 
+/// Extra attempts for the install nonce exchange on connection errors and 5xx responses
+const INSTALL_NONCE_EXCHANGE_RETRIES: u32 = 3;
+
+/// Token endpoint path under the API base, unless overridden with [`OAuthClient::with_token_path`]
+/// or saved by the last login (see [`CredentialStore::oauth_token_path`])
+const DEFAULT_TOKEN_PATH: &str = "/worker/oauth/token";
+
 /// OAuth client for device authorization flow
 pub struct OAuthClient {
     base_url: String,
+    token_path: String,
 }
 
 /// Validate that a URL uses HTTPS (security requirement for OAuth)
@@ -41,7 +52,11 @@ impl OAuthClient {
             panic!("{}", e);
         }
 
-        Self { base_url }
+        Self {
+            base_url,
+            token_path: CredentialStore::oauth_token_path()
+                .unwrap_or_else(|| DEFAULT_TOKEN_PATH.to_string()),
+        }
     }
 
     /// Create an OAuthClient with a custom base URL (for install script flow)
@@ -49,23 +64,44 @@ impl OAuthClient {
         validate_https_url(base_url)?;
         Ok(Self {
             base_url: base_url.trim_end_matches('/').to_string(),
+            token_path: DEFAULT_TOKEN_PATH.to_string(),
         })
     }
 
-    /// Common token exchange logic - POST to /worker/oauth/token with given body
-    fn exchange_token(&self, body: serde_json::Value) -> Result<StoredCredentials, String> {
-        let url = format!("{}/worker/oauth/token", self.base_url);
+    /// Use `token_path` instead of `/worker/oauth/token` for token requests, for deployments
+    /// with a different API layout. Leading and trailing slashes are optional.
+    pub fn with_token_path(mut self, token_path: &str) -> Self {
+        self.token_path = token_path.to_string();
+        self
+    }
+
+    /// Full URL of the token endpoint
+    fn token_url(&self) -> String {
+        format!(
+            "{}/{}",
+            self.base_url.trim_end_matches('/'),
+            self.token_path.trim_matches('/')
+        )
+    }
 
-        let response = ApiContext::http_post(&url)
+    /// Common token exchange logic - POST to the token endpoint with given body,
+    /// retrying connection errors and 5xx responses up to `retries` times
+    fn exchange_token(
+        &self,
+        body: serde_json::Value,
+        retries: u32,
+    ) -> Result<StoredCredentials, TokenExchangeError> {
+        let url = self.token_url();
+
+        let request = ApiContext::http_post(&url)
             .with_header("Content-Type", "application/json")
-            .with_body(body.to_string())
-            .with_timeout(30)
-            .send()
-            .map_err(|e| format!("Failed to connect to server: {}", e))?;
+            .with_body(body.to_string());
+        let response = ApiContext::send_with_retries(request, retries, Some(30))
+            .map_err(|e| TokenExchangeError::Connection(e.to_string()))?;
 
-        let response_body = response
-            .as_str()
-            .map_err(|e| format!("Invalid response encoding: {}", e))?;
+        let response_body = response.as_str().map_err(|e| {
+            TokenExchangeError::InvalidResponse(format!("Invalid response encoding: {}", e))
+        })?;
 
         if response.status_code != 200 {
             let error: OAuthError = serde_json::from_str(response_body).unwrap_or(OAuthError {
@@ -73,22 +109,23 @@ impl OAuthClient {
                 error_description: None,
             });
 
-            let msg = error
+            let message = error
                 .error_description
                 .unwrap_or_else(|| error.error.clone());
-            return Err(msg);
+            return Err(TokenExchangeError::Rejected {
+                status: response.status_code,
+                message,
+            });
         }
 
-        let token_response: TokenResponse = serde_json::from_str(response_body)
-            .map_err(|e| format!("Invalid token response: {}", e))?;
-
-        let now = chrono::Utc::now().timestamp();
-        Ok(StoredCredentials {
-            access_token: token_response.access_token,
-            refresh_token: token_response.refresh_token,
-            access_token_expires_at: now + token_response.expires_in as i64,
-            refresh_token_expires_at: now + token_response.refresh_expires_in as i64,
-        })
+        let invalid = |e: String| {
+            TokenExchangeError::InvalidResponse(format!("Invalid token response: {}", e))
+        };
+        let token_response: TokenResponse =
+            serde_json::from_str(response_body).map_err(|e| invalid(e.to_string()))?;
+        token_response
+            .into_credentials(chrono::Utc::now().timestamp())
+            .map_err(invalid)
     }
 
     /// Start the device authorization flow
@@ -127,7 +164,7 @@ impl OAuthClient {
         interval: u32,
         expires_in: u32,
     ) -> Result<StoredCredentials, String> {
-        let url = format!("{}/worker/oauth/token", self.base_url);
+        let url = self.token_url();
         let mut elapsed = 0u32;
         let mut current_interval = interval;
 
@@ -157,13 +194,9 @@ impl OAuthClient {
                 let token_response: TokenResponse = serde_json::from_str(response_body)
                     .map_err(|e| format!("Invalid token response: {}", e))?;
 
-                let now = chrono::Utc::now().timestamp();
-                return Ok(StoredCredentials {
-                    access_token: token_response.access_token,
-                    refresh_token: token_response.refresh_token,
-                    access_token_expires_at: now + token_response.expires_in as i64,
-                    refresh_token_expires_at: now + token_response.refresh_expires_in as i64,
-                });
+                return token_response
+                    .into_credentials(chrono::Utc::now().timestamp())
+                    .map_err(|e| format!("Invalid token response: {}", e));
             }
 
             // Parse error response
@@ -210,20 +243,22 @@ impl OAuthClient {
             "client_id": "git-ai-cli"
         });
 
-        self.exchange_token(body)
+        self.exchange_token(body, 0)
             .map_err(|e| format!("Token refresh failed: {}", e))
     }
 
     /// Exchange an install nonce for credentials (auto-login from web install page)
-    pub fn exchange_install_nonce(&self, nonce: &str) -> Result<StoredCredentials, String> {
+    pub fn exchange_install_nonce(
+        &self,
+        nonce: &str,
+    ) -> Result<StoredCredentials, TokenExchangeError> {
         let body = serde_json::json!({
             "grant_type": "install_nonce",
             "install_nonce": nonce,
             "client_id": "git-ai-cli"
         });
 
-        self.exchange_token(body)
-            .map_err(|e| format!("Nonce exchange failed: {}", e))
+        self.exchange_token(body, INSTALL_NONCE_EXCHANGE_RETRIES)
     }
 }
 
@@ -292,6 +327,40 @@ mod tests {
         assert!(result.is_err());
     }
 
+    // ============= Token Endpoint Tests =============
+
+    #[test]
+    fn test_token_url_defaults_to_worker_path() {
+        let client = OAuthClient::with_base_url("https://example.com/").unwrap();
+        assert_eq!(client.token_url(), "https://example.com/worker/oauth/token");
+    }
+
+    #[test]
+    fn test_token_url_uses_custom_path() {
+        for token_path in ["/api/oauth/token", "api/oauth/token", "/api/oauth/token/"] {
+            let client = OAuthClient::with_base_url("https://git-ai.internal.example/")
+                .unwrap()
+                .with_token_path(token_path);
+            assert_eq!(
+                client.token_url(),
+                "https://git-ai.internal.example/api/oauth/token",
+                "{}",
+                token_path
+            );
+        }
+    }
+
+    #[test]
+    fn test_new_uses_token_path_saved_by_login() {
+        CredentialStore::store_oauth_token_path(Some("/api/oauth/token")).unwrap();
+        let saved = OAuthClient::new().token_url();
+        CredentialStore::store_oauth_token_path(None).unwrap();
+        let default = OAuthClient::new().token_url();
+
+        assert!(saved.ends_with("/api/oauth/token"), "{}", saved);
+        assert!(default.ends_with("/worker/oauth/token"), "{}", default);
+    }
+
     // ============= Token Response Parsing Tests =============
 
     #[test]
@@ -316,12 +385,12 @@ mod tests {
 
     #[test]
     fn test_parse_token_response_missing_field() {
-        // Missing refresh_expires_in
+        // Missing refresh_token (absent expiries fall back to defaults instead)
         let json = r#"{
             "access_token": "test_access",
             "token_type": "Bearer",
             "expires_in": 3600,
-            "refresh_token": "test_refresh"
+            "refresh_expires_in": 7776000
         }"#;
 
         let result: Result<TokenResponse, _> = serde_json::from_str(json);
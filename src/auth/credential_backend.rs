@@ -1,5 +1,6 @@
 use std::fs;
-use std::path::PathBuf;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 
 /// Trait for credential storage backends
 pub trait CredentialBackend: Send + Sync {
@@ -136,15 +137,20 @@ impl CredentialBackend for FileBackend {
             fs::create_dir_all(parent).map_err(|e| format!("Failed to create directory: {}", e))?;
         }
 
-        fs::write(&self.path, value)
+        // Write a uniquely named sibling temp file (created 0600 on unix) and rename it into
+        // place, so an interrupted write leaves the previous credentials intact instead of a
+        // truncated file, and concurrent stores can't clobber each other's temp file
+        let parent = match self.path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        let mut tmp = tempfile::NamedTempFile::new_in(parent)
             .map_err(|e| format!("Failed to write credentials file: {}", e))?;
-
-        #[cfg(unix)]
-        {
-            use std::os::unix::fs::PermissionsExt;
-            fs::set_permissions(&self.path, fs::Permissions::from_mode(0o600))
-                .map_err(|e| format!("Failed to set file permissions: {}", e))?;
-        }
+        tmp.write_all(value.as_bytes())
+            .and_then(|()| tmp.as_file().sync_all())
+            .map_err(|e| format!("Failed to write credentials file: {}", e))?;
+        tmp.persist(&self.path)
+            .map_err(|e| format!("Failed to write credentials file: {}", e.error))?;
 
         #[cfg(windows)]
         {
@@ -369,6 +375,31 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_file_backend_concurrent_stores_leave_one_complete_value() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("credentials");
+
+        let threads: Vec<_> = (0..8)
+            .map(|i| {
+                let backend = FileBackend::new(path.clone());
+                std::thread::spawn(move || {
+                    for _ in 0..20 {
+                        backend.store(&format!("credentials-{}", i)).unwrap();
+                    }
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+
+        let stored = FileBackend::new(path).load().unwrap().unwrap();
+        assert!(stored.starts_with("credentials-"), "{}", stored);
+        // No temp files are left next to the credentials
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
     #[test]
     fn test_file_backend_clear_nonexistent() {
         let temp_path = std::env::temp_dir()
@@ -1,10 +1,31 @@
+use crate::auth::client::OAuthClient;
 #[cfg(all(not(test), feature = "keyring"))]
 use crate::auth::credential_backend::KeyringBackend;
 use crate::auth::credential_backend::{CredentialBackend, FileBackend};
-use crate::auth::types::StoredCredentials;
+use crate::auth::types::{DEFAULT_ACCOUNT, StoredAccounts, StoredCredentials};
 #[cfg(not(test))]
 use crate::config::Config;
+use crate::config::CredentialStoreKind;
+use crate::error::GitAiError;
+#[cfg(not(test))]
+use crate::utils::debug_log;
+use once_cell::sync::Lazy;
 use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// Session file holding the install page URL, see [`CredentialStore::install_page_url`]
+pub(crate) const INSTALL_PAGE_URL_FILE: &str = "install_page_url";
+/// Session file holding a custom token endpoint path, see [`CredentialStore::oauth_token_path`]
+pub(crate) const OAUTH_TOKEN_PATH_FILE: &str = "oauth_token_path";
+
+/// Refresh the access token once it's within this many seconds of expiring. Matches the
+/// window the API client has always used, so both callers refresh at the same point.
+const ACCESS_TOKEN_EXPIRY_SKEW_SECS: i64 = 300;
+
+/// Global mutex to prevent multiple threads from refreshing simultaneously.
+/// This provides in-process synchronization to avoid thundering herd issues.
+/// Note: Cross-process races are acceptable - both processes get valid tokens.
+pub(crate) static REFRESH_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
 
 #[cfg(all(not(test), feature = "keyring"))]
 const SERVICE_NAME: &str = "git-ai";
@@ -32,9 +53,13 @@ impl CredentialStore {
         // Production build with keyring feature enabled
         #[cfg(all(not(test), feature = "keyring"))]
         {
-            let use_keyring = Config::get().get_feature_flags().auth_keyring;
+            let config = Config::get();
+            let use_keyring = wants_keychain(
+                config.credential_store(),
+                config.get_feature_flags().auth_keyring,
+            );
 
-            if use_keyring && KeyringBackend::is_available(SERVICE_NAME) {
+            let store = if use_keyring && KeyringBackend::is_available(SERVICE_NAME) {
                 Self {
                     backend: Box::new(KeyringBackend::new(SERVICE_NAME, USERNAME)),
                 }
@@ -48,23 +73,33 @@ impl CredentialStore {
                 Self {
                     backend: Box::new(FileBackend::new(Self::default_production_path())),
                 }
-            }
+            };
+            debug_log(&format!(
+                "Using {} credential backend",
+                store.backend_name()
+            ));
+            store
         }
 
         // Production build without keyring feature
         #[cfg(all(not(test), not(feature = "keyring")))]
         {
-            let use_keyring = Config::get().get_feature_flags().auth_keyring;
+            let config = Config::get();
+            let use_keyring = wants_keychain(
+                config.credential_store(),
+                config.get_feature_flags().auth_keyring,
+            );
 
             if use_keyring {
                 // User wanted keyring but binary was built without keyring support
                 use std::io::IsTerminal;
                 if std::io::stderr().is_terminal() {
                     eprintln!(
-                        "Note: auth_keyring is enabled but this binary was built without keyring support. Using file-based storage."
+                        "Note: keychain credential storage is enabled but this binary was built without keyring support. Using file-based storage."
                     );
                 }
             }
+            debug_log("Using file credential backend");
             Self {
                 backend: Box::new(FileBackend::new(Self::default_production_path())),
             }
@@ -99,31 +134,265 @@ impl CredentialStore {
         ))
     }
 
-    /// Store credentials securely
+    /// Path of a plain per-user session file such as [`INSTALL_PAGE_URL_FILE`]
+    #[cfg(not(test))]
+    fn session_file_path(name: &str) -> PathBuf {
+        dirs::home_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join(".git-ai")
+            .join("internal")
+            .join(name)
+    }
+
+    #[cfg(test)]
+    fn session_file_path(name: &str) -> PathBuf {
+        let credentials_path = Self::default_test_path();
+        let file_name = credentials_path.file_name().unwrap().to_string_lossy();
+        credentials_path.with_file_name(file_name.replace("credentials", name))
+    }
+
+    #[cfg(test)]
+    pub(crate) fn install_page_url_path() -> PathBuf {
+        Self::session_file_path(INSTALL_PAGE_URL_FILE)
+    }
+
+    /// Write a session file next to the file-backed credentials, or remove it for `None`
+    fn store_session_file(name: &str, value: Option<&str>) -> Result<(), String> {
+        let path = Self::session_file_path(name);
+        let Some(value) = value else {
+            return match std::fs::remove_file(&path) {
+                Ok(()) => Ok(()),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+                Err(e) => Err(format!("Failed to remove {}: {}", path.display(), e)),
+            };
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        std::fs::write(&path, value)
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+
+    fn session_file(name: &str) -> Option<String> {
+        std::fs::read_to_string(Self::session_file_path(name))
+            .ok()
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    }
+
+    /// Remember the install page a login came from, so errors after the install script has
+    /// exited (e.g. a failed token refresh) can still point users back to it. The URL isn't
+    /// secret, so it lives in a plain file next to the file-backed credentials regardless of
+    /// which backend holds the tokens.
+    pub fn store_install_page_url(url: &str) -> Result<(), String> {
+        Self::store_session_file(INSTALL_PAGE_URL_FILE, Some(url))
+    }
+
+    /// Install page URL saved by the last automatic login, if any
+    pub fn install_page_url() -> Option<String> {
+        Self::session_file(INSTALL_PAGE_URL_FILE)
+    }
+
+    /// Remember the token endpoint path the last login used (`None` for the default), so
+    /// token refreshes keep talking to the same endpoint after `OAUTH_TOKEN_PATH` is gone.
+    pub fn store_oauth_token_path(token_path: Option<&str>) -> Result<(), String> {
+        Self::store_session_file(OAUTH_TOKEN_PATH_FILE, token_path)
+    }
+
+    /// Token endpoint path saved by the last login, if it overrode the default
+    pub fn oauth_token_path() -> Option<String> {
+        Self::session_file(OAUTH_TOKEN_PATH_FILE)
+    }
+
+    /// Store credentials securely for the active account
+    ///
+    /// Credentials that fail [`StoredCredentials::validate`] are refused, so nothing `load`
+    /// would reject is ever written. Unreadable stored credentials are replaced rather than
+    /// blocking the store, so `git-ai login` can always recover from
+    /// [`GitAiError::CorruptCredentials`].
     pub fn store(&self, creds: &StoredCredentials) -> Result<(), String> {
-        let json = serde_json::to_string(creds)
-            .map_err(|e| format!("Failed to serialize credentials: {}", e))?;
+        creds
+            .validate()
+            .map_err(|e| format!("Refusing to store invalid credentials: {}", e))?;
+        let mut accounts = match self.load_accounts() {
+            Ok(accounts) => accounts,
+            Err(GitAiError::CorruptCredentials(_)) => StoredAccounts::default(),
+            Err(e) => return Err(e.to_string()),
+        };
+        accounts
+            .accounts
+            .insert(accounts.active.clone(), creds.clone());
+        self.store_accounts(&accounts)
+    }
 
-        self.backend.store(&json)
+    /// Load the active account's stored credentials.
+    ///
+    /// Returns [`GitAiError::CorruptCredentials`] when the stored value doesn't parse or fails
+    /// [`StoredCredentials::validate`], rather than handing back unusable tokens.
+    pub fn load(&self) -> Result<Option<StoredCredentials>, GitAiError> {
+        let mut accounts = self.load_accounts()?;
+        let Some(creds) = accounts.accounts.remove(&accounts.active) else {
+            return Ok(None);
+        };
+        creds.validate().map_err(GitAiError::CorruptCredentials)?;
+        Ok(Some(creds))
     }
 
-    /// Load stored credentials
-    pub fn load(&self) -> Result<Option<StoredCredentials>, String> {
-        let json = self.backend.load()?;
+    /// Clear the active account's credentials. Succeeds when nothing is stored. Once no account
+    /// has credentials left, the backend entry itself is removed.
+    pub fn clear(&self) -> Result<(), GitAiError> {
+        let mut accounts = match self.load_accounts() {
+            Ok(accounts) => accounts,
+            // Unreadable credentials can't be cleared selectively; drop them all
+            Err(GitAiError::CorruptCredentials(_)) => StoredAccounts::default(),
+            Err(e) => return Err(e),
+        };
+        accounts.accounts.remove(&accounts.active);
+        if accounts.accounts.is_empty() {
+            return self.backend.clear().map_err(GitAiError::Generic);
+        }
+        self.store_accounts(&accounts).map_err(GitAiError::Generic)
+    }
 
-        match json {
-            Some(json) => {
-                let creds: StoredCredentials = serde_json::from_str(&json)
-                    .map_err(|e| format!("Failed to parse credentials: {}", e))?;
-                Ok(Some(creds))
+    /// Clear the credentials of every account, e.g. for `git-ai logout --all`. Succeeds when
+    /// nothing is stored.
+    pub fn clear_all(&self) -> Result<(), GitAiError> {
+        self.backend.clear().map_err(GitAiError::Generic)
+    }
+
+    /// Names of the accounts with stored credentials, sorted
+    pub fn list_accounts(&self) -> Result<Vec<String>, GitAiError> {
+        Ok(self.load_accounts()?.accounts.into_keys().collect())
+    }
+
+    /// Name of the account `load`, `store` and token refreshes act on
+    pub fn active(&self) -> Result<String, GitAiError> {
+        Ok(self.load_accounts()?.active)
+    }
+
+    /// Make `account` the active account. It doesn't need stored credentials yet; the next
+    /// `store` (e.g. from `git-ai login`) fills them in.
+    pub fn set_active(&self, account: &str) -> Result<(), GitAiError> {
+        let account = account.trim();
+        if account.is_empty() {
+            return Err(GitAiError::Generic(
+                "Account name must not be empty".to_string(),
+            ));
+        }
+        let mut accounts = self.load_accounts()?;
+        accounts.active = account.to_string();
+        self.store_accounts(&accounts).map_err(GitAiError::Generic)
+    }
+
+    /// Read every stored account. Credentials written before multi-account support (a bare
+    /// [`StoredCredentials`] object) are read as [`DEFAULT_ACCOUNT`]; the stored value is left
+    /// alone, since older binaries sharing `~/.git-ai` can only read that format.
+    fn load_accounts(&self) -> Result<StoredAccounts, GitAiError> {
+        let Some(json) = self.backend.load().map_err(GitAiError::Generic)? else {
+            return Ok(StoredAccounts::default());
+        };
+
+        let corrupt = |e: serde_json::Error| {
+            GitAiError::CorruptCredentials(format!("failed to parse credentials: {}", e))
+        };
+        let value: serde_json::Value = serde_json::from_str(&json).map_err(corrupt)?;
+        if value.get("accounts").is_some() {
+            return serde_json::from_value(value).map_err(corrupt);
+        }
+
+        let creds: StoredCredentials = serde_json::from_value(value).map_err(corrupt)?;
+        let mut accounts = StoredAccounts::default();
+        accounts.accounts.insert(DEFAULT_ACCOUNT.to_string(), creds);
+        Ok(accounts)
+    }
+
+    /// Write `accounts` back. A lone active [`DEFAULT_ACCOUNT`] is written as bare
+    /// [`StoredCredentials`], so single-account users stay readable by older binaries.
+    fn store_accounts(&self, accounts: &StoredAccounts) -> Result<(), String> {
+        let legacy = match accounts.accounts.get(DEFAULT_ACCOUNT) {
+            Some(creds) if accounts.accounts.len() == 1 && accounts.active == DEFAULT_ACCOUNT => {
+                Some(creds)
             }
-            None => Ok(None),
+            _ => None,
+        };
+        let json = match legacy {
+            Some(creds) => serde_json::to_string(creds),
+            None => serde_json::to_string(accounts),
         }
+        .map_err(|e| format!("Failed to serialize credentials: {}", e))?;
+
+        self.backend.store(&json)
     }
 
-    /// Clear stored credentials
-    pub fn clear(&self) -> Result<(), String> {
-        self.backend.clear()
+    /// Get an access token that's valid for at least the next five minutes, refreshing and
+    /// persisting new credentials first if the stored one is about to expire.
+    ///
+    /// Returns [`GitAiError::AuthRequired`] when there are no stored credentials or the refresh
+    /// token has expired too.
+    pub fn get_valid_access_token(&self) -> Result<String, GitAiError> {
+        self.get_valid_access_token_with(|refresh_token| {
+            OAuthClient::new().refresh_access_token(refresh_token)
+        })
+    }
+
+    fn get_valid_access_token_with<F>(&self, refresh: F) -> Result<String, GitAiError>
+    where
+        F: FnOnce(&str) -> Result<StoredCredentials, String>,
+    {
+        let creds = self.load_refreshable()?;
+        if !creds.is_access_token_expired(ACCESS_TOKEN_EXPIRY_SKEW_SECS) {
+            return Ok(creds.access_token);
+        }
+
+        // Serialize refreshes within this process, then re-check in case another thread
+        // refreshed while we were waiting
+        let _guard = REFRESH_LOCK
+            .lock()
+            .map_err(|_| GitAiError::Generic("Token refresh lock poisoned".to_string()))?;
+        let creds = self.load_refreshable()?;
+        if !creds.is_access_token_expired(ACCESS_TOKEN_EXPIRY_SKEW_SECS) {
+            return Ok(creds.access_token);
+        }
+
+        let new_creds = refresh(&creds.refresh_token)
+            .map_err(|e| GitAiError::Generic(Self::with_install_page_hint(e)))?;
+        // A failed store still leaves us with a usable token for this process
+        if let Err(e) = self.store(&new_creds) {
+            crate::utils::debug_log(&format!("Failed to store refreshed credentials: {}", e));
+        }
+        Ok(new_creds.access_token)
+    }
+
+    /// Point a refresh failure at the install page the last automatic login came from, if any
+    fn with_install_page_hint(message: String) -> String {
+        match Self::install_page_url() {
+            Some(url) => format!(
+                "{}. If this keeps happening, copy the install command again from {} or run `git-ai login`.",
+                message, url
+            ),
+            None => message,
+        }
+    }
+
+    /// Load credentials whose refresh token is still usable
+    fn load_refreshable(&self) -> Result<StoredCredentials, GitAiError> {
+        let creds = self.load()?.ok_or_else(|| {
+            GitAiError::AuthRequired("not logged in. Run `git-ai login`.".to_string())
+        })?;
+        if creds.is_refresh_token_expired() {
+            return Err(GitAiError::AuthRequired(
+                "session expired. Run `git-ai login` to sign in again.".to_string(),
+            ));
+        }
+        Ok(creds)
+    }
+
+    /// Whether the active account has credentials whose refresh token hasn't expired, i.e.
+    /// API calls can go ahead without a new `git-ai login`. Unreadable credentials count as
+    /// logged out.
+    pub fn is_logged_in(&self) -> bool {
+        matches!(self.load(), Ok(Some(creds)) if !creds.is_refresh_token_expired())
     }
 
     /// Check if credentials are stored
@@ -139,6 +408,16 @@ impl CredentialStore {
     }
 }
 
+/// Whether to try the OS keychain. An explicit `credential_store` setting wins; when it's unset
+/// the `auth_keyring` feature flag decides.
+fn wants_keychain(credential_store: Option<CredentialStoreKind>, auth_keyring: bool) -> bool {
+    match credential_store {
+        Some(CredentialStoreKind::Keychain) => true,
+        Some(CredentialStoreKind::File) => false,
+        None => auth_keyring,
+    }
+}
+
 impl Default for CredentialStore {
     fn default() -> Self {
         Self::new()
@@ -220,6 +499,18 @@ mod tests {
         assert!(result.is_none());
     }
 
+    #[test]
+    fn test_clear_is_idempotent_with_mock() {
+        let store = CredentialStore::with_backend(Box::new(MockBackend::new()));
+        store.store(&make_test_credentials()).unwrap();
+
+        store.clear().unwrap();
+        store.clear().unwrap();
+
+        assert!(!store.has_credentials());
+        assert!(store.load().unwrap().is_none());
+    }
+
     #[test]
     fn test_has_credentials_with_mock() {
         let store = CredentialStore::with_backend(Box::new(MockBackend::new()));
@@ -233,6 +524,290 @@ mod tests {
         assert!(!store.has_credentials());
     }
 
+    #[test]
+    fn test_is_logged_in_without_credentials() {
+        let store = CredentialStore::with_backend(Box::new(MockBackend::new()));
+
+        assert!(!store.is_logged_in());
+    }
+
+    #[test]
+    fn test_is_logged_in_with_expired_refresh_token() {
+        let store = CredentialStore::with_backend(Box::new(MockBackend::new()));
+        let mut creds = make_test_credentials();
+        let now = chrono::Utc::now().timestamp();
+        creds.access_token_expires_at = now - 3600;
+        creds.refresh_token_expires_at = now - 60;
+        store.store(&creds).unwrap();
+
+        assert!(store.has_credentials());
+        assert!(!store.is_logged_in());
+    }
+
+    #[test]
+    fn test_is_logged_in_with_valid_credentials() {
+        let store = CredentialStore::with_backend(Box::new(MockBackend::new()));
+        let mut creds = make_test_credentials();
+        // An expired access token is fine while it can still be refreshed
+        creds.access_token_expires_at = chrono::Utc::now().timestamp() - 60;
+        store.store(&creds).unwrap();
+
+        assert!(store.is_logged_in());
+    }
+
+    // ============= Multiple Account Tests =============
+
+    #[test]
+    fn test_two_accounts_switch_active() {
+        let store = CredentialStore::with_backend(Box::new(MockBackend::new()));
+        let mut personal = make_test_credentials();
+        personal.access_token = "personal_access_token".to_string();
+        let mut contract = make_test_credentials();
+        contract.access_token = "contract_access_token".to_string();
+
+        assert_eq!(store.active().unwrap(), DEFAULT_ACCOUNT);
+        store.store(&personal).unwrap();
+        store.set_active("acme").unwrap();
+        assert!(store.load().unwrap().is_none());
+        store.store(&contract).unwrap();
+
+        assert_eq!(store.list_accounts().unwrap(), vec!["acme", "default"]);
+        assert_eq!(store.active().unwrap(), "acme");
+        assert_eq!(
+            store
+                .get_valid_access_token_with(|_| panic!("refresh should not be called"))
+                .unwrap(),
+            "contract_access_token"
+        );
+
+        store.set_active(DEFAULT_ACCOUNT).unwrap();
+        assert_eq!(
+            store
+                .get_valid_access_token_with(|_| panic!("refresh should not be called"))
+                .unwrap(),
+            "personal_access_token"
+        );
+
+        // Clearing only logs out of the active account
+        store.clear().unwrap();
+        assert_eq!(store.list_accounts().unwrap(), vec!["acme"]);
+        store.set_active("acme").unwrap();
+        assert_eq!(
+            store.load().unwrap().unwrap().access_token,
+            "contract_access_token"
+        );
+        assert!(store.set_active("  ").is_err());
+    }
+
+    #[test]
+    fn test_active_account_persists_across_stores() {
+        let temp_dir = env::temp_dir().join(format!("git-ai-test-accounts-{}", std::process::id()));
+        let test_path = temp_dir.join("credentials");
+        let _ = fs::remove_dir_all(&temp_dir);
+
+        let store = CredentialStore::with_backend(Box::new(FileBackend::new(test_path.clone())));
+        store.set_active("acme").unwrap();
+        store.store(&make_test_credentials()).unwrap();
+
+        let reopened = CredentialStore::with_backend(Box::new(FileBackend::new(test_path)));
+        assert_eq!(reopened.active().unwrap(), "acme");
+        assert!(reopened.load().unwrap().is_some());
+
+        let _ = fs::remove_dir_all(&temp_dir);
+    }
+
+    #[test]
+    fn test_legacy_single_credentials_read_as_default_account() {
+        let mock = MockBackend::new();
+        let creds = make_test_credentials();
+        let legacy = serde_json::to_string(&creds).unwrap();
+        mock.store(&legacy).unwrap();
+        let store = CredentialStore::with_backend(Box::new(mock));
+
+        assert_eq!(store.active().unwrap(), DEFAULT_ACCOUNT);
+        assert_eq!(store.list_accounts().unwrap(), vec![DEFAULT_ACCOUNT]);
+        assert_eq!(
+            store.load().unwrap().unwrap().access_token,
+            creds.access_token
+        );
+
+        // Reading never rewrites the file older binaries share
+        assert_eq!(store.backend.load().unwrap(), Some(legacy));
+    }
+
+    #[test]
+    fn test_single_default_account_is_stored_in_legacy_format() {
+        let store = CredentialStore::with_backend(Box::new(MockBackend::new()));
+        store.store(&make_test_credentials()).unwrap();
+
+        let stored = store.backend.load().unwrap().unwrap();
+        let legacy: StoredCredentials = serde_json::from_str(&stored).unwrap();
+        assert_eq!(legacy.access_token, make_test_credentials().access_token);
+
+        store.set_active("work").unwrap();
+        store.store(&make_test_credentials()).unwrap();
+        let stored = store.backend.load().unwrap().unwrap();
+        let accounts: StoredAccounts = serde_json::from_str(&stored).unwrap();
+        assert_eq!(accounts.active, "work");
+        assert_eq!(accounts.accounts.len(), 2);
+    }
+
+    #[test]
+    fn test_store_replaces_corrupt_credentials() {
+        let mock = MockBackend::new();
+        mock.store("{\"access_token\": ").unwrap();
+        let store = CredentialStore::with_backend(Box::new(mock));
+        assert!(matches!(
+            store.load(),
+            Err(GitAiError::CorruptCredentials(_))
+        ));
+
+        store.store(&make_test_credentials()).unwrap();
+
+        assert_eq!(
+            store.load().unwrap().unwrap().access_token,
+            make_test_credentials().access_token
+        );
+    }
+
+    #[test]
+    fn test_clear_all_removes_every_account() {
+        let store = CredentialStore::with_backend(Box::new(MockBackend::new()));
+        store.store(&make_test_credentials()).unwrap();
+        store.set_active("work").unwrap();
+        store.store(&make_test_credentials()).unwrap();
+
+        store.clear_all().unwrap();
+
+        assert!(store.list_accounts().unwrap().is_empty());
+        assert!(store.backend.load().unwrap().is_none());
+    }
+
+    // ============= Access Token Refresh Tests =============
+
+    #[test]
+    fn test_get_valid_access_token_not_expired_skips_refresh() {
+        let store = CredentialStore::with_backend(Box::new(MockBackend::new()));
+        store.store(&make_test_credentials()).unwrap();
+
+        let token = store
+            .get_valid_access_token_with(|_| panic!("refresh should not be called"))
+            .unwrap();
+
+        assert_eq!(token, "test_access_token_12345");
+    }
+
+    #[test]
+    fn test_get_valid_access_token_refreshes_within_skew() {
+        let store = CredentialStore::with_backend(Box::new(MockBackend::new()));
+        let mut creds = make_test_credentials();
+        creds.access_token_expires_at = chrono::Utc::now().timestamp() + 30;
+        store.store(&creds).unwrap();
+
+        let mut refreshed_with = None;
+        let token = store
+            .get_valid_access_token_with(|refresh_token| {
+                refreshed_with = Some(refresh_token.to_string());
+                let mut new_creds = make_test_credentials();
+                new_creds.access_token = "refreshed_access_token".to_string();
+                new_creds.refresh_token = "rotated_refresh_token".to_string();
+                Ok(new_creds)
+            })
+            .unwrap();
+
+        assert_eq!(token, "refreshed_access_token");
+        assert_eq!(refreshed_with.as_deref(), Some("test_refresh_token_67890"));
+        let stored = store.load().unwrap().unwrap();
+        assert_eq!(stored.access_token, "refreshed_access_token");
+        assert_eq!(stored.refresh_token, "rotated_refresh_token");
+    }
+
+    #[test]
+    fn test_get_valid_access_token_refresh_failure_keeps_credentials() {
+        let store = CredentialStore::with_backend(Box::new(MockBackend::new()));
+        let mut creds = make_test_credentials();
+        creds.access_token_expires_at = chrono::Utc::now().timestamp() - 10;
+        store.store(&creds).unwrap();
+
+        let result = store.get_valid_access_token_with(|_| Err("server unavailable".to_string()));
+
+        assert!(
+            matches!(result, Err(GitAiError::Generic(msg)) if msg.contains("server unavailable"))
+        );
+        assert_eq!(
+            store.load().unwrap().unwrap().access_token,
+            "test_access_token_12345"
+        );
+    }
+
+    #[test]
+    fn test_get_valid_access_token_refresh_failure_points_at_install_page() {
+        let store = CredentialStore::with_backend(Box::new(MockBackend::new()));
+        let mut creds = make_test_credentials();
+        creds.access_token_expires_at = chrono::Utc::now().timestamp() - 10;
+        store.store(&creds).unwrap();
+        CredentialStore::store_install_page_url("https://example.com/install").unwrap();
+
+        let result = store.get_valid_access_token_with(|_| Err("server unavailable".to_string()));
+        let _ = std::fs::remove_file(CredentialStore::install_page_url_path());
+
+        assert!(matches!(
+            result,
+            Err(GitAiError::Generic(msg)) if msg.contains("server unavailable")
+                && msg.contains("https://example.com/install")
+        ));
+    }
+
+    #[test]
+    fn test_get_valid_access_token_fully_expired_requires_reauth() {
+        let store = CredentialStore::with_backend(Box::new(MockBackend::new()));
+        let mut creds = make_test_credentials();
+        let now = chrono::Utc::now().timestamp();
+        creds.access_token_expires_at = now - 3600;
+        creds.refresh_token_expires_at = now - 60;
+        store.store(&creds).unwrap();
+
+        let result = store.get_valid_access_token_with(|_| panic!("refresh should not be called"));
+
+        assert!(matches!(result, Err(GitAiError::AuthRequired(_))));
+    }
+
+    #[test]
+    fn test_get_valid_access_token_not_logged_in_requires_reauth() {
+        let store = CredentialStore::with_backend(Box::new(MockBackend::new()));
+
+        let result = store.get_valid_access_token_with(|_| panic!("refresh should not be called"));
+
+        assert!(matches!(result, Err(GitAiError::AuthRequired(_))));
+    }
+
+    // ============= Backend Selection Tests =============
+
+    #[test]
+    fn test_wants_keychain_explicit_setting_overrides_flag() {
+        assert!(wants_keychain(Some(CredentialStoreKind::Keychain), false));
+        assert!(!wants_keychain(Some(CredentialStoreKind::File), true));
+    }
+
+    #[test]
+    fn test_wants_keychain_unset_defers_to_flag() {
+        assert!(wants_keychain(None, true));
+        assert!(!wants_keychain(None, false));
+    }
+
+    #[test]
+    fn test_credential_store_kind_parsing() {
+        assert_eq!(
+            CredentialStoreKind::parse(" Keychain "),
+            Some(CredentialStoreKind::Keychain)
+        );
+        assert_eq!(
+            CredentialStoreKind::parse("file"),
+            Some(CredentialStoreKind::File)
+        );
+        assert_eq!(CredentialStoreKind::parse("vault"), None);
+    }
+
     // ============= Error Handling Tests with Mock =============
 
     #[test]
@@ -245,6 +820,22 @@ mod tests {
         assert!(result.unwrap_err().contains("Keyring locked"));
     }
 
+    #[test]
+    fn test_store_rejects_invalid_credentials() {
+        let store = CredentialStore::with_backend(Box::new(MockBackend::new()));
+        store.store(&make_test_credentials()).unwrap();
+        let mut creds = make_test_credentials();
+        creds.refresh_token = String::new();
+
+        let result = store.store(&creds);
+
+        assert!(result.unwrap_err().contains("refresh token is empty"));
+        assert_eq!(
+            store.load().unwrap().unwrap().refresh_token,
+            make_test_credentials().refresh_token
+        );
+    }
+
     #[test]
     fn test_load_error_handling() {
         let mock = MockBackend::new().fail_load("Access denied");
@@ -252,7 +843,7 @@ mod tests {
 
         let result = store.load();
         assert!(result.is_err());
-        assert!(result.unwrap_err().contains("Access denied"));
+        assert!(result.unwrap_err().to_string().contains("Access denied"));
     }
 
     #[test]
@@ -262,7 +853,12 @@ mod tests {
 
         let result = store.clear();
         assert!(result.is_err());
-        assert!(result.unwrap_err().contains("Permission denied"));
+        assert!(
+            result
+                .unwrap_err()
+                .to_string()
+                .contains("Permission denied")
+        );
     }
 
     #[test]
@@ -406,8 +1002,10 @@ mod tests {
         let store = CredentialStore::with_backend(Box::new(mock));
         let result = store.load();
 
-        assert!(result.is_err());
-        assert!(result.unwrap_err().contains("parse"));
+        assert!(matches!(
+            result,
+            Err(GitAiError::CorruptCredentials(msg)) if msg.contains("parse")
+        ));
     }
 
     #[test]
@@ -419,8 +1017,10 @@ mod tests {
         let store = CredentialStore::with_backend(Box::new(mock));
         let result = store.load();
 
-        assert!(result.is_err());
-        assert!(result.unwrap_err().contains("parse"));
+        assert!(matches!(
+            result,
+            Err(GitAiError::CorruptCredentials(msg)) if msg.contains("parse")
+        ));
     }
 
     #[test]
@@ -456,6 +1056,72 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_corrupted_credentials_fail_validation() {
+        let mock = MockBackend::new();
+        let mut creds = make_test_credentials();
+        creds.refresh_token = String::new();
+        mock.store(&serde_json::to_string(&creds).unwrap()).unwrap();
+
+        let store = CredentialStore::with_backend(Box::new(mock));
+        let err = store.load().unwrap_err();
+
+        assert!(
+            matches!(&err, GitAiError::CorruptCredentials(msg) if msg == "refresh token is empty")
+        );
+        assert!(err.to_string().contains("git-ai login"));
+    }
+
+    #[test]
+    fn test_interrupted_write_keeps_previous_credentials() {
+        let temp_dir = env::temp_dir().join(format!("git-ai-test-atomic-{}", std::process::id()));
+        let test_path = temp_dir.join("credentials");
+        let _ = fs::remove_dir_all(&temp_dir);
+
+        let store = CredentialStore::with_backend(Box::new(FileBackend::new(test_path.clone())));
+        let creds = make_test_credentials();
+        store.store(&creds).unwrap();
+        assert_eq!(fs::read_dir(&temp_dir).unwrap().count(), 1);
+
+        // Simulate a crash mid-write: a partial temp file is left beside the real one
+        fs::write(temp_dir.join(".tmpcrash"), r#"{"access_token": "new"#).unwrap();
+
+        let loaded = store.load().unwrap().unwrap();
+        assert_eq!(loaded.access_token, creds.access_token);
+
+        // The next store goes through its own temp file and leaves no other behind
+        let mut updated = make_test_credentials();
+        updated.access_token = "updated_access_token".to_string();
+        store.store(&updated).unwrap();
+        assert_eq!(
+            store.load().unwrap().unwrap().access_token,
+            "updated_access_token"
+        );
+        assert_eq!(fs::read_dir(&temp_dir).unwrap().count(), 2);
+
+        let _ = fs::remove_dir_all(&temp_dir);
+    }
+
+    #[test]
+    fn test_truncated_credentials_file_is_corrupt() {
+        let temp_dir =
+            env::temp_dir().join(format!("git-ai-test-truncated-{}", std::process::id()));
+        let test_path = temp_dir.join("credentials");
+        let _ = fs::remove_dir_all(&temp_dir);
+
+        let store = CredentialStore::with_backend(Box::new(FileBackend::new(test_path.clone())));
+        store.store(&make_test_credentials()).unwrap();
+        let json = fs::read_to_string(&test_path).unwrap();
+        fs::write(&test_path, &json[..json.len() / 2]).unwrap();
+
+        assert!(matches!(
+            store.load(),
+            Err(GitAiError::CorruptCredentials(_))
+        ));
+
+        let _ = fs::remove_dir_all(&temp_dir);
+    }
+
     // ============= Path Tests =============
 
     #[test]
@@ -60,7 +60,7 @@ pub fn collect_auth_status() -> AuthStatus {
         }
         Err(err) => AuthStatus {
             backend,
-            state: AuthState::Error(err),
+            state: AuthState::Error(err.to_string()),
             access_token_expires_at: None,
             refresh_token_expires_at: None,
             user_id: None,
@@ -1,6 +1,11 @@
+use crate::api::client::is_retryable_status;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fmt;
 
+/// Account that credentials stored before multi-account support are migrated into
+pub const DEFAULT_ACCOUNT: &str = "default";
+
 /// Stored credentials for OAuth tokens
 /// NOTE: Debug intentionally redacts tokens to prevent accidental exposure in logs
 #[derive(Clone, Serialize, Deserialize)]
@@ -15,6 +20,24 @@ pub struct StoredCredentials {
     pub refresh_token_expires_at: i64,
 }
 
+/// Everything the credential backend holds: credentials keyed by account name, plus which
+/// account is active
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredAccounts {
+    /// Account used by `load`/`store` and token refreshes
+    pub active: String,
+    pub accounts: BTreeMap<String, StoredCredentials>,
+}
+
+impl Default for StoredAccounts {
+    fn default() -> Self {
+        Self {
+            active: DEFAULT_ACCOUNT.to_string(),
+            accounts: BTreeMap::new(),
+        }
+    }
+}
+
 /// Custom Debug implementation that redacts sensitive token values
 impl fmt::Debug for StoredCredentials {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -39,6 +62,24 @@ impl StoredCredentials {
         let now = chrono::Utc::now().timestamp();
         self.refresh_token_expires_at <= now
     }
+
+    /// Check that these look like credentials we stored: both tokens present and expiry
+    /// timestamps positive, with the access token expiring no later than the refresh token
+    pub fn validate(&self) -> Result<(), String> {
+        if self.access_token.trim().is_empty() {
+            return Err("access token is empty".to_string());
+        }
+        if self.refresh_token.trim().is_empty() {
+            return Err("refresh token is empty".to_string());
+        }
+        if self.access_token_expires_at <= 0 || self.refresh_token_expires_at <= 0 {
+            return Err("expiry timestamps must be positive".to_string());
+        }
+        if self.access_token_expires_at > self.refresh_token_expires_at {
+            return Err("access token outlives the refresh token".to_string());
+        }
+        Ok(())
+    }
 }
 
 /// Response from device authorization endpoint
@@ -52,17 +93,84 @@ pub struct DeviceAuthResponse {
     pub interval: u32,
 }
 
+/// Access token lifetime assumed when the server omits `expires_in` or sends 0
+pub const DEFAULT_ACCESS_TOKEN_LIFETIME_SECS: u64 = 3600;
+/// Refresh token lifetime assumed when the server omits `refresh_expires_in` or sends 0 (90 days)
+pub const DEFAULT_REFRESH_TOKEN_LIFETIME_SECS: u64 = 7_776_000;
+/// Shortest access token lifetime we store; longer than the five-minute refresh window, so a
+/// token isn't due for refresh as soon as it arrives
+const MIN_ACCESS_TOKEN_LIFETIME_SECS: u64 = 600;
+/// Shortest refresh token lifetime we store, so a fresh login isn't immediately expired
+const MIN_REFRESH_TOKEN_LIFETIME_SECS: u64 = 3600;
+
 /// Response from token endpoint
-#[derive(Debug, Deserialize)]
+/// NOTE: Debug intentionally redacts tokens, like [`StoredCredentials`]
+#[derive(Deserialize)]
 pub struct TokenResponse {
     pub access_token: String,
     #[allow(dead_code)]
     pub token_type: String,
+    /// Seconds until the access token expires; 0 when the server didn't say
+    #[serde(default)]
     pub expires_in: u64,
     pub refresh_token: String,
+    /// Seconds until the refresh token expires; 0 when the server didn't say
+    #[serde(default)]
     pub refresh_expires_in: u64,
 }
 
+impl TokenResponse {
+    /// Credentials to store for this response, received at unix time `now`.
+    ///
+    /// Missing or zero lifetimes fall back to the defaults and very short ones are raised to a
+    /// minimum, so we never store credentials that are already expired and loop on re-auth.
+    /// The access token never outlives the refresh token. Empty tokens are rejected.
+    pub fn into_credentials(self, now: i64) -> Result<StoredCredentials, String> {
+        if self.access_token.trim().is_empty() {
+            return Err("access token is empty".to_string());
+        }
+        if self.refresh_token.trim().is_empty() {
+            return Err("refresh token is empty".to_string());
+        }
+
+        let lifetime = |secs: u64, default: u64, min: u64| {
+            let secs = if secs == 0 { default } else { secs.max(min) };
+            i64::try_from(secs).unwrap_or(i64::MAX)
+        };
+        let refresh_lifetime = lifetime(
+            self.refresh_expires_in,
+            DEFAULT_REFRESH_TOKEN_LIFETIME_SECS,
+            MIN_REFRESH_TOKEN_LIFETIME_SECS,
+        );
+        let access_lifetime = lifetime(
+            self.expires_in,
+            DEFAULT_ACCESS_TOKEN_LIFETIME_SECS,
+            MIN_ACCESS_TOKEN_LIFETIME_SECS,
+        )
+        .min(refresh_lifetime);
+
+        Ok(StoredCredentials {
+            access_token: self.access_token,
+            refresh_token: self.refresh_token,
+            access_token_expires_at: now.saturating_add(access_lifetime),
+            refresh_token_expires_at: now.saturating_add(refresh_lifetime),
+        })
+    }
+}
+
+/// Custom Debug implementation that redacts sensitive token values
+impl fmt::Debug for TokenResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TokenResponse")
+            .field("access_token", &"[REDACTED]")
+            .field("token_type", &self.token_type)
+            .field("expires_in", &self.expires_in)
+            .field("refresh_token", &"[REDACTED]")
+            .field("refresh_expires_in", &self.refresh_expires_in)
+            .finish()
+    }
+}
+
 /// OAuth error response
 #[derive(Debug, Deserialize)]
 pub struct OAuthError {
@@ -70,18 +178,57 @@ pub struct OAuthError {
     pub error_description: Option<String>,
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Why a request to the token endpoint failed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TokenExchangeError {
+    /// The server couldn't be reached or didn't answer in time
+    Connection(String),
+    /// The server answered with a non-200 status; `message` is its OAuth error description
+    Rejected { status: i32, message: String },
+    /// A 200 response whose body wasn't a token response
+    InvalidResponse(String),
+}
+
+impl TokenExchangeError {
+    /// Whether sending the same request again could succeed: connection failures and the
+    /// statuses [`is_retryable_status`] accepts are transient, while any other 4xx means the
+    /// grant itself was refused
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            TokenExchangeError::Connection(_) => true,
+            TokenExchangeError::Rejected { status, .. } => is_retryable_status(*status),
+            TokenExchangeError::InvalidResponse(_) => false,
+        }
+    }
+}
 
-    fn make_credentials(access_expires_at: i64, refresh_expires_at: i64) -> StoredCredentials {
-        StoredCredentials {
-            access_token: "test_access_token".to_string(),
-            refresh_token: "test_refresh_token".to_string(),
-            access_token_expires_at: access_expires_at,
-            refresh_token_expires_at: refresh_expires_at,
+impl fmt::Display for TokenExchangeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TokenExchangeError::Connection(e) => write!(f, "Failed to connect to server: {}", e),
+            TokenExchangeError::Rejected { message, .. } => write!(f, "{}", message),
+            TokenExchangeError::InvalidResponse(e) => write!(f, "{}", e),
         }
     }
+}
+
+/// Credentials with fixed test tokens that expire at the given timestamps
+#[cfg(test)]
+pub(crate) fn make_credentials(
+    access_expires_at: i64,
+    refresh_expires_at: i64,
+) -> StoredCredentials {
+    StoredCredentials {
+        access_token: "test_access_token".to_string(),
+        refresh_token: "test_refresh_token".to_string(),
+        access_token_expires_at: access_expires_at,
+        refresh_token_expires_at: refresh_expires_at,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
     // ============= is_access_token_expired() tests =============
 
@@ -175,6 +322,37 @@ mod tests {
         assert!(!creds.is_refresh_token_expired());
     }
 
+    // ============= validate() tests =============
+
+    #[test]
+    fn test_validate_accepts_fresh_credentials() {
+        let now = chrono::Utc::now().timestamp();
+        assert!(make_credentials(now + 3600, now + 86400).validate().is_ok());
+        // Expired but well-formed credentials are still valid; refresh logic handles expiry
+        assert!(make_credentials(1000, 2000).validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_rejects_empty_tokens() {
+        let mut creds = make_credentials(1000, 2000);
+        creds.access_token = "  ".to_string();
+        assert_eq!(creds.validate().unwrap_err(), "access token is empty");
+
+        let mut creds = make_credentials(1000, 2000);
+        creds.refresh_token = String::new();
+        assert_eq!(creds.validate().unwrap_err(), "refresh token is empty");
+    }
+
+    #[test]
+    fn test_validate_rejects_insane_expiry() {
+        assert!(make_credentials(0, 2000).validate().is_err());
+        assert!(make_credentials(1000, -1).validate().is_err());
+        assert_eq!(
+            make_credentials(3000, 2000).validate().unwrap_err(),
+            "access token outlives the refresh token"
+        );
+    }
+
     // ============= Debug implementation tests =============
 
     #[test]
@@ -192,6 +370,123 @@ mod tests {
         assert!(!debug_output.contains("test_refresh_token"));
     }
 
+    fn token_response(json: &str) -> TokenResponse {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_token_response_absent_or_zero_expiry_uses_defaults() {
+        let now = 1_700_000_000;
+        for json in [
+            r#"{"access_token": "a", "token_type": "Bearer", "refresh_token": "r"}"#,
+            r#"{"access_token": "a", "token_type": "Bearer", "expires_in": 0,
+                "refresh_token": "r", "refresh_expires_in": 0}"#,
+        ] {
+            let creds = token_response(json).into_credentials(now).unwrap();
+            assert_eq!(
+                creds.access_token_expires_at,
+                now + DEFAULT_ACCESS_TOKEN_LIFETIME_SECS as i64
+            );
+            assert_eq!(
+                creds.refresh_token_expires_at,
+                now + DEFAULT_REFRESH_TOKEN_LIFETIME_SECS as i64
+            );
+            assert!(creds.validate().is_ok());
+        }
+    }
+
+    #[test]
+    fn test_token_response_short_expiry_is_clamped() {
+        let now = 1_700_000_000;
+        let creds = token_response(
+            r#"{"access_token": "a", "token_type": "Bearer", "expires_in": 1,
+                "refresh_token": "r", "refresh_expires_in": 1}"#,
+        )
+        .into_credentials(now)
+        .unwrap();
+
+        assert_eq!(
+            creds.access_token_expires_at,
+            now + MIN_ACCESS_TOKEN_LIFETIME_SECS as i64
+        );
+        assert_eq!(
+            creds.refresh_token_expires_at,
+            now + MIN_REFRESH_TOKEN_LIFETIME_SECS as i64
+        );
+    }
+
+    #[test]
+    fn test_token_response_access_token_never_outlives_refresh_token() {
+        let now = 1_700_000_000;
+        let creds = token_response(
+            r#"{"access_token": "a", "token_type": "Bearer", "expires_in": 86400,
+                "refresh_token": "r", "refresh_expires_in": 3600}"#,
+        )
+        .into_credentials(now)
+        .unwrap();
+
+        assert_eq!(
+            creds.access_token_expires_at,
+            creds.refresh_token_expires_at
+        );
+    }
+
+    #[test]
+    fn test_token_response_empty_tokens_rejected() {
+        let err = token_response(
+            r#"{"access_token": " ", "token_type": "Bearer", "expires_in": 3600,
+                "refresh_token": "r", "refresh_expires_in": 7776000}"#,
+        )
+        .into_credentials(0)
+        .unwrap_err();
+        assert!(err.contains("access token is empty"));
+
+        let err = token_response(
+            r#"{"access_token": "a", "token_type": "Bearer", "expires_in": 3600,
+                "refresh_token": "", "refresh_expires_in": 7776000}"#,
+        )
+        .into_credentials(0)
+        .unwrap_err();
+        assert!(err.contains("refresh token is empty"));
+    }
+
+    #[test]
+    fn test_token_response_debug_redacts_tokens() {
+        let response: TokenResponse = serde_json::from_str(
+            r#"{
+                "access_token": "secret_access_value",
+                "token_type": "Bearer",
+                "expires_in": 3600,
+                "refresh_token": "secret_refresh_value",
+                "refresh_expires_in": 7776000
+            }"#,
+        )
+        .unwrap();
+
+        let debug_output = format!("{:?}", response);
+        assert!(!debug_output.contains("secret_access_value"));
+        assert!(!debug_output.contains("secret_refresh_value"));
+        assert!(debug_output.contains("Bearer"));
+        assert!(debug_output.contains("3600"));
+    }
+
+    #[test]
+    fn test_token_exchange_error_retryable_statuses() {
+        let rejected = |status| TokenExchangeError::Rejected {
+            status,
+            message: "error".to_string(),
+        };
+
+        for status in [408, 429, 500, 503] {
+            assert!(rejected(status).is_retryable(), "{}", status);
+        }
+        for status in [400, 401, 403, 404] {
+            assert!(!rejected(status).is_retryable(), "{}", status);
+        }
+        assert!(TokenExchangeError::Connection("timed out".to_string()).is_retryable());
+        assert!(!TokenExchangeError::InvalidResponse("not json".to_string()).is_retryable());
+    }
+
     #[test]
     fn test_debug_shows_timestamps() {
         let creds = make_credentials(1234567890, 9876543210);
@@ -1,5 +1,6 @@
 use crate::authorship::authorship_log::{Author, LineRange, PromptRecord};
 use crate::authorship::working_log::CheckpointKind;
+use crate::error::GitAiError;
 use crate::git::repository::Repository;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
@@ -8,8 +9,8 @@ use std::fmt;
 use std::io::{BufRead, Write};
 use std::time::{SystemTime, UNIX_EPOCH};
 
-/// Authorship log format version identifier
-pub const AUTHORSHIP_LOG_VERSION: &str = "authorship/3.0.0";
+/// Authorship log format version written by this build
+pub const CURRENT_SCHEMA_VERSION: &str = "authorship/3.0.0";
 
 #[cfg(all(debug_assertions, test))]
 pub const GIT_AI_VERSION: &str = "development";
@@ -20,6 +21,16 @@ pub const GIT_AI_VERSION: &str = concat!("development:", env!("CARGO_PKG_VERSION
 #[cfg(not(debug_assertions))]
 pub const GIT_AI_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+/// Major version of [`CURRENT_SCHEMA_VERSION`]
+const CURRENT_SCHEMA_MAJOR_VERSION: u64 = 3;
+
+/// Whether this build reads notes of `schema_version` as they are, i.e. the version shares
+/// [`CURRENT_SCHEMA_VERSION`]'s major. Older majors are only readable after
+/// [`AuthorshipLog::migrate`].
+pub fn is_supported_schema_version(version: &str) -> bool {
+    schema_major_version(version) == Some(CURRENT_SCHEMA_MAJOR_VERSION)
+}
+
 /// Metadata section that goes below the divider as JSON
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct AuthorshipMetadata {
@@ -32,7 +43,7 @@ pub struct AuthorshipMetadata {
 impl AuthorshipMetadata {
     pub fn new() -> Self {
         Self {
-            schema_version: AUTHORSHIP_LOG_VERSION.to_string(),
+            schema_version: CURRENT_SCHEMA_VERSION.to_string(),
             git_ai_version: Some(GIT_AI_VERSION.to_string()),
             base_commit_sha: String::new(),
             prompts: BTreeMap::new(),
@@ -151,6 +162,34 @@ impl AuthorshipLog {
             .unwrap()
     }
 
+    /// Fold `other`'s attributions into this log, for notes that two clones changed
+    /// independently. Line ranges of the same prompt in the same file are unioned, files and
+    /// prompts only `other` knows are added, and prompt records in both keep this log's copy.
+    pub fn union_with(&mut self, other: AuthorshipLog) {
+        for other_file in other.attestations {
+            let file = self.get_or_create_file(&other_file.file_path);
+            for other_entry in other_file.entries {
+                match file.entries.iter_mut().find(|e| e.hash == other_entry.hash) {
+                    Some(entry) => {
+                        let mut lines: Vec<u32> = entry
+                            .line_ranges
+                            .iter()
+                            .chain(&other_entry.line_ranges)
+                            .flat_map(LineRange::expand)
+                            .collect();
+                        lines.sort_unstable();
+                        lines.dedup();
+                        entry.line_ranges = LineRange::compress_lines(&lines);
+                    }
+                    None => file.add_entry(other_entry),
+                }
+            }
+        }
+        for (hash, prompt) in other.metadata.prompts {
+            self.metadata.prompts.entry(hash).or_insert(prompt);
+        }
+    }
+
     /// Serialize to the new text format
     pub fn serialize_to_string(&self) -> Result<String, fmt::Error> {
         let mut output = String::new();
@@ -208,10 +247,11 @@ impl AuthorshipLog {
         let attestation_lines = &lines[..divider_pos];
         let attestations = parse_attestation_section(attestation_lines)?;
 
-        // Parse JSON metadata section (after divider)
+        // Parse JSON metadata section (after divider), upgrading older schemas
         let json_lines = &lines[divider_pos + 1..];
         let json_content = json_lines.join("\n");
-        let metadata: AuthorshipMetadata = serde_json::from_str(&json_content)?;
+        let raw_metadata: serde_json::Value = serde_json::from_str(&json_content)?;
+        let metadata: AuthorshipMetadata = serde_json::from_value(Self::migrate(raw_metadata)?)?;
 
         Ok(Self {
             attestations,
@@ -219,6 +259,58 @@ impl AuthorshipLog {
         })
     }
 
+    /// Upgrade raw note metadata to the current schema.
+    ///
+    /// 1.x and 2.x notes share the attestation layout, but their metadata may lack
+    /// `base_commit_sha`, and their prompt records may omit `human_author`/`messages` or
+    /// spell the override counter `overridden_lines`. Those gaps are filled in and the
+    /// version is bumped. 3.x metadata passes through unchanged; later majors are rejected
+    /// with [`GitAiError::UnsupportedSchema`], as is metadata without a `schema_version`
+    /// (reported as an empty `found`).
+    pub fn migrate(mut metadata: serde_json::Value) -> Result<serde_json::Value, GitAiError> {
+        let version = metadata
+            .get("schema_version")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string();
+        if is_supported_schema_version(&version) {
+            return Ok(metadata);
+        }
+
+        match schema_major_version(&version) {
+            Some(1 | 2) => {
+                let Some(fields) = metadata.as_object_mut() else {
+                    return Err(GitAiError::UnsupportedSchema { found: version });
+                };
+                fields.insert(
+                    "schema_version".to_string(),
+                    serde_json::Value::String(CURRENT_SCHEMA_VERSION.to_string()),
+                );
+                fields
+                    .entry("base_commit_sha")
+                    .or_insert_with(|| serde_json::Value::String(String::new()));
+                let prompts = fields
+                    .entry("prompts")
+                    .or_insert_with(|| serde_json::Value::Object(Default::default()));
+                if let Some(prompts) = prompts.as_object_mut() {
+                    for prompt in prompts.values_mut().filter_map(|p| p.as_object_mut()) {
+                        prompt
+                            .entry("human_author")
+                            .or_insert(serde_json::Value::Null);
+                        prompt
+                            .entry("messages")
+                            .or_insert_with(|| serde_json::Value::Array(Vec::new()));
+                        if let Some(overridden) = prompt.remove("overridden_lines") {
+                            prompt.entry("overriden_lines").or_insert(overridden);
+                        }
+                    }
+                }
+                Ok(metadata)
+            }
+            _ => Err(GitAiError::UnsupportedSchema { found: version }),
+        }
+    }
+
     /// Read from a reader in the new format
     pub fn _deserialize_from_reader<R: BufRead>(
         reader: R,
@@ -620,6 +712,12 @@ fn parse_attestation_section(
     Ok(attestations)
 }
 
+/// Major version of a `schema_version` such as `authorship/2.1.0` (the prefix is optional)
+fn schema_major_version(version: &str) -> Option<u64> {
+    let number = version.strip_prefix("authorship/").unwrap_or(version);
+    number.split('.').next()?.parse().ok()
+}
+
 /// Check if a file path needs quoting (contains spaces or whitespace)
 fn needs_quoting(path: &str) -> bool {
     path.contains(' ') || path.contains('\t') || path.contains('\n')
@@ -1350,4 +1448,161 @@ mod tests {
             .sum();
         assert_eq!(lines_session2, 20);
     }
+
+    const LEGACY_V2_NOTE: &str = r#"src/main.rs
+  c9883b0 1-3,7
+"docs/read me.md"
+  c9883b0 2
+---
+{
+  "schema_version": "authorship/2.1.0",
+  "git_ai_version": "0.9.4",
+  "prompts": {
+    "c9883b0": {
+      "agent_id": {
+        "tool": "cursor",
+        "id": "session_123",
+        "model": "gpt-4o"
+      },
+      "total_additions": 4,
+      "total_deletions": 1,
+      "accepted_lines": 4,
+      "overridden_lines": 1
+    }
+  }
+}"#;
+
+    #[test]
+    fn test_legacy_v2_note_migrates_and_round_trips() {
+        let log = AuthorshipLog::deserialize_from_string(LEGACY_V2_NOTE).unwrap();
+
+        assert_eq!(log.metadata.schema_version, CURRENT_SCHEMA_VERSION);
+        assert_eq!(log.metadata.git_ai_version.as_deref(), Some("0.9.4"));
+        assert_eq!(log.metadata.base_commit_sha, "");
+        let prompt = &log.metadata.prompts["c9883b0"];
+        assert_eq!(prompt.agent_id.model, "gpt-4o");
+        assert_eq!(prompt.human_author, None);
+        assert!(prompt.messages.is_empty());
+        assert_eq!(prompt.accepted_lines, 4);
+        assert_eq!(prompt.overriden_lines, 1);
+
+        assert_eq!(log.attestations.len(), 2);
+        assert_eq!(log.attestations[1].file_path, "docs/read me.md");
+        assert_eq!(
+            log.attestations[0].entries[0].line_ranges,
+            vec![LineRange::Range(1, 3), LineRange::Single(7)]
+        );
+
+        let reserialized = log.serialize_to_string().unwrap();
+        assert!(reserialized.contains(CURRENT_SCHEMA_VERSION));
+        assert_eq!(
+            AuthorshipLog::deserialize_from_string(&reserialized).unwrap(),
+            log
+        );
+    }
+
+    #[test]
+    fn test_union_with_merges_ranges_of_the_same_prompt() {
+        let mut ours = AuthorshipLog::new();
+        let mut file = FileAttestation::new("src/lib.rs".to_string());
+        file.add_entry(AttestationEntry::new(
+            "aaaaaaa".to_string(),
+            vec![LineRange::Range(1, 3)],
+        ));
+        ours.attestations.push(file);
+
+        let mut theirs = AuthorshipLog::new();
+        let mut file = FileAttestation::new("src/lib.rs".to_string());
+        file.add_entry(AttestationEntry::new(
+            "aaaaaaa".to_string(),
+            vec![LineRange::Range(3, 5), LineRange::Single(9)],
+        ));
+        theirs.attestations.push(file);
+
+        ours.union_with(theirs);
+
+        assert_eq!(ours.attestations.len(), 1);
+        assert_eq!(
+            ours.attestations[0].entries[0].line_ranges,
+            vec![LineRange::Range(1, 5), LineRange::Single(9)]
+        );
+    }
+
+    #[test]
+    fn test_legacy_v1_note_without_prompts_migrates() {
+        let note = "src/lib.rs\n  abc1234 5\n---\n{\"schema_version\":\"authorship/1.0.0\"}";
+        let log = AuthorshipLog::deserialize_from_string(note).unwrap();
+        assert_eq!(log.metadata.schema_version, CURRENT_SCHEMA_VERSION);
+        assert!(log.metadata.prompts.is_empty());
+        assert_eq!(log.attestations[0].file_path, "src/lib.rs");
+    }
+
+    /// The version an unreadable note reported, if deserializing failed on its schema
+    fn unsupported_schema(err: &(dyn std::error::Error + 'static)) -> Option<String> {
+        match err.downcast_ref::<GitAiError>() {
+            Some(GitAiError::UnsupportedSchema { found }) => Some(found.clone()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_future_major_version_is_typed_error() {
+        let note = "---\n{\"schema_version\":\"authorship/4.0.0\",\"base_commit_sha\":\"\",\"prompts\":{}}";
+        let err = AuthorshipLog::deserialize_from_string(note).unwrap_err();
+        assert_eq!(
+            unsupported_schema(err.as_ref()).as_deref(),
+            Some("authorship/4.0.0")
+        );
+    }
+
+    #[test]
+    fn test_newer_minor_version_is_supported() {
+        assert!(is_supported_schema_version("authorship/3.1.0"));
+        assert!(!is_supported_schema_version("authorship/4.0.0"));
+        assert!(!is_supported_schema_version("authorship/2.0.0"));
+
+        let note = "---\n{\"schema_version\":\"authorship/3.1.0\",\"base_commit_sha\":\"\",\"prompts\":{}}";
+        let log = AuthorshipLog::deserialize_from_string(note).unwrap();
+        assert_eq!(log.metadata.schema_version, "authorship/3.1.0");
+    }
+
+    #[test]
+    fn test_stub_metadata_with_current_version_parses() {
+        // The shape readers fill in when a note's own metadata is unreadable
+        let note = format!(
+            "src/lib.rs\n  abc1234 1-3\n---\n{{\"schema_version\":\"{}\",\"base_commit_sha\":\"\",\"prompts\":{{}}}}",
+            CURRENT_SCHEMA_VERSION
+        );
+        let log = AuthorshipLog::deserialize_from_string(&note).unwrap();
+        assert_eq!(log.metadata.schema_version, CURRENT_SCHEMA_VERSION);
+        assert!(log.metadata.prompts.is_empty());
+        assert_eq!(log.attestations[0].file_path, "src/lib.rs");
+    }
+
+    #[test]
+    fn test_empty_schema_version_is_unsupported() {
+        let note = "---\n{\"schema_version\":\"\",\"base_commit_sha\":\"\",\"prompts\":{}}";
+        let err = AuthorshipLog::deserialize_from_string(note).unwrap_err();
+        assert_eq!(unsupported_schema(err.as_ref()).as_deref(), Some(""));
+    }
+
+    #[test]
+    fn test_missing_or_malformed_schema_version_is_typed_error() {
+        let missing = AuthorshipLog::deserialize_from_string("---\n{\"prompts\":{}}").unwrap_err();
+        assert_eq!(unsupported_schema(missing.as_ref()).as_deref(), Some(""));
+
+        let malformed =
+            AuthorshipLog::deserialize_from_string("---\n{\"schema_version\":\"authorship/x\"}")
+                .unwrap_err();
+        assert_eq!(
+            unsupported_schema(malformed.as_ref()).as_deref(),
+            Some("authorship/x")
+        );
+    }
+
+    #[test]
+    fn test_migrate_leaves_current_schema_untouched() {
+        let metadata = serde_json::to_value(AuthorshipMetadata::new()).unwrap();
+        assert_eq!(AuthorshipLog::migrate(metadata.clone()).unwrap(), metadata);
+    }
 }
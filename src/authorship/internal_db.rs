@@ -2,7 +2,7 @@ use crate::authorship::authorship_log_serialization::generate_short_hash;
 use crate::authorship::transcript::AiTranscript;
 use crate::authorship::working_log::Checkpoint;
 use crate::error::GitAiError;
-use crate::utils::debug_log;
+use crate::utils::{debug_log, error_log};
 use dirs;
 use rusqlite::{Connection, params};
 use std::collections::HashMap;
@@ -286,7 +286,7 @@ impl InternalDatabase {
                 Ok(db) => Mutex::new(db),
                 Err(e) => {
                     // Log error during initialization
-                    eprintln!("[Error] Failed to initialize internal database: {}", e);
+                    error_log(&format!("Failed to initialize internal database: {}", e));
                     crate::observability::log_error(
                         &e,
                         Some(serde_json::json!({"function": "InternalDatabase::global"})),
@@ -13,6 +13,7 @@ pub mod pre_commit;
 pub mod prompt_utils;
 pub mod range_authorship;
 pub mod rebase_authorship;
+pub mod record_attestation;
 pub mod secrets;
 pub mod stats;
 pub mod transcript;
@@ -203,6 +203,8 @@ pub fn post_commit(
         .serialize_to_string()
         .map_err(|_| GitAiError::Generic("Failed to serialize authorship log".to_string()))?;
 
+    // The commit was just created, so nothing (including `append_attestations_to_commit`) can
+    // have attached a note to it yet; this writes the first note rather than replacing one.
     notes_add(repo, &commit_sha, &authorship_json)?;
 
     // Compute stats once (needed for both metrics and terminal output), unless preflight
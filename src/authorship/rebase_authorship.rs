@@ -7,7 +7,10 @@ use crate::git::authorship_traversal::{
 use crate::git::refs::{
     commits_with_authorship_notes, get_reference_as_authorship_log_v3, note_blob_oids_for_commits,
 };
-use crate::git::repository::{CommitRange, Repository, exec_git, exec_git_stdin};
+use crate::git::repository::{
+    CatFileBatchHeader, CommitRange, Repository, exec_git, exec_git_stdin,
+    parse_cat_file_batch_header,
+};
 use crate::git::rewrite_log::RewriteLogEvent;
 use crate::utils::{debug_log, debug_performance_log};
 use std::collections::{BTreeMap, HashMap, HashSet};
@@ -135,7 +138,7 @@ pub fn rewrite_authorship_if_needed(
 /// exist, only INITIAL attributions are merged into the new directory -- checkpoints
 /// from the old directory are intentionally dropped because the new directory's
 /// checkpoints already reflect the post-rebase state.
-fn migrate_working_log_after_rebase(
+pub(crate) fn migrate_working_log_after_rebase(
     repo: &Repository,
     original_head: &str,
     new_head: &str,
@@ -197,11 +200,9 @@ pub fn prepare_working_log_after_squash(
     // Step 1: Find merge base between source and target to optimize blame
     // We only need to look at commits after the merge base, not entire history
     let merge_base = repo
-        .merge_base(
-            source_head_sha.to_string(),
-            target_branch_head_sha.to_string(),
-        )
-        .ok();
+        .merge_base(source_head_sha, target_branch_head_sha)
+        .ok()
+        .flatten();
 
     // Step 2: Get list of changed files between the two branches
     let changed_files = repo.diff_changed_files(source_head_sha, target_branch_head_sha)?;
@@ -303,11 +304,9 @@ pub fn rewrite_authorship_after_squash_or_rebase(
     // Step 2: Find merge base between source and target to optimize blame
     // We only need to look at commits after the merge base, not entire history
     let merge_base = repo
-        .merge_base(
-            source_head_sha.to_string(),
-            target_branch_head_sha.to_string(),
-        )
-        .ok();
+        .merge_base(source_head_sha, &target_branch_head_sha)
+        .ok()
+        .flatten();
 
     // Step 3: Get list of changed files between the two branches
     let changed_files = repo.diff_changed_files(source_head_sha, &target_branch_head_sha)?;
@@ -529,9 +528,7 @@ pub fn rewrite_authorship_after_rebase_v2(
     // Step 2: Create VirtualAttributions from original_head (before rebase)
     // Compute merge base to bound blame depth — without this, blame walks entire file history
     let new_head = new_commits.last().unwrap();
-    let merge_base = repo
-        .merge_base(original_head.to_string(), new_head.to_string())
-        .ok();
+    let merge_base = repo.merge_base(original_head, new_head).ok().flatten();
 
     let repo_clone = repo.clone();
     let original_head_clone = original_head.to_string();
@@ -1085,26 +1082,14 @@ fn parse_cat_file_batch_output_with_oids(
         };
 
         let header = std::str::from_utf8(&data[pos..header_end])?;
-        let parts: Vec<&str> = header.split_whitespace().collect();
-        if parts.len() < 2 {
-            pos = header_end + 1;
-            continue;
-        }
-
-        let oid = parts[0].to_string();
-        if parts[1] == "missing" {
-            pos = header_end + 1;
-            continue;
-        }
-
-        if parts.len() < 3 {
-            pos = header_end + 1;
-            continue;
-        }
-
-        let size: usize = parts[2]
-            .parse()
-            .map_err(|e| GitAiError::Generic(format!("Invalid size in cat-file output: {}", e)))?;
+        let (oid, size) = match parse_cat_file_batch_header(header)? {
+            Some(CatFileBatchHeader::Object { oid, size, .. }) => (oid.to_string(), size),
+            // `missing`, `ambiguous`, ... lines have no content to skip over
+            _ => {
+                pos = header_end + 1;
+                continue;
+            }
+        };
 
         let content_start = header_end + 1;
         let content_end = content_start + size;
@@ -1167,28 +1152,17 @@ fn load_commit_metadata_batch(
             None => break,
         };
         let header = std::str::from_utf8(&data[pos..header_end])?;
-        let mut parts = header.split_whitespace();
-        let oid = match parts.next() {
-            Some(v) => v.to_string(),
-            None => {
+        let (oid, object_type, size) = match parse_cat_file_batch_header(header)? {
+            Some(CatFileBatchHeader::Object {
+                oid,
+                object_type,
+                size,
+            }) => (oid.to_string(), object_type, size),
+            _ => {
                 pos = header_end + 1;
                 continue;
             }
         };
-        let object_type = parts.next().unwrap_or_default();
-        if object_type == "missing" {
-            pos = header_end + 1;
-            continue;
-        }
-        let size: usize = parts
-            .next()
-            .ok_or_else(|| {
-                GitAiError::Generic("Malformed cat-file --batch header: missing size".to_string())
-            })?
-            .parse()
-            .map_err(|e| {
-                GitAiError::Generic(format!("Invalid cat-file --batch object size: {}", e))
-            })?;
 
         let content_start = header_end + 1;
         let content_end = content_start + size;
@@ -1545,12 +1519,7 @@ pub fn walk_commits_to_base(
 
     // Guard against pathological traversals when `base` is not actually an ancestor.
     // The old BFS fallback could walk huge histories in this case.
-    let mut is_ancestor_args = repository.global_args_for_exec();
-    is_ancestor_args.push("merge-base".to_string());
-    is_ancestor_args.push("--is-ancestor".to_string());
-    is_ancestor_args.push(base.to_string());
-    is_ancestor_args.push(head.to_string());
-    if exec_git(&is_ancestor_args).is_err() {
+    if !repository.is_ancestor(base, head).unwrap_or(false) {
         return Err(GitAiError::Generic(format!(
             "Base commit {} is not an ancestor of {}",
             base, head
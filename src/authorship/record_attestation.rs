@@ -0,0 +1,661 @@
+//! Record AI attestations on commits without going through the CLI.
+//!
+//! Editor integrations that know exactly which lines they generated can call
+//! [`record_attestation`] (or [`append_attestations_to_commit`] for a batch) to add them to
+//! the commit's authorship note, the same storage the commit hooks write to, and
+//! [`attestations_for_commit`] to read one commit's back.
+
+use crate::authorship::authorship_log::{LineRange, PromptRecord};
+use crate::authorship::authorship_log_serialization::{
+    AttestationEntry, AuthorshipLog, generate_short_hash,
+};
+use crate::authorship::working_log::AgentId;
+use crate::error::GitAiError;
+use crate::git::refs::{get_reference_as_authorship_log_v3, notes_add, show_authorship_note};
+use crate::git::repository::Repository;
+use crate::utils::normalize_to_posix;
+use std::collections::HashMap;
+
+/// Lines of one file at a commit that an agent generated
+#[derive(Debug, Clone)]
+pub struct Attestation {
+    /// Path relative to the repository root
+    pub file_path: String,
+    /// 1-based, inclusive line ranges in the file as committed
+    pub line_ranges: Vec<LineRange>,
+    pub agent_id: AgentId,
+    pub human_author: Option<String>,
+}
+
+impl Attestation {
+    /// Coalesce attestations for the same file and prompt into one with contiguous ranges.
+    ///
+    /// Overlapping and adjacent ranges are joined; disjoint ranges stay separate. Output keeps
+    /// the order in which each (file, prompt) pair first appears, and the first attestation's
+    /// human author wins. Reversed ranges cover no lines and are left out.
+    pub fn merge_adjacent(attestations: Vec<Attestation>) -> Vec<Attestation> {
+        let mut merged: Vec<(String, Attestation)> = Vec::new();
+        for attestation in attestations {
+            let hash = generate_short_hash(&attestation.agent_id.id, &attestation.agent_id.tool);
+            match merged.iter_mut().find(|(existing_hash, existing)| {
+                *existing_hash == hash && existing.file_path == attestation.file_path
+            }) {
+                Some((_, existing)) => existing.line_ranges.extend(attestation.line_ranges),
+                None => merged.push((hash, attestation)),
+            }
+        }
+
+        merged
+            .into_iter()
+            .map(|(_, mut attestation)| {
+                attestation.line_ranges = merge_line_ranges(&attestation.line_ranges);
+                attestation
+            })
+            .collect()
+    }
+}
+
+/// Join overlapping and adjacent ranges by sorting on start and extending the previous
+/// interval, so the cost doesn't depend on how many lines the ranges span
+fn merge_line_ranges(line_ranges: &[LineRange]) -> Vec<LineRange> {
+    let mut intervals: Vec<(u32, u32)> = line_ranges
+        .iter()
+        .map(|range| match *range {
+            LineRange::Single(line) => (line, line),
+            LineRange::Range(start, end) => (start, end),
+        })
+        .filter(|(start, end)| start <= end)
+        .collect();
+    intervals.sort_unstable();
+
+    let mut merged: Vec<(u32, u32)> = Vec::with_capacity(intervals.len());
+    for (start, end) in intervals {
+        match merged.last_mut() {
+            Some((_, prev_end)) if start <= prev_end.saturating_add(1) => {
+                *prev_end = (*prev_end).max(end);
+            }
+            _ => merged.push((start, end)),
+        }
+    }
+
+    merged
+        .into_iter()
+        .map(|(start, end)| {
+            if start == end {
+                LineRange::Single(start)
+            } else {
+                LineRange::Range(start, end)
+            }
+        })
+        .collect()
+}
+
+/// Attribute `attestation`'s lines to its agent in the authorship note of `commit_sha`.
+///
+/// The note is created if the commit has none. Lines already attributed to another prompt
+/// move to this one: the prompt's accepted line count grows by the newly attributed lines and
+/// the previous prompt's shrinks by the lines it lost.
+/// Fails without touching the note if the file doesn't exist in the commit, a range is
+/// outside the file, or an existing note can't be parsed.
+pub fn record_attestation(
+    repo: &Repository,
+    commit_sha: &str,
+    attestation: Attestation,
+) -> Result<(), GitAiError> {
+    append_attestations_to_commit(repo, commit_sha, vec![attestation])
+}
+
+/// Add a batch of attestations to the authorship note of `commit_sha`, keeping everything the
+/// note already attributes elsewhere.
+///
+/// Every attestation is validated, then the batch is coalesced with
+/// [`Attestation::merge_adjacent`] and each result is applied as
+/// by [`record_attestation`], so repeated writes to one commit accumulate rather than replace
+/// each other. The note is written once, and not at all if any attestation fails validation.
+pub fn append_attestations_to_commit(
+    repo: &Repository,
+    commit_sha: &str,
+    new: Vec<Attestation>,
+) -> Result<(), GitAiError> {
+    if new.is_empty() {
+        return Ok(());
+    }
+    let commit_sha = repo
+        .revparse_single(commit_sha)
+        .and_then(|obj| obj.peel_to_commit())?
+        .id();
+
+    let attestations: Vec<Attestation> = new
+        .into_iter()
+        .map(|attestation| Attestation {
+            file_path: normalize_to_posix(&attestation.file_path),
+            ..attestation
+        })
+        .collect();
+
+    // Validate the caller's ranges as given, before merging can hide a reversed or
+    // out-of-bounds one
+    let mut line_counts: HashMap<String, u32> = HashMap::new();
+    for attestation in &attestations {
+        let line_count = match line_counts.get(&attestation.file_path) {
+            Some(count) => *count,
+            None => {
+                let count = committed_line_count(repo, &commit_sha, &attestation.file_path)?;
+                line_counts.insert(attestation.file_path.clone(), count);
+                count
+            }
+        };
+        validate_line_ranges(&attestation.line_ranges, line_count, &attestation.file_path)?;
+    }
+    let attestations = Attestation::merge_adjacent(attestations);
+
+    let mut authorship_log = if show_authorship_note(repo, &commit_sha).is_some() {
+        get_reference_as_authorship_log_v3(repo, &commit_sha)?
+    } else {
+        let mut log = AuthorshipLog::new();
+        log.metadata.base_commit_sha = commit_sha.clone();
+        log
+    };
+    for attestation in attestations {
+        apply_attestation(&mut authorship_log, attestation);
+    }
+
+    let note = authorship_log
+        .serialize_to_string()
+        .map_err(|_| GitAiError::Generic("Failed to serialize authorship log".to_string()))?;
+    notes_add(repo, &commit_sha, &note)
+}
+
+/// Attribute one validated attestation's lines in `authorship_log`
+fn apply_attestation(authorship_log: &mut AuthorshipLog, attestation: Attestation) {
+    let file_path = attestation.file_path.clone();
+    let hash = generate_short_hash(&attestation.agent_id.id, &attestation.agent_id.tool);
+    let file = authorship_log.get_or_create_file(&file_path);
+    let mut lines: Vec<u32> = attestation
+        .line_ranges
+        .iter()
+        .flat_map(LineRange::expand)
+        .collect();
+    lines.sort_unstable();
+    lines.dedup();
+    let already_attributed = file
+        .entries
+        .iter()
+        .filter(|entry| entry.hash == hash)
+        .flat_map(|entry| entry.line_ranges.iter().flat_map(LineRange::expand))
+        .filter(|line| lines.binary_search(line).is_ok())
+        .count();
+    let new_lines = (lines.len() - already_attributed) as u32;
+
+    // Lines taken over from other prompts no longer count as accepted for them
+    let mut reassigned: HashMap<String, u32> = HashMap::new();
+    for entry in file.entries.iter_mut().filter(|entry| entry.hash != hash) {
+        let before = line_ranges_len(&entry.line_ranges);
+        entry.remove_line_ranges(&attestation.line_ranges);
+        let removed = before - line_ranges_len(&entry.line_ranges);
+        if removed > 0 {
+            *reassigned.entry(entry.hash.clone()).or_default() += removed;
+        }
+    }
+    file.entries.retain(|entry| !entry.line_ranges.is_empty());
+    match file.entries.iter_mut().find(|entry| entry.hash == hash) {
+        Some(entry) => {
+            lines.extend(entry.line_ranges.iter().flat_map(LineRange::expand));
+            lines.sort_unstable();
+            lines.dedup();
+            entry.line_ranges = LineRange::compress_lines(&lines);
+        }
+        None => file.add_entry(AttestationEntry::new(
+            hash.clone(),
+            LineRange::compress_lines(&lines),
+        )),
+    }
+
+    let prompt = authorship_log
+        .metadata
+        .prompts
+        .entry(hash)
+        .or_insert_with(|| PromptRecord {
+            agent_id: attestation.agent_id,
+            human_author: attestation.human_author,
+            messages: Vec::new(),
+            total_additions: 0,
+            total_deletions: 0,
+            accepted_lines: 0,
+            overriden_lines: 0,
+            messages_url: None,
+            custom_attributes: None,
+        });
+    prompt.accepted_lines += new_lines;
+
+    for (other_hash, removed) in reassigned {
+        if let Some(other) = authorship_log.metadata.prompts.get_mut(&other_hash) {
+            other.accepted_lines = other.accepted_lines.saturating_sub(removed);
+        }
+    }
+}
+
+/// Number of lines covered by `ranges`
+fn line_ranges_len(ranges: &[LineRange]) -> u32 {
+    ranges.iter().map(|range| range.expand().len() as u32).sum()
+}
+
+/// Attestations in the authorship note of `commit_sha`, one per file and prompt.
+///
+/// Reads only that commit's note, so it's much cheaper than traversing all of the notes ref
+/// for single-commit queries. Returns an empty list if the commit has no note. Entries whose
+/// hash has no prompt record are skipped since there's no agent to attribute them to.
+pub fn attestations_for_commit(
+    repo: &Repository,
+    commit_sha: &str,
+) -> Result<Vec<Attestation>, GitAiError> {
+    if show_authorship_note(repo, commit_sha).is_none() {
+        return Ok(Vec::new());
+    }
+
+    let authorship_log = get_reference_as_authorship_log_v3(repo, commit_sha)?;
+    let mut attestations = Vec::new();
+    for file in &authorship_log.attestations {
+        for entry in &file.entries {
+            let Some(prompt) = authorship_log.metadata.prompts.get(&entry.hash) else {
+                continue;
+            };
+            attestations.push(Attestation {
+                file_path: file.file_path.clone(),
+                line_ranges: entry.line_ranges.clone(),
+                agent_id: prompt.agent_id.clone(),
+                human_author: prompt.human_author.clone(),
+            });
+        }
+    }
+    Ok(attestations)
+}
+
+/// Number of lines in `file_path` as committed in `commit_sha`
+fn committed_line_count(
+    repo: &Repository,
+    commit_sha: &str,
+    file_path: &str,
+) -> Result<u32, GitAiError> {
+    let mut args = repo.global_args_for_exec();
+    args.push("cat-file".to_string());
+    args.push("blob".to_string());
+    args.push(format!("{}:{}", commit_sha, file_path));
+    let output = repo.exec_git(&args).map_err(|_| {
+        GitAiError::Generic(format!(
+            "File {} does not exist in commit {}",
+            file_path, commit_sha
+        ))
+    })?;
+    Ok(String::from_utf8_lossy(&output.stdout).lines().count() as u32)
+}
+
+fn validate_line_ranges(
+    line_ranges: &[LineRange],
+    line_count: u32,
+    file_path: &str,
+) -> Result<(), GitAiError> {
+    if line_ranges.is_empty() {
+        return Err(GitAiError::Generic(format!(
+            "No line ranges to attest for {}",
+            file_path
+        )));
+    }
+    for range in line_ranges {
+        let (start, end) = match *range {
+            LineRange::Single(line) => (line, line),
+            LineRange::Range(start, end) => (start, end),
+        };
+        if start == 0 || start > end || end > line_count {
+            return Err(GitAiError::Generic(format!(
+                "Line range {} is out of bounds for {} ({} lines)",
+                range, file_path, line_count
+            )));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::authorship_traversal::load_attestations_with_commits;
+    use crate::git::test_utils::TmpRepo;
+
+    fn editor_agent() -> AgentId {
+        AgentId {
+            tool: "my-editor".to_string(),
+            id: "session-1".to_string(),
+            model: "some-model".to_string(),
+        }
+    }
+
+    fn attestation(file_path: &str, line_ranges: Vec<LineRange>) -> Attestation {
+        Attestation {
+            file_path: file_path.to_string(),
+            line_ranges,
+            agent_id: editor_agent(),
+            human_author: Some("Test User <test@example.com>".to_string()),
+        }
+    }
+
+    fn repo_with_commit() -> (TmpRepo, String) {
+        let repo = TmpRepo::new().unwrap();
+        repo.write_file("src/lib.rs", "one\ntwo\nthree\nfour\n", true)
+            .unwrap();
+        repo.commit_with_message("add lib").unwrap();
+        let sha = repo.get_head_commit_sha().unwrap();
+        (repo, sha)
+    }
+
+    fn ranges_by_file(attestations: &[Attestation]) -> Vec<(&str, Vec<LineRange>)> {
+        attestations
+            .iter()
+            .map(|a| (a.file_path.as_str(), a.line_ranges.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_merge_adjacent_joins_touching_ranges() {
+        let merged = Attestation::merge_adjacent(vec![
+            attestation("a.rs", vec![LineRange::Range(1, 3)]),
+            attestation("a.rs", vec![LineRange::Single(4)]),
+            attestation("a.rs", vec![LineRange::Range(5, 6)]),
+        ]);
+        assert_eq!(
+            ranges_by_file(&merged),
+            vec![("a.rs", vec![LineRange::Range(1, 6)])]
+        );
+    }
+
+    #[test]
+    fn test_merge_adjacent_joins_overlapping_ranges() {
+        let merged = Attestation::merge_adjacent(vec![
+            attestation("a.rs", vec![LineRange::Range(5, 10)]),
+            attestation("a.rs", vec![LineRange::Range(2, 7), LineRange::Single(9)]),
+        ]);
+        assert_eq!(
+            ranges_by_file(&merged),
+            vec![("a.rs", vec![LineRange::Range(2, 10)])]
+        );
+    }
+
+    #[test]
+    fn test_merge_adjacent_keeps_disjoint_ranges_files_and_prompts_apart() {
+        let mut other_agent = attestation("a.rs", vec![LineRange::Single(4)]);
+        other_agent.agent_id.id = "session-2".to_string();
+
+        let merged = Attestation::merge_adjacent(vec![
+            attestation("a.rs", vec![LineRange::Range(8, 9)]),
+            attestation("b.rs", vec![LineRange::Single(4)]),
+            other_agent,
+            attestation("a.rs", vec![LineRange::Range(1, 2)]),
+        ]);
+        assert_eq!(
+            ranges_by_file(&merged),
+            vec![
+                ("a.rs", vec![LineRange::Range(1, 2), LineRange::Range(8, 9)]),
+                ("b.rs", vec![LineRange::Single(4)]),
+                ("a.rs", vec![LineRange::Single(4)]),
+            ]
+        );
+        assert_eq!(merged[2].agent_id.id, "session-2");
+    }
+
+    #[test]
+    fn test_merge_adjacent_handles_huge_and_reversed_ranges() {
+        let merged = Attestation::merge_adjacent(vec![
+            attestation("a.rs", vec![LineRange::Range(1, u32::MAX)]),
+            attestation("a.rs", vec![LineRange::Range(5, 1), LineRange::Single(7)]),
+        ]);
+        assert_eq!(
+            ranges_by_file(&merged),
+            vec![("a.rs", vec![LineRange::Range(1, u32::MAX)])]
+        );
+    }
+
+    #[test]
+    fn test_recorded_attestation_is_visible_to_note_traversal() {
+        let (repo, sha) = repo_with_commit();
+        let gitai_repo = repo.gitai_repo();
+
+        record_attestation(
+            gitai_repo,
+            &sha,
+            attestation("src/lib.rs", vec![LineRange::Range(2, 3)]),
+        )
+        .unwrap();
+
+        let attestations = load_attestations_with_commits(gitai_repo).unwrap();
+        let (commit, file) = attestations
+            .iter()
+            .find(|(_, file)| file.file_path == "src/lib.rs")
+            .expect("recorded file should be in the notes");
+        assert_eq!(commit, &sha);
+        let hash = generate_short_hash("session-1", "my-editor");
+        assert_eq!(
+            file.entries,
+            vec![AttestationEntry::new(
+                hash.clone(),
+                vec![LineRange::Range(2, 3)]
+            )]
+        );
+
+        let log = get_reference_as_authorship_log_v3(gitai_repo, &sha).unwrap();
+        assert_eq!(log.metadata.prompts[&hash].accepted_lines, 2);
+    }
+
+    #[test]
+    fn test_attestations_for_commit_reads_only_that_commit() {
+        let (repo, first_sha) = repo_with_commit();
+        repo.write_file("src/other.rs", "a\nb\n", true).unwrap();
+        repo.commit_with_message("add other").unwrap();
+        let second_sha = repo.get_head_commit_sha().unwrap();
+        repo.write_file("src/third.rs", "x\n", true).unwrap();
+        repo.commit_with_message("add third").unwrap();
+        let third_sha = repo.get_head_commit_sha().unwrap();
+        let gitai_repo = repo.gitai_repo();
+
+        record_attestation(
+            gitai_repo,
+            &first_sha,
+            attestation("src/lib.rs", vec![LineRange::Single(1)]),
+        )
+        .unwrap();
+        record_attestation(
+            gitai_repo,
+            &second_sha,
+            attestation("src/other.rs", vec![LineRange::Range(1, 2)]),
+        )
+        .unwrap();
+
+        let attestations = attestations_for_commit(gitai_repo, &second_sha).unwrap();
+        assert_eq!(
+            ranges_by_file(&attestations),
+            vec![("src/other.rs", vec![LineRange::Range(1, 2)])]
+        );
+        assert_eq!(attestations[0].agent_id, editor_agent());
+
+        let notes_ref = gitai_repo.notes_ref();
+        repo.git_command(&["notes", "--ref", &notes_ref, "remove", &third_sha])
+            .unwrap();
+        assert!(
+            attestations_for_commit(gitai_repo, &third_sha)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_record_attestation_merges_with_existing_lines() {
+        let (repo, sha) = repo_with_commit();
+        let gitai_repo = repo.gitai_repo();
+
+        record_attestation(
+            gitai_repo,
+            &sha,
+            attestation("src/lib.rs", vec![LineRange::Single(1)]),
+        )
+        .unwrap();
+        record_attestation(
+            gitai_repo,
+            "HEAD",
+            attestation("src/lib.rs", vec![LineRange::Range(1, 2)]),
+        )
+        .unwrap();
+
+        let log = get_reference_as_authorship_log_v3(gitai_repo, &sha).unwrap();
+        let hash = generate_short_hash("session-1", "my-editor");
+        let file = log
+            .attestations
+            .iter()
+            .find(|file| file.file_path == "src/lib.rs")
+            .unwrap();
+        assert_eq!(file.entries[0].line_ranges, vec![LineRange::Range(1, 2)]);
+        assert_eq!(log.metadata.prompts[&hash].accepted_lines, 2);
+    }
+
+    #[test]
+    fn test_record_attestation_takes_reassigned_lines_from_the_previous_prompt() {
+        let (repo, sha) = repo_with_commit();
+        let gitai_repo = repo.gitai_repo();
+        let other_agent = AgentId {
+            tool: "other-editor".to_string(),
+            id: "session-2".to_string(),
+            model: "other-model".to_string(),
+        };
+
+        record_attestation(
+            gitai_repo,
+            &sha,
+            attestation("src/lib.rs", vec![LineRange::Range(1, 3)]),
+        )
+        .unwrap();
+        record_attestation(
+            gitai_repo,
+            &sha,
+            Attestation {
+                agent_id: other_agent,
+                ..attestation("src/lib.rs", vec![LineRange::Range(2, 4)])
+            },
+        )
+        .unwrap();
+
+        let log = get_reference_as_authorship_log_v3(gitai_repo, &sha).unwrap();
+        let first = generate_short_hash("session-1", "my-editor");
+        let second = generate_short_hash("session-2", "other-editor");
+        assert_eq!(log.metadata.prompts[&first].accepted_lines, 1);
+        assert_eq!(log.metadata.prompts[&second].accepted_lines, 3);
+    }
+
+    #[test]
+    fn test_append_attestations_keeps_earlier_batches() {
+        let repo = TmpRepo::new().unwrap();
+        repo.write_file("src/lib.rs", "one\ntwo\nthree\nfour\n", true)
+            .unwrap();
+        repo.write_file("src/main.rs", "fn main() {}\n", true)
+            .unwrap();
+        repo.commit_with_message("add lib and main").unwrap();
+        let sha = repo.get_head_commit_sha().unwrap();
+        let gitai_repo = repo.gitai_repo();
+        let other_agent = AgentId {
+            tool: "other-editor".to_string(),
+            id: "session-2".to_string(),
+            model: "other-model".to_string(),
+        };
+
+        append_attestations_to_commit(
+            gitai_repo,
+            &sha,
+            vec![
+                attestation("src/lib.rs", vec![LineRange::Single(1)]),
+                attestation("src/lib.rs", vec![LineRange::Single(2)]),
+            ],
+        )
+        .unwrap();
+        append_attestations_to_commit(
+            gitai_repo,
+            &sha,
+            vec![
+                Attestation {
+                    agent_id: other_agent,
+                    ..attestation("src/lib.rs", vec![LineRange::Single(4)])
+                },
+                attestation("src/main.rs", vec![LineRange::Single(1)]),
+            ],
+        )
+        .unwrap();
+
+        let mut stored: Vec<(String, String, Vec<LineRange>)> =
+            attestations_for_commit(gitai_repo, &sha)
+                .unwrap()
+                .into_iter()
+                .map(|a| (a.file_path, a.agent_id.tool, a.line_ranges))
+                .collect();
+        stored.sort_by(|a, b| (&a.0, &a.1).cmp(&(&b.0, &b.1)));
+        assert_eq!(
+            stored,
+            vec![
+                (
+                    "src/lib.rs".to_string(),
+                    "my-editor".to_string(),
+                    vec![LineRange::Range(1, 2)]
+                ),
+                (
+                    "src/lib.rs".to_string(),
+                    "other-editor".to_string(),
+                    vec![LineRange::Single(4)]
+                ),
+                (
+                    "src/main.rs".to_string(),
+                    "my-editor".to_string(),
+                    vec![LineRange::Single(1)]
+                ),
+            ]
+        );
+        let log = get_reference_as_authorship_log_v3(gitai_repo, &sha).unwrap();
+        let hash = generate_short_hash("session-1", "my-editor");
+        assert_eq!(log.metadata.prompts[&hash].accepted_lines, 3);
+    }
+
+    #[test]
+    fn test_record_attestation_rejects_missing_file_and_out_of_bounds_ranges() {
+        let (repo, sha) = repo_with_commit();
+        let gitai_repo = repo.gitai_repo();
+
+        let err = record_attestation(
+            gitai_repo,
+            &sha,
+            attestation("src/missing.rs", vec![LineRange::Single(1)]),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("does not exist"), "{}", err);
+
+        for ranges in [
+            vec![LineRange::Range(3, 5)],
+            vec![LineRange::Single(0)],
+            vec![LineRange::Range(3, 2)],
+            vec![LineRange::Range(1, u32::MAX)],
+            vec![LineRange::Single(1), LineRange::Range(4, 1)],
+            vec![],
+        ] {
+            let err = record_attestation(gitai_repo, &sha, attestation("src/lib.rs", ranges))
+                .unwrap_err();
+            let message = err.to_string();
+            assert!(
+                message.contains("out of bounds") || message.contains("No line ranges"),
+                "{}",
+                message
+            );
+        }
+        // A directory is not a file
+        assert!(
+            record_attestation(
+                gitai_repo,
+                &sha,
+                attestation("src", vec![LineRange::Single(1)])
+            )
+            .is_err()
+        );
+    }
+}
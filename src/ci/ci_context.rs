@@ -151,10 +151,7 @@ impl CiContext {
                 // Detect squash vs rebase merge by counting commits
                 // For squash: N original commits → 1 merge commit
                 // For rebase: N original commits → N rebased commits
-                let merge_base = self
-                    .repo
-                    .merge_base(head_sha.to_string(), base_ref.to_string())
-                    .ok();
+                let merge_base = self.repo.merge_base(head_sha, base_ref).ok().flatten();
 
                 let original_commits = if let Some(ref base) = merge_base {
                     CommitRange::new_infer_refname(
@@ -1341,12 +1341,7 @@ fn output_json_format(
         .collect();
 
     // Compute metadata
-    let is_logged_in = CredentialStore::new()
-        .load()
-        .ok()
-        .flatten()
-        .map(|creds| !creds.is_refresh_token_expired())
-        .unwrap_or(false);
+    let is_logged_in = CredentialStore::new().is_logged_in();
 
     let current_user = repo.git_author_identity().formatted();
 
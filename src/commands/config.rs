@@ -2,6 +2,7 @@ use dirs;
 use serde_json::Value;
 use std::collections::HashMap;
 
+use crate::config::CredentialStoreKind;
 use crate::git::repository::find_repository_in_path;
 
 /// Determines the type of pattern value provided
@@ -111,6 +112,7 @@ fn print_config_help() {
     eprintln!("  include_prompts_in_repositories  Repos to include for prompt storage (array)");
     eprintln!("  default_prompt_storage       Fallback storage mode for non-included repos");
     eprintln!("  quiet                        Suppress chart output after commits (bool)");
+    eprintln!("  credential_store             Where login credentials are kept (keychain/file)");
     eprintln!("  git_ai_hooks                 Hook name -> shell commands map (object)");
     eprintln!();
     eprintln!("Repository Patterns:");
@@ -307,6 +309,13 @@ fn show_all_config() -> Result<(), String> {
 
     effective_config.insert("quiet".to_string(), Value::Bool(runtime_config.is_quiet()));
 
+    if let Some(store) = runtime_config.credential_store() {
+        effective_config.insert(
+            "credential_store".to_string(),
+            Value::String(store.as_str().to_string()),
+        );
+    }
+
     effective_config.insert(
         "git_ai_hooks".to_string(),
         serde_json::to_value(runtime_config.git_ai_hooks())
@@ -401,6 +410,10 @@ fn get_config_value(key: &str) -> Result<(), String> {
                 }
             }
             "quiet" => Value::Bool(runtime_config.is_quiet()),
+            "credential_store" => match runtime_config.credential_store() {
+                Some(store) => Value::String(store.as_str().to_string()),
+                None => Value::Null,
+            },
             "git_ai_hooks" => serde_json::to_value(runtime_config.git_ai_hooks())
                 .unwrap_or_else(|_| Value::Object(serde_json::Map::new())),
             _ => return Err(format!("Unknown config key: {}", key)),
@@ -568,6 +581,17 @@ fn set_config_value(key: &str, value: &str, add_mode: bool) -> Result<(), String
                 crate::config::save_file_config(&file_config)?;
                 eprintln!("[quiet]: {}", bool_value);
             }
+            "credential_store" => {
+                if CredentialStoreKind::parse(value).is_none() {
+                    return Err(format!(
+                        "Invalid credential_store value '{}'. Expected 'keychain' or 'file'",
+                        value
+                    ));
+                }
+                file_config.credential_store = Some(value.to_string());
+                crate::config::save_file_config(&file_config)?;
+                eprintln!("[credential_store]: {}", value);
+            }
             "git_ai_hooks" => {
                 if add_mode {
                     return Err("Cannot use --add with git_ai_hooks at top level. Use dot notation: git_ai_hooks.post_notes_updated".to_string());
@@ -781,6 +805,13 @@ fn unset_config_value(key: &str) -> Result<(), String> {
                     eprintln!("- [quiet]: {}", v);
                 }
             }
+            "credential_store" => {
+                let old_value = file_config.credential_store.take();
+                crate::config::save_file_config(&file_config)?;
+                if let Some(v) = old_value {
+                    eprintln!("- [credential_store]: {}", v);
+                }
+            }
             "git_ai_hooks" => {
                 let old_value = file_config.git_ai_hooks.take();
                 crate::config::save_file_config(&file_config)?;
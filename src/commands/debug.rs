@@ -1,6 +1,7 @@
 use crate::auth::{AuthState, collect_auth_status, format_unix_timestamp};
 use crate::config;
 use crate::git::find_repository_in_path;
+use crate::git::repo_storage::PlannedWorkingLogRename;
 use std::env;
 use std::fmt::Write as _;
 use std::process::Command;
@@ -174,6 +175,16 @@ fn build_debug_report() -> String {
                 let _ = writeln!(out, "  {} = {}", name, url);
             }
         }
+        if !repository_info.planned_renames.is_empty() {
+            let _ = writeln!(out, "Working log renames skipped by GIT_AI_DRY_RUN:");
+            for planned in repository_info.planned_renames {
+                let _ = writeln!(
+                    out,
+                    "  {} -> {} ({}, {})",
+                    planned.old_sha, planned.new_sha, planned.reason, planned.recorded_at
+                );
+            }
+        }
     }
     let _ = writeln!(out);
 
@@ -483,6 +494,7 @@ struct RepositoryInfo {
     head: Option<String>,
     hooks_path: Option<String>,
     remotes: Vec<(String, String)>,
+    planned_renames: Vec<PlannedWorkingLogRename>,
 }
 
 fn collect_repository_info() -> RepositoryInfo {
@@ -503,6 +515,7 @@ fn collect_repository_info() -> RepositoryInfo {
                 head: None,
                 hooks_path: None,
                 remotes: Vec::new(),
+                planned_renames: Vec::new(),
             };
         }
     };
@@ -519,6 +532,7 @@ fn collect_repository_info() -> RepositoryInfo {
         head: head.as_ref().and_then(|h| h.target().ok()),
         hooks_path: repo.config_get_str("core.hooksPath").ok().flatten(),
         remotes: repo.remotes_with_urls().unwrap_or_default(),
+        planned_renames: repo.storage.read_planned_working_log_renames(),
     }
 }
 
@@ -0,0 +1,286 @@
+use crate::auth::CredentialStore;
+use crate::auth::types::StoredCredentials;
+use crate::config;
+use crate::feature_flags::FeatureFlags;
+use crate::git::authorship_traversal::{get_note_blob_shas, notes_ref_tip};
+use crate::git::find_repository;
+use crate::git::refs::DEFAULT_NOTES_REF;
+use crate::git::repository::{Repository, exec_git};
+use serde::Serialize;
+
+/// Outcome of the `git-ai doctor` self-check
+#[derive(Debug, Serialize)]
+struct DoctorReport {
+    git_version: Option<String>,
+    /// `None` when not run inside a repository
+    notes: Option<NotesCheck>,
+    credentials: CredentialsCheck,
+    /// Remotes whose `remote.<name>.fetch` refspecs include the notes ref
+    notes_refspec_remotes: Vec<String>,
+    feature_flags: FeatureFlags,
+}
+
+#[derive(Debug, Serialize)]
+struct NotesCheck {
+    notes_ref: String,
+    /// Tip SHA of the notes ref, `None` if it doesn't exist
+    tip: Option<String>,
+    /// Number of distinct note blobs under the ref
+    note_count: usize,
+    error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct CredentialsCheck {
+    present: bool,
+    access_token_valid: bool,
+    refresh_token_valid: bool,
+    error: Option<String>,
+}
+
+pub fn handle_doctor(args: &[String]) {
+    let mut json_output = false;
+    for arg in args {
+        match arg.as_str() {
+            "--json" => json_output = true,
+            "--help" | "-h" => {
+                eprintln!("git-ai doctor - Check git-ai setup for common problems");
+                eprintln!();
+                eprintln!("Usage: git-ai doctor [--json]");
+                std::process::exit(0);
+            }
+            other => {
+                eprintln!("Unknown doctor argument: {}", other);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    let repo = find_repository(&[]).ok();
+    let report = build_doctor_report(
+        repo.as_ref(),
+        CredentialStore::new().load().map_err(|e| e.to_string()),
+        config::Config::get().feature_flags(),
+    );
+
+    if json_output {
+        match serde_json::to_string_pretty(&report) {
+            Ok(json) => println!("{}", json),
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        }
+    } else {
+        print!("{}", format_doctor_report(&report));
+    }
+}
+
+fn build_doctor_report(
+    repo: Option<&Repository>,
+    credentials: Result<Option<StoredCredentials>, String>,
+    feature_flags: &FeatureFlags,
+) -> DoctorReport {
+    DoctorReport {
+        git_version: git_version(),
+        notes: repo.map(check_notes),
+        credentials: check_credentials(credentials),
+        notes_refspec_remotes: repo.map(notes_refspec_remotes).unwrap_or_default(),
+        feature_flags: feature_flags.clone(),
+    }
+}
+
+fn git_version() -> Option<String> {
+    let output = exec_git(&["--version".to_string()]).ok()?;
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+fn check_notes(repo: &Repository) -> NotesCheck {
+    let tip = notes_ref_tip(repo);
+    let (note_count, error) = match &tip {
+        Some(_) => match get_note_blob_shas(repo) {
+            Ok(blobs) => (blobs.len(), None),
+            Err(e) => (0, Some(e.to_string())),
+        },
+        None => (0, None),
+    };
+    NotesCheck {
+        notes_ref: repo.notes_ref(),
+        tip,
+        note_count,
+        error,
+    }
+}
+
+fn check_credentials(credentials: Result<Option<StoredCredentials>, String>) -> CredentialsCheck {
+    match credentials {
+        Ok(Some(creds)) => CredentialsCheck {
+            present: true,
+            access_token_valid: !creds.is_access_token_expired(0),
+            refresh_token_valid: !creds.is_refresh_token_expired(),
+            error: None,
+        },
+        Ok(None) => CredentialsCheck {
+            present: false,
+            access_token_valid: false,
+            refresh_token_valid: false,
+            error: None,
+        },
+        Err(e) => CredentialsCheck {
+            present: false,
+            access_token_valid: false,
+            refresh_token_valid: false,
+            error: Some(e),
+        },
+    }
+}
+
+fn notes_refspec_remotes(repo: &Repository) -> Vec<String> {
+    let notes_ref = repo.notes_ref();
+    let mut remotes: Vec<String> = repo
+        .config_get_regexp_multi(r"^remote\..+\.fetch$")
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|(_, refspec)| refspec.contains(notes_ref.as_str()))
+        .filter_map(|(key, _)| {
+            key.strip_prefix("remote.")
+                .and_then(|rest| rest.strip_suffix(".fetch"))
+                .map(str::to_string)
+        })
+        .collect();
+    remotes.sort();
+    remotes.dedup();
+    remotes
+}
+
+fn format_doctor_report(report: &DoctorReport) -> String {
+    let mut out = String::new();
+    out.push_str("git-ai doctor\n\n");
+
+    out.push_str(&format!(
+        "Git: {}\n",
+        report.git_version.as_deref().unwrap_or("<unavailable>")
+    ));
+
+    match &report.notes {
+        None => out.push_str("Authorship notes: not in a git repository\n"),
+        Some(notes) => match (&notes.tip, &notes.error) {
+            (None, _) => out.push_str(&format!(
+                "Authorship notes: missing ({} does not exist yet; nothing has been attributed or fetched)\n",
+                notes.notes_ref
+            )),
+            (Some(tip), Some(error)) => out.push_str(&format!(
+                "Authorship notes: {} at {} could not be read: {}\n",
+                notes.notes_ref, tip, error
+            )),
+            (Some(tip), None) => out.push_str(&format!(
+                "Authorship notes: {} at {} ({} notes)\n",
+                notes.notes_ref, tip, notes.note_count
+            )),
+        },
+    }
+
+    let credentials = &report.credentials;
+    let credentials_summary = if let Some(error) = &credentials.error {
+        format!("could not be read: {}", error)
+    } else if !credentials.present {
+        "not logged in (run `git-ai login`)".to_string()
+    } else if credentials.access_token_valid {
+        "logged in, access token valid".to_string()
+    } else if credentials.refresh_token_valid {
+        "logged in, access token expired (it will be refreshed on next use)".to_string()
+    } else {
+        "session expired (run `git-ai login`)".to_string()
+    };
+    out.push_str(&format!("Credentials: {}\n", credentials_summary));
+
+    if report.notes_refspec_remotes.is_empty() {
+        let notes_ref = report
+            .notes
+            .as_ref()
+            .map(|notes| notes.notes_ref.as_str())
+            .unwrap_or(DEFAULT_NOTES_REF);
+        out.push_str(&format!(
+            "Notes refspec: no remote fetches {} (git-ai fetches notes itself during fetch/pull)\n",
+            notes_ref
+        ));
+    } else {
+        out.push_str(&format!(
+            "Notes refspec: configured on {}\n",
+            report.notes_refspec_remotes.join(", ")
+        ));
+    }
+
+    out.push_str("Feature flags:\n");
+    if let Ok(serde_json::Value::Object(flags)) = serde_json::to_value(&report.feature_flags) {
+        for (name, value) in flags {
+            out.push_str(&format!("  {} = {}\n", name, value));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::test_utils::TmpRepo;
+
+    #[test]
+    fn test_doctor_reports_missing_notes_on_fresh_repo() {
+        let repo = TmpRepo::new().unwrap();
+
+        let report =
+            build_doctor_report(Some(repo.gitai_repo()), Ok(None), &FeatureFlags::default());
+
+        let notes = report.notes.as_ref().unwrap();
+        assert_eq!(notes.tip, None);
+        assert_eq!(notes.note_count, 0);
+        assert!(!report.credentials.present);
+        assert!(report.notes_refspec_remotes.is_empty());
+        let text = format_doctor_report(&report);
+        assert!(text.contains("Authorship notes: missing"), "{}", text);
+        assert!(text.contains("not logged in"), "{}", text);
+    }
+
+    #[test]
+    fn test_doctor_reports_notes_tip_and_refspec_remotes() {
+        let repo = TmpRepo::new().unwrap();
+        repo.write_file("a.txt", "a\n", true).unwrap();
+        repo.commit_with_message("a").unwrap();
+        repo.git_command(&["remote", "add", "origin", "https://example.com/repo.git"])
+            .unwrap();
+        repo.git_command(&[
+            "config",
+            "--add",
+            "remote.origin.fetch",
+            "+refs/notes/ai:refs/notes/ai",
+        ])
+        .unwrap();
+
+        let report =
+            build_doctor_report(Some(repo.gitai_repo()), Ok(None), &FeatureFlags::default());
+
+        let notes = report.notes.as_ref().unwrap();
+        assert!(notes.tip.is_some());
+        assert_eq!(notes.note_count, 1);
+        assert_eq!(report.notes_refspec_remotes, vec!["origin".to_string()]);
+    }
+
+    #[test]
+    fn test_doctor_credentials_expiry() {
+        let now = chrono::Utc::now().timestamp();
+        let creds = StoredCredentials {
+            access_token: "a".to_string(),
+            refresh_token: "r".to_string(),
+            access_token_expires_at: now - 10,
+            refresh_token_expires_at: now + 3600,
+        };
+        let check = check_credentials(Ok(Some(creds)));
+        assert!(check.present);
+        assert!(!check.access_token_valid);
+        assert!(check.refresh_token_valid);
+
+        let check = check_credentials(Err("keyring locked".to_string()));
+        assert_eq!(check.error.as_deref(), Some("keyring locked"));
+    }
+}
@@ -3,52 +3,529 @@
 //! This command is called by the install script to exchange a nonce for
 //! OAuth credentials. It reads INSTALL_NONCE and API_BASE from environment
 //! variables and stores credentials in ~/.git-ai/internal/credentials.
+//! OAUTH_TOKEN_PATH optionally overrides the token endpoint path under API_BASE
+//! (default `/worker/oauth/token`) for self-hosted deployments. A successful login saves
+//! it (see [`CredentialStore::oauth_token_path`]) so token refreshes use it too.
 //!
-//! On failure, exits with code 1 silently so the install script can fall back
-//! to running `git-ai login`. Errors are recorded server-side for debugging.
+//! When the user is already logged in the nonce is left unused and the command succeeds,
+//! so reinstalling doesn't replace a working session.
+//!
+//! On failure, exits with code 1 so the install script can fall back to running
+//! `git-ai login`. Errors are recorded server-side for debugging; the message
+//! printed says whether running the same install command again may work (the
+//! server was unreachable or failed) or a fresh one is needed (the nonce was
+//! rejected, e.g. expired or already used).
+//!
+//! Install nonces are URL-safe tokens: 16 to 256 characters from `A-Z`, `a-z`,
+//! `0-9`, `-` and `_`. Anything else is rejected before contacting the server,
+//! with a message pointing at `INSTALL_PAGE_URL` when the install script sets it.
+//! `API_BASE` must be an `https` URL (plain `http` only for localhost), so the
+//! nonce is never sent in the clear or to a mistyped endpoint.
+//! A successful exchange saves that URL (see [`CredentialStore::install_page_url`]) so
+//! later errors can point there too.
 
 use crate::auth::CredentialStore;
 use crate::auth::client::OAuthClient;
+use crate::auth::types::{StoredCredentials, TokenExchangeError};
+use crate::utils::debug_log;
+use std::fmt;
+use url::{Host, Url};
+
+/// What an automatic login attempt did
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoginOutcome {
+    /// Credentials were exchanged and stored
+    LoggedIn,
+    /// Nothing was attempted
+    Skipped { reason: SkipReason },
+    /// The exchange couldn't be made or was refused; `message` says what to do next
+    Failed { message: String },
+}
+
+/// Why an automatic login wasn't attempted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkipReason {
+    /// The install script didn't provide a nonce
+    NoNonce,
+    /// There's already a usable session
+    AlreadyLoggedIn,
+}
+
+impl fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SkipReason::NoNonce => write!(f, "no install nonce provided"),
+            SkipReason::AlreadyLoggedIn => write!(f, "already logged in"),
+        }
+    }
+}
 
 /// Handle the exchange-nonce command (internal - called by install scripts)
 ///
-/// Exits with code 1 on failure (silently) so install script can run `git-ai login`.
-/// Exits with code 0 on success.
+/// Exits with code 1 on failure so install script can run `git-ai login`.
+/// Exits with code 0 on success or when there's nothing to do.
 pub fn handle_exchange_nonce(_args: &[String]) {
     // Read from environment variables (injected by install script)
     let nonce = std::env::var("INSTALL_NONCE")
         .ok()
+        .map(|s| s.trim().to_string())
         .filter(|s| !s.is_empty());
     let api_base = std::env::var("API_BASE").ok().filter(|s| !s.is_empty());
 
-    // If no nonce provided, silently exit success (not an error - just means no auto-login)
+    let outcome = exchange_nonce_result(
+        nonce.as_deref(),
+        api_base.as_deref(),
+        &CredentialStore::new(),
+        exchange_with_server,
+    );
+    match outcome {
+        Ok(LoginOutcome::LoggedIn) => eprintln!("\x1b[32m✓ Logged in automatically\x1b[0m"),
+        // No nonce just means no auto-login, so there's nothing to report
+        Ok(LoginOutcome::Skipped {
+            reason: SkipReason::NoNonce,
+        }) => {}
+        Ok(LoginOutcome::Skipped { reason }) => {
+            eprintln!("Skipping automatic login: {}", reason)
+        }
+        Ok(LoginOutcome::Failed { message }) => {
+            eprintln!("{}", message);
+            std::process::exit(1);
+        }
+        // A local failure, such as credentials that couldn't be saved; the exchange itself
+        // worked, so the nonce hints format_error adds don't apply
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Log in with the install `nonce` against `api_base`, storing the credentials `exchange`
+/// returns in `store`. `exchange` is called with the API base and nonce once both pass
+/// validation; [`exchange_with_server`] is the real one.
+///
+/// Refusals and unusable input come back as [`LoginOutcome::Failed`] with a message ready to
+/// print; `Err` is reserved for local failures, such as credentials that couldn't be saved.
+pub fn exchange_nonce_result<F>(
+    nonce: Option<&str>,
+    api_base: Option<&str>,
+    store: &CredentialStore,
+    exchange: F,
+) -> Result<LoginOutcome, String>
+where
+    F: FnOnce(&str, &str) -> Result<StoredCredentials, (String, FailureKind)>,
+{
+    let failed = |message: &str, kind| LoginOutcome::Failed {
+        message: format_error(message, kind),
+    };
+
     let Some(nonce) = nonce else {
-        return;
+        return Ok(LoginOutcome::Skipped {
+            reason: SkipReason::NoNonce,
+        });
     };
 
-    // If API_BASE missing, exit with failure so login runs
+    // Nothing to exchange for when there's already a usable session
+    if store.is_logged_in() {
+        return Ok(LoginOutcome::Skipped {
+            reason: SkipReason::AlreadyLoggedIn,
+        });
+    }
+
+    // A mangled nonce can't succeed, so explain instead of making a doomed request
+    if let Err(e) = validate_nonce(nonce) {
+        return Ok(failed(&e, FailureKind::Terminal));
+    }
+
     let Some(api_base) = api_base else {
-        std::process::exit(1);
+        return Ok(failed("API_BASE is not set", FailureKind::Terminal));
     };
 
-    // Perform the exchange - exit with failure code on error (silently)
-    // The error is already recorded server-side, so no need to print anything
-    if exchange_nonce(&nonce, &api_base).is_err() {
-        std::process::exit(1);
+    // Never send the nonce somewhere that isn't an https endpoint (or a local dev server)
+    if let Err(e) = validate_api_base(api_base) {
+        return Ok(failed(&e, FailureKind::Terminal));
     }
+
+    let credentials = match exchange(api_base, nonce) {
+        Ok(credentials) => credentials,
+        Err((message, kind)) => return Ok(failed(&message, kind)),
+    };
+    store.store(&credentials)?;
+
+    // Keep the install page around for errors raised after this process exits
+    if let Some(url) = install_page_url_from_env()
+        && let Err(e) = CredentialStore::store_install_page_url(&url)
+    {
+        debug_log(&format!("Failed to store install page URL: {}", e));
+    }
+    // Later refreshes and logins use the token endpoint this login used
+    if let Err(e) = CredentialStore::store_oauth_token_path(oauth_token_path_from_env().as_deref())
+    {
+        debug_log(&format!("Failed to store OAuth token path: {}", e));
+    }
+
+    Ok(LoginOutcome::LoggedIn)
 }
 
-fn exchange_nonce(nonce: &str, api_base: &str) -> Result<(), String> {
-    // Create OAuth client with custom base URL
-    let client = OAuthClient::with_base_url(api_base)?;
+/// Exchange `nonce` at the token endpoint under `api_base` (honouring `OAUTH_TOKEN_PATH`)
+fn exchange_with_server(
+    api_base: &str,
+    nonce: &str,
+) -> Result<StoredCredentials, (String, FailureKind)> {
+    let mut client =
+        OAuthClient::with_base_url(api_base).map_err(|e| (e, FailureKind::Terminal))?;
+    if let Some(token_path) = oauth_token_path_from_env() {
+        client = client.with_token_path(&token_path);
+    }
 
-    // Exchange the nonce for credentials
-    let credentials = client.exchange_install_nonce(nonce)?;
+    client
+        .exchange_install_nonce(nonce)
+        .map_err(|e| classify_exchange_error(&e))
+}
 
-    // Store credentials
-    let store = CredentialStore::new();
-    store.store(&credentials)?;
+const MIN_NONCE_LEN: usize = 16;
+const MAX_NONCE_LEN: usize = 256;
 
-    eprintln!("\x1b[32m✓ Logged in automatically\x1b[0m");
+/// Check that `nonce` has the shape of an install nonce (see the module docs)
+fn validate_nonce(nonce: &str) -> Result<(), String> {
+    if nonce.len() < MIN_NONCE_LEN {
+        return Err(format!(
+            "Install nonce is too short ({} characters, expected at least {}); it may have been truncated",
+            nonce.len(),
+            MIN_NONCE_LEN
+        ));
+    }
+    if nonce.len() > MAX_NONCE_LEN {
+        return Err(format!(
+            "Install nonce is too long ({} characters, expected at most {})",
+            nonce.len(),
+            MAX_NONCE_LEN
+        ));
+    }
+    if let Some(c) = nonce
+        .chars()
+        .find(|c| !(c.is_ascii_alphanumeric() || *c == '-' || *c == '_'))
+    {
+        return Err(format!(
+            "Install nonce contains an invalid character {:?}",
+            c
+        ));
+    }
     Ok(())
 }
+
+/// Check that `api_base` is a well-formed `https` URL. Plain `http` is only accepted for
+/// loopback hosts, so a local dev server works without exposing the nonce on the network.
+fn validate_api_base(api_base: &str) -> Result<(), String> {
+    let url = Url::parse(api_base)
+        .map_err(|e| format!("API_BASE {:?} is not a valid URL ({})", api_base, e))?;
+    let is_loopback = match url.host() {
+        Some(Host::Domain(domain)) => domain.eq_ignore_ascii_case("localhost"),
+        Some(Host::Ipv4(ip)) => ip.is_loopback(),
+        Some(Host::Ipv6(ip)) => ip.is_loopback(),
+        None => {
+            return Err(format!("API_BASE {:?} has no host", api_base));
+        }
+    };
+    match url.scheme() {
+        "https" => Ok(()),
+        "http" if is_loopback => Ok(()),
+        scheme => Err(format!(
+            "API_BASE {:?} must use https, not {}",
+            api_base, scheme
+        )),
+    }
+}
+
+fn oauth_token_path_from_env() -> Option<String> {
+    std::env::var("OAUTH_TOKEN_PATH")
+        .ok()
+        .map(|path| path.trim().to_string())
+        .filter(|path| !path.is_empty())
+}
+
+fn install_page_url_from_env() -> Option<String> {
+    std::env::var("INSTALL_PAGE_URL")
+        .ok()
+        .filter(|url| !url.is_empty())
+}
+
+/// Whether an exchange failure can be fixed by running the same install command again
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailureKind {
+    /// The server was unreachable or failed; the nonce may still be good
+    Retryable,
+    /// The nonce or setup was rejected; a new install command is needed
+    Terminal,
+}
+
+/// Error message and [`FailureKind`] for a failed nonce exchange
+fn classify_exchange_error(error: &TokenExchangeError) -> (String, FailureKind) {
+    let kind = if error.is_retryable() {
+        FailureKind::Retryable
+    } else {
+        FailureKind::Terminal
+    };
+    (format!("Nonce exchange failed: {}", error), kind)
+}
+
+/// Append what to do next to an exchange error message. Retryable failures point at the
+/// network; terminal ones at where to get a fresh install command, preferring
+/// `INSTALL_PAGE_URL` and falling back to the URL saved by an earlier automatic login.
+fn format_error(message: &str, kind: FailureKind) -> String {
+    if kind == FailureKind::Retryable {
+        return format!(
+            "{}. Check your network connection and run the install command again.",
+            message
+        );
+    }
+    match install_page_url_from_env().or_else(CredentialStore::install_page_url) {
+        Some(url) => format!(
+            "{}. Copy the install command again from {} or run `git-ai login`.",
+            message, url
+        ),
+        None => format!("{}. Run `git-ai login` to sign in.", message),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::credential_backend::MockBackend;
+    use crate::auth::types::make_credentials;
+    use serial_test::serial;
+
+    const NONCE: &str = "aB3_x-9Zq7Lm2Pk0Wv8Rt";
+
+    #[test]
+    #[serial]
+    fn test_format_error_falls_back_to_stored_install_page_url() {
+        CredentialStore::store_install_page_url("https://example.com/install").unwrap();
+        let prev_install_page_url = std::env::var_os("INSTALL_PAGE_URL");
+        // SAFETY: tests are serialized via #[serial], so mutating process env is safe.
+        unsafe {
+            std::env::remove_var("INSTALL_PAGE_URL");
+        }
+
+        let message = format_error("Install nonce is too short", FailureKind::Terminal);
+        let _ = std::fs::remove_file(CredentialStore::install_page_url_path());
+
+        // SAFETY: tests are serialized via #[serial], so restoring process env is safe.
+        if let Some(value) = prev_install_page_url {
+            unsafe {
+                std::env::set_var("INSTALL_PAGE_URL", value);
+            }
+        }
+
+        assert_eq!(
+            message,
+            "Install nonce is too short. Copy the install command again from https://example.com/install or run `git-ai login`."
+        );
+    }
+
+    #[test]
+    fn test_exchange_nonce_result_skips_without_nonce() {
+        let store = CredentialStore::with_backend(Box::new(MockBackend::new()));
+
+        let outcome = exchange_nonce_result(None, Some("https://usegitai.com"), &store, |_, _| {
+            panic!("exchange should not be called")
+        });
+
+        assert_eq!(
+            outcome,
+            Ok(LoginOutcome::Skipped {
+                reason: SkipReason::NoNonce
+            })
+        );
+        assert!(!store.has_credentials());
+    }
+
+    #[test]
+    fn test_exchange_nonce_result_skips_when_logged_in() {
+        let store = CredentialStore::with_backend(Box::new(MockBackend::new()));
+        let now = chrono::Utc::now().timestamp();
+        store
+            .store(&make_credentials(now + 3600, now + 86400))
+            .unwrap();
+
+        let outcome =
+            exchange_nonce_result(Some(NONCE), Some("https://usegitai.com"), &store, |_, _| {
+                panic!("exchange should not be called")
+            });
+
+        assert_eq!(
+            outcome,
+            Ok(LoginOutcome::Skipped {
+                reason: SkipReason::AlreadyLoggedIn
+            })
+        );
+    }
+
+    #[test]
+    fn test_exchange_nonce_result_stores_exchanged_credentials() {
+        let store = CredentialStore::with_backend(Box::new(MockBackend::new()));
+        let now = chrono::Utc::now().timestamp();
+
+        let outcome = exchange_nonce_result(
+            Some(NONCE),
+            Some("https://usegitai.com"),
+            &store,
+            |api_base, nonce| {
+                assert_eq!(api_base, "https://usegitai.com");
+                assert_eq!(nonce, NONCE);
+                Ok(make_credentials(now + 3600, now + 86400))
+            },
+        );
+
+        assert_eq!(outcome, Ok(LoginOutcome::LoggedIn));
+        assert_eq!(
+            store.load().unwrap().unwrap().access_token,
+            "test_access_token"
+        );
+    }
+
+    #[test]
+    fn test_exchange_nonce_result_reports_failed_exchange() {
+        let store = CredentialStore::with_backend(Box::new(MockBackend::new()));
+
+        let outcome =
+            exchange_nonce_result(Some(NONCE), Some("https://usegitai.com"), &store, |_, _| {
+                Err(classify_exchange_error(&TokenExchangeError::Connection(
+                    "connection refused".to_string(),
+                )))
+            });
+
+        let Ok(LoginOutcome::Failed { message }) = outcome else {
+            panic!("expected a failed outcome, got {:?}", outcome);
+        };
+        assert!(
+            message.contains("Check your network connection"),
+            "{}",
+            message
+        );
+        assert!(!store.has_credentials());
+    }
+
+    #[test]
+    fn test_connection_error_suggests_retrying() {
+        let (message, kind) = classify_exchange_error(&TokenExchangeError::Connection(
+            "operation timed out".to_string(),
+        ));
+
+        assert_eq!(kind, FailureKind::Retryable);
+        assert_eq!(
+            format_error(&message, kind),
+            "Nonce exchange failed: Failed to connect to server: operation timed out. Check your network connection and run the install command again."
+        );
+    }
+
+    #[test]
+    fn test_server_error_suggests_retrying() {
+        let (_, kind) = classify_exchange_error(&TokenExchangeError::Rejected {
+            status: 503,
+            message: "unknown_error".to_string(),
+        });
+
+        assert_eq!(kind, FailureKind::Retryable);
+    }
+
+    #[test]
+    fn test_rate_limited_exchange_suggests_retrying() {
+        let (_, kind) = classify_exchange_error(&TokenExchangeError::Rejected {
+            status: 429,
+            message: "rate_limited".to_string(),
+        });
+
+        assert_eq!(kind, FailureKind::Retryable);
+    }
+
+    #[test]
+    fn test_rejected_nonce_suggests_new_install_command() {
+        let (message, kind) = classify_exchange_error(&TokenExchangeError::Rejected {
+            status: 400,
+            message: "Install nonce expired".to_string(),
+        });
+        let formatted = format_error(&message, kind);
+
+        assert_eq!(kind, FailureKind::Terminal);
+        assert!(formatted.starts_with("Nonce exchange failed: Install nonce expired. "));
+        assert!(formatted.contains("`git-ai login`"), "{}", formatted);
+        assert!(!formatted.contains("network"), "{}", formatted);
+    }
+
+    #[test]
+    fn test_validate_api_base_accepts_https_and_local_http() {
+        for api_base in [
+            "https://usegitai.com",
+            "https://usegitai.com/",
+            "https://api.example.com:8443/v1",
+            "http://localhost:8787",
+            "http://127.0.0.1:8787",
+            "http://[::1]:8787",
+        ] {
+            assert!(validate_api_base(api_base).is_ok(), "{}", api_base);
+        }
+    }
+
+    #[test]
+    fn test_validate_api_base_rejects_insecure_schemes() {
+        for api_base in [
+            "http://usegitai.com",
+            "http://localhost.attacker.example",
+            "ftp://usegitai.com",
+            "file:///etc/passwd",
+        ] {
+            let err = validate_api_base(api_base).unwrap_err();
+            assert!(
+                err.contains("must use https") || err.contains("has no host"),
+                "{}: {}",
+                api_base,
+                err
+            );
+        }
+    }
+
+    #[test]
+    fn test_validate_api_base_rejects_malformed_urls() {
+        for api_base in [
+            "usegitai.com",
+            "https://",
+            "https//usegitai.com",
+            "https://exa mple.com",
+        ] {
+            let err = validate_api_base(api_base).unwrap_err();
+            assert!(err.contains("not a valid URL"), "{}: {}", api_base, err);
+        }
+    }
+
+    #[test]
+    fn test_validate_nonce_accepts_url_safe_token() {
+        assert!(validate_nonce("aB3_x-9Zq7Lm2Pk0Wv8Rt").is_ok());
+        assert!(validate_nonce(&"a".repeat(MAX_NONCE_LEN)).is_ok());
+    }
+
+    #[test]
+    fn test_validate_nonce_rejects_truncated() {
+        let err = validate_nonce("aB3_x-9Z").unwrap_err();
+        assert!(err.contains("too short"));
+    }
+
+    #[test]
+    fn test_validate_nonce_rejects_overlong() {
+        let err = validate_nonce(&"a".repeat(MAX_NONCE_LEN + 1)).unwrap_err();
+        assert!(err.contains("too long"));
+    }
+
+    #[test]
+    fn test_validate_nonce_rejects_invalid_characters() {
+        for nonce in [
+            "aB3_x-9Zq7Lm 2Pk0Wv8Rt",
+            "\"aB3_x-9Zq7Lm2Pk0Wv8Rt\"",
+            "aB3_x-9Zq7Lm2Pk0Wv8Rt…",
+            "aB3+x/9Zq7Lm2Pk0Wv8Rt=",
+        ] {
+            let err = validate_nonce(nonce).unwrap_err();
+            assert!(err.contains("invalid character"), "{}: {}", nonce, err);
+        }
+    }
+}
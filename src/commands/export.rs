@@ -0,0 +1,281 @@
+use crate::authorship::authorship_log::LineRange;
+use crate::error::GitAiError;
+use crate::git::authorship_traversal::load_all_note_logs;
+use crate::git::find_repository;
+use crate::git::repository::Repository;
+use serde::Serialize;
+use std::borrow::Cow;
+use std::io::Write;
+
+const USAGE: &str = "Usage: git-ai export [--format json|csv] [--output <path>]";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ExportFormat {
+    Json,
+    Csv,
+}
+
+/// One attributed line range: a single range of one attestation entry in one commit's note
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ExportRecord {
+    pub commit_sha: String,
+    pub file_path: String,
+    pub start_line: u32,
+    pub end_line: u32,
+    pub prompt_id: String,
+    /// The prompt's human author, when the note records it
+    pub author: Option<String>,
+}
+
+pub fn handle_export(args: &[String]) {
+    let mut format = ExportFormat::Json;
+    let mut output_path = None;
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--format" => {
+                format = match args.get(i + 1).map(String::as_str) {
+                    Some("json") => ExportFormat::Json,
+                    Some("csv") => ExportFormat::Csv,
+                    other => {
+                        eprintln!(
+                            "--format must be json or csv, got {}",
+                            other.unwrap_or("nothing")
+                        );
+                        std::process::exit(1);
+                    }
+                };
+                i += 2;
+            }
+            "--output" | "-o" => {
+                let Some(path) = args.get(i + 1) else {
+                    eprintln!("--output requires a path");
+                    std::process::exit(1);
+                };
+                output_path = Some(path.clone());
+                i += 2;
+            }
+            "--help" | "-h" => {
+                eprintln!("git-ai export - Export AI attribution records as JSON or CSV");
+                eprintln!();
+                eprintln!("{}", USAGE);
+                std::process::exit(0);
+            }
+            other => {
+                eprintln!("Unknown export argument: {}", other);
+                eprintln!("{}", USAGE);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    let repo = match find_repository(&[]) {
+        Ok(repo) => repo,
+        Err(e) => {
+            eprintln!("Failed to find repository: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let result = collect_export_records(&repo).and_then(|records| match &output_path {
+        Some(path) => {
+            let mut file = std::io::BufWriter::new(std::fs::File::create(path)?);
+            write_records(&records, format, &mut file)?;
+            file.flush()?;
+            Ok(())
+        }
+        None => write_records(&records, format, &mut std::io::stdout().lock()),
+    });
+    if let Err(e) = result {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+}
+
+/// Flatten every authorship note into one record per attested line range, ordered by commit
+/// SHA and then by position in the note
+pub fn collect_export_records(repo: &Repository) -> Result<Vec<ExportRecord>, GitAiError> {
+    let mut records = Vec::new();
+    for (commit_sha, log) in load_all_note_logs(repo)? {
+        for attestation in &log.attestations {
+            for entry in &attestation.entries {
+                let author = log
+                    .metadata
+                    .prompts
+                    .get(&entry.hash)
+                    .and_then(|prompt| prompt.human_author.clone());
+                for range in &entry.line_ranges {
+                    let (start_line, end_line) = match *range {
+                        LineRange::Single(line) => (line, line),
+                        LineRange::Range(start, end) => (start, end),
+                    };
+                    records.push(ExportRecord {
+                        commit_sha: commit_sha.clone(),
+                        file_path: attestation.file_path.clone(),
+                        start_line,
+                        end_line,
+                        prompt_id: entry.hash.clone(),
+                        author: author.clone(),
+                    });
+                }
+            }
+        }
+    }
+    Ok(records)
+}
+
+fn write_records(
+    records: &[ExportRecord],
+    format: ExportFormat,
+    out: &mut dyn Write,
+) -> Result<(), GitAiError> {
+    match format {
+        ExportFormat::Json => {
+            serde_json::to_writer_pretty(&mut *out, records)?;
+            writeln!(out)?;
+        }
+        ExportFormat::Csv => {
+            writeln!(
+                out,
+                "commit_sha,file_path,start_line,end_line,prompt_id,author"
+            )?;
+            for record in records {
+                writeln!(
+                    out,
+                    "{},{},{},{},{},{}",
+                    csv_field(&record.commit_sha),
+                    csv_field(&record.file_path),
+                    record.start_line,
+                    record.end_line,
+                    csv_field(&record.prompt_id),
+                    csv_field(record.author.as_deref().unwrap_or("")),
+                )?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Quote a CSV field per RFC 4180 when it contains a delimiter, quote or line break
+fn csv_field(value: &str) -> Cow<'_, str> {
+    if value.contains([',', '"', '\n', '\r']) {
+        Cow::Owned(format!("\"{}\"", value.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::authorship::authorship_log_serialization::{
+        AttestationEntry, AuthorshipLog, FileAttestation,
+    };
+    use crate::git::refs::notes_add;
+    use crate::git::test_utils::{TmpRepo, test_prompt_record};
+
+    fn render(records: &[ExportRecord], format: ExportFormat) -> String {
+        let mut out = Vec::new();
+        write_records(records, format, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    /// Note for one commit: two ranges in a plain path attributed to Alice, and one line in a
+    /// path needing CSV quoting from a prompt with no recorded author
+    fn synthetic_note() -> String {
+        let mut log = AuthorshipLog::new();
+        let mut lib = FileAttestation::new("src/lib.rs".to_string());
+        lib.add_entry(AttestationEntry::new(
+            "aaaaaaa".to_string(),
+            vec![LineRange::Range(1, 3), LineRange::Single(7)],
+        ));
+        let mut quoted = FileAttestation::new("docs/a, \"b\".md".to_string());
+        quoted.add_entry(AttestationEntry::new(
+            "bbbbbbb".to_string(),
+            vec![LineRange::Single(2)],
+        ));
+        log.attestations = vec![lib, quoted];
+        log.metadata.prompts.insert(
+            "aaaaaaa".to_string(),
+            test_prompt_record("aaaaaaa", "Alice <alice@example.com>"),
+        );
+        log.serialize_to_string().unwrap()
+    }
+
+    const CSV_FIXTURE: &str = "\
+commit_sha,file_path,start_line,end_line,prompt_id,author
+{sha},src/lib.rs,1,3,aaaaaaa,Alice <alice@example.com>
+{sha},src/lib.rs,7,7,aaaaaaa,Alice <alice@example.com>
+{sha},\"docs/a, \"\"b\"\".md\",2,2,bbbbbbb,
+";
+
+    const JSON_FIXTURE: &str = r#"[
+  {
+    "commit_sha": "{sha}",
+    "file_path": "src/lib.rs",
+    "start_line": 1,
+    "end_line": 3,
+    "prompt_id": "aaaaaaa",
+    "author": "Alice <alice@example.com>"
+  },
+  {
+    "commit_sha": "{sha}",
+    "file_path": "src/lib.rs",
+    "start_line": 7,
+    "end_line": 7,
+    "prompt_id": "aaaaaaa",
+    "author": "Alice <alice@example.com>"
+  },
+  {
+    "commit_sha": "{sha}",
+    "file_path": "docs/a, \"b\".md",
+    "start_line": 2,
+    "end_line": 2,
+    "prompt_id": "bbbbbbb",
+    "author": null
+  }
+]
+"#;
+
+    #[test]
+    fn test_export_matches_fixtures_for_synthetic_repo() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        tmp_repo.write_file("a.txt", "a\n", true).unwrap();
+        tmp_repo.commit_with_message("Commit A").unwrap();
+        let sha = tmp_repo.get_head_commit_sha().unwrap();
+        notes_add(tmp_repo.gitai_repo(), &sha, &synthetic_note()).unwrap();
+
+        let records = collect_export_records(tmp_repo.gitai_repo()).unwrap();
+
+        assert_eq!(
+            render(&records, ExportFormat::Csv),
+            CSV_FIXTURE.replace("{sha}", &sha)
+        );
+        assert_eq!(
+            render(&records, ExportFormat::Json),
+            JSON_FIXTURE.replace("{sha}", &sha)
+        );
+    }
+
+    #[test]
+    fn test_csv_field_escaping() {
+        assert_eq!(csv_field("plain/path.rs"), "plain/path.rs");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("line\nbreak"), "\"line\nbreak\"");
+    }
+
+    #[test]
+    fn test_export_without_notes_is_empty() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        let records = collect_export_records(tmp_repo.gitai_repo()).unwrap();
+
+        assert!(records.is_empty());
+        assert_eq!(render(&records, ExportFormat::Json), "[]\n");
+        assert_eq!(
+            render(&records, ExportFormat::Csv),
+            "commit_sha,file_path,start_line,end_line,prompt_id,author\n"
+        );
+    }
+}
@@ -42,17 +42,19 @@ pub fn handle_git_ai(args: &[String]) {
         _ => {}
     }
 
+    if requires_git(args[0].as_str())
+        && let Err(e) = crate::git::repository::ensure_git_available()
+    {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+
     match args[0].as_str() {
         "help" | "--help" | "-h" => {
             print_help();
         }
         "version" | "--version" | "-v" => {
-            if cfg!(debug_assertions) {
-                println!("{} (debug)", env!("CARGO_PKG_VERSION"));
-            } else {
-                println!(env!("CARGO_PKG_VERSION"));
-            }
-            std::process::exit(0);
+            commands::upgrade::handle_version(&args[1..]);
         }
         "config" => {
             commands::config::handle_config(&args[1..]);
@@ -63,6 +65,9 @@ pub fn handle_git_ai(args: &[String]) {
         "debug" => {
             commands::debug::handle_debug(&args[1..]);
         }
+        "doctor" => {
+            commands::doctor::handle_doctor(&args[1..]);
+        }
         "stats" => {
             if is_interactive_terminal() {
                 log_message("stats", "info", None)
@@ -123,6 +128,15 @@ pub fn handle_git_ai(args: &[String]) {
         "squash-authorship" => {
             commands::squash_authorship::handle_squash_authorship(&args[1..]);
         }
+        "export" => {
+            commands::export::handle_export(&args[1..]);
+        }
+        "relink" => {
+            commands::relink::handle_relink(&args[1..]);
+        }
+        "prune" => {
+            commands::prune::handle_prune(&args[1..]);
+        }
         "ci" => {
             commands::ci_handlers::handle_ci(&args[1..]);
         }
@@ -194,6 +208,31 @@ pub fn handle_git_ai(args: &[String]) {
     }
 }
 
+/// Commands that read or write repository data and so can't do anything useful without git.
+/// The rest (help, config, auth, upgrades, diagnostics) must keep working when git is broken.
+fn requires_git(command: &str) -> bool {
+    matches!(
+        command,
+        "checkpoint"
+            | "stats"
+            | "status"
+            | "show"
+            | "blame"
+            | "diff"
+            | "squash-authorship"
+            | "export"
+            | "relink"
+            | "prune"
+            | "ci"
+            | "effective-ignore-patterns"
+            | "blame-analysis"
+            | "fetch-authorship-notes"
+            | "fetch_authorship_notes"
+            | "push-authorship-notes"
+            | "push_authorship_notes"
+    )
+}
+
 fn print_help() {
     eprintln!("git-ai - git proxy with AI authorship tracking");
     eprintln!();
@@ -224,6 +263,7 @@ fn print_help() {
     eprintln!("  stats [commit]     Show AI authorship statistics for a commit");
     eprintln!("    --json                 Output in JSON format");
     eprintln!("  status             Show uncommitted AI authorship status (debug)");
+    eprintln!("    --files [<glob>]       List changed files with recorded AI attributions");
     eprintln!("    --json                 Output in JSON format");
     eprintln!("  show <rev|range>   Display authorship logs for a revision or range");
     eprintln!("  show-prompt <id>   Display a prompt record by its ID");
@@ -246,6 +286,8 @@ fn print_help() {
     eprintln!("    --add <key> <value>   Add to array or upsert into object");
     eprintln!("    unset <key>           Remove config value (reverts to default)");
     eprintln!("  debug              Print support/debug diagnostics");
+    eprintln!("  doctor             Check notes, credentials, remotes and feature flags");
+    eprintln!("    --json                Output in JSON format");
     eprintln!("  install-hooks      Install git hooks for AI authorship tracking");
     eprintln!("  uninstall-hooks    Remove git-ai hooks from all detected tools");
     eprintln!("  git-hooks ensure   Ensure repo-local git-ai hooks are installed/healed");
@@ -257,7 +299,17 @@ fn print_help() {
         "    <base_branch> <new_sha> <old_sha>  Required: base branch, new commit SHA, old commit SHA"
     );
     eprintln!("    --dry-run             Show what would be done without making changes");
+    eprintln!("  relink <old> <new> Move a stranded working log from one commit to another");
+    eprintln!("    --auto                Offer to relink every working log not on HEAD");
+    eprintln!("    --yes                 With --auto, relink without asking");
+    eprintln!("  prune              Drop notes for commits no ref reaches anymore");
+    eprintln!("    --dry-run             List the notes that would be pruned");
+    eprintln!("  export             Export AI attribution records for every note");
+    eprintln!("    --format <json|csv>   Output format (default: json)");
+    eprintln!("    --output <path>       Write to a file instead of stdout");
     eprintln!("  git-path           Print the path to the underlying git executable");
+    eprintln!("  version            Print the git-ai version");
+    eprintln!("    --check               Also check whether a newer release is available");
     eprintln!("  upgrade            Check for updates and install if available");
     eprintln!("    --force               Reinstall latest version even if already up to date");
     eprintln!("  prompts            Create local SQLite database for prompt analysis");
@@ -294,6 +346,7 @@ fn print_help() {
     eprintln!("    --json                Output context as structured JSON");
     eprintln!("  login              Authenticate with Git AI");
     eprintln!("  logout             Clear stored credentials");
+    eprintln!("    --all                 Also remove cached session files");
     eprintln!("  whoami             Show auth state and login identity");
     eprintln!("  version, -v, --version     Print the git-ai version");
     eprintln!("  help, -h, --help           Show this help message");
@@ -17,7 +17,9 @@ use crate::commands::hooks::stash_hooks;
 use crate::commands::hooks::switch_hooks;
 use crate::commands::hooks::update_ref_hooks;
 use crate::config;
-use crate::git::cli_parser::{ParsedGitInvocation, parse_git_cli_args};
+use crate::git::cli_parser::{
+    ParsedGitInvocation, is_dry_run, is_git_ai_dry_run_env, parse_git_cli_args,
+};
 use crate::git::find_repository;
 use crate::git::repository::{Repository, disable_internal_git_hooks};
 use crate::observability;
@@ -90,16 +92,21 @@ fn uninstall_forwarding_handlers() {
     }
 }
 
+#[derive(Default)]
 pub struct CommandHooksContext {
     pub pre_commit_hook_result: Option<bool>,
     pub rebase_original_head: Option<String>,
     pub rebase_onto: Option<String>,
-    pub fetch_authorship_handle: Option<std::thread::JoinHandle<()>>,
+    pub fetch_authorship_handle: Option<fetch_hooks::BackgroundAuthorshipFetch>,
     pub stash_sha: Option<String>,
     pub push_authorship_handle: Option<std::thread::JoinHandle<()>>,
     /// VirtualAttributions captured before a pull --rebase --autostash operation.
     /// Used to preserve uncommitted AI attributions that git's internal stash would lose.
     pub stashed_va: Option<VirtualAttributions>,
+    /// Whether the command is a `--dry-run` or `GIT_AI_DRY_RUN=1` is set, decided once at
+    /// dispatch. Hooks consult this rather than re-parsing args or the environment, and skip
+    /// every note fetch, push and write when it's set.
+    pub dry_run: bool,
 }
 
 pub fn handle_git(args: &[String]) {
@@ -141,22 +148,17 @@ pub fn handle_git(args: &[String]) {
 
     // run with hooks
     let exit_status = if !parsed_args.is_help && has_repo && !skip_hooks {
-        let mut command_hooks_context = CommandHooksContext {
-            pre_commit_hook_result: None,
-            rebase_original_head: None,
-            rebase_onto: None,
-            fetch_authorship_handle: None,
-            stash_sha: None,
-            push_authorship_handle: None,
-            stashed_va: None,
-        };
-
         let repository = repository_option.as_mut().unwrap();
 
         if let Some(resolved) = resolve_alias_invocation(&parsed_args, repository) {
             parsed_args = resolved;
         }
 
+        let mut command_hooks_context = CommandHooksContext {
+            dry_run: is_dry_run(&parsed_args.command_args) || is_git_ai_dry_run_env(),
+            ..Default::default()
+        };
+
         let pre_command_start = Instant::now();
         run_pre_command_hooks(&mut command_hooks_context, &mut parsed_args, repository);
         let pre_command_duration = pre_command_start.elapsed();
@@ -340,7 +342,8 @@ fn run_pre_command_hooks(
         match parsed_args.command.as_deref() {
             Some("commit") => {
                 command_hooks_context.pre_commit_hook_result = Some(
-                    commit_hooks::commit_pre_command_hook(parsed_args, repository),
+                    !command_hooks_context.dry_run
+                        && commit_hooks::commit_pre_command_hook(parsed_args, repository),
                 );
             }
             Some("rebase") => {
@@ -357,8 +360,11 @@ fn run_pre_command_hooks(
                 );
             }
             Some("push") => {
-                command_hooks_context.push_authorship_handle =
-                    push_hooks::push_pre_command_hook(parsed_args, repository);
+                command_hooks_context.push_authorship_handle = push_hooks::push_pre_command_hook(
+                    parsed_args,
+                    repository,
+                    command_hooks_context,
+                );
             }
             Some("pull") => {
                 fetch_hooks::pull_pre_command_hook(parsed_args, repository, command_hooks_context);
@@ -437,7 +443,9 @@ fn run_post_command_hooks(
                 command_hooks_context,
             ),
             Some("reset") => reset_hooks::post_reset_hook(parsed_args, repository, exit_status),
-            Some("merge") => merge_hooks::post_merge_hook(parsed_args, exit_status, repository),
+            Some("merge") if !command_hooks_context.dry_run => {
+                merge_hooks::post_merge_hook(parsed_args, exit_status, repository)
+            }
             Some("rebase") => rebase_hooks::handle_rebase_post_command(
                 command_hooks_context,
                 parsed_args,
@@ -746,8 +754,11 @@ fn in_shell_completion_context() -> bool {
 #[cfg(test)]
 mod tests {
     use super::parse_alias_tokens;
+    use super::{CommandHooksContext, run_post_command_hooks, run_pre_command_hooks};
     use super::{parse_git_cli_args, resolve_child_git_hooks_path_override};
     use crate::git::find_repository_in_path;
+    use crate::git::refs::{notes_add, ref_exists, tracking_ref_for_remote};
+    use crate::git::test_utils::{TmpRepo, success_exit_status};
     use std::process::Command;
     use tempfile::tempdir;
 
@@ -921,4 +932,77 @@ mod tests {
             .expect("failed to run success test");
         assert!(!super::exit_status_was_interrupted(&status));
     }
+
+    #[test]
+    fn dry_run_context_skips_note_sync_and_writes_across_hook_lifecycle() {
+        let repo = TmpRepo::new().unwrap();
+        repo.write_file("a.txt", "a\n", true).unwrap();
+        repo.commit_with_message("Commit A").unwrap();
+        let commit_a = repo.get_head_commit_sha().unwrap();
+
+        let remote_dir = tempdir().unwrap();
+        let output = Command::new(crate::config::Config::get().git_cmd())
+            .args(["clone", "-q"])
+            .arg(repo.path())
+            .arg(remote_dir.path())
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{:?}", output);
+        let remote_path = remote_dir.path().to_str().unwrap();
+        repo.git_command(&["remote", "add", "origin", remote_path])
+            .unwrap();
+        let remote = find_repository_in_path(remote_path).unwrap();
+        notes_add(repo.gitai_repo(), &commit_a, "local note").unwrap();
+        notes_add(&remote, &commit_a, "remote note").unwrap();
+        repo.write_file("b.txt", "b\n", true).unwrap();
+
+        let notes_tip = |repo: &crate::git::repository::Repository| {
+            let mut args = repo.global_args_for_exec();
+            args.extend(["rev-parse".to_string(), repo.notes_ref()]);
+            crate::git::repository::exec_git(&args)
+                .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+                .unwrap()
+        };
+        let local_tip = notes_tip(repo.gitai_repo());
+        let remote_tip = notes_tip(&remote);
+        let rewrite_events = repo.gitai_repo().storage.read_rewrite_events().unwrap();
+
+        let success = success_exit_status();
+        let mut repository = repo.gitai_repo().clone();
+        // The flag isn't in the args; the context alone must stop the hooks
+        for args in [
+            vec!["pull", "origin"],
+            vec!["push", "origin", "HEAD"],
+            vec!["commit", "-m", "next"],
+            vec!["rebase", "main"],
+            vec!["cherry-pick", "HEAD"],
+        ] {
+            let args: Vec<String> = args.into_iter().map(String::from).collect();
+            let mut parsed_args = parse_git_cli_args(&args);
+            let mut context = CommandHooksContext {
+                dry_run: true,
+                ..Default::default()
+            };
+
+            run_pre_command_hooks(&mut context, &mut parsed_args, &mut repository);
+            assert!(context.fetch_authorship_handle.is_none(), "{:?}", args);
+            assert!(context.push_authorship_handle.is_none(), "{:?}", args);
+            run_post_command_hooks(&mut context, &parsed_args, success, &mut repository);
+        }
+
+        assert_eq!(notes_tip(repo.gitai_repo()), local_tip);
+        assert_eq!(notes_tip(&remote), remote_tip);
+        assert!(!ref_exists(
+            repo.gitai_repo(),
+            &tracking_ref_for_remote("origin")
+        ));
+        assert_eq!(
+            repo.gitai_repo()
+                .storage
+                .read_rewrite_events()
+                .unwrap()
+                .len(),
+            rewrite_events.len()
+        );
+    }
 }
@@ -1,13 +1,14 @@
 use crate::commands::git_handlers::CommandHooksContext;
 use crate::commands::hooks::checkout_hooks;
 use crate::commands::hooks::commit_hooks;
+use crate::commands::hooks::fetch_hooks;
 use crate::commands::hooks::merge_hooks;
 use crate::commands::hooks::push_hooks;
 use crate::commands::hooks::rebase_hooks;
 use crate::commands::hooks::stash_hooks;
 use crate::config;
 use crate::error::GitAiError;
-use crate::git::cli_parser::ParsedGitInvocation;
+use crate::git::cli_parser::{ParsedGitInvocation, is_git_ai_dry_run_env};
 use crate::git::repository::{Repository, disable_internal_git_hooks};
 use crate::git::sync_authorship::fetch_authorship_notes;
 use crate::utils::{debug_log, debug_performance_log_structured};
@@ -1479,18 +1480,6 @@ fn parsed_invocation(command: &str, command_args: Vec<String>) -> ParsedGitInvoc
     }
 }
 
-fn default_context() -> CommandHooksContext {
-    CommandHooksContext {
-        pre_commit_hook_result: None,
-        rebase_original_head: None,
-        rebase_onto: None,
-        fetch_authorship_handle: None,
-        stash_sha: None,
-        push_authorship_handle: None,
-        stashed_va: None,
-    }
-}
-
 fn is_pull_reflog_action() -> bool {
     std::env::var("GIT_REFLOG_ACTION")
         .map(|action| action.starts_with("pull"))
@@ -1584,33 +1573,6 @@ fn fetch_notes_from_pull_remote(repo: &Repository) {
     let _ = fetch_authorship_notes(repo, &remote);
 }
 
-fn was_fast_forward_pull(repository: &Repository, expected_new_head: &str) -> bool {
-    let mut args = repository.global_args_for_exec();
-    args.extend(
-        ["reflog", "-1", "--format=%H %gs"]
-            .iter()
-            .map(|s| s.to_string()),
-    );
-
-    match crate::git::repository::exec_git(&args) {
-        Ok(output) => {
-            let output_str = String::from_utf8_lossy(&output.stdout);
-            let output_str = output_str.trim();
-
-            let Some((sha, subject)) = output_str.split_once(' ') else {
-                return false;
-            };
-
-            if sha != expected_new_head {
-                return false;
-            }
-
-            subject.starts_with("pull") && subject.ends_with(": Fast-forward")
-        }
-        Err(_) => false,
-    }
-}
-
 fn parse_reference_transaction_stdin(stdin: &[u8]) -> Vec<(String, String, String)> {
     parse_whitespace_fields(stdin, 3)
         .into_iter()
@@ -1619,15 +1581,12 @@ fn parse_reference_transaction_stdin(stdin: &[u8]) -> Vec<(String, String, Strin
 }
 
 fn latest_head_reflog_subject(repository: &Repository) -> Option<String> {
-    let mut args = repository.global_args_for_exec();
-    args.extend(
-        ["reflog", "-1", "--format=%gs"]
-            .iter()
-            .map(|s| s.to_string()),
-    );
-    let output = crate::git::repository::exec_git(&args).ok()?;
-    let subject = String::from_utf8(output.stdout).ok()?;
-    Some(subject.trim().to_string())
+    let entry = repository
+        .reflog_entries("HEAD", 1)
+        .ok()?
+        .into_iter()
+        .next()?;
+    Some(entry.subject.trim().to_string())
 }
 
 fn maybe_handle_reset_reference_transaction(
@@ -1674,9 +1633,10 @@ fn maybe_handle_reset_reference_transaction(
     }
 
     let is_backward_reset = repo
-        .merge_base(new_head.clone(), old_head.clone())
-        .map(|merge_base| merge_base == new_head)
-        .unwrap_or(false);
+        .merge_base(&new_head, &old_head)
+        .ok()
+        .flatten()
+        .is_some_and(|merge_base| merge_base == new_head);
     if !is_backward_reset {
         return;
     }
@@ -1801,7 +1761,7 @@ fn maybe_handle_stash_reference_transaction(
     if !new_is_zero && (old_is_zero || after_count > before_count) {
         // Stash push/save created a new stash entry. Persist authorship in stash notes.
         let parsed = parsed_invocation("stash", vec!["push".to_string()]);
-        let context = default_context();
+        let context = CommandHooksContext::default();
         stash_hooks::post_stash_hook(&context, &parsed, repo, success_exit_status());
         return;
     }
@@ -1814,8 +1774,10 @@ fn maybe_handle_stash_reference_transaction(
         }
 
         let parsed = parsed_invocation("stash", vec!["pop".to_string()]);
-        let mut context = default_context();
-        context.stash_sha = Some(old);
+        let context = CommandHooksContext {
+            stash_sha: Some(old),
+            ..Default::default()
+        };
         stash_hooks::post_stash_hook(&context, &parsed, repo, success_exit_status());
         return;
     }
@@ -1829,13 +1791,15 @@ fn maybe_handle_stash_reference_transaction(
         if action.contains("pop") || action.contains("apply") || action.contains("autostash") {
             if has_working_tree_changes(repo) {
                 let parsed = parsed_invocation("stash", vec!["pop".to_string()]);
-                let mut context = default_context();
-                context.stash_sha = Some(old);
+                let context = CommandHooksContext {
+                    stash_sha: Some(old),
+                    ..Default::default()
+                };
                 stash_hooks::post_stash_hook(&context, &parsed, repo, success_exit_status());
             }
         } else {
             let parsed = parsed_invocation("stash", vec!["push".to_string()]);
-            let context = default_context();
+            let context = CommandHooksContext::default();
             stash_hooks::post_stash_hook(&context, &parsed, repo, success_exit_status());
         }
     }
@@ -1873,10 +1837,6 @@ fn maybe_handle_pull_post_merge(repo: &mut Repository) {
         return;
     };
 
-    if !was_fast_forward_pull(repo, &new_head) {
-        return;
-    }
-
     let Ok(old_head_obj) = repo.revparse_single("HEAD@{1}") else {
         return;
     };
@@ -1885,7 +1845,17 @@ fn maybe_handle_pull_post_merge(repo: &mut Repository) {
         return;
     }
 
-    let _ = repo.storage.rename_working_log(&old_head, &new_head);
+    if !fetch_hooks::was_fast_forward_pull(repo, &old_head, &new_head) {
+        return;
+    }
+
+    fetch_hooks::rename_working_log_after_pull(
+        repo,
+        &old_head,
+        &new_head,
+        "fast-forward pull",
+        is_git_ai_dry_run_env(),
+    );
 }
 
 fn maybe_handle_pull_post_rewrite(repo: &mut Repository) {
@@ -1916,7 +1886,13 @@ fn maybe_handle_pull_post_rewrite(repo: &mut Repository) {
 
     // Preserve uncommitted attribution logs (including autostash/applied changes)
     // by moving the old-head working log to the new head after pull --rebase.
-    let _ = repo.storage.rename_working_log(&old_head, &new_head);
+    fetch_hooks::rename_working_log_after_pull(
+        repo,
+        &old_head,
+        &new_head,
+        "pull --rebase",
+        is_git_ai_dry_run_env(),
+    );
 
     // In skipped-commit pulls (`noop`), Git may not emit post-rewrite and no rebased
     // commits are created. Avoid mapping upstream history as "new" commits.
@@ -2351,8 +2327,10 @@ fn run_managed_hook(
                 repo.pre_command_base_commit = Some(parent.id());
             }
             let parsed = parsed_invocation("commit", vec![]);
-            let mut context = default_context();
-            context.pre_commit_hook_result = Some(true);
+            let mut context = CommandHooksContext {
+                pre_commit_hook_result: Some(true),
+                ..Default::default()
+            };
             commit_hooks::commit_post_command_hook(
                 &parsed,
                 success_exit_status(),
@@ -2367,7 +2345,7 @@ fn run_managed_hook(
                 maybe_capture_pull_pre_rebase_state(&repo);
             } else {
                 let parsed = parsed_invocation("rebase", hook_args.to_vec());
-                let mut context = default_context();
+                let mut context = CommandHooksContext::default();
                 rebase_hooks::pre_rebase_hook(&parsed, &mut repo, &mut context);
             }
             0
@@ -2411,7 +2389,7 @@ fn run_managed_hook(
 
                 if !is_pull_rebase_checkout {
                     let parsed = parsed_invocation("checkout", vec![]);
-                    let mut context = default_context();
+                    let mut context = CommandHooksContext::default();
                     checkout_hooks::post_checkout_hook(
                         &parsed,
                         &mut repo,
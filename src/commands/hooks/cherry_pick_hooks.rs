@@ -1,7 +1,7 @@
 use crate::authorship::rebase_authorship::walk_commits_to_base;
 use crate::commands::git_handlers::CommandHooksContext;
 use crate::commands::hooks::commit_hooks::get_commit_default_author;
-use crate::git::cli_parser::{ParsedGitInvocation, is_dry_run};
+use crate::git::cli_parser::ParsedGitInvocation;
 use crate::git::repository::Repository;
 use crate::git::rewrite_log::RewriteLogEvent;
 use crate::utils::debug_log;
@@ -9,10 +9,15 @@ use crate::utils::debug_log;
 pub fn pre_cherry_pick_hook(
     parsed_args: &ParsedGitInvocation,
     repository: &mut Repository,
-    _command_hooks_context: &mut CommandHooksContext,
+    command_hooks_context: &mut CommandHooksContext,
 ) {
     debug_log("=== CHERRY-PICK PRE-COMMAND HOOK ===");
 
+    if command_hooks_context.dry_run {
+        debug_log("Skipping cherry-pick pre-hook for dry-run");
+        return;
+    }
+
     // Check if we're continuing an existing cherry-pick or starting a new one
     let cherry_pick_head = repository.path().join("CHERRY_PICK_HEAD");
     let sequencer_dir = repository.path().join("sequencer");
@@ -74,7 +79,7 @@ pub fn pre_cherry_pick_hook(
 }
 
 pub fn post_cherry_pick_hook(
-    _context: &CommandHooksContext,
+    context: &CommandHooksContext,
     parsed_args: &ParsedGitInvocation,
     exit_status: std::process::ExitStatus,
     repository: &mut Repository,
@@ -101,7 +106,7 @@ pub fn post_cherry_pick_hook(
         return;
     }
 
-    if is_dry_run(&parsed_args.command_args) {
+    if context.dry_run {
         debug_log("Skipping cherry-pick post-hook for dry-run");
         return;
     }
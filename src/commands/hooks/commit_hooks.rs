@@ -40,7 +40,7 @@ pub fn commit_post_command_hook(
     repository: &mut Repository,
     command_hooks_context: &mut CommandHooksContext,
 ) {
-    if is_dry_run(&parsed_args.command_args) {
+    if command_hooks_context.dry_run {
         return;
     }
 
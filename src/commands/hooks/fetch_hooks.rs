@@ -1,54 +1,352 @@
+use crate::authorship::rebase_authorship::migrate_working_log_after_rebase;
 use crate::authorship::virtual_attribution::{VirtualAttributions, restore_stashed_va};
 use crate::commands::git_handlers::CommandHooksContext;
 use crate::commands::hooks::commit_hooks::get_commit_default_author;
 use crate::commands::hooks::rebase_hooks::build_rebase_commit_mappings;
 use crate::commands::upgrade;
-use crate::git::cli_parser::{ParsedGitInvocation, is_dry_run};
-use crate::git::repository::{Repository, exec_git, find_repository};
+use crate::git::authorship_traversal::notes_ref_tip;
+use crate::git::cli_parser::ParsedGitInvocation;
+use crate::git::repo_storage::WorkingLogRename;
+use crate::git::repository::{Repository, find_repository};
 use crate::git::rewrite_log::RewriteLogEvent;
-use crate::git::sync_authorship::{fetch_authorship_notes, fetch_remote_from_args};
-use crate::utils::debug_log;
+use crate::git::sync_authorship::{
+    NotesExistence, fetch_authorship_notes, fetch_remotes_from_args,
+};
+use crate::observability::sync_events::{SyncEvent, SyncEventEmitter, fetch_failure_reason};
+use crate::utils::{LockFile, debug_log};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, mpsc};
+use std::time::Duration;
+
+/// Default for `git-ai.fetchNotesTimeoutMs`
+const DEFAULT_FETCH_NOTES_TIMEOUT_MS: u64 = 10_000;
+
+/// Names a file to write a [`SyncReport`] to once the fetch/pull post hook finishes
+const SYNC_REPORT_ENV: &str = "GIT_AI_SYNC_REPORT";
+
+/// Machine-readable summary of the notes sync done alongside a fetch/pull, written as JSON to
+/// the path in `GIT_AI_SYNC_REPORT` so CI can assert that authorship notes actually synced.
+/// Only written when the background notes fetch ran.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyncReport {
+    /// Remotes the notes were fetched from, in fetch order, with how each fetch ended
+    pub remotes: Vec<RemoteSync>,
+    pub notes_ref: String,
+    /// Notes ref tip before the fetch started, `None` if the ref didn't exist
+    pub notes_tip_before: Option<String>,
+    /// Notes ref tip once the post hook finished
+    pub notes_tip_after: Option<String>,
+    /// Whether a pull moved the working log onto the new HEAD; always false for fetch
+    pub working_log_migrated: bool,
+}
+
+/// How the notes fetch from one remote ended
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RemoteSync {
+    pub remote: String,
+    #[serde(flatten)]
+    pub outcome: RemoteSyncOutcome,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum RemoteSyncOutcome {
+    /// The notes ref was fetched and merged, or the remote has none
+    Succeeded,
+    /// The fetch or merge failed, with the same reason reported to telemetry
+    Failed { reason: String },
+    /// The post hook stopped waiting before this remote finished
+    TimedOut,
+}
+
+/// Per-remote outcomes recorded by the fetch as each remote finishes, so the ones that finished
+/// before a timeout are still reported
+type RemoteSyncOutcomes = Arc<Mutex<Vec<RemoteSync>>>;
+
+/// A [`SyncReport`] started by the pre-command hook, finished and written by the post hook
+struct PendingSyncReport {
+    path: PathBuf,
+    report: SyncReport,
+    remotes: Vec<String>,
+    outcomes: RemoteSyncOutcomes,
+    timed_out: bool,
+}
+
+impl PendingSyncReport {
+    /// Start a report if `GIT_AI_SYNC_REPORT` asks for one
+    fn from_env(repository: &Repository, remotes: &[String]) -> Option<Self> {
+        let path = std::env::var_os(SYNC_REPORT_ENV).filter(|path| !path.is_empty())?;
+        Some(Self::start(PathBuf::from(path), repository, remotes))
+    }
+
+    fn start(path: PathBuf, repository: &Repository, remotes: &[String]) -> Self {
+        Self {
+            path,
+            report: SyncReport {
+                remotes: Vec::new(),
+                notes_ref: repository.notes_ref(),
+                notes_tip_before: notes_ref_tip(repository),
+                notes_tip_after: None,
+                working_log_migrated: false,
+            },
+            remotes: remotes.to_vec(),
+            outcomes: RemoteSyncOutcomes::default(),
+            timed_out: false,
+        }
+    }
+
+    fn finish(mut self, repository: &Repository, working_log_migrated: bool) {
+        let finished = std::mem::take(&mut *self.outcomes.lock().unwrap());
+        self.report.remotes = self
+            .remotes
+            .iter()
+            .map(|remote| {
+                let outcome = finished
+                    .iter()
+                    .find(|sync| &sync.remote == remote)
+                    .map(|sync| sync.outcome.clone())
+                    .unwrap_or_else(|| {
+                        if self.timed_out {
+                            RemoteSyncOutcome::TimedOut
+                        } else {
+                            RemoteSyncOutcome::Failed {
+                                reason: "fetch_interrupted".to_string(),
+                            }
+                        }
+                    });
+                RemoteSync {
+                    remote: remote.clone(),
+                    outcome,
+                }
+            })
+            .collect();
+        self.report.notes_tip_after = notes_ref_tip(repository);
+        self.report.working_log_migrated = working_log_migrated;
+        let result = serde_json::to_vec_pretty(&self.report)
+            .map_err(|e| e.to_string())
+            .and_then(|json| std::fs::write(&self.path, json).map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            debug_log(&format!(
+                "Failed to write sync report to {}: {}",
+                self.path.display(),
+                e
+            ));
+        }
+    }
+}
+
+/// Background notes-ref fetch started by the pre-command hook.
+///
+/// The post-command hook waits for it at most `timeout`, so a hanging remote can't hold up the
+/// user's fetch/pull.
+pub struct BackgroundAuthorshipFetch {
+    /// Fetch thread and its completion signal; `None` when the fetch already ran inline
+    running: Option<(std::thread::JoinHandle<()>, mpsc::Receiver<()>)>,
+    timeout: Duration,
+    report: Option<PendingSyncReport>,
+}
+
+impl BackgroundAuthorshipFetch {
+    fn spawn<F>(timeout: Duration, fetch: F) -> Self
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        let (done_tx, done) = mpsc::channel();
+        let handle = std::thread::spawn(move || {
+            fetch();
+            let _ = done_tx.send(());
+        });
+        Self {
+            running: Some((handle, done)),
+            timeout,
+            report: None,
+        }
+    }
+
+    /// Run `fetch` to completion on the calling thread; waiting on the result returns at once
+    fn run_inline<F>(fetch: F) -> Self
+    where
+        F: FnOnce(),
+    {
+        fetch();
+        Self {
+            running: None,
+            timeout: Duration::ZERO,
+            report: None,
+        }
+    }
+
+    fn with_report(mut self, report: Option<PendingSyncReport>) -> Self {
+        self.report = report;
+        self
+    }
+
+    /// Join the fetch thread if it finishes within the timeout, otherwise detach it. Returns the
+    /// sync report to finish, if one was requested.
+    fn wait(mut self) -> Option<PendingSyncReport> {
+        let Some((handle, done)) = self.running else {
+            return self.report;
+        };
+        match done.recv_timeout(self.timeout) {
+            Err(mpsc::RecvTimeoutError::Timeout) => {
+                debug_log(&format!(
+                    "authorship fetch still running after {}ms; continuing without it",
+                    self.timeout.as_millis()
+                ));
+                if let Some(report) = self.report.as_mut() {
+                    report.timed_out = true;
+                }
+            }
+            // Disconnected means the thread exited without signalling (e.g. it panicked)
+            Ok(()) | Err(mpsc::RecvTimeoutError::Disconnected) => {
+                let _ = handle.join();
+            }
+        }
+        self.report
+    }
+}
 
 pub fn fetch_pull_pre_command_hook(
     parsed_args: &ParsedGitInvocation,
     repository: &Repository,
-) -> Option<std::thread::JoinHandle<()>> {
-    upgrade::maybe_schedule_background_update_check();
+    command_hooks_context: &CommandHooksContext,
+) -> Option<BackgroundAuthorshipFetch> {
+    upgrade::maybe_schedule_background_update_check(repository);
 
     // Early return for dry-run
-    if is_dry_run(&parsed_args.command_args) {
+    if command_hooks_context.dry_run {
+        return None;
+    }
+
+    if !fetch_notes_enabled(repository) {
+        debug_log("git-ai.fetchNotes is false; skipping authorship fetch");
         return None;
     }
 
     crate::observability::spawn_background_flush();
 
-    // Extract the remote name
-    let remote = match fetch_remote_from_args(repository, parsed_args) {
-        Ok(remote) => remote,
+    // Extract the remote names (several for --all/--multiple)
+    let remotes = match fetch_remotes_from_args(repository, parsed_args) {
+        Ok(remotes) => remotes,
         Err(_) => {
             debug_log("failed to extract remote for authorship fetch; skipping");
             return None;
         }
     };
 
-    // Clone what we need for the background thread
+    // Clone what we need for the background thread, including the config environment so its
+    // config reads don't consult the process environment concurrently
     let global_args = repository.global_args_for_exec();
+    let config_env = Repository::process_config_env();
+    let events = SyncEventEmitter::for_repository(repository);
+
+    let report = PendingSyncReport::from_env(repository, &remotes);
+    let outcomes = report
+        .as_ref()
+        .map(|report| report.outcomes.clone())
+        .unwrap_or_default();
+
+    if !background_fetch_enabled(repository) {
+        debug_log("fetching authorship notes before the main fetch");
+        let fetch = BackgroundAuthorshipFetch::run_inline(|| {
+            fetch_notes_from_remotes(repository, &remotes, &events, &outcomes)
+        });
+        return Some(fetch.with_report(report));
+    }
 
     // Spawn background thread to fetch authorship notes in parallel with main fetch
-    Some(std::thread::spawn(move || {
+    let timeout = fetch_notes_timeout(repository);
+    let fetch = BackgroundAuthorshipFetch::spawn(timeout, move || {
+        // Recreate repository in the background thread
+        let Ok(repo) = find_repository(&global_args) else {
+            debug_log("failed to open repository for authorship fetch");
+            return;
+        };
+        let repo = repo.with_config_env(config_env);
+        fetch_notes_from_remotes(&repo, &remotes, &events, &outcomes);
+    });
+    Some(fetch.with_report(report))
+}
+
+/// Fetch and merge authorship notes from each remote in turn, reporting each step to `events`
+/// and recording how each remote ended in `outcomes`.
+///
+/// Remotes are fetched one at a time: each lands in its own tracking ref but is then merged into
+/// the notes ref, which must not be updated concurrently.
+fn fetch_notes_from_remotes(
+    repo: &Repository,
+    remotes: &[String],
+    events: &SyncEventEmitter,
+    outcomes: &RemoteSyncOutcomes,
+) {
+    for remote in remotes {
         debug_log(&format!(
             "started fetching authorship notes from remote: {}",
             remote
         ));
-        // Recreate repository in the background thread
-        if let Ok(repo) = find_repository(&global_args) {
-            if let Err(e) = fetch_authorship_notes(&repo, &remote) {
+        events.emit(SyncEvent::NotesFetchStarted {
+            remote: remote.clone(),
+        });
+        let outcome = match fetch_authorship_notes(repo, remote) {
+            Ok(existence) => {
+                events.emit(SyncEvent::NotesFetchCompleted {
+                    remote: remote.clone(),
+                    found: matches!(existence, NotesExistence::Found),
+                });
+                RemoteSyncOutcome::Succeeded
+            }
+            Err(e) => {
                 debug_log(&format!("authorship fetch failed: {}", e));
+                let reason = fetch_failure_reason(&e);
+                events.emit(SyncEvent::NotesFetchFailed {
+                    remote: remote.clone(),
+                    reason: reason.clone(),
+                });
+                RemoteSyncOutcome::Failed { reason }
             }
-        } else {
-            debug_log("failed to open repository for authorship fetch");
+        };
+        outcomes.lock().unwrap().push(RemoteSync {
+            remote: remote.clone(),
+            outcome,
+        });
+    }
+}
+
+/// Whether `git-ai.fetchNotes` allows fetching the notes ref alongside fetch/pull.
+/// Defaults to true when unset or not a valid boolean.
+fn fetch_notes_enabled(repository: &Repository) -> bool {
+    repository.config_get_bool_or("git-ai.fetchNotes", true)
+}
+
+/// Whether the notes fetch runs on a background thread alongside the user's fetch/pull.
+/// `GIT_AI_SYNC=1` or `git-ai.backgroundFetch=false` make the pre-command hook fetch inline
+/// instead, so the outcome is settled, in order, before the main fetch starts.
+fn background_fetch_enabled(repository: &Repository) -> bool {
+    if std::env::var("GIT_AI_SYNC").unwrap_or_default() == "1" {
+        return false;
+    }
+    repository.config_get_bool_or("git-ai.backgroundFetch", true)
+}
+
+/// How long the post-command hook waits for the background fetch, from
+/// `git-ai.fetchNotesTimeoutMs`. Defaults to 10s when unset or not a valid non-negative integer.
+fn fetch_notes_timeout(repository: &Repository) -> Duration {
+    let timeout_ms = match repository.config_get_int("git-ai.fetchNotesTimeoutMs") {
+        Ok(Some(ms)) => u64::try_from(ms).unwrap_or_else(|_| {
+            debug_log(&format!(
+                "ignoring negative git-ai.fetchNotesTimeoutMs: {}",
+                ms
+            ));
+            DEFAULT_FETCH_NOTES_TIMEOUT_MS
+        }),
+        Ok(None) => DEFAULT_FETCH_NOTES_TIMEOUT_MS,
+        Err(e) => {
+            debug_log(&format!("ignoring git-ai.fetchNotesTimeoutMs: {}", e));
+            DEFAULT_FETCH_NOTES_TIMEOUT_MS
         }
-    }))
+    };
+    Duration::from_millis(timeout_ms)
 }
 
 /// Pre-command hook for git pull.
@@ -61,22 +359,28 @@ pub fn pull_pre_command_hook(
 ) {
     // Start the background authorship fetch (same as regular fetch)
     command_hooks_context.fetch_authorship_handle =
-        fetch_pull_pre_command_hook(parsed_args, repository);
+        fetch_pull_pre_command_hook(parsed_args, repository, command_hooks_context);
+
+    // Without a working tree there's no working log to carry across the pull
+    if repository.is_bare_repository().unwrap_or(false) {
+        debug_log("pull pre-hook: bare repository, skipping working-log capture");
+        return;
+    }
 
     // Capture HEAD before pull to detect changes
     repository.require_pre_command_head();
 
     // Check if this is a rebase pull with autostash (single git config call)
-    let config = get_pull_rebase_autostash_config(parsed_args, repository);
+    let config = get_pull_config(parsed_args, repository);
     let has_changes = has_uncommitted_changes(repository);
 
     debug_log(&format!(
-        "pull pre-hook: rebase={}, autostash={}, has_changes={}",
-        config.is_rebase, config.is_autostash, has_changes
+        "pull pre-hook: integration={:?}, autostash={}, has_changes={}",
+        config.integration, config.is_autostash, has_changes
     ));
 
     // Only capture VA if we're in rebase+autostash mode AND have uncommitted changes
-    if config.is_rebase && config.is_autostash && has_changes {
+    if config.integration.is_rebase() && config.is_autostash && has_changes {
         debug_log(
             "Detected pull --rebase --autostash with uncommitted changes, capturing VirtualAttributions",
         );
@@ -116,23 +420,25 @@ pub fn pull_pre_command_hook(
 }
 
 pub fn fetch_pull_post_command_hook(
-    _repository: &Repository,
+    repository: &Repository,
     _parsed_args: &ParsedGitInvocation,
     _exit_status: std::process::ExitStatus,
     command_hooks_context: &mut CommandHooksContext,
 ) {
-    // Always wait for the authorship fetch thread to complete if it was started,
-    // regardless of whether the main fetch/pull succeeded or failed.
-    // This ensures proper cleanup of the background thread.
-    if let Some(handle) = command_hooks_context.fetch_authorship_handle.take() {
-        let _ = handle.join();
+    // Always wait for the authorship fetch thread if it was started, regardless of whether
+    // the main fetch/pull succeeded or failed. The wait is bounded by fetchNotesTimeoutMs.
+    if let Some(fetch) = command_hooks_context.fetch_authorship_handle.take()
+        && let Some(report) = fetch.wait()
+    {
+        report.finish(repository, false);
     }
 }
 
 /// Post-command hook for git pull.
 /// Handles two scenarios:
 /// 1. Restores AI attributions after a pull --rebase --autostash operation.
-/// 2. Renames working log for fast-forward pulls to preserve attributions.
+/// 2. Migrates the working log to the new HEAD, the way the pull's flags and config say it
+///    integrated upstream (see [`pull_working_log_migration`]).
 pub fn pull_post_command_hook(
     repository: &mut Repository,
     parsed_args: &ParsedGitInvocation,
@@ -140,114 +446,464 @@ pub fn pull_post_command_hook(
     command_hooks_context: &mut CommandHooksContext,
 ) {
     // Wait for authorship fetch thread
-    if let Some(handle) = command_hooks_context.fetch_authorship_handle.take() {
-        let _ = handle.join();
-    }
+    let sync_report = command_hooks_context
+        .fetch_authorship_handle
+        .take()
+        .and_then(BackgroundAuthorshipFetch::wait);
 
+    let migrated =
+        exit_status.success() && migrate_after_pull(repository, parsed_args, command_hooks_context);
     if !exit_status.success() {
         debug_log("Pull failed, skipping post-pull authorship restoration");
-        return;
+    }
+
+    if let Some(report) = sync_report {
+        report.finish(repository, migrated);
+    }
+}
+
+/// Carry attributions across a successful pull. Returns whether the working log was moved
+/// onto the new HEAD.
+fn migrate_after_pull(
+    repository: &mut Repository,
+    parsed_args: &ParsedGitInvocation,
+    command_hooks_context: &mut CommandHooksContext,
+) -> bool {
+    if repository.is_bare_repository().unwrap_or(false) {
+        debug_log("Bare repository, skipping post-pull working-log migration");
+        return false;
     }
 
     // Get old HEAD from pre-command capture
     let old_head = match &repository.pre_command_base_commit {
         Some(sha) => sha.clone(),
-        None => return,
+        None => return false,
     };
 
     // Get new HEAD
     let new_head = match repository.head().ok().and_then(|h| h.target().ok()) {
         Some(sha) => sha,
-        None => return,
+        None => return false,
     };
 
     if old_head == new_head {
         debug_log("HEAD unchanged, skipping post-pull authorship handling");
-        return;
+        return false;
     }
 
+    let dry_run = command_hooks_context.dry_run;
+
     // Check if we have a stashed VA to restore (from pull --rebase --autostash)
-    if let Some(stashed_va) = command_hooks_context.stashed_va.take() {
+    if let Some(stashed_va) = command_hooks_context.stashed_va.take()
+        && !dry_run
+        && let Some(_lock) = lock_working_logs(repository, "restore autostashed attributions")
+    {
         restore_stashed_va(repository, &old_head, &new_head, stashed_va);
     }
 
-    // Check for fast-forward pull and rename working log if applicable
-    if was_fast_forward_pull(repository, &new_head) {
-        debug_log(&format!(
-            "Fast-forward detected: {} -> {}",
-            old_head, new_head
-        ));
-        let _ = repository.storage.rename_working_log(&old_head, &new_head);
-        return;
-    }
+    let config = get_pull_config(parsed_args, repository);
+    let migration = pull_working_log_migration(config.integration, || {
+        was_fast_forward_pull(repository, &old_head, &new_head)
+    });
+    debug_log(&format!(
+        "Pull {:?} migrates working log by {:?}: {} -> {}",
+        config.integration, migration, old_head, new_head
+    ));
 
-    // Handle committed authorship rewriting for pull --rebase
-    let config = get_pull_rebase_autostash_config(parsed_args, repository);
-    if config.is_rebase {
-        process_completed_pull_rebase(repository, &old_head, &new_head);
+    match migration {
+        PullWorkingLogMigration::Rename { reason } => {
+            rename_working_log_after_pull(repository, &old_head, &new_head, reason, dry_run)
+        }
+        // Handle committed authorship rewriting for pull --rebase
+        PullWorkingLogMigration::Walk { interactive } => {
+            if dry_run {
+                debug_log("Dry run: skipping authorship rewrite after pull --rebase");
+                return false;
+            }
+            let Some(_lock) =
+                lock_working_logs(repository, "rewrite authorship after pull --rebase")
+            else {
+                return false;
+            };
+            process_completed_pull_rebase(repository, &old_head, &new_head, interactive);
+            true
+        }
     }
 }
 
-/// Check if the most recent reflog entry indicates a fast-forward pull operation.
-/// Uses format "%H %gs" to get both the commit SHA and the reflog subject.
-/// Verifies:
-/// 1. The reflog SHA matches the expected new HEAD (confirms we have the right entry)
-/// 2. The subject starts with "pull" (confirms it was a pull operation)
-/// 3. The subject ends with ": Fast-forward" (confirms it was a fast-forward)
-fn was_fast_forward_pull(repository: &Repository, expected_new_head: &str) -> bool {
-    let mut args = repository.global_args_for_exec();
-    args.extend(
-        ["reflog", "-1", "--format=%H %gs"]
-            .iter()
-            .map(|s| s.to_string()),
-    );
+/// How the working log follows HEAD after a successful pull
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PullWorkingLogMigration {
+    /// The working log is renamed to the new HEAD. Used both when HEAD fast-forwarded and when
+    /// it moved to a merge commit whose first parent is the old HEAD, since uncommitted
+    /// attributions carry over unchanged either way; `reason` only labels the rename in logs.
+    Rename { reason: &'static str },
+    /// Local commits were rebased onto upstream, so the rewritten commits are walked
+    Walk { interactive: bool },
+}
 
-    match exec_git(&args) {
-        Ok(output) => {
-            let output_str = String::from_utf8_lossy(&output.stdout);
-            let output_str = output_str.trim();
+const FAST_FORWARD_RENAME: PullWorkingLogMigration = PullWorkingLogMigration::Rename {
+    reason: "fast-forward pull",
+};
+const MERGE_RENAME: PullWorkingLogMigration = PullWorkingLogMigration::Rename {
+    reason: "merge pull",
+};
 
-            // Format: "<sha> <subject>"
-            // Example: "1f9a5dc45612afcbef17e9d07441d9b57c7bb5d0 pull: Fast-forward"
-            let Some((sha, subject)) = output_str.split_once(' ') else {
-                return false;
-            };
+/// Pick the working-log migration for a pull from how it was asked to integrate upstream.
+/// `fast_forwarded` is only consulted when the flags allow more than one outcome.
+fn pull_working_log_migration(
+    integration: PullIntegration,
+    fast_forwarded: impl FnOnce() -> bool,
+) -> PullWorkingLogMigration {
+    match integration {
+        PullIntegration::FastForwardOnly => FAST_FORWARD_RENAME,
+        PullIntegration::Merge { no_ff: true } => MERGE_RENAME,
+        PullIntegration::Merge { no_ff: false } => {
+            if fast_forwarded() {
+                FAST_FORWARD_RENAME
+            } else {
+                MERGE_RENAME
+            }
+        }
+        // Without local commits, a rebase pull just fast-forwards
+        PullIntegration::Rebase { interactive } => {
+            if fast_forwarded() {
+                FAST_FORWARD_RENAME
+            } else {
+                PullWorkingLogMigration::Walk { interactive }
+            }
+        }
+    }
+}
+
+/// Take the working-log lock before `action` mutates working logs, so overlapping pulls can't
+/// move the same log at once. Returns `None` if another git-ai process keeps the lock past the
+/// retry window or the lock can't be taken at all; the caller then skips `action` rather than
+/// risking a half-moved log, leaving the working log in place for `git-ai relink`.
+fn lock_working_logs(repository: &Repository, action: &str) -> Option<LockFile> {
+    let lock = repository.storage.lock_working_logs();
+    if lock.is_none() {
+        debug_log(&format!(
+            "Could not take the working log lock, deferring to a later relink: {}",
+            action
+        ));
+    }
+    lock
+}
 
-            // Verify the SHA matches our expected new HEAD
-            if sha != expected_new_head {
+/// Move the working log from `old_head` to `new_head` after a pull, returning whether a log
+/// was moved. With `dry_run` the rename is only logged and recorded in repo storage, so it can be
+/// inspected with `git-ai debug`.
+pub(crate) fn rename_working_log_after_pull(
+    repository: &Repository,
+    old_head: &str,
+    new_head: &str,
+    reason: &str,
+    dry_run: bool,
+) -> bool {
+    let Some(_lock) = lock_working_logs(repository, reason) else {
+        return false;
+    };
+    rename_or_preview_working_log(repository, old_head, new_head, reason, dry_run)
+}
+
+fn rename_or_preview_working_log(
+    repository: &Repository,
+    old_head: &str,
+    new_head: &str,
+    reason: &str,
+    dry_run: bool,
+) -> bool {
+    if !dry_run {
+        let result = match repository.storage.rename_working_log(old_head, new_head) {
+            // A previous run may already have moved part of the log; combine instead of
+            // leaving the old log behind where nothing will read it
+            Ok(WorkingLogRename::DestinationExists) => repository
+                .storage
+                .merge_working_logs(old_head, new_head)
+                .map(|()| Some(true)),
+            Ok(WorkingLogRename::Renamed) => Ok(Some(false)),
+            Ok(WorkingLogRename::SourceMissing) => Ok(None),
+            Err(e) => Err(e),
+        };
+        return match result {
+            Ok(Some(merged)) => {
+                SyncEventEmitter::for_repository(repository).emit(SyncEvent::WorkingLogRenamed {
+                    reason: reason.to_string(),
+                    merged,
+                });
+                true
+            }
+            Ok(None) => false,
+            Err(e) => {
                 debug_log(&format!(
-                    "Reflog SHA {} doesn't match expected HEAD {}",
-                    sha, expected_new_head
+                    "Failed to move working log {} -> {}: {}",
+                    old_head, new_head, e
                 ));
-                return false;
+                false
             }
+        };
+    }
 
-            // Must be a pull command that resulted in fast-forward
-            subject.starts_with("pull") && subject.ends_with(": Fast-forward")
+    debug_log(&format!(
+        "Dry run: would rename working log {} -> {} ({})",
+        old_head, new_head, reason
+    ));
+    if let Err(e) = repository
+        .storage
+        .record_planned_working_log_rename(old_head, new_head, reason)
+    {
+        debug_log(&format!(
+            "Failed to record planned working log rename: {}",
+            e
+        ));
+    }
+    false
+}
+
+/// Check if a pull fast-forwarded HEAD from `old_head` to `expected_new_head`.
+/// History is the primary signal (see [`pull_fast_forward_by_ancestry`]). When it can't decide,
+/// fall back to the most recent reflog entry: the subject normally ends with ": Fast-forward",
+/// e.g. "pull: Fast-forward", and other pull entries that aren't a recognizable merge or rebase
+/// are checked against the history shape (see [`is_merge_onto`]), since some git versions and
+/// locales word it differently.
+pub(crate) fn was_fast_forward_pull(
+    repository: &Repository,
+    old_head: &str,
+    expected_new_head: &str,
+) -> bool {
+    if let Some(is_fast_forward) =
+        pull_fast_forward_by_ancestry(repository, old_head, expected_new_head)
+    {
+        return is_fast_forward;
+    }
+
+    let Some(subject) = latest_pull_reflog_subject(repository, expected_new_head) else {
+        return false;
+    };
+    if subject.ends_with(": Fast-forward") {
+        return true;
+    }
+    if is_merge_pull_subject(&subject) || is_rebase_pull_subject(&subject) {
+        return false;
+    }
+    repository.is_fast_forward(old_head, expected_new_head)
+        && !is_merge_onto(repository, old_head, expected_new_head)
+}
+
+/// Whether `new_head` is a merge commit whose first parent is `old_head`, the shape a merging
+/// pull leaves behind. Only a tie-breaker for pull reflog entries we can't read, since a
+/// fast-forward onto an upstream merge of `old_head` looks the same.
+fn is_merge_onto(repository: &Repository, old_head: &str, new_head: &str) -> bool {
+    let Ok(new_commit) = repository.find_commit(new_head.to_string()) else {
+        return false;
+    };
+    let parents: Vec<String> = new_commit.parents().map(|p| p.id()).collect();
+    parents.len() > 1 && parents[0] == old_head
+}
+
+/// Decide from history whether a pull fast-forwarded `old_head` to `new_head`: `old_head` must
+/// be an ancestor of `new_head`, and `new_head` must be reachable from what the pull fetched
+/// (the upstream tracking ref or a `FETCH_HEAD` merge candidate).
+///
+/// Returns `None` when history can't decide, e.g. nothing was fetched or git failed, so callers
+/// can fall back to the reflog. Unlike the reflog, this can't be confused by another git command
+/// writing a newer entry between the pull and our probe.
+fn pull_fast_forward_by_ancestry(
+    repository: &Repository,
+    old_head: &str,
+    new_head: &str,
+) -> Option<bool> {
+    if old_head == new_head {
+        return Some(false);
+    }
+
+    let fetched_tips = pull_fetched_tips(repository);
+    if fetched_tips.is_empty() {
+        return None;
+    }
+
+    if !repository.is_ancestor(old_head, new_head).ok()? {
+        return Some(false);
+    }
+
+    let mut inconclusive = false;
+    for tip in &fetched_tips {
+        match repository.is_ancestor(new_head, tip) {
+            Ok(true) => return Some(true),
+            Ok(false) => {}
+            Err(_) => inconclusive = true,
         }
-        Err(_) => false,
+    }
+    if inconclusive { None } else { Some(false) }
+}
+
+/// Commits a pull may have merged: the upstream tracking ref and `FETCH_HEAD` merge candidates
+fn pull_fetched_tips(repository: &Repository) -> Vec<String> {
+    let mut tips: Vec<String> = resolve_pull_rebase_onto_head(repository)
+        .into_iter()
+        .collect();
+
+    let fetch_head =
+        std::fs::read_to_string(repository.path().join("FETCH_HEAD")).unwrap_or_default();
+    // Lines look like "<sha>\t[not-for-merge]\t<description>"
+    for line in fetch_head.lines() {
+        let mut fields = line.split('\t');
+        let (Some(sha), Some(marker)) = (fields.next(), fields.next()) else {
+            continue;
+        };
+        if marker.is_empty() && !tips.iter().any(|tip| tip == sha) {
+            tips.push(sha.to_string());
+        }
+    }
+    tips
+}
+
+fn is_merge_pull_subject(subject: &str) -> bool {
+    subject.contains(": Merge made by the ")
+}
+
+fn is_rebase_pull_subject(subject: &str) -> bool {
+    (subject.starts_with("pull") || subject.starts_with("rebase")) && subject.contains("(finish)")
+}
+
+/// Subject of the most recent reflog entry if it was written by a pull.
+fn latest_pull_reflog_subject(repository: &Repository, expected_new_head: &str) -> Option<String> {
+    latest_reflog_subject(repository, expected_new_head)
+        .filter(|subject| subject.starts_with("pull"))
+}
+
+/// Subject of the most recent HEAD reflog entry.
+/// Returns `None` unless the entry moved HEAD to the expected new HEAD (confirms we have the
+/// right entry).
+fn latest_reflog_subject(repository: &Repository, expected_new_head: &str) -> Option<String> {
+    let entry = repository
+        .reflog_entries("HEAD", 1)
+        .ok()?
+        .into_iter()
+        .next()?;
+
+    if entry.new_sha != expected_new_head {
+        debug_log(&format!(
+            "Reflog SHA {} doesn't match expected HEAD {}",
+            entry.new_sha, expected_new_head
+        ));
+        return None;
+    }
+
+    Some(entry.subject)
+}
+
+/// How `git pull` integrates the fetched commits into the current branch
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PullIntegration {
+    /// `--ff-only` or `pull.ff=only`: HEAD can only fast-forward
+    FastForwardOnly,
+    /// A merge that fast-forwards when it can, unless `no_ff` (`--no-ff` or `pull.ff=false`)
+    Merge { no_ff: bool },
+    /// `--rebase[=<mode>]`, `pull.rebase` or `branch.<name>.rebase`
+    Rebase { interactive: bool },
+}
+
+impl PullIntegration {
+    fn is_rebase(self) -> bool {
+        matches!(self, PullIntegration::Rebase { .. })
+    }
+}
+
+/// A `--rebase=<mode>` / `pull.rebase` value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PullRebase {
+    Off,
+    On,
+    Interactive,
+}
+
+/// A `--ff` / `--no-ff` / `--ff-only` / `pull.ff` value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PullFastForward {
+    Allow,
+    Never,
+    Only,
+}
+
+/// pull.rebase can be true, false, merges, interactive, or preserve (and their one-letter
+/// forms); anything but a false boolean means rebase mode is enabled
+fn parse_pull_rebase(value: &str) -> PullRebase {
+    match value.to_lowercase().as_str() {
+        "false" | "no" | "off" | "0" => PullRebase::Off,
+        "interactive" | "i" => PullRebase::Interactive,
+        _ => PullRebase::On,
+    }
+}
+
+fn parse_pull_fast_forward(value: &str) -> PullFastForward {
+    match value.to_lowercase().as_str() {
+        "only" => PullFastForward::Only,
+        "false" | "no" | "off" | "0" => PullFastForward::Never,
+        _ => PullFastForward::Allow,
+    }
+}
+
+/// Rebase mode requested on the command line; like git, the last flag wins
+fn pull_rebase_from_cli(parsed_args: &ParsedGitInvocation) -> Option<PullRebase> {
+    parsed_args
+        .command_args
+        .iter()
+        .fold(None, |rebase, arg| match arg.as_str() {
+            "--rebase" | "-r" => Some(PullRebase::On),
+            "--no-rebase" => Some(PullRebase::Off),
+            _ => arg
+                .strip_prefix("--rebase=")
+                .map(parse_pull_rebase)
+                .or(rebase),
+        })
+}
+
+/// Fast-forward mode requested on the command line; like git, the last flag wins
+fn pull_fast_forward_from_cli(parsed_args: &ParsedGitInvocation) -> Option<PullFastForward> {
+    parsed_args
+        .command_args
+        .iter()
+        .fold(None, |fast_forward, arg| match arg.as_str() {
+            "--ff" => Some(PullFastForward::Allow),
+            "--no-ff" => Some(PullFastForward::Never),
+            "--ff-only" => Some(PullFastForward::Only),
+            _ => fast_forward,
+        })
+}
+
+fn pull_integration(rebase: PullRebase, fast_forward: PullFastForward) -> PullIntegration {
+    match (rebase, fast_forward) {
+        (PullRebase::On, _) => PullIntegration::Rebase { interactive: false },
+        (PullRebase::Interactive, _) => PullIntegration::Rebase { interactive: true },
+        (PullRebase::Off, PullFastForward::Only) => PullIntegration::FastForwardOnly,
+        (PullRebase::Off, PullFastForward::Never) => PullIntegration::Merge { no_ff: true },
+        (PullRebase::Off, PullFastForward::Allow) => PullIntegration::Merge { no_ff: false },
     }
 }
 
-/// Result of checking pull rebase and autostash settings
-struct PullRebaseAutostashConfig {
-    is_rebase: bool,
+/// Result of checking pull integration and autostash settings
+struct PullConfig {
+    integration: PullIntegration,
     is_autostash: bool,
 }
 
-/// Check if a pull operation will use rebase and autostash based on config and CLI flags.
-/// CLI flags override config settings. Uses a single git config call to minimize overhead.
-fn get_pull_rebase_autostash_config(
-    parsed_args: &ParsedGitInvocation,
-    repository: &Repository,
-) -> PullRebaseAutostashConfig {
+/// Check how a pull operation will integrate upstream and whether it autostashes, based on
+/// config and CLI flags. CLI flags override config settings, and `--ff-only` on the command
+/// line overrides any rebase setting, as in git. Uses a single git config call to minimize
+/// overhead.
+fn get_pull_config(parsed_args: &ParsedGitInvocation, repository: &Repository) -> PullConfig {
     // Check CLI flags first - they take precedence and don't require git calls
-    let rebase_from_cli = if parsed_args.has_command_flag("--no-rebase") {
-        Some(false)
-    } else if parsed_args.has_command_flag("--rebase") || parsed_args.has_command_flag("-r") {
-        Some(true)
-    } else {
-        None
+    let rebase_from_cli = pull_rebase_from_cli(parsed_args);
+    let fast_forward_from_cli = pull_fast_forward_from_cli(parsed_args);
+    let integration_from_cli = match (rebase_from_cli, fast_forward_from_cli) {
+        (_, Some(PullFastForward::Only)) => Some(PullIntegration::FastForwardOnly),
+        (Some(rebase), Some(fast_forward)) => Some(pull_integration(rebase, fast_forward)),
+        (Some(rebase), None) if rebase != PullRebase::Off => {
+            Some(pull_integration(rebase, PullFastForward::Allow))
+        }
+        _ => None,
     };
 
     let autostash_from_cli = if parsed_args.has_command_flag("--no-autostash") {
@@ -259,40 +915,56 @@ fn get_pull_rebase_autostash_config(
     };
 
     // If both are determined by CLI flags, no need to check config
-    if let (Some(is_rebase), Some(is_autostash)) = (rebase_from_cli, autostash_from_cli) {
-        return PullRebaseAutostashConfig {
-            is_rebase,
+    if let (Some(integration), Some(is_autostash)) = (integration_from_cli, autostash_from_cli) {
+        return PullConfig {
+            integration,
             is_autostash,
         };
     }
 
-    // Get relevant config values in a single git call
-    // Pattern matches: pull.rebase, rebase.autoStash
+    // Get relevant config values in a single config read.
+    // Keys are canonicalized to lowercase, so the pattern must be lowercase too.
+    // Pattern matches: pull.rebase, pull.ff, rebase.autoStash, branch.<name>.rebase
     let config = repository
-        .config_get_regexp(r"^(pull\.rebase|rebase\.autoStash)$")
+        .config_get_regexp_exact(r"pull\.rebase|pull\.ff|rebase\.autostash|branch\..*\.rebase")
         .unwrap_or_default();
+    // Like git, the last occurrence of a key wins (the map keeps only the last value).
+    let last_value = |key: &str| config.get(key).map(|v| v.to_lowercase());
 
-    // Determine rebase setting
-    let is_rebase = rebase_from_cli.unwrap_or_else(|| {
-        // Check git config: pull.rebase can be true, false, merges, interactive, or preserve
-        // Any value other than "false" means rebase mode is enabled
-        config
-            .get("pull.rebase")
-            .map(|v| v.to_lowercase() != "false")
-            .unwrap_or(false)
+    // Determine integration setting; branch.<name>.rebase overrides pull.rebase
+    let integration = integration_from_cli.unwrap_or_else(|| {
+        // Only look up the current branch when some branch has a rebase setting
+        let branch_rebase = config
+            .keys()
+            .any(|k| k.starts_with("branch."))
+            .then(|| {
+                let branch = repository.head_branch_name().ok()??;
+                last_value(&format!("branch.{}.rebase", branch))
+            })
+            .flatten();
+        let rebase = rebase_from_cli
+            .or_else(|| {
+                branch_rebase
+                    .or_else(|| last_value("pull.rebase"))
+                    .map(|v| parse_pull_rebase(&v))
+            })
+            .unwrap_or(PullRebase::Off);
+        let fast_forward = fast_forward_from_cli
+            .or_else(|| last_value("pull.ff").map(|v| parse_pull_fast_forward(&v)))
+            .unwrap_or(PullFastForward::Allow);
+        pull_integration(rebase, fast_forward)
     });
 
     // Determine autostash setting
     let is_autostash = autostash_from_cli.unwrap_or_else(|| {
         // Check git config: rebase.autoStash (used when rebasing)
-        config
-            .get("rebase.autoStash")
-            .map(|v| v.to_lowercase() == "true")
+        last_value("rebase.autostash")
+            .map(|v| v == "true")
             .unwrap_or(false)
     });
 
-    PullRebaseAutostashConfig {
-        is_rebase,
+    PullConfig {
+        integration,
         is_autostash,
     }
 }
@@ -308,7 +980,12 @@ fn has_uncommitted_changes(repository: &Repository) -> bool {
 
 /// Rewrite authorship for committed local changes that were rebased by `git pull --rebase`.
 /// Uses the same commit-mapping and rewrite logic as `rebase_hooks::process_completed_rebase`.
-fn process_completed_pull_rebase(repository: &mut Repository, original_head: &str, new_head: &str) {
+fn process_completed_pull_rebase(
+    repository: &mut Repository,
+    original_head: &str,
+    new_head: &str,
+    interactive: bool,
+) {
     debug_log(&format!(
         "Processing pull --rebase authorship: {} -> {}",
         original_head, new_head
@@ -335,12 +1012,19 @@ fn process_completed_pull_rebase(repository: &mut Repository, original_head: &st
         }
     };
 
-    if original_commits.is_empty() {
-        debug_log("No committed changes to rewrite authorship for after pull --rebase");
-        return;
-    }
-    if new_commits.is_empty() {
-        debug_log("No newly rebased commits to rewrite authorship for after pull --rebase");
+    if original_commits.is_empty() || new_commits.is_empty() {
+        if original_commits.is_empty() {
+            debug_log("No committed changes to rewrite authorship for after pull --rebase");
+        } else {
+            debug_log("No newly rebased commits to rewrite authorship for after pull --rebase");
+        }
+        // Uncommitted attributions still need to follow HEAD onto the rebased tip
+        if let Err(e) = migrate_working_log_after_rebase(repository, original_head, new_head) {
+            debug_log(&format!(
+                "Failed to migrate working log after pull --rebase: {}",
+                e
+            ));
+        }
         return;
     }
 
@@ -348,7 +1032,7 @@ fn process_completed_pull_rebase(repository: &mut Repository, original_head: &st
         RewriteLogEvent::rebase_complete(crate::git::rewrite_log::RebaseCompleteEvent::new(
             original_head.to_string(),
             new_head.to_string(),
-            false, // pull --rebase is not interactive
+            interactive,
             original_commits,
             new_commits,
         ));
@@ -371,3 +1055,939 @@ fn resolve_pull_rebase_onto_head(repository: &Repository) -> Option<String> {
         .map(|commit| commit.id())
         .ok()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::refs::{notes_add, ref_exists, tracking_ref_for_remote};
+    use crate::git::repository::find_repository_in_path;
+    use crate::git::test_utils::{TmpRepo, seed_working_log, success_exit_status};
+    use std::sync::{Arc, Mutex};
+
+    /// Move HEAD to a fresh commit with the given reflog subject, returning the new SHA
+    fn commit_with_reflog_subject(repo: &TmpRepo, subject: &str) -> String {
+        repo.write_file("file.txt", &format!("{}\n", subject), true)
+            .unwrap();
+        repo.commit_with_message("next").unwrap();
+        let sha = repo.get_head_commit_sha().unwrap();
+        repo.git_command(&["update-ref", "-m", subject, "HEAD", &sha])
+            .unwrap();
+        sha
+    }
+
+    fn fetch_invocation() -> ParsedGitInvocation {
+        ParsedGitInvocation {
+            global_args: Vec::new(),
+            command: Some("fetch".to_string()),
+            command_args: vec!["origin".to_string()],
+            saw_end_of_opts: false,
+            is_help: false,
+        }
+    }
+
+    fn hooks_context(dry_run: bool) -> CommandHooksContext {
+        CommandHooksContext {
+            dry_run,
+            ..Default::default()
+        }
+    }
+
+    fn pull_invocation(args: &[&str]) -> ParsedGitInvocation {
+        ParsedGitInvocation {
+            global_args: Vec::new(),
+            command: Some("pull".to_string()),
+            command_args: args.iter().map(|arg| arg.to_string()).collect(),
+            saw_end_of_opts: false,
+            is_help: false,
+        }
+    }
+
+    #[test]
+    fn test_fetch_all_fetches_notes_from_every_remote() {
+        let remotes = ["first", "second"].map(|name| {
+            let remote = TmpRepo::new().unwrap();
+            remote
+                .write_file(&format!("{}.txt", name), "content\n", true)
+                .unwrap();
+            remote.commit_with_message(name).unwrap();
+            let sha = remote.get_head_commit_sha().unwrap();
+            notes_add(remote.gitai_repo(), &sha, &format!("note from {}", name)).unwrap();
+            (name, remote)
+        });
+
+        let repo = TmpRepo::new().unwrap();
+        for (name, remote) in &remotes {
+            repo.git_command(&["remote", "add", name, remote.path().to_str().unwrap()])
+                .unwrap();
+        }
+        let mut parsed_args = fetch_invocation();
+        parsed_args.command_args = vec!["--all".to_string()];
+
+        fetch_pull_pre_command_hook(&parsed_args, repo.gitai_repo(), &hooks_context(false))
+            .expect("fetch --all should start a background authorship fetch")
+            .wait();
+
+        for (name, _remote) in &remotes {
+            assert!(
+                ref_exists(repo.gitai_repo(), &tracking_ref_for_remote(name)),
+                "notes were not fetched from {}",
+                name
+            );
+        }
+    }
+
+    fn capture_sync_events(repo: &TmpRepo) -> (SyncEventEmitter, Arc<Mutex<Vec<SyncEvent>>>) {
+        let captured = Arc::new(Mutex::new(Vec::new()));
+        let sink = captured.clone();
+        let emitter = SyncEventEmitter::with_sink(repo.gitai_repo(), move |event| {
+            sink.lock().unwrap().push(event.clone())
+        });
+        (emitter, captured)
+    }
+
+    #[test]
+    fn test_fetch_notes_from_remotes_emits_sync_events() {
+        let remote = TmpRepo::new().unwrap();
+        remote.write_file("a.txt", "a\n", true).unwrap();
+        remote.commit_with_message("a").unwrap();
+        let sha = remote.get_head_commit_sha().unwrap();
+        notes_add(remote.gitai_repo(), &sha, "note").unwrap();
+
+        let repo = TmpRepo::new().unwrap();
+        repo.git_command(&["remote", "add", "origin", remote.path().to_str().unwrap()])
+            .unwrap();
+        repo.git_command(&["remote", "add", "gone", "/nonexistent/git-ai-remote"])
+            .unwrap();
+
+        let (events, captured) = capture_sync_events(&repo);
+        let outcomes = RemoteSyncOutcomes::default();
+        fetch_notes_from_remotes(
+            repo.gitai_repo(),
+            &["origin".to_string(), "gone".to_string()],
+            &events,
+            &outcomes,
+        );
+
+        assert_eq!(
+            *captured.lock().unwrap(),
+            vec![
+                SyncEvent::NotesFetchStarted {
+                    remote: "origin".to_string()
+                },
+                SyncEvent::NotesFetchCompleted {
+                    remote: "origin".to_string(),
+                    found: true
+                },
+                SyncEvent::NotesFetchStarted {
+                    remote: "gone".to_string()
+                },
+                SyncEvent::NotesFetchFailed {
+                    remote: "gone".to_string(),
+                    reason: "remote_unreachable".to_string()
+                },
+            ]
+        );
+        assert_eq!(
+            *outcomes.lock().unwrap(),
+            vec![
+                RemoteSync {
+                    remote: "origin".to_string(),
+                    outcome: RemoteSyncOutcome::Succeeded
+                },
+                RemoteSync {
+                    remote: "gone".to_string(),
+                    outcome: RemoteSyncOutcome::Failed {
+                        reason: "remote_unreachable".to_string()
+                    }
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fetch_notes_from_remotes_emits_nothing_with_telemetry_off() {
+        let repo = TmpRepo::new().unwrap();
+        repo.git_command(&["config", "git-ai.telemetry", "false"])
+            .unwrap();
+        repo.git_command(&["remote", "add", "gone", "/nonexistent/git-ai-remote"])
+            .unwrap();
+
+        let (events, captured) = capture_sync_events(&repo);
+        fetch_notes_from_remotes(
+            repo.gitai_repo(),
+            &["gone".to_string()],
+            &events,
+            &RemoteSyncOutcomes::default(),
+        );
+
+        assert!(captured.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_fetch_notes_disabled_skips_background_fetch() {
+        let repo = TmpRepo::new().unwrap();
+        repo.git_command(&["config", "git-ai.fetchNotes", "false"])
+            .unwrap();
+
+        let handle = fetch_pull_pre_command_hook(
+            &fetch_invocation(),
+            repo.gitai_repo(),
+            &hooks_context(false),
+        );
+
+        assert!(handle.is_none());
+    }
+
+    #[test]
+    fn test_synchronous_fetch_completes_before_pre_hook_returns() {
+        let remote = TmpRepo::new().unwrap();
+        remote.write_file("a.txt", "a\n", true).unwrap();
+        remote.commit_with_message("a").unwrap();
+        let sha = remote.get_head_commit_sha().unwrap();
+        notes_add(remote.gitai_repo(), &sha, "note").unwrap();
+
+        let repo = TmpRepo::new().unwrap();
+        repo.git_command(&["remote", "add", "origin", remote.path().to_str().unwrap()])
+            .unwrap();
+        assert!(background_fetch_enabled(repo.gitai_repo()));
+        repo.git_command(&["config", "git-ai.backgroundFetch", "false"])
+            .unwrap();
+        assert!(!background_fetch_enabled(repo.gitai_repo()));
+
+        let fetch = fetch_pull_pre_command_hook(
+            &fetch_invocation(),
+            repo.gitai_repo(),
+            &hooks_context(false),
+        )
+        .expect("notes fetch should still run");
+
+        // Fetched before the hook returned, not by a thread that might still be running
+        assert!(fetch.running.is_none());
+        assert!(ref_exists(
+            repo.gitai_repo(),
+            &tracking_ref_for_remote("origin")
+        ));
+        fetch.wait();
+    }
+
+    #[test]
+    fn test_background_fetch_wait_detaches_slow_fetch_after_timeout() {
+        let fetch = BackgroundAuthorshipFetch::spawn(Duration::from_millis(50), || {
+            std::thread::sleep(Duration::from_secs(10));
+        });
+
+        let start = std::time::Instant::now();
+        fetch.wait();
+
+        assert!(
+            start.elapsed() < Duration::from_secs(5),
+            "wait should return at the timeout, took {:?}",
+            start.elapsed()
+        );
+    }
+
+    #[test]
+    fn test_background_fetch_wait_joins_finished_fetch() {
+        let (ran_tx, ran) = mpsc::channel();
+        let fetch = BackgroundAuthorshipFetch::spawn(Duration::from_secs(10), move || {
+            ran_tx.send(()).unwrap();
+        });
+
+        fetch.wait();
+
+        assert!(ran.try_recv().is_ok());
+    }
+
+    #[test]
+    fn test_fetch_notes_timeout_reads_config() {
+        let repo = TmpRepo::new().unwrap();
+        assert_eq!(
+            fetch_notes_timeout(repo.gitai_repo()),
+            Duration::from_millis(DEFAULT_FETCH_NOTES_TIMEOUT_MS)
+        );
+
+        repo.git_command(&["config", "git-ai.fetchNotesTimeoutMs", "250"])
+            .unwrap();
+        assert_eq!(
+            fetch_notes_timeout(repo.gitai_repo()),
+            Duration::from_millis(250)
+        );
+
+        repo.git_command(&["config", "git-ai.fetchNotesTimeoutMs", "-1"])
+            .unwrap();
+        assert_eq!(
+            fetch_notes_timeout(repo.gitai_repo()),
+            Duration::from_millis(DEFAULT_FETCH_NOTES_TIMEOUT_MS)
+        );
+    }
+
+    #[test]
+    fn test_fetch_notes_defaults_to_enabled() {
+        let repo = TmpRepo::new().unwrap();
+        assert!(fetch_notes_enabled(repo.gitai_repo()));
+
+        repo.git_command(&["config", "git-ai.fetchNotes", "not-a-bool"])
+            .unwrap();
+        assert!(fetch_notes_enabled(repo.gitai_repo()));
+
+        repo.git_command(&["config", "git-ai.fetchNotes", "off"])
+            .unwrap();
+        assert!(!fetch_notes_enabled(repo.gitai_repo()));
+    }
+
+    #[test]
+    fn test_pull_rebase_without_local_commits_keeps_working_log() {
+        let repo = TmpRepo::new().unwrap();
+        repo.write_file("file.txt", "base\n", true).unwrap();
+        repo.commit_with_message("base").unwrap();
+        let old_head = repo.get_head_commit_sha().unwrap();
+
+        // Upstream commit pulled in on top of the old HEAD
+        let new_head = commit_with_reflog_subject(
+            &repo,
+            "pull --rebase (finish): returning to refs/heads/main",
+        );
+
+        let mut gitai_repo = repo.gitai_repo().clone();
+        seed_working_log(&gitai_repo, &old_head);
+
+        assert!(!was_fast_forward_pull(&gitai_repo, &old_head, &new_head));
+        process_completed_pull_rebase(&mut gitai_repo, &old_head, &new_head, false);
+
+        assert!(!gitai_repo.storage.has_working_log(&old_head));
+        let initial = gitai_repo
+            .storage
+            .working_log_for_base_commit(&new_head)
+            .read_initial_attributions();
+        assert_eq!(initial.files["file.txt"].len(), 1);
+    }
+
+    #[test]
+    fn test_pull_post_hook_skips_working_log_migration_in_bare_repo() {
+        let repo = TmpRepo::new().unwrap();
+        repo.write_file("file.txt", "base\n", true).unwrap();
+        repo.commit_with_message("base").unwrap();
+        let old_head = repo.get_head_commit_sha().unwrap();
+        repo.write_file("file.txt", "base\nnext\n", true).unwrap();
+        repo.commit_with_message("next").unwrap();
+        let new_head = repo.get_head_commit_sha().unwrap();
+
+        let bare_dir = tempfile::tempdir().unwrap();
+        let bare_path = bare_dir.path().join("bare.git");
+        repo.git_command(&["clone", "--bare", ".", bare_path.to_str().unwrap()])
+            .unwrap();
+        let mut bare_repo = find_repository_in_path(bare_path.to_str().unwrap()).unwrap();
+        assert!(bare_repo.is_bare_repository().unwrap());
+
+        seed_working_log(&bare_repo, &old_head);
+        bare_repo.pre_command_base_commit = Some(old_head.clone());
+
+        let success = success_exit_status();
+        let mut context = hooks_context(false);
+        pull_post_command_hook(
+            &mut bare_repo,
+            &pull_invocation(&["--ff-only"]),
+            success,
+            &mut context,
+        );
+
+        assert!(bare_repo.storage.has_working_log(&old_head));
+        assert!(!bare_repo.storage.has_working_log(&new_head));
+    }
+
+    #[test]
+    fn test_pull_post_hook_previews_rename_in_dry_run() {
+        let repo = TmpRepo::new().unwrap();
+        repo.write_file("file.txt", "base\n", true).unwrap();
+        repo.commit_with_message("base").unwrap();
+        let old_head = repo.get_head_commit_sha().unwrap();
+        let new_head = fetch_upstream_commit(&repo);
+        repo.git_command(&["merge", "--ff-only", "upstream"])
+            .unwrap();
+
+        let mut gitai_repo = repo.gitai_repo().clone();
+        seed_working_log(&gitai_repo, &old_head);
+        gitai_repo.pre_command_base_commit = Some(old_head.clone());
+
+        let mut context = hooks_context(true);
+        pull_post_command_hook(
+            &mut gitai_repo,
+            &pull_invocation(&["--ff-only"]),
+            success_exit_status(),
+            &mut context,
+        );
+
+        assert!(gitai_repo.storage.has_working_log(&old_head));
+        assert!(!gitai_repo.storage.has_working_log(&new_head));
+        let planned = gitai_repo.storage.read_planned_working_log_renames();
+        assert_eq!(planned.len(), 1);
+        assert_eq!(planned[0].new_sha, new_head);
+    }
+
+    #[test]
+    fn test_pull_post_hook_writes_sync_report_after_fast_forward() {
+        let repo = TmpRepo::new().unwrap();
+        repo.write_file("file.txt", "base\n", true).unwrap();
+        repo.commit_with_message("base").unwrap();
+        let old_head = repo.get_head_commit_sha().unwrap();
+        let new_head = fetch_upstream_commit(&repo);
+        repo.git_command(&["merge", "--ff-only", "upstream"])
+            .unwrap();
+
+        let mut gitai_repo = repo.gitai_repo().clone();
+        seed_working_log(&gitai_repo, &old_head);
+        gitai_repo.pre_command_base_commit = Some(old_head.clone());
+
+        let report_dir = tempfile::tempdir().unwrap();
+        let report_path = report_dir.path().join("sync-report.json");
+        let tip_before = notes_ref_tip(&gitai_repo);
+        let report =
+            PendingSyncReport::start(report_path.clone(), &gitai_repo, &["origin".to_string()]);
+        // Stands in for the notes fetched from origin during the pull
+        let fetch_repo = gitai_repo.clone();
+        let noted_commit = new_head.clone();
+        let outcomes = report.outcomes.clone();
+        let fetch = BackgroundAuthorshipFetch::spawn(Duration::from_secs(10), move || {
+            notes_add(&fetch_repo, &noted_commit, "fetched note").unwrap();
+            outcomes.lock().unwrap().push(RemoteSync {
+                remote: "origin".to_string(),
+                outcome: RemoteSyncOutcome::Succeeded,
+            });
+        });
+        let mut context = hooks_context(false);
+        context.fetch_authorship_handle = Some(fetch.with_report(Some(report)));
+
+        let success = success_exit_status();
+        pull_post_command_hook(
+            &mut gitai_repo,
+            &pull_invocation(&["--ff-only"]),
+            success,
+            &mut context,
+        );
+
+        let report: SyncReport =
+            serde_json::from_slice(&std::fs::read(&report_path).unwrap()).unwrap();
+        assert_eq!(
+            report.remotes,
+            vec![RemoteSync {
+                remote: "origin".to_string(),
+                outcome: RemoteSyncOutcome::Succeeded
+            }]
+        );
+        assert_eq!(report.notes_ref, gitai_repo.notes_ref());
+        assert_eq!(report.notes_tip_before, tip_before);
+        assert_eq!(report.notes_tip_after, notes_ref_tip(&gitai_repo));
+        assert_ne!(report.notes_tip_after, report.notes_tip_before);
+        assert!(report.working_log_migrated);
+        assert!(gitai_repo.storage.has_working_log(&new_head));
+    }
+
+    #[test]
+    fn test_sync_report_marks_remotes_unfinished_at_the_timeout() {
+        let repo = TmpRepo::new().unwrap();
+        let gitai_repo = repo.gitai_repo().clone();
+        let report_dir = tempfile::tempdir().unwrap();
+        let report_path = report_dir.path().join("sync-report.json");
+        let report = PendingSyncReport::start(
+            report_path.clone(),
+            &gitai_repo,
+            &["origin".to_string(), "upstream".to_string()],
+        );
+        // origin finishes; upstream is still fetching when the hook stops waiting
+        let outcomes = report.outcomes.clone();
+        let fetch = BackgroundAuthorshipFetch::spawn(Duration::from_millis(200), move || {
+            outcomes.lock().unwrap().push(RemoteSync {
+                remote: "origin".to_string(),
+                outcome: RemoteSyncOutcome::Succeeded,
+            });
+            std::thread::sleep(Duration::from_secs(10));
+        });
+        let mut context = hooks_context(false);
+        context.fetch_authorship_handle = Some(fetch.with_report(Some(report)));
+
+        fetch_pull_post_command_hook(
+            &gitai_repo,
+            &fetch_invocation(),
+            success_exit_status(),
+            &mut context,
+        );
+
+        let report: SyncReport =
+            serde_json::from_slice(&std::fs::read(&report_path).unwrap()).unwrap();
+        assert_eq!(
+            report.remotes,
+            vec![
+                RemoteSync {
+                    remote: "origin".to_string(),
+                    outcome: RemoteSyncOutcome::Succeeded
+                },
+                RemoteSync {
+                    remote: "upstream".to_string(),
+                    outcome: RemoteSyncOutcome::TimedOut
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_was_fast_forward_pull_rejects_merge_reflog_entry() {
+        let repo = TmpRepo::new().unwrap();
+        repo.write_file("file.txt", "base\n", true).unwrap();
+        repo.commit_with_message("base").unwrap();
+        let base_sha = repo.get_head_commit_sha().unwrap();
+
+        let sha = commit_with_reflog_subject(
+            &repo,
+            "pull origin main: Merge made by the 'ort' strategy.",
+        );
+
+        assert!(!was_fast_forward_pull(repo.gitai_repo(), &base_sha, &sha));
+    }
+
+    #[test]
+    fn test_was_fast_forward_pull_detects_localized_reflog_subject() {
+        let repo = TmpRepo::new().unwrap();
+        repo.write_file("file.txt", "base\n", true).unwrap();
+        repo.commit_with_message("base").unwrap();
+        let base_sha = repo.get_head_commit_sha().unwrap();
+
+        // Reflog wording as written by a non-English git
+        let sha = commit_with_reflog_subject(&repo, "pull: Vorspulen");
+
+        assert!(was_fast_forward_pull(repo.gitai_repo(), &base_sha, &sha));
+        assert!(!was_fast_forward_pull(repo.gitai_repo(), &sha, &sha));
+    }
+
+    #[test]
+    fn test_was_fast_forward_pull_rejects_localized_merge_pull() {
+        let repo = TmpRepo::new().unwrap();
+        repo.write_file("file.txt", "base\n", true).unwrap();
+        repo.commit_with_message("base").unwrap();
+        let main_branch = repo.current_branch().unwrap();
+
+        repo.git_command(&["checkout", "-b", "upstream"]).unwrap();
+        repo.write_file("upstream.txt", "upstream\n", true).unwrap();
+        repo.commit_with_message("upstream").unwrap();
+        repo.git_command(&["checkout", &main_branch]).unwrap();
+        repo.write_file("local.txt", "local\n", true).unwrap();
+        repo.commit_with_message("local").unwrap();
+        let local_sha = repo.get_head_commit_sha().unwrap();
+        repo.git_command(&["merge", "--no-ff", "-m", "merge upstream", "upstream"])
+            .unwrap();
+        let merge_sha = repo.get_head_commit_sha().unwrap();
+        repo.git_command(&[
+            "update-ref",
+            "-m",
+            "pull: Merge durch die 'ort'-Strategie.",
+            "HEAD",
+            &merge_sha,
+        ])
+        .unwrap();
+
+        assert!(!was_fast_forward_pull(
+            repo.gitai_repo(),
+            &local_sha,
+            &merge_sha
+        ));
+    }
+
+    #[test]
+    fn test_was_fast_forward_pull_parses_mocked_reflog() {
+        use crate::git::git_executor::MockGitExecutor;
+        use crate::git::repository::from_bare_repository;
+
+        let old_head = "1".repeat(40);
+        let new_head = "2".repeat(40);
+        let temp = tempfile::tempdir().unwrap();
+        // No upstream and no FETCH_HEAD, so history can't decide and the reflog is consulted
+        let was_fast_forward = |reflog_sha: &str, subject: &str| {
+            let reflog = format!("{}\0Dev <dev@example.com>\0{}\n", reflog_sha, subject);
+            let executor = Arc::new(MockGitExecutor::new().on(&["reflog", "show"], &reflog));
+            let repo = from_bare_repository(temp.path())
+                .unwrap()
+                .with_executor(executor.clone());
+            let was_fast_forward = was_fast_forward_pull(&repo, &old_head, &new_head);
+            // The answer has to come from the canned reflog, not from the repository on disk
+            assert!(
+                executor
+                    .calls()
+                    .iter()
+                    .any(|args| args.iter().any(|arg| arg == "reflog")),
+                "reflog was not read through the executor"
+            );
+            was_fast_forward
+        };
+
+        assert!(was_fast_forward(&new_head, "pull: Fast-forward"));
+        assert!(!was_fast_forward(
+            &new_head,
+            "pull origin main: Merge made by the 'ort' strategy."
+        ));
+        assert!(!was_fast_forward(
+            &new_head,
+            "pull --rebase (finish): returning to refs/heads/main"
+        ));
+        // An entry for some other commit isn't the pull we're looking for
+        assert!(!was_fast_forward(&old_head, "pull: Fast-forward"));
+        assert!(!was_fast_forward(&new_head, "commit: next"));
+    }
+
+    /// Commit `upstream.txt` on a new `upstream` branch off HEAD, switch back, and record the
+    /// commit in FETCH_HEAD as if a pull had just fetched it
+    fn fetch_upstream_commit(repo: &TmpRepo) -> String {
+        let main_branch = repo.current_branch().unwrap();
+        repo.git_command(&["checkout", "-b", "upstream"]).unwrap();
+        repo.write_file("upstream.txt", "upstream\n", true).unwrap();
+        repo.commit_with_message("upstream").unwrap();
+        let upstream_sha = repo.get_head_commit_sha().unwrap();
+        repo.git_command(&["checkout", &main_branch]).unwrap();
+        std::fs::write(
+            repo.gitai_repo().path().join("FETCH_HEAD"),
+            format!("{}\t\tbranch 'main' of ../origin\n", upstream_sha),
+        )
+        .unwrap();
+        upstream_sha
+    }
+
+    #[test]
+    fn test_pull_fast_forward_by_ancestry_detects_fast_forward() {
+        let repo = TmpRepo::new().unwrap();
+        repo.write_file("file.txt", "base\n", true).unwrap();
+        repo.commit_with_message("base").unwrap();
+        let base_sha = repo.get_head_commit_sha().unwrap();
+        let upstream_sha = fetch_upstream_commit(&repo);
+
+        repo.git_command(&["merge", "--ff-only", "upstream"])
+            .unwrap();
+        // A newer, unrelated reflog entry must not hide the fast-forward
+        repo.git_command(&[
+            "update-ref",
+            "-m",
+            "checkout: moving",
+            "HEAD",
+            &upstream_sha,
+        ])
+        .unwrap();
+
+        assert_eq!(
+            pull_fast_forward_by_ancestry(repo.gitai_repo(), &base_sha, &upstream_sha),
+            Some(true)
+        );
+        assert!(was_fast_forward_pull(
+            repo.gitai_repo(),
+            &base_sha,
+            &upstream_sha
+        ));
+    }
+
+    #[test]
+    fn test_pull_fast_forward_by_ancestry_rejects_merge_commit() {
+        let repo = TmpRepo::new().unwrap();
+        repo.write_file("file.txt", "base\n", true).unwrap();
+        repo.commit_with_message("base").unwrap();
+        fetch_upstream_commit(&repo);
+        repo.write_file("local.txt", "local\n", true).unwrap();
+        repo.commit_with_message("local").unwrap();
+        let local_sha = repo.get_head_commit_sha().unwrap();
+
+        repo.git_command(&["merge", "--no-ff", "-m", "merge upstream", "upstream"])
+            .unwrap();
+        let merge_sha = repo.get_head_commit_sha().unwrap();
+        // Even a reflog entry claiming a fast-forward doesn't override history
+        repo.git_command(&["update-ref", "-m", "pull: Fast-forward", "HEAD", &merge_sha])
+            .unwrap();
+
+        assert_eq!(
+            pull_fast_forward_by_ancestry(repo.gitai_repo(), &local_sha, &merge_sha),
+            Some(false)
+        );
+        assert!(!was_fast_forward_pull(
+            repo.gitai_repo(),
+            &local_sha,
+            &merge_sha
+        ));
+    }
+
+    #[test]
+    fn test_pull_fast_forward_by_ancestry_rejects_diverged_history() {
+        let repo = TmpRepo::new().unwrap();
+        repo.write_file("file.txt", "base\n", true).unwrap();
+        repo.commit_with_message("base").unwrap();
+        let upstream_sha = fetch_upstream_commit(&repo);
+        repo.write_file("local.txt", "local\n", true).unwrap();
+        repo.commit_with_message("local").unwrap();
+        let local_sha = repo.get_head_commit_sha().unwrap();
+
+        assert_eq!(
+            pull_fast_forward_by_ancestry(repo.gitai_repo(), &local_sha, &upstream_sha),
+            Some(false)
+        );
+    }
+
+    #[test]
+    fn test_was_fast_forward_pull_in_sha256_repository() {
+        let temp = tempfile::tempdir().unwrap();
+        let upstream = temp.path().join("upstream");
+        let local = temp.path().join("local");
+        std::fs::create_dir_all(&upstream).unwrap();
+        let git = |cwd: &std::path::Path, args: &[&str]| {
+            let output = std::process::Command::new(crate::config::Config::get().git_cmd())
+                .current_dir(cwd)
+                .args([
+                    "-c",
+                    "user.name=Test User",
+                    "-c",
+                    "user.email=test@example.com",
+                ])
+                .args(args)
+                .output()
+                .unwrap();
+            assert!(output.status.success(), "{:?}", output);
+            String::from_utf8(output.stdout).unwrap().trim().to_string()
+        };
+
+        git(&upstream, &["init", "-q", "--object-format=sha256"]);
+        std::fs::write(upstream.join("file.txt"), "base\n").unwrap();
+        git(&upstream, &["add", "file.txt"]);
+        git(&upstream, &["commit", "-q", "-m", "base"]);
+        git(
+            temp.path(),
+            &[
+                "clone",
+                "-q",
+                upstream.to_str().unwrap(),
+                local.to_str().unwrap(),
+            ],
+        );
+        let base_sha = git(&local, &["rev-parse", "HEAD"]);
+        assert_eq!(base_sha.len(), 64);
+
+        std::fs::write(upstream.join("file.txt"), "base\nupstream\n").unwrap();
+        git(&upstream, &["commit", "-q", "-am", "upstream"]);
+        git(&local, &["pull", "-q", "--ff-only"]);
+        let new_sha = git(&local, &["rev-parse", "HEAD"]);
+        assert_ne!(new_sha, base_sha);
+
+        let repository = crate::git::find_repository_in_path(local.to_str().unwrap()).unwrap();
+        assert_eq!(
+            pull_fast_forward_by_ancestry(&repository, &base_sha, &new_sha),
+            Some(true)
+        );
+        assert!(
+            latest_pull_reflog_subject(&repository, &new_sha)
+                .is_some_and(|subject| subject.ends_with(": Fast-forward"))
+        );
+        assert!(was_fast_forward_pull(&repository, &base_sha, &new_sha));
+    }
+
+    #[test]
+    fn test_pull_fast_forward_by_ancestry_is_inconclusive_without_fetched_tips() {
+        let repo = TmpRepo::new().unwrap();
+        repo.write_file("file.txt", "base\n", true).unwrap();
+        repo.commit_with_message("base").unwrap();
+        let base_sha = repo.get_head_commit_sha().unwrap();
+        let sha = commit_with_reflog_subject(&repo, "pull: Fast-forward");
+
+        assert_eq!(
+            pull_fast_forward_by_ancestry(repo.gitai_repo(), &base_sha, &sha),
+            None
+        );
+        // Falls back to the reflog subject
+        assert!(was_fast_forward_pull(repo.gitai_repo(), &base_sha, &sha));
+    }
+
+    #[test]
+    fn test_dry_run_records_working_log_rename_without_renaming() {
+        let repo = TmpRepo::new().unwrap();
+        repo.write_file("file.txt", "base\n", true).unwrap();
+        repo.commit_with_message("base").unwrap();
+        let old_head = repo.get_head_commit_sha().unwrap();
+        let new_head = commit_with_reflog_subject(&repo, "pull: Fast-forward");
+
+        let storage = &repo.gitai_repo().storage;
+        seed_working_log(repo.gitai_repo(), &old_head);
+
+        rename_or_preview_working_log(
+            repo.gitai_repo(),
+            &old_head,
+            &new_head,
+            "fast-forward pull",
+            true,
+        );
+
+        assert!(storage.has_working_log(&old_head));
+        assert!(!storage.has_working_log(&new_head));
+        let planned = storage.read_planned_working_log_renames();
+        assert_eq!(planned.len(), 1);
+        assert_eq!(planned[0].old_sha, old_head);
+        assert_eq!(planned[0].new_sha, new_head);
+        assert_eq!(planned[0].reason, "fast-forward pull");
+
+        rename_or_preview_working_log(
+            repo.gitai_repo(),
+            &old_head,
+            &new_head,
+            "fast-forward pull",
+            false,
+        );
+        assert!(!storage.has_working_log(&old_head));
+        assert!(storage.has_working_log(&new_head));
+        assert_eq!(storage.read_planned_working_log_renames().len(), 1);
+    }
+
+    #[test]
+    fn test_latest_pull_reflog_subject_rejects_non_pull_and_stale_entries() {
+        let repo = TmpRepo::new().unwrap();
+        repo.write_file("file.txt", "base\n", true).unwrap();
+        repo.commit_with_message("base").unwrap();
+        let base_sha = repo.get_head_commit_sha().unwrap();
+
+        let sha =
+            commit_with_reflog_subject(&repo, "merge feature: Merge made by the 'ort' strategy.");
+        assert_eq!(latest_pull_reflog_subject(repo.gitai_repo(), &sha), None);
+
+        let sha = commit_with_reflog_subject(&repo, "pull: Merge made by the 'ort' strategy.");
+        assert_eq!(
+            latest_pull_reflog_subject(repo.gitai_repo(), &sha).as_deref(),
+            Some("pull: Merge made by the 'ort' strategy.")
+        );
+        assert_eq!(
+            latest_pull_reflog_subject(repo.gitai_repo(), &base_sha),
+            None
+        );
+    }
+
+    #[test]
+    fn test_pull_integration_from_flags() {
+        let repo = TmpRepo::new().unwrap();
+        for (args, expected) in [
+            (&[][..], PullIntegration::Merge { no_ff: false }),
+            (&["--ff"][..], PullIntegration::Merge { no_ff: false }),
+            (&["--no-ff"][..], PullIntegration::Merge { no_ff: true }),
+            (&["--ff-only"][..], PullIntegration::FastForwardOnly),
+            (
+                &["--rebase"][..],
+                PullIntegration::Rebase { interactive: false },
+            ),
+            (&["-r"][..], PullIntegration::Rebase { interactive: false }),
+            (
+                &["--rebase=merges"][..],
+                PullIntegration::Rebase { interactive: false },
+            ),
+            (
+                &["--rebase=interactive"][..],
+                PullIntegration::Rebase { interactive: true },
+            ),
+            (
+                &["--rebase=i", "--no-ff"][..],
+                PullIntegration::Rebase { interactive: true },
+            ),
+            (
+                &["--rebase", "--ff-only"][..],
+                PullIntegration::FastForwardOnly,
+            ),
+            (
+                &["--rebase=false", "--no-ff"][..],
+                PullIntegration::Merge { no_ff: true },
+            ),
+            (
+                &["--rebase", "--no-rebase"][..],
+                PullIntegration::Merge { no_ff: false },
+            ),
+            (
+                &["--ff-only", "--no-ff"][..],
+                PullIntegration::Merge { no_ff: true },
+            ),
+        ] {
+            let config = get_pull_config(&pull_invocation(args), repo.gitai_repo());
+            assert_eq!(config.integration, expected, "{:?}", args);
+        }
+    }
+
+    #[test]
+    fn test_pull_integration_from_config() {
+        let repo = TmpRepo::new().unwrap();
+        repo.write_file("file.txt", "base\n", true).unwrap();
+        repo.commit_with_message("base").unwrap();
+        let branch = repo.current_branch().unwrap();
+        let integration =
+            |args: &[&str]| get_pull_config(&pull_invocation(args), repo.gitai_repo()).integration;
+
+        repo.git_command(&["config", "pull.ff", "only"]).unwrap();
+        assert_eq!(integration(&[]), PullIntegration::FastForwardOnly);
+        assert_eq!(
+            integration(&["--ff"]),
+            PullIntegration::Merge { no_ff: false }
+        );
+
+        repo.git_command(&["config", "pull.ff", "false"]).unwrap();
+        assert_eq!(integration(&[]), PullIntegration::Merge { no_ff: true });
+
+        repo.git_command(&["config", "pull.rebase", "interactive"])
+            .unwrap();
+        assert_eq!(
+            integration(&[]),
+            PullIntegration::Rebase { interactive: true }
+        );
+        assert_eq!(
+            integration(&["--ff-only"]),
+            PullIntegration::FastForwardOnly
+        );
+        assert_eq!(
+            integration(&["--no-rebase"]),
+            PullIntegration::Merge { no_ff: true }
+        );
+
+        // The current branch's rebase setting overrides pull.rebase
+        let branch_rebase = format!("branch.{}.rebase", branch);
+        repo.git_command(&["config", &branch_rebase, "false"])
+            .unwrap();
+        assert_eq!(integration(&[]), PullIntegration::Merge { no_ff: true });
+        repo.git_command(&["config", "branch.other.rebase", "true"])
+            .unwrap();
+        assert_eq!(integration(&[]), PullIntegration::Merge { no_ff: true });
+    }
+
+    #[test]
+    fn test_pull_working_log_migration_for_each_integration() {
+        let rebase = PullIntegration::Rebase { interactive: false };
+        let interactive = PullIntegration::Rebase { interactive: true };
+        for (integration, fast_forwarded, expected) in [
+            (PullIntegration::FastForwardOnly, true, FAST_FORWARD_RENAME),
+            (
+                PullIntegration::Merge { no_ff: false },
+                true,
+                FAST_FORWARD_RENAME,
+            ),
+            (PullIntegration::Merge { no_ff: false }, false, MERGE_RENAME),
+            (PullIntegration::Merge { no_ff: true }, false, MERGE_RENAME),
+            (rebase, true, FAST_FORWARD_RENAME),
+            (
+                rebase,
+                false,
+                PullWorkingLogMigration::Walk { interactive: false },
+            ),
+            (
+                interactive,
+                false,
+                PullWorkingLogMigration::Walk { interactive: true },
+            ),
+        ] {
+            assert_eq!(
+                pull_working_log_migration(integration, || fast_forwarded),
+                expected,
+                "{:?} (fast-forwarded: {})",
+                integration,
+                fast_forwarded
+            );
+        }
+
+        // Flags that allow only one outcome don't look at history
+        for integration in [
+            PullIntegration::FastForwardOnly,
+            PullIntegration::Merge { no_ff: true },
+        ] {
+            pull_working_log_migration(integration, || panic!("history consulted"));
+        }
+    }
+}
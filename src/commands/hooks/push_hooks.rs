@@ -8,11 +8,12 @@ use crate::utils::debug_log;
 pub fn push_pre_command_hook(
     parsed_args: &ParsedGitInvocation,
     repository: &Repository,
+    command_hooks_context: &CommandHooksContext,
 ) -> Option<std::thread::JoinHandle<()>> {
-    upgrade::maybe_schedule_background_update_check();
+    upgrade::maybe_schedule_background_update_check(repository);
 
     // Early returns for cases where we shouldn't push authorship notes
-    if should_skip_authorship_push(&parsed_args.command_args) {
+    if should_skip_authorship_push(command_hooks_context.dry_run, &parsed_args.command_args) {
         return None;
     }
     let remote = resolve_push_remote(parsed_args, repository);
@@ -51,9 +52,13 @@ pub fn push_pre_command_hook(
 }
 
 pub fn run_pre_push_hook_managed(parsed_args: &ParsedGitInvocation, repository: &Repository) {
-    upgrade::maybe_schedule_background_update_check();
+    upgrade::maybe_schedule_background_update_check(repository);
 
-    if should_skip_authorship_push(&parsed_args.command_args) {
+    // Managed hooks have no dispatch context, so dry-run comes from the args
+    if should_skip_authorship_push(
+        is_dry_run(&parsed_args.command_args),
+        &parsed_args.command_args,
+    ) {
         return;
     }
 
@@ -93,8 +98,8 @@ pub fn push_post_command_hook(
     }
 }
 
-fn should_skip_authorship_push(command_args: &[String]) -> bool {
-    is_dry_run(command_args)
+fn should_skip_authorship_push(dry_run: bool, command_args: &[String]) -> bool {
+    dry_run
         || command_args.iter().any(|a| a == "-d" || a == "--delete")
         || command_args.iter().any(|a| a == "--mirror")
 }
@@ -207,18 +212,19 @@ mod tests {
 
     #[test]
     fn skip_authorship_push_when_dry_run() {
-        assert!(should_skip_authorship_push(&strings(&["--dry-run"])));
+        assert!(should_skip_authorship_push(true, &strings(&["origin"])));
+        assert!(!should_skip_authorship_push(false, &strings(&["origin"])));
     }
 
     #[test]
     fn skip_authorship_push_when_delete() {
-        assert!(should_skip_authorship_push(&strings(&["--delete"])));
-        assert!(should_skip_authorship_push(&strings(&["-d"])));
+        assert!(should_skip_authorship_push(false, &strings(&["--delete"])));
+        assert!(should_skip_authorship_push(false, &strings(&["-d"])));
     }
 
     #[test]
     fn skip_authorship_push_when_mirror() {
-        assert!(should_skip_authorship_push(&strings(&["--mirror"])));
+        assert!(should_skip_authorship_push(false, &strings(&["--mirror"])));
     }
 
     #[test]
@@ -264,4 +270,34 @@ mod tests {
         );
         assert_eq!(with_default.as_deref(), Some("origin"));
     }
+
+    #[test]
+    fn extract_remote_from_push_args_skips_flags_and_their_values() {
+        let known = strings(&["origin", "upstream"]);
+        let cases: &[(&[&str], Option<&str>)] = &[
+            (&["-u", "upstream", "main"], Some("upstream")),
+            (
+                &["--force-with-lease=main:abc123", "origin", "main"],
+                Some("origin"),
+            ),
+            (&["-o", "ci.skip", "upstream", "HEAD"], Some("upstream")),
+            (&["--push-option", "ci.skip", "origin"], Some("origin")),
+            (&["--repo=upstream", "main"], Some("upstream")),
+            (&["--tags", "--", "upstream"], Some("upstream")),
+            (
+                &["git@example.com:org/repo.git", "main"],
+                Some("git@example.com:org/repo.git"),
+            ),
+            (&["--follow-tags"], None),
+        ];
+
+        for (args, expected) in cases {
+            assert_eq!(
+                extract_remote_from_push_args(&strings(args), &known).as_deref(),
+                *expected,
+                "args: {:?}",
+                args
+            );
+        }
+    }
 }
@@ -2,7 +2,6 @@ use crate::authorship::rebase_authorship::walk_commits_to_base;
 use crate::commands::git_handlers::CommandHooksContext;
 use crate::commands::hooks::commit_hooks::get_commit_default_author;
 use crate::git::cli_parser::ParsedGitInvocation;
-use crate::git::cli_parser::is_dry_run;
 use crate::git::repository::Repository;
 use crate::git::rewrite_log::RewriteLogEvent;
 use crate::utils::debug_log;
@@ -14,6 +13,11 @@ pub fn pre_rebase_hook(
 ) {
     debug_log("=== REBASE PRE-COMMAND HOOK ===");
 
+    if command_hooks_context.dry_run {
+        debug_log("Skipping rebase pre-hook for dry-run");
+        return;
+    }
+
     // Check if we're continuing an existing rebase or starting a new one
     let rebase_dir = repository.path().join("rebase-merge");
     let rebase_apply_dir = repository.path().join("rebase-apply");
@@ -103,7 +107,7 @@ pub fn handle_rebase_post_command(
         return;
     }
 
-    if is_dry_run(&parsed_args.command_args) {
+    if context.dry_run {
         debug_log("Skipping rebase post-hook for dry-run");
         return;
     }
@@ -323,7 +327,14 @@ pub(crate) fn build_rebase_commit_mappings(
     let original_head_commit = repository.find_commit(original_head.to_string())?;
 
     // Find merge base between original and new
-    let merge_base = repository.merge_base(original_head_commit.id(), new_head_commit.id())?;
+    let merge_base = repository
+        .merge_base(&original_head_commit.id(), &new_head_commit.id())?
+        .ok_or_else(|| {
+            crate::error::GitAiError::Generic(format!(
+                "No merge base between {} and {}",
+                original_head, new_head
+            ))
+        })?;
 
     // Walk from original_head to merge_base to get the commits that were rebased
     let mut original_commits = walk_commits_to_base(repository, original_head, &merge_base)?;
@@ -342,7 +353,7 @@ pub(crate) fn build_rebase_commit_mappings(
     // Prefer the rebase target (onto) as the lower bound for new commits. This prevents
     // skipped/no-op rebases from sweeping unrelated target-branch history.
     let new_commits_base = onto_head
-        .filter(|onto| is_ancestor(repository, onto, new_head))
+        .filter(|onto| repository.is_ancestor(onto, new_head).unwrap_or(false))
         .unwrap_or(merge_base.as_str());
 
     // Walk from new_head to base to get the actual rebased commits
@@ -415,15 +426,6 @@ fn resolve_commitish(repository: &Repository, spec: &str) -> Option<String> {
         .ok()
 }
 
-fn is_ancestor(repository: &Repository, ancestor: &str, descendant: &str) -> bool {
-    let mut args = repository.global_args_for_exec();
-    args.push("merge-base".to_string());
-    args.push("--is-ancestor".to_string());
-    args.push(ancestor.to_string());
-    args.push(descendant.to_string());
-    crate::git::repository::exec_git(&args).is_ok()
-}
-
 struct RebaseArgsSummary {
     is_control_mode: bool,
     has_root: bool,
@@ -197,7 +197,9 @@ fn handle_reset_preserve_working_dir(
     }
 
     // Check direction: are we resetting backward or forward?
-    let is_backward = is_ancestor(repository, target_commit_sha, old_head_sha);
+    let is_backward = repository
+        .is_ancestor(target_commit_sha, old_head_sha)
+        .unwrap_or(false);
 
     if !is_backward {
         // Non-ancestor reset (e.g. Graphite restacking the currently checked-out branch).
@@ -272,7 +274,9 @@ fn handle_reset_pathspec_preserve_working_dir(
     // but only for the specified pathspecs
 
     // Check if this is a backward reset
-    let is_backward = is_ancestor(repository, target_commit_sha, old_head_sha);
+    let is_backward = repository
+        .is_ancestor(target_commit_sha, old_head_sha)
+        .unwrap_or(false);
 
     if !is_backward {
         debug_log("Pathspec reset forward or to unrelated commit, no reconstruction needed");
@@ -367,17 +371,6 @@ fn resolve_tree_ish_to_commit(
         .map(|commit| commit.id().to_string())
 }
 
-/// Check if 'ancestor' is an ancestor of 'descendant'
-fn is_ancestor(repository: &Repository, ancestor: &str, descendant: &str) -> bool {
-    let mut args = repository.global_args_for_exec();
-    args.push("merge-base".to_string());
-    args.push("--is-ancestor".to_string());
-    args.push(ancestor.to_string());
-    args.push(descendant.to_string());
-
-    crate::git::repository::exec_git(&args).is_ok()
-}
-
 /// Extract the tree-ish argument from git reset command
 /// Returns "HEAD" by default if no tree-ish is provided
 fn extract_tree_ish(parsed_args: &ParsedGitInvocation) -> String {
@@ -69,7 +69,10 @@ pub fn post_update_ref_hook(
         return;
     }
 
-    if is_ancestor(repository, &old_target, &new_target) {
+    if repository
+        .is_ancestor(&old_target, &new_target)
+        .unwrap_or(false)
+    {
         if affects_checked_out_branch {
             let _ = repository
                 .storage
@@ -78,7 +81,10 @@ pub fn post_update_ref_hook(
         return;
     }
 
-    if is_ancestor(repository, &new_target, &old_target) {
+    if repository
+        .is_ancestor(&new_target, &old_target)
+        .unwrap_or(false)
+    {
         debug_log(&format!(
             "Skipping wrapper update-ref rewind handling for {}: {} -> {}",
             ref_name, old_target, new_target
@@ -165,15 +171,6 @@ fn resolve_ref_target(repository: &Repository, ref_name: &str) -> Option<String>
         .ok()
 }
 
-fn is_ancestor(repository: &Repository, ancestor: &str, descendant: &str) -> bool {
-    let mut args = repository.global_args_for_exec();
-    args.push("merge-base".to_string());
-    args.push("--is-ancestor".to_string());
-    args.push(ancestor.to_string());
-    args.push(descendant.to_string());
-    crate::git::repository::exec_git(&args).is_ok()
-}
-
 #[cfg(test)]
 mod tests {
     use super::parse_simple_update_ref;
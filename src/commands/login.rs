@@ -7,9 +7,7 @@ pub fn handle_login(_args: &[String]) {
     let store = CredentialStore::new();
 
     // Check if already logged in
-    if let Ok(Some(creds)) = store.load()
-        && !creds.is_refresh_token_expired()
-    {
+    if store.is_logged_in() {
         eprintln!("Already logged in. Use 'git-ai logout' to log out first.");
         std::process::exit(0);
     }
@@ -1,25 +1,105 @@
 use crate::auth::CredentialStore;
+use crate::auth::credentials::{INSTALL_PAGE_URL_FILE, OAUTH_TOKEN_PATH_FILE};
+use crate::config;
+use crate::error::GitAiError;
+use crate::feature_flags::REMOTE_FLAGS_CACHE_FILE;
+use std::path::Path;
+
+/// Files in `~/.git-ai/internal` that `logout --all` removes along with every account's
+/// credentials.
+/// Each is recreated on demand, so removing them only resets cached state.
+const SESSION_CACHE_FILES: &[&str] = &[
+    "update_check",
+    "last_flush_trigger_ts",
+    INSTALL_PAGE_URL_FILE,
+    OAUTH_TOKEN_PATH_FILE,
+    REMOTE_FLAGS_CACHE_FILE,
+];
 
 /// Handle the `git-ai logout` command
-pub fn handle_logout(_args: &[String]) {
+pub fn handle_logout(args: &[String]) {
+    let mut clear_all = false;
+    for arg in args {
+        match arg.as_str() {
+            "--all" => clear_all = true,
+            _ => {
+                eprintln!("Unknown option: {}", arg);
+                std::process::exit(1);
+            }
+        }
+    }
+
     let store = CredentialStore::new();
 
-    // Check if currently logged in
-    match store.load() {
-        Ok(Some(_)) => {
-            // Clear credentials
-            if let Err(e) = store.clear() {
-                eprintln!("Failed to clear credentials: {}", e);
+    // Unreadable credentials are cleared too, so only a successful load counts as logged in
+    let was_logged_in = matches!(store.load(), Ok(Some(_)));
+    let cleared = if clear_all {
+        store.clear_all()
+    } else {
+        store.clear()
+    };
+    if let Err(e) = cleared {
+        eprintln!("Failed to clear credentials: {}", e);
+        std::process::exit(1);
+    }
+    if was_logged_in {
+        eprintln!("Successfully logged out.");
+    } else {
+        eprintln!("Not currently logged in.");
+    }
+
+    if clear_all && let Some(internal_dir) = config::internal_dir_path() {
+        match remove_session_cache_files(&internal_dir) {
+            Ok(0) => {}
+            Ok(removed) => eprintln!("Removed {} cached session file(s).", removed),
+            Err(e) => {
+                eprintln!("Failed to remove cached session files: {}", e);
                 std::process::exit(1);
             }
-            eprintln!("Successfully logged out.");
         }
-        Ok(None) => {
-            eprintln!("Not currently logged in.");
+    }
+}
+
+/// Remove every [`SESSION_CACHE_FILES`] entry in `internal_dir`, returning how many existed
+fn remove_session_cache_files(internal_dir: &Path) -> Result<usize, GitAiError> {
+    let mut removed = 0;
+    for name in SESSION_CACHE_FILES {
+        match std::fs::remove_file(internal_dir.join(name)) {
+            Ok(()) => removed += 1,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
         }
-        Err(e) => {
-            eprintln!("Error checking credentials: {}", e);
-            std::process::exit(1);
+    }
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remove_session_cache_files_clears_each_cache_file() {
+        for name in SESSION_CACHE_FILES {
+            let dir = tempfile::tempdir().unwrap();
+            std::fs::write(dir.path().join(name), "cached").unwrap();
+            std::fs::write(dir.path().join("distinct_id"), "id").unwrap();
+
+            assert_eq!(
+                remove_session_cache_files(dir.path()).unwrap(),
+                1,
+                "{}",
+                name
+            );
+            assert!(!dir.path().join(name).exists(), "{}", name);
+            assert!(dir.path().join("distinct_id").exists(), "{}", name);
+
+            // Already gone, so a second logout removes nothing
+            assert_eq!(
+                remove_session_cache_files(dir.path()).unwrap(),
+                0,
+                "{}",
+                name
+            );
         }
     }
 }
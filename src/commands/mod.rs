@@ -6,7 +6,9 @@ pub mod config;
 pub mod continue_session;
 pub mod debug;
 pub mod diff;
+pub mod doctor;
 pub mod exchange_nonce;
+pub mod export;
 pub mod flush_cas;
 pub mod flush_logs;
 pub mod flush_metrics_db;
@@ -20,6 +22,8 @@ pub mod logout;
 pub mod personal_dashboard;
 pub mod prompt_picker;
 pub mod prompts_db;
+pub mod prune;
+pub mod relink;
 pub mod search;
 pub mod share;
 pub mod share_tui;
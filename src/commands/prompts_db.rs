@@ -7,7 +7,8 @@ use crate::authorship::internal_db::InternalDatabase;
 use crate::authorship::transcript::AiTranscript;
 use crate::error::GitAiError;
 use crate::git::find_repository_in_path;
-use crate::git::repository::{Repository, exec_git, exec_git_stdin};
+use crate::git::repository::{Repository, exec_git, exec_git_stdin_writer};
+use crate::utils::debug_log;
 use chrono::{Local, TimeZone};
 use rusqlite::{Connection, params};
 use serde::Serialize;
@@ -917,7 +918,7 @@ fn get_commits_with_notes_since(repo: &Repository, since_timestamp: i64) -> Vec<
     let commit_set: HashSet<String> = commits_since.into_iter().collect();
 
     // Step 2: Get all notes mappings (note_blob_sha, commit_sha)
-    let note_mappings = get_notes_list(&global_args);
+    let note_mappings = get_notes_list(&global_args, &repo.notes_ref());
 
     // Step 3: Filter to notes for commits in our time range
     let filtered: Vec<(String, String)> = note_mappings
@@ -963,11 +964,11 @@ fn get_commits_since(global_args: &[String], since_timestamp: i64) -> Vec<String
         .collect()
 }
 
-/// Get all notes as (note_blob_sha, commit_sha) pairs
-fn get_notes_list(global_args: &[String]) -> Vec<(String, String)> {
+/// Get all notes on `notes_ref` as (note_blob_sha, commit_sha) pairs
+fn get_notes_list(global_args: &[String], notes_ref: &str) -> Vec<(String, String)> {
     let mut args = global_args.to_vec();
     args.push("notes".to_string());
-    args.push("--ref=ai".to_string());
+    args.push(format!("--ref={}", notes_ref));
     args.push("list".to_string());
 
     let output = match exec_git(&args) {
@@ -1002,12 +1003,19 @@ fn batch_read_blobs(global_args: &[String], blob_shas: &[String]) -> Vec<String>
     args.push("cat-file".to_string());
     args.push("--batch".to_string());
 
-    // Prepare stdin: one SHA per line
-    let stdin_data = blob_shas.join("\n") + "\n";
-
-    let output = match exec_git_stdin(&args, stdin_data.as_bytes()) {
+    // Stream stdin: one SHA per line
+    let shas = blob_shas.to_vec();
+    let output = match exec_git_stdin_writer(&args, move |stdin| {
+        for sha in &shas {
+            writeln!(stdin, "{}", sha)?;
+        }
+        Ok(())
+    }) {
         Ok(o) => o,
-        Err(_) => return Vec::new(),
+        Err(e) => {
+            debug_log(&format!("Failed to read prompt note blobs: {}", e));
+            return Vec::new();
+        }
     };
 
     // Parse batch output
@@ -1084,3 +1092,30 @@ fn parse_cat_file_batch_output(data: &[u8]) -> Vec<String> {
 
     results
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::refs::notes_add;
+    use crate::git::test_utils::TmpRepo;
+
+    #[test]
+    fn test_get_notes_list_reads_configured_notes_ref() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        tmp_repo.write_file("a.txt", "a\n", true).unwrap();
+        tmp_repo.commit_with_message("Initial").unwrap();
+        let commit_sha = tmp_repo.get_head_commit_sha().unwrap();
+        tmp_repo
+            .git_command(&["config", "git-ai.notesRef", "team-ai"])
+            .unwrap();
+        notes_add(tmp_repo.gitai_repo(), &commit_sha, "note").unwrap();
+
+        let repo = tmp_repo.gitai_repo();
+        let global_args = repo.global_args_for_exec();
+        let mappings = get_notes_list(&global_args, &repo.notes_ref());
+
+        assert_eq!(repo.notes_ref(), "refs/notes/team-ai");
+        assert_eq!(mappings.len(), 1);
+        assert_eq!(mappings[0].1, commit_sha);
+    }
+}
@@ -0,0 +1,164 @@
+use crate::error::GitAiError;
+use crate::git::authorship_traversal::unreachable_annotated_commits;
+use crate::git::find_repository;
+use crate::git::refs::notes_remove_batch;
+use crate::git::repository::Repository;
+
+const USAGE: &str = "Usage: git-ai prune [--dry-run]";
+
+pub fn handle_prune(args: &[String]) {
+    let mut dry_run = false;
+    for arg in args {
+        match arg.as_str() {
+            "--dry-run" | "-n" => dry_run = true,
+            "--help" | "-h" => {
+                eprintln!("git-ai prune - Drop authorship notes for commits no ref reaches");
+                eprintln!();
+                eprintln!("{}", USAGE);
+                eprintln!();
+                eprintln!("Only commits present in this clone are considered. Removed notes are");
+                eprintln!("also removed from remotes the next time authorship notes are pushed.");
+                std::process::exit(0);
+            }
+            other => {
+                eprintln!("Unknown prune argument: {}", other);
+                eprintln!("{}", USAGE);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    let repo = match find_repository(&[]) {
+        Ok(repo) => repo,
+        Err(e) => {
+            eprintln!("Failed to find repository: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    match prune_unreachable_notes(&repo, dry_run) {
+        Ok(pruned) if dry_run => {
+            for commit_sha in &pruned {
+                println!("Would prune note for {}", commit_sha);
+            }
+            println!("{} note(s) would be pruned", pruned.len());
+        }
+        Ok(pruned) => {
+            println!("Pruned {} note(s)", pruned.len());
+            if !pruned.is_empty() {
+                println!("The removals reach remotes on the next authorship notes push.");
+            }
+        }
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Remove the notes of local commits no ref reaches anymore, returning those commits. With
+/// `dry_run` the notes are left in place.
+///
+/// The notes ref is shared, so removals propagate to remotes on the next notes push; see
+/// [`unreachable_annotated_commits`] for why commits missing from this clone are kept.
+pub fn prune_unreachable_notes(
+    repo: &Repository,
+    dry_run: bool,
+) -> Result<Vec<String>, GitAiError> {
+    let unreachable = unreachable_annotated_commits(repo)?;
+    if !dry_run {
+        notes_remove_batch(repo, &unreachable)?;
+    }
+    Ok(unreachable)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::refs::{merge_notes_from_ref, notes_add, show_authorship_note};
+    use crate::git::test_utils::TmpRepo;
+
+    #[test]
+    fn prune_removes_only_notes_of_unreachable_commits() {
+        let repo = TmpRepo::new().unwrap();
+        repo.write_file("a.txt", "a\n", true).unwrap();
+        repo.commit_with_message("Commit A").unwrap();
+        let commit_a = repo.get_head_commit_sha().unwrap();
+        let main = repo.current_branch().unwrap();
+
+        repo.git_command(&["checkout", "-q", "-b", "doomed"])
+            .unwrap();
+        repo.write_file("b.txt", "b\n", true).unwrap();
+        repo.commit_with_message("Commit B").unwrap();
+        let commit_b = repo.get_head_commit_sha().unwrap();
+        repo.git_command(&["checkout", "-q", &main]).unwrap();
+        repo.git_command(&["branch", "-q", "-D", "doomed"]).unwrap();
+
+        let gitai_repo = repo.gitai_repo();
+        notes_add(gitai_repo, &commit_a, "note for A").unwrap();
+        notes_add(gitai_repo, &commit_b, "note for B").unwrap();
+
+        assert_eq!(
+            prune_unreachable_notes(gitai_repo, true).unwrap(),
+            vec![commit_b.clone()]
+        );
+        assert!(show_authorship_note(gitai_repo, &commit_b).is_some());
+
+        assert_eq!(
+            prune_unreachable_notes(gitai_repo, false).unwrap(),
+            vec![commit_b.clone()]
+        );
+        assert!(show_authorship_note(gitai_repo, &commit_b).is_none());
+        assert_eq!(
+            show_authorship_note(gitai_repo, &commit_a).as_deref(),
+            Some("note for A")
+        );
+        assert!(
+            prune_unreachable_notes(gitai_repo, false)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn prune_keeps_notes_of_commits_missing_from_this_clone() {
+        let repo = TmpRepo::new().unwrap();
+        repo.write_file("a.txt", "a\n", true).unwrap();
+        repo.commit_with_message("Commit A").unwrap();
+
+        // A teammate's commit whose note arrives without the commit itself
+        let teammate = TmpRepo::new().unwrap();
+        teammate.write_file("b.txt", "b\n", true).unwrap();
+        teammate.commit_with_message("Commit B").unwrap();
+        let commit_b = teammate.get_head_commit_sha().unwrap();
+        notes_add(teammate.gitai_repo(), &commit_b, "note for B").unwrap();
+        repo.git_command(&[
+            "fetch",
+            "-q",
+            teammate.path().to_str().unwrap(),
+            "refs/notes/ai:refs/notes/teammate",
+        ])
+        .unwrap();
+        let gitai_repo = repo.gitai_repo();
+        merge_notes_from_ref(gitai_repo, "refs/notes/teammate").unwrap();
+        assert!(
+            repo.git_command(&["cat-file", "-e", &commit_b]).is_err(),
+            "commit B should not be in this clone"
+        );
+
+        assert!(
+            prune_unreachable_notes(gitai_repo, true)
+                .unwrap()
+                .is_empty()
+        );
+        assert!(
+            prune_unreachable_notes(gitai_repo, false)
+                .unwrap()
+                .is_empty()
+        );
+        assert_eq!(
+            show_authorship_note(gitai_repo, &commit_b).as_deref(),
+            Some("note for B")
+        );
+    }
+}
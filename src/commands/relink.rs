@@ -0,0 +1,231 @@
+use crate::error::GitAiError;
+use crate::git::find_repository;
+use crate::git::repo_storage::WorkingLogRename;
+use crate::git::repository::Repository;
+use std::io::{BufRead, IsTerminal, Write};
+
+const USAGE: &str = "Usage: git-ai relink <old-sha> <new-sha>\n       git-ai relink --auto [--yes]";
+
+pub fn handle_relink(args: &[String]) {
+    let mut auto = false;
+    let mut assume_yes = false;
+    let mut positional = Vec::new();
+
+    for arg in args {
+        match arg.as_str() {
+            "--auto" => auto = true,
+            "--yes" | "-y" => assume_yes = true,
+            "--help" | "-h" => {
+                eprintln!("git-ai relink - Move a stranded working log onto another commit");
+                eprintln!();
+                eprintln!("{}", USAGE);
+                std::process::exit(0);
+            }
+            other if other.starts_with('-') => {
+                eprintln!("Unknown relink argument: {}", other);
+                eprintln!("{}", USAGE);
+                std::process::exit(1);
+            }
+            other => positional.push(other.to_string()),
+        }
+    }
+
+    let repo = match find_repository(&[]) {
+        Ok(repo) => repo,
+        Err(e) => {
+            eprintln!("Failed to find repository: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let result = match (auto, positional.as_slice()) {
+        (true, []) => run_relink_auto(&repo, assume_yes),
+        (false, [old_sha, new_sha]) => relink_working_log(&repo, old_sha, new_sha).map(|_| ()),
+        _ => {
+            eprintln!("{}", USAGE);
+            std::process::exit(1);
+        }
+    };
+    if let Err(e) = result {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+}
+
+/// Move the working log for `old_rev` onto `new_rev`, merging into any log already there.
+/// Both revisions must resolve to commits and `old_rev` must have a working log.
+/// Returns the resolved (old, new) commit SHAs.
+pub fn relink_working_log(
+    repo: &Repository,
+    old_rev: &str,
+    new_rev: &str,
+) -> Result<(String, String), GitAiError> {
+    let old_sha = resolve_commit(repo, old_rev)?;
+    let new_sha = resolve_commit(repo, new_rev)?;
+
+    if !repo.storage.has_working_log(&old_sha) {
+        return Err(GitAiError::Generic(format!(
+            "No working log found for {}",
+            old_sha
+        )));
+    }
+    if old_sha == new_sha {
+        eprintln!("Working log is already on {}", new_sha);
+        return Ok((old_sha, new_sha));
+    }
+
+    match repo.storage.rename_working_log(&old_sha, &new_sha)? {
+        WorkingLogRename::DestinationExists => {
+            repo.storage.merge_working_logs(&old_sha, &new_sha)?;
+            eprintln!(
+                "Merged working log {} into existing log for {}",
+                short(&old_sha),
+                short(&new_sha)
+            );
+        }
+        _ => eprintln!(
+            "Relinked working log {} -> {}",
+            short(&old_sha),
+            short(&new_sha)
+        ),
+    }
+    Ok((old_sha, new_sha))
+}
+
+/// Working logs keyed on a commit other than HEAD, sorted by SHA
+pub fn stranded_working_logs(repo: &Repository) -> Result<Vec<String>, GitAiError> {
+    let head_sha = repo.head()?.target()?;
+    let mut stranded: Vec<String> = std::fs::read_dir(&repo.storage.working_logs)?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        // Skips the `old-<sha>` copies kept by debug builds
+        .filter(|name| is_full_sha(name) && *name != head_sha)
+        .collect();
+    stranded.sort();
+    Ok(stranded)
+}
+
+fn run_relink_auto(repo: &Repository, assume_yes: bool) -> Result<(), GitAiError> {
+    let head_sha = repo.head()?.target()?;
+    let stranded = stranded_working_logs(repo)?;
+    if stranded.is_empty() {
+        eprintln!("No stranded working logs found");
+        return Ok(());
+    }
+
+    let interactive = std::io::stdin().is_terminal();
+    if !assume_yes && !interactive {
+        eprintln!("Working logs not on HEAD ({}):", short(&head_sha));
+        for sha in &stranded {
+            eprintln!("  {}", sha);
+        }
+        eprintln!("Re-run with --yes to relink them to HEAD");
+        return Ok(());
+    }
+
+    for sha in stranded {
+        if !assume_yes
+            && !confirm(&format!(
+                "Relink working log {} to HEAD ({})? [y/N] ",
+                short(&sha),
+                short(&head_sha)
+            ))?
+        {
+            continue;
+        }
+        relink_working_log(repo, &sha, &head_sha)?;
+    }
+    Ok(())
+}
+
+fn resolve_commit(repo: &Repository, rev: &str) -> Result<String, GitAiError> {
+    repo.revparse_single(rev)
+        .and_then(|object| object.peel_to_commit())
+        .map(|commit| commit.id())
+        .map_err(|_| GitAiError::Generic(format!("Commit not found: {}", rev)))
+}
+
+fn confirm(prompt: &str) -> Result<bool, GitAiError> {
+    eprint!("{}", prompt);
+    std::io::stderr().flush().ok();
+    let mut input = String::new();
+    std::io::stdin()
+        .lock()
+        .read_line(&mut input)
+        .map_err(|e| GitAiError::Generic(format!("Failed to read input: {}", e)))?;
+    Ok(matches!(input.trim(), "y" | "Y" | "yes"))
+}
+
+fn is_full_sha(name: &str) -> bool {
+    (name.len() == 40 || name.len() == 64) && name.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+fn short(sha: &str) -> &str {
+    &sha[..sha.len().min(7)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::test_utils::{TmpRepo, seed_working_log};
+
+    #[test]
+    fn test_relink_moves_working_log_to_new_commit() {
+        let repo = TmpRepo::new().unwrap();
+        repo.write_file("file.txt", "one\n", true).unwrap();
+        repo.commit_with_message("one").unwrap();
+        let old_sha = repo.get_head_commit_sha().unwrap();
+        repo.write_file("file.txt", "two\n", true).unwrap();
+        repo.commit_with_message("two").unwrap();
+        let new_sha = repo.get_head_commit_sha().unwrap();
+        seed_working_log(repo.gitai_repo(), &old_sha);
+
+        let storage = &repo.gitai_repo().storage;
+        assert_eq!(
+            stranded_working_logs(repo.gitai_repo()).unwrap(),
+            vec![old_sha.clone()]
+        );
+
+        let resolved = relink_working_log(repo.gitai_repo(), &old_sha[..10], "HEAD").unwrap();
+
+        assert_eq!(resolved, (old_sha.clone(), new_sha.clone()));
+        assert!(!storage.has_working_log(&old_sha));
+        let initial = storage
+            .working_log_for_base_commit(&new_sha)
+            .read_initial_attributions();
+        assert_eq!(initial.files["file.txt"][0].author_id, "ai-author");
+        assert!(stranded_working_logs(repo.gitai_repo()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_relink_errors_when_source_log_is_missing() {
+        let repo = TmpRepo::new().unwrap();
+        repo.write_file("file.txt", "one\n", true).unwrap();
+        repo.commit_with_message("one").unwrap();
+        let old_sha = repo.get_head_commit_sha().unwrap();
+        repo.write_file("file.txt", "two\n", true).unwrap();
+        repo.commit_with_message("two").unwrap();
+        repo.gitai_repo()
+            .storage
+            .delete_working_log_for_base_commit(&old_sha)
+            .unwrap();
+
+        let err = relink_working_log(repo.gitai_repo(), &old_sha, "HEAD").unwrap_err();
+        assert!(err.to_string().contains("No working log found"), "{}", err);
+    }
+
+    #[test]
+    fn test_relink_rejects_unknown_commits() {
+        let repo = TmpRepo::new().unwrap();
+        repo.write_file("file.txt", "one\n", true).unwrap();
+        repo.commit_with_message("one").unwrap();
+
+        let err = relink_working_log(repo.gitai_repo(), "no-such-rev", "HEAD").unwrap_err();
+        assert!(
+            err.to_string().contains("Commit not found: no-such-rev"),
+            "{}",
+            err
+        );
+    }
+}
@@ -6,10 +6,11 @@ use crate::authorship::virtual_attribution::VirtualAttributions;
 use crate::authorship::working_log::CheckpointKind;
 use crate::commands::checkpoint;
 use crate::error::GitAiError;
+use crate::git::authorship_traversal::{load_ai_touched_files_matching, load_all_ai_touched_files};
 use crate::git::find_repository;
 use crate::git::repo_storage::InitialAttributions;
 use crate::git::repository::{InternalGitProfile, Repository, exec_git_with_profile};
-use crate::git::status::MAX_PATHSPEC_ARGS;
+use crate::git::status::{EntryKind, MAX_PATHSPEC_ARGS, StatusCode, StatusEntry};
 use serde::Serialize;
 use std::collections::{BTreeMap, HashSet};
 use std::time::{SystemTime, UNIX_EPOCH};
@@ -29,18 +30,45 @@ struct StatusOutput {
     checkpoints: Vec<CheckpointInfo>,
 }
 
+/// Files with recorded AI attributions that have uncommitted changes, grouped like `git status`
+#[derive(Debug, Default, PartialEq, Serialize)]
+struct AttributedFilesStatus {
+    /// Number of files with AI attributions in the authorship notes
+    attributed_files: usize,
+    staged: Vec<String>,
+    unstaged: Vec<String>,
+    untracked: Vec<String>,
+}
+
 pub fn handle_status(args: &[String]) {
     let mut json_output = false;
+    let mut files_output = false;
+    let mut pattern = None;
 
     let mut i = 0;
     while i < args.len() {
-        if args[i].as_str() == "--json" {
-            json_output = true;
+        match args[i].as_str() {
+            "--json" => json_output = true,
+            "--files" => files_output = true,
+            arg if !arg.starts_with('-') => pattern = Some(arg),
+            _ => {}
         }
         i += 1;
     }
 
-    if let Err(e) = run_status(json_output) {
+    // The glob only filters `--files` output, so reject it rather than silently ignore it
+    if let Some(pattern) = pattern.filter(|_| !files_output) {
+        eprintln!("Error: '{}' requires --files", pattern);
+        eprintln!("Usage: git-ai status [--json] [--files [<glob>]]");
+        std::process::exit(1);
+    }
+
+    let result = if files_output {
+        run_status_files(json_output, pattern)
+    } else {
+        run_status(json_output)
+    };
+    if let Err(e) = result {
         eprintln!("Error: {}", e);
         std::process::exit(1);
     }
@@ -195,6 +223,109 @@ fn run_status(json: bool) -> Result<(), GitAiError> {
     Ok(())
 }
 
+fn run_status_files(json: bool, pattern: Option<&str>) -> Result<(), GitAiError> {
+    let repo = find_repository(&[])?;
+    let status = attributed_files_status(&repo, pattern)?;
+
+    if json {
+        println!("{}", serde_json::to_string(&status)?);
+        return Ok(());
+    }
+
+    if status.attributed_files == 0 {
+        println!("No AI attributions recorded");
+        return Ok(());
+    }
+
+    println!(
+        "{} file{} with AI attributions",
+        status.attributed_files,
+        if status.attributed_files == 1 {
+            ""
+        } else {
+            "s"
+        }
+    );
+    if status.staged.is_empty() && status.unstaged.is_empty() && status.untracked.is_empty() {
+        println!("No attributed files have uncommitted changes");
+        return Ok(());
+    }
+    for (label, files) in [
+        ("Staged", &status.staged),
+        ("Unstaged", &status.unstaged),
+        ("Untracked", &status.untracked),
+    ] {
+        if files.is_empty() {
+            continue;
+        }
+        println!();
+        println!("{}:", label);
+        for file in files {
+            println!("  {}", file);
+        }
+    }
+
+    Ok(())
+}
+
+/// Intersect the files named in authorship notes (only those matching `pattern`, if given) with
+/// the working tree status
+fn attributed_files_status(
+    repo: &Repository,
+    pattern: Option<&str>,
+) -> Result<AttributedFilesStatus, GitAiError> {
+    let loaded = match pattern {
+        Some(pattern) => smol::block_on(load_ai_touched_files_matching(repo, pattern)),
+        None => smol::block_on(load_all_ai_touched_files(repo)),
+    };
+    let attributed = match loaded {
+        Ok(files) => files,
+        Err(GitAiError::CorruptNotes(ref_name)) => {
+            eprintln!(
+                "Warning: {} is corrupt or unreadable; ignoring recorded AI attributions",
+                ref_name
+            );
+            HashSet::new()
+        }
+        Err(e) => return Err(e),
+    };
+    if attributed.is_empty() {
+        return Ok(AttributedFilesStatus::default());
+    }
+    let entries = repo.status(Some(&attributed), false)?;
+    Ok(group_attributed_files(&attributed, &entries))
+}
+
+fn group_attributed_files(
+    attributed: &HashSet<String>,
+    entries: &[StatusEntry],
+) -> AttributedFilesStatus {
+    let mut status = AttributedFilesStatus {
+        attributed_files: attributed.len(),
+        ..Default::default()
+    };
+
+    for entry in entries.iter().filter(|e| attributed.contains(&e.path)) {
+        match entry.kind {
+            EntryKind::Untracked => status.untracked.push(entry.path.clone()),
+            EntryKind::Ignored => {}
+            _ => {
+                if entry.staged != StatusCode::Unmodified {
+                    status.staged.push(entry.path.clone());
+                }
+                if entry.unstaged != StatusCode::Unmodified {
+                    status.unstaged.push(entry.path.clone());
+                }
+            }
+        }
+    }
+
+    status.staged.sort();
+    status.unstaged.sort();
+    status.untracked.sort();
+    status
+}
+
 fn format_time_ago(timestamp: u64) -> String {
     let now = SystemTime::now()
         .duration_since(UNIX_EPOCH)
@@ -331,7 +462,7 @@ fn count_ai_lines_from_initial(
 mod tests {
     use super::*;
     use crate::git::status::MAX_PATHSPEC_ARGS;
-    use crate::git::test_utils::TmpRepo;
+    use crate::git::test_utils::{TmpRepo, note_with_attestations};
 
     /// Pad a set of real paths with non-existent paths to exceed MAX_PATHSPEC_ARGS.
     fn padded_pathspecs(real_paths: &[&str]) -> HashSet<String> {
@@ -540,4 +671,71 @@ mod tests {
         let ai_lines = count_ai_lines_from_initial(&initial, &ignore_matcher);
         assert_eq!(ai_lines, 2);
     }
+
+    #[test]
+    fn test_attributed_files_status_groups_changed_files() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        for file in [
+            "staged.rs",
+            "unstaged.rs",
+            "clean.rs",
+            "recreated.rs",
+            "human.rs",
+        ] {
+            tmp_repo.write_file(file, "fn a() {}\n", true).unwrap();
+        }
+        tmp_repo.commit_with_message("Initial").unwrap();
+        let commit_sha = tmp_repo.get_head_commit_sha().unwrap();
+        crate::git::refs::notes_add(
+            tmp_repo.gitai_repo(),
+            &commit_sha,
+            &note_with_attestations(&[
+                ("staged.rs", "aaaaaaa", "Test <test@example.com>"),
+                ("unstaged.rs", "aaaaaaa", "Test <test@example.com>"),
+                ("clean.rs", "aaaaaaa", "Test <test@example.com>"),
+                ("recreated.rs", "aaaaaaa", "Test <test@example.com>"),
+            ]),
+        )
+        .unwrap();
+
+        tmp_repo.git_command(&["rm", "-q", "recreated.rs"]).unwrap();
+        tmp_repo.commit_with_message("Remove file").unwrap();
+
+        tmp_repo
+            .write_file("staged.rs", "fn a() {}\nfn b() {}\n", true)
+            .unwrap();
+        tmp_repo
+            .write_file("unstaged.rs", "fn a() {}\nfn b() {}\n", false)
+            .unwrap();
+        tmp_repo
+            .write_file("recreated.rs", "fn again() {}\n", false)
+            .unwrap();
+        tmp_repo
+            .write_file("human.rs", "fn a() {}\nfn b() {}\n", true)
+            .unwrap();
+
+        let status = attributed_files_status(tmp_repo.gitai_repo(), None).unwrap();
+        assert_eq!(
+            status,
+            AttributedFilesStatus {
+                attributed_files: 4,
+                staged: vec!["staged.rs".to_string()],
+                unstaged: vec!["unstaged.rs".to_string()],
+                untracked: vec!["recreated.rs".to_string()],
+            }
+        );
+    }
+
+    #[test]
+    fn test_attributed_files_status_without_notes() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        tmp_repo.write_file("a.rs", "fn a() {}\n", true).unwrap();
+        tmp_repo.commit_with_message("Initial").unwrap();
+        tmp_repo
+            .write_file("a.rs", "fn a() {}\nfn b() {}\n", true)
+            .unwrap();
+
+        let status = attributed_files_status(tmp_repo.gitai_repo(), None).unwrap();
+        assert_eq!(status, AttributedFilesStatus::default());
+    }
 }
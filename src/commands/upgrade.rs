@@ -1,6 +1,8 @@
 use crate::api::client::ApiContext;
 use crate::config::{self, UpdateChannel};
+use crate::git::repository::Repository;
 use crate::observability::log_message;
+use crate::utils::debug_log;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use std::collections::HashMap;
@@ -124,8 +126,14 @@ fn current_timestamp() -> u64 {
         .as_secs()
 }
 
-fn should_check_for_updates(channel: UpdateChannel, cache: Option<&UpdateCache>) -> bool {
-    let now = current_timestamp();
+/// Whether the cached check is older than `interval` at time `now` (seconds since the epoch),
+/// or was made for a different channel
+fn should_check_for_updates(
+    channel: UpdateChannel,
+    cache: Option<&UpdateCache>,
+    now: u64,
+    interval: Duration,
+) -> bool {
     match cache {
         Some(cache) if cache.last_checked_at > 0 => {
             // If cache doesn't match the channel, we should check for updates
@@ -133,12 +141,32 @@ fn should_check_for_updates(channel: UpdateChannel, cache: Option<&UpdateCache>)
                 return true;
             }
             let elapsed = now.saturating_sub(cache.last_checked_at);
-            elapsed > UPDATE_CHECK_INTERVAL_HOURS * 3600
+            elapsed > interval.as_secs()
         }
         _ => true,
     }
 }
 
+/// Minimum time between update checks, from `git-ai.updateCheckIntervalHours`. Defaults to 24h
+/// when unset or not a valid non-negative integer.
+fn update_check_interval(repository: &Repository) -> Duration {
+    let hours = match repository.config_get_int("git-ai.updateCheckIntervalHours") {
+        Ok(Some(hours)) => u64::try_from(hours).unwrap_or_else(|_| {
+            debug_log(&format!(
+                "ignoring negative git-ai.updateCheckIntervalHours: {}",
+                hours
+            ));
+            UPDATE_CHECK_INTERVAL_HOURS
+        }),
+        Ok(None) => UPDATE_CHECK_INTERVAL_HOURS,
+        Err(e) => {
+            debug_log(&format!("ignoring git-ai.updateCheckIntervalHours: {}", e));
+            UPDATE_CHECK_INTERVAL_HOURS
+        }
+    };
+    Duration::from_secs(hours.saturating_mul(3600))
+}
+
 fn semver_from_tag(tag: &str) -> String {
     let trimmed = tag
         .trim()
@@ -281,10 +309,27 @@ fn fetch_and_verify_install_script(
     Ok(script.to_string())
 }
 
+/// Why the release lookup failed: the API couldn't be reached, or it answered with something
+/// unusable
+#[derive(Debug)]
+enum ReleaseFetchError {
+    Network(String),
+    InvalidResponse(String),
+}
+
+impl std::fmt::Display for ReleaseFetchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReleaseFetchError::Network(e) => write!(f, "Failed to check for updates: {}", e),
+            ReleaseFetchError::InvalidResponse(e) => write!(f, "{}", e),
+        }
+    }
+}
+
 fn fetch_release_for_channel(
     api_base_url: &str,
     channel: UpdateChannel,
-) -> Result<ChannelRelease, String> {
+) -> Result<ChannelRelease, ReleaseFetchError> {
     #[cfg(test)]
     if let Some(result) = try_mock_releases(api_base_url, channel) {
         return result;
@@ -294,15 +339,16 @@ fn fetch_release_for_channel(
 
     let response = context
         .get(releases_endpoint())
-        .map_err(|e| format!("Failed to check for updates: {}", e))?;
+        .map_err(|e| ReleaseFetchError::Network(e.to_string()))?;
 
-    let body = response
-        .as_str()
-        .map_err(|e| format!("Failed to read response body: {}", e))?;
-    let releases: ReleasesResponse = serde_json::from_str(body)
-        .map_err(|e| format!("Failed to parse release response: {}", e))?;
+    let body = response.as_str().map_err(|e| {
+        ReleaseFetchError::InvalidResponse(format!("Failed to read response body: {}", e))
+    })?;
+    let releases: ReleasesResponse = serde_json::from_str(body).map_err(|e| {
+        ReleaseFetchError::InvalidResponse(format!("Failed to parse release response: {}", e))
+    })?;
 
-    release_from_response(releases, channel)
+    release_from_response(releases, channel).map_err(ReleaseFetchError::InvalidResponse)
 }
 
 fn release_from_response(
@@ -339,12 +385,16 @@ fn release_from_response(
 }
 
 #[cfg(test)]
-fn try_mock_releases(base: &str, channel: UpdateChannel) -> Option<Result<ChannelRelease, String>> {
+fn try_mock_releases(
+    base: &str,
+    channel: UpdateChannel,
+) -> Option<Result<ChannelRelease, ReleaseFetchError>> {
     let json = base.strip_prefix("mock://")?;
     Some(
         serde_json::from_str::<ReleasesResponse>(json)
             .map_err(|e| format!("Invalid mock releases payload: {}", e))
-            .and_then(|releases| release_from_response(releases, channel)),
+            .and_then(|releases| release_from_response(releases, channel))
+            .map_err(ReleaseFetchError::InvalidResponse),
     )
 }
 
@@ -491,6 +541,77 @@ fn run_install_script(script_content: &str, tag: &str, silent: bool) -> Result<(
     }
 }
 
+/// Version of the running git-ai binary
+pub fn current_version() -> &'static str {
+    env!("CARGO_PKG_VERSION")
+}
+
+/// `git-ai version [--check]`: print the running version and, with `--check`, ask the release
+/// API whether the configured channel has something newer.
+pub fn handle_version(args: &[String]) {
+    let mut check = false;
+    for arg in args {
+        match arg.as_str() {
+            "--check" => check = true,
+            _ => {
+                eprintln!("Unknown argument: {}", arg);
+                eprintln!("Usage: git-ai version [--check]");
+                std::process::exit(1);
+            }
+        }
+    }
+
+    if cfg!(debug_assertions) {
+        println!("{} (debug)", current_version());
+    } else {
+        println!("{}", current_version());
+    }
+
+    if check {
+        let config = config::Config::get();
+        let channel = config.update_channel();
+        let release = fetch_release_for_channel(config.api_base_url(), channel);
+        println!(
+            "{}",
+            version_check_summary(current_version(), channel, &release)
+        );
+    }
+    std::process::exit(0);
+}
+
+/// One-line verdict for `git-ai version --check`
+fn version_check_summary(
+    current_version: &str,
+    channel: UpdateChannel,
+    release: &Result<ChannelRelease, ReleaseFetchError>,
+) -> String {
+    let release = match release {
+        Ok(release) => release,
+        Err(ReleaseFetchError::Network(_)) => {
+            return "could not check: network error".to_string();
+        }
+        Err(e @ ReleaseFetchError::InvalidResponse(_)) => {
+            return format!("could not check: {}", e);
+        }
+    };
+
+    match determine_action(false, release, current_version) {
+        UpgradeAction::UpgradeAvailable => format!(
+            "update available: v{} on the {} channel (run `git-ai upgrade`)",
+            release.semver,
+            channel.as_str()
+        ),
+        UpgradeAction::RunningNewerVersion => format!(
+            "running a newer version than the {} channel (v{})",
+            channel.as_str(),
+            release.semver
+        ),
+        UpgradeAction::AlreadyLatest | UpgradeAction::ForceReinstall => {
+            format!("up to date with the {} channel", channel.as_str())
+        }
+    }
+}
+
 pub fn run_with_args(args: &[String]) {
     let mut force = false;
     let mut background = false;
@@ -523,7 +644,7 @@ fn run_impl_with_url(
     channel: UpdateChannel,
     skip_install: bool,
 ) -> UpgradeAction {
-    let current_version = env!("CARGO_PKG_VERSION");
+    let current_version = current_version();
 
     println!("Checking for updates (channel: {})...", channel.as_str());
 
@@ -671,7 +792,7 @@ fn print_cached_notice(cache: &UpdateCache) {
         return;
     }
 
-    let current_version = env!("CARGO_PKG_VERSION");
+    let current_version = current_version();
     let available_version = cache.available_semver.as_deref().unwrap_or("");
 
     eprintln!();
@@ -685,7 +806,24 @@ fn print_cached_notice(cache: &UpdateCache) {
     eprintln!();
 }
 
-pub fn maybe_schedule_background_update_check() {
+/// Spawn a detached `git-ai upgrade --background` worker when an update check is due.
+///
+/// Only local state is read here (config and the update-check cache); the release lookup runs
+/// in the worker process under the API client's timeout, so this never waits on the network.
+/// `git-ai.autoUpdateCheck=false` turns it off entirely.
+pub fn maybe_schedule_background_update_check(repository: &Repository) {
+    maybe_schedule_background_update_check_with(repository, spawn_background_upgrade_process);
+}
+
+fn maybe_schedule_background_update_check_with<F>(repository: &Repository, spawn_worker: F)
+where
+    F: FnOnce() -> bool,
+{
+    if !auto_update_check_enabled(repository) {
+        debug_log("git-ai.autoUpdateCheck is false; skipping update check");
+        return;
+    }
+
     let config = config::Config::get();
     if config.version_checks_disabled() {
         return;
@@ -702,21 +840,32 @@ pub fn maybe_schedule_background_update_check() {
         print_cached_notice(cache);
     }
 
-    if !should_check_for_updates(channel, cache.as_ref()) {
+    let now = current_timestamp();
+    if !should_check_for_updates(
+        channel,
+        cache.as_ref(),
+        now,
+        update_check_interval(repository),
+    ) {
         return;
     }
 
-    let now = current_timestamp();
     let last_spawn = LAST_BACKGROUND_SPAWN.load(Ordering::SeqCst);
     if now.saturating_sub(last_spawn) < BACKGROUND_SPAWN_THROTTLE_SECS {
         return;
     }
 
-    if spawn_background_upgrade_process() {
+    if spawn_worker() {
         LAST_BACKGROUND_SPAWN.store(now, Ordering::SeqCst);
     }
 }
 
+/// Whether `git-ai.autoUpdateCheck` allows background update checks. Defaults to true when
+/// unset or not a valid boolean.
+fn auto_update_check_enabled(repository: &Repository) -> bool {
+    repository.config_get_bool_or("git-ai.autoUpdateCheck", true)
+}
+
 fn spawn_background_upgrade_process() -> bool {
     crate::utils::spawn_internal_git_ai_subcommand(
         "upgrade",
@@ -938,39 +1087,148 @@ mod tests {
     }
 
     #[test]
-    fn test_should_check_for_updates_respects_interval() {
-        let now = current_timestamp();
+    fn test_auto_update_check_disabled_does_no_work() {
+        let repo = crate::git::test_utils::TmpRepo::new().unwrap();
+        repo.git_command(&["config", "git-ai.autoUpdateCheck", "false"])
+            .unwrap();
+        let last_spawn = LAST_BACKGROUND_SPAWN.load(Ordering::SeqCst);
+
+        maybe_schedule_background_update_check_with(repo.gitai_repo(), || {
+            panic!("update check worker must not be spawned when autoUpdateCheck is off")
+        });
+
+        assert_eq!(LAST_BACKGROUND_SPAWN.load(Ordering::SeqCst), last_spawn);
+    }
+
+    #[test]
+    fn test_auto_update_check_defaults_to_enabled() {
+        let repo = crate::git::test_utils::TmpRepo::new().unwrap();
+        assert!(auto_update_check_enabled(repo.gitai_repo()));
+
+        repo.git_command(&["config", "git-ai.autoUpdateCheck", "maybe"])
+            .unwrap();
+        assert!(auto_update_check_enabled(repo.gitai_repo()));
+    }
+
+    const DAY: Duration = Duration::from_secs(24 * 3600);
+
+    #[test]
+    fn test_should_check_for_updates_skips_recent_check() {
+        let checked_at = 1_700_000_000;
         let mut cache = UpdateCache::new(UpdateChannel::Latest);
-        cache.last_checked_at = now;
-        assert!(!should_check_for_updates(
+        cache.last_checked_at = checked_at;
+
+        for now in [checked_at, checked_at + 3600, checked_at + DAY.as_secs()] {
+            assert!(!should_check_for_updates(
+                UpdateChannel::Latest,
+                Some(&cache),
+                now,
+                DAY
+            ));
+        }
+    }
+
+    #[test]
+    fn test_should_check_for_updates_performs_stale_check() {
+        let checked_at = 1_700_000_000;
+        let mut cache = UpdateCache::new(UpdateChannel::Latest);
+        cache.last_checked_at = checked_at;
+
+        let stale = checked_at + DAY.as_secs() + 10;
+        assert!(should_check_for_updates(
             UpdateChannel::Latest,
-            Some(&cache)
+            Some(&cache),
+            stale,
+            DAY
         ));
-
-        let stale_offset = (UPDATE_CHECK_INTERVAL_HOURS * 3600) + 10;
-        cache.last_checked_at = now.saturating_sub(stale_offset);
+        // A shorter configured interval makes the same check stale sooner
         assert!(should_check_for_updates(
             UpdateChannel::Latest,
-            Some(&cache)
+            Some(&cache),
+            checked_at + 7200,
+            Duration::from_secs(3600)
+        ));
+        assert!(should_check_for_updates(
+            UpdateChannel::Latest,
+            None,
+            checked_at,
+            DAY
         ));
-
-        assert!(should_check_for_updates(UpdateChannel::Latest, None));
     }
 
     #[test]
     fn test_should_check_for_updates_verifies_channel() {
-        let now = current_timestamp();
+        let now = 1_700_000_000;
         let mut cache = UpdateCache::new(UpdateChannel::Latest);
         cache.last_checked_at = now;
 
         // Cache matches channel - should respect interval
         assert!(!should_check_for_updates(
             UpdateChannel::Latest,
-            Some(&cache)
+            Some(&cache),
+            now,
+            DAY
         ));
 
         // Cache doesn't match channel - should check for updates
-        assert!(should_check_for_updates(UpdateChannel::Next, Some(&cache)));
+        assert!(should_check_for_updates(
+            UpdateChannel::Next,
+            Some(&cache),
+            now,
+            DAY
+        ));
+    }
+
+    #[test]
+    fn test_version_check_summary_from_mocked_release() {
+        let check = |payload: &str, current: &str| {
+            let release =
+                fetch_release_for_channel(&format!("mock://{}", payload), UpdateChannel::Latest);
+            version_check_summary(current, UpdateChannel::Latest, &release)
+        };
+        let payload = r#"{"channels":{"latest":{"version":"v1.4.0","checksum":"abc"}}}"#;
+
+        assert_eq!(
+            check(payload, "1.4.0"),
+            "up to date with the latest channel"
+        );
+        assert_eq!(
+            check(payload, "1.3.9"),
+            "update available: v1.4.0 on the latest channel (run `git-ai upgrade`)"
+        );
+        assert_eq!(
+            check(payload, "1.5.0"),
+            "running a newer version than the latest channel (v1.4.0)"
+        );
+        assert!(check("not json", "1.4.0").starts_with("could not check: "));
+    }
+
+    #[test]
+    fn test_version_check_summary_offline() {
+        let release = Err(ReleaseFetchError::Network(
+            "HTTP request failed: connection refused".to_string(),
+        ));
+        assert_eq!(
+            version_check_summary(current_version(), UpdateChannel::Latest, &release),
+            "could not check: network error"
+        );
+    }
+
+    #[test]
+    fn test_update_check_interval_reads_config() {
+        let repo = crate::git::test_utils::TmpRepo::new().unwrap();
+        assert_eq!(update_check_interval(repo.gitai_repo()), DAY);
+
+        repo.git_command(&["config", "git-ai.updateCheckIntervalHours", "6"])
+            .unwrap();
+        assert_eq!(
+            update_check_interval(repo.gitai_repo()),
+            Duration::from_secs(6 * 3600)
+        );
+
+        repo.git_command(&["config", "git-ai.updateCheckIntervalHours", "-1"])
+            .unwrap();
+        assert_eq!(update_check_interval(repo.gitai_repo()), DAY);
     }
 
     #[test]
@@ -1300,7 +1558,12 @@ mod tests {
 
     #[test]
     fn test_should_check_for_updates_no_cache() {
-        assert!(should_check_for_updates(UpdateChannel::Latest, None));
+        assert!(should_check_for_updates(
+            UpdateChannel::Latest,
+            None,
+            current_timestamp(),
+            DAY
+        ));
     }
 
     #[test]
@@ -1313,7 +1576,9 @@ mod tests {
         };
         assert!(should_check_for_updates(
             UpdateChannel::Latest,
-            Some(&cache)
+            Some(&cache),
+            current_timestamp(),
+            DAY
         ));
     }
 
@@ -1326,7 +1591,12 @@ mod tests {
             available_semver: None,
             channel: "latest".to_string(),
         };
-        assert!(should_check_for_updates(UpdateChannel::Next, Some(&cache)));
+        assert!(should_check_for_updates(
+            UpdateChannel::Next,
+            Some(&cache),
+            now,
+            DAY
+        ));
     }
 
     #[test]
@@ -75,12 +75,15 @@ pub struct Config {
     disable_auto_updates: bool,
     update_channel: UpdateChannel,
     feature_flags: FeatureFlags,
+    #[serde(skip)]
+    unknown_feature_flags: Vec<String>,
     api_base_url: String,
     prompt_storage: String,
     default_prompt_storage: Option<String>,
     #[serde(serialize_with = "serialize_masked_api_key")]
     api_key: Option<String>,
     quiet: bool,
+    credential_store: Option<CredentialStoreKind>,
     custom_attributes: HashMap<String, String>,
     git_ai_hooks: HashMap<String, Vec<String>>,
 }
@@ -116,6 +119,37 @@ impl UpdateChannel {
     }
 }
 
+/// Where OAuth credentials are stored, from the `credential_store` key in `~/.git-ai/config.json`.
+///
+/// This is a git-ai config key rather than a `git-ai.credentialStore` git config entry: the
+/// credentials belong to the user, not a repository, and `git-ai login`/`logout` must resolve
+/// them outside any repository without shelling out to git.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CredentialStoreKind {
+    /// The OS keychain (macOS Keychain, Windows Credential Manager, Secret Service on Linux)
+    Keychain,
+    /// `~/.git-ai/internal/credentials`
+    File,
+}
+
+impl CredentialStoreKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CredentialStoreKind::Keychain => "keychain",
+            CredentialStoreKind::File => "file",
+        }
+    }
+
+    pub fn parse(input: &str) -> Option<Self> {
+        match input.trim().to_lowercase().as_str() {
+            "keychain" => Some(CredentialStoreKind::Keychain),
+            "file" => Some(CredentialStoreKind::File),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Deserialize, Serialize, Default)]
 pub struct FileConfig {
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -151,6 +185,8 @@ pub struct FileConfig {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub quiet: Option<bool>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub credential_store: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub custom_attributes: Option<HashMap<String, String>>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub git_ai_hooks: Option<HashMap<String, Vec<String>>>,
@@ -310,6 +346,11 @@ impl Config {
         &self.feature_flags
     }
 
+    /// Returns the `feature_flags` keys in the config file that don't name a known flag.
+    pub fn unknown_feature_flags(&self) -> &[String] {
+        &self.unknown_feature_flags
+    }
+
     /// Returns the API base URL
     pub fn api_base_url(&self) -> &str {
         &self.api_base_url
@@ -396,6 +437,12 @@ impl Config {
         self.quiet
     }
 
+    /// Returns the configured credential store, or None to fall back to the `auth_keyring`
+    /// feature flag
+    pub fn credential_store(&self) -> Option<CredentialStoreKind> {
+        self.credential_store
+    }
+
     /// Returns the custom attributes map (from config file + env var override).
     pub fn custom_attributes(&self) -> &HashMap<String, String> {
         &self.custom_attributes
@@ -583,7 +630,7 @@ fn build_config() -> Config {
     let git_path = resolve_git_path(&file_cfg);
 
     // Build feature flags from file config
-    let feature_flags = build_feature_flags(&file_cfg);
+    let (feature_flags, unknown_feature_flags) = build_feature_flags(&file_cfg);
 
     // Get API base URL from config, env var, or default
     let api_base_url = file_cfg
@@ -640,6 +687,18 @@ fn build_config() -> Config {
     // Get quiet setting (defaults to false)
     let quiet = file_cfg.as_ref().and_then(|c| c.quiet).unwrap_or(false);
 
+    // Get credential_store setting (keychain/file, unset defers to the auth_keyring flag)
+    let credential_store = file_cfg
+        .as_ref()
+        .and_then(|c| c.credential_store.as_deref())
+        .and_then(|s| {
+            let kind = CredentialStoreKind::parse(s);
+            if kind.is_none() {
+                eprintln!("Warning: Invalid credential_store value '{}', ignoring", s);
+            }
+            kind
+        });
+
     // Build custom attributes: file config as base, env var overrides
     let custom_attributes = build_custom_attributes(&file_cfg);
 
@@ -681,11 +740,13 @@ fn build_config() -> Config {
             disable_auto_updates,
             update_channel,
             feature_flags,
+            unknown_feature_flags,
             api_base_url,
             prompt_storage,
             default_prompt_storage,
             api_key,
             quiet,
+            credential_store,
             custom_attributes: custom_attributes.clone(),
             git_ai_hooks: git_ai_hooks.clone(),
         };
@@ -706,11 +767,13 @@ fn build_config() -> Config {
         disable_auto_updates,
         update_channel,
         feature_flags,
+        unknown_feature_flags,
         api_base_url,
         prompt_storage,
         default_prompt_storage,
         api_key,
         quiet,
+        credential_store,
         custom_attributes,
         git_ai_hooks,
     }
@@ -749,7 +812,9 @@ fn build_custom_attributes(file_cfg: &Option<FileConfig>) -> HashMap<String, Str
     attrs
 }
 
-fn build_feature_flags(file_cfg: &Option<FileConfig>) -> FeatureFlags {
+/// Build feature flags from the config file, remote cache and environment, along with the
+/// config file keys that don't name a known flag.
+fn build_feature_flags(file_cfg: &Option<FileConfig>) -> (FeatureFlags, Vec<String>) {
     let mut file_flags_value = file_cfg
         .as_ref()
         .and_then(|c| c.feature_flags.as_ref())
@@ -763,13 +828,16 @@ fn build_feature_flags(file_cfg: &Option<FileConfig>) -> FeatureFlags {
         flags.insert("global_git_hooks".to_string(), value);
     }
 
-    // Try to deserialize the feature flags from the JSON value
-    let file_flags = file_flags_value.and_then(|value| {
-        // Use from_value to deserialize, but ignore any errors and fall back to defaults
-        serde_json::from_value(value).ok()
-    });
+    let (file_flags, unknown_keys) = match file_flags_value {
+        Some(value) => FeatureFlags::parse_file_flags(value),
+        None => (None, Vec::new()),
+    };
 
-    FeatureFlags::from_env_and_file(file_flags)
+    let flags = FeatureFlags::from_env_remote_and_file(
+        file_flags,
+        crate::feature_flags::cached_remote_flags(),
+    );
+    (flags, unknown_keys)
 }
 
 fn resolve_git_path(file_cfg: &Option<FileConfig>) -> String {
@@ -1022,11 +1090,13 @@ mod tests {
             disable_auto_updates: false,
             update_channel: UpdateChannel::Latest,
             feature_flags: FeatureFlags::default(),
+            unknown_feature_flags: vec![],
             api_base_url: DEFAULT_API_BASE_URL.to_string(),
             prompt_storage: "default".to_string(),
             default_prompt_storage: None,
             api_key: None,
             quiet: false,
+            credential_store: None,
             custom_attributes: HashMap::new(),
             git_ai_hooks: HashMap::new(),
         }
@@ -1131,11 +1201,13 @@ mod tests {
             disable_auto_updates: false,
             update_channel: UpdateChannel::Latest,
             feature_flags: FeatureFlags::default(),
+            unknown_feature_flags: vec![],
             api_base_url: DEFAULT_API_BASE_URL.to_string(),
             prompt_storage: "default".to_string(),
             default_prompt_storage: None,
             api_key: None,
             quiet: false,
+            credential_store: None,
             custom_attributes: HashMap::new(),
             git_ai_hooks: HashMap::new(),
         }
@@ -1249,11 +1321,13 @@ mod tests {
             disable_auto_updates: false,
             update_channel: UpdateChannel::Latest,
             feature_flags: FeatureFlags::default(),
+            unknown_feature_flags: vec![],
             api_base_url: DEFAULT_API_BASE_URL.to_string(),
             prompt_storage: prompt_storage.to_string(),
             default_prompt_storage: default_prompt_storage.map(|s| s.to_string()),
             api_key: None,
             quiet: false,
+            credential_store: None,
             custom_attributes: HashMap::new(),
             git_ai_hooks: HashMap::new(),
         }
@@ -1560,4 +1634,20 @@ mod tests {
         let parsed = parse_file_config_bytes(data).expect("regular config should parse");
         assert_eq!(parsed.git_path.as_deref(), Some("/usr/bin/git"));
     }
+
+    #[test]
+    fn test_build_feature_flags_reports_unknown_keys() {
+        let data = br#"{"feature_flags":{"rewrite_stash":true,"rewrite_stsah":true}}"#;
+        let parsed = parse_file_config_bytes(data).expect("config should parse");
+
+        let (_, unknown_keys) = build_feature_flags(&Some(parsed));
+        assert_eq!(unknown_keys, vec!["rewrite_stsah".to_string()]);
+
+        let mut config = create_test_config(vec![], vec![]);
+        config.unknown_feature_flags = unknown_keys;
+        assert_eq!(
+            config.unknown_feature_flags(),
+            ["rewrite_stsah".to_string()]
+        );
+    }
 }
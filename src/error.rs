@@ -18,6 +18,20 @@ pub enum GitAiError {
     FromUtf8Error(std::string::FromUtf8Error),
     PresetError(String),
     SqliteError(rusqlite::Error),
+    /// Stored credentials are missing or can no longer be refreshed; the user must log in again
+    AuthRequired(String),
+    /// A notes ref exists but its tree can't be read (holds the ref name)
+    CorruptNotes(String),
+    /// The git binary is missing or older than we support (holds why)
+    GitUnavailable(String),
+    /// Stored credentials can't be parsed or fail validation (holds what's wrong); the user must
+    /// log in again
+    CorruptCredentials(String),
+    /// An authorship note's `schema_version` is missing (empty `found`), malformed or newer than
+    /// this build can read
+    UnsupportedSchema {
+        found: String,
+    },
     Generic(String),
 }
 
@@ -27,21 +41,45 @@ impl fmt::Display for GitAiError {
             #[cfg(feature = "test-support")]
             GitAiError::GitError(e) => write!(f, "Git error: {}", e),
             GitAiError::IoError(e) => write!(f, "IO error: {}", e),
-            GitAiError::GitCliError { code, stderr, args } => match code {
-                Some(c) => write!(
-                    f,
-                    "Git CLI ({}) failed with exit code {}: {}",
-                    args.join(" "),
-                    c,
+            GitAiError::GitCliError { code, stderr, args } => {
+                let stderr = stderr.trim();
+                let stderr = if stderr.is_empty() {
+                    "(no stderr output)"
+                } else {
                     stderr
-                ),
-                None => write!(f, "Git CLI ({}) failed: {}", args.join(" "), stderr),
-            },
+                };
+                match code {
+                    Some(c) => write!(
+                        f,
+                        "Git CLI ({}) failed with exit code {}: {}",
+                        args.join(" "),
+                        c,
+                        stderr
+                    ),
+                    None => write!(f, "Git CLI ({}) failed: {}", args.join(" "), stderr),
+                }
+            }
             GitAiError::JsonError(e) => write!(f, "JSON error: {}", e),
             GitAiError::Utf8Error(e) => write!(f, "UTF-8 error: {}", e),
             GitAiError::FromUtf8Error(e) => write!(f, "From UTF-8 error: {}", e),
             GitAiError::PresetError(e) => write!(f, "{}", e),
             GitAiError::SqliteError(e) => write!(f, "SQLite error: {}", e),
+            GitAiError::AuthRequired(e) => write!(f, "Authentication required: {}", e),
+            GitAiError::CorruptNotes(r) => write!(f, "Notes ref {} is corrupt or unreadable", r),
+            GitAiError::GitUnavailable(e) => write!(f, "git is unavailable: {}", e),
+            GitAiError::CorruptCredentials(e) => write!(
+                f,
+                "Stored credentials are corrupt ({}). Run `git-ai login` to sign in again.",
+                e
+            ),
+            GitAiError::UnsupportedSchema { found } if found.is_empty() => {
+                write!(f, "Authorship log metadata is missing schema_version")
+            }
+            GitAiError::UnsupportedSchema { found } => write!(
+                f,
+                "Unsupported authorship log version: {}. Upgrade git-ai to read this note.",
+                found
+            ),
             GitAiError::Generic(e) => write!(f, "Generic error: {}", e),
             GitAiError::GixError(e) => write!(f, "Gix error: {}", e),
         }
@@ -105,6 +143,13 @@ impl Clone for GitAiError {
             GitAiError::FromUtf8Error(e) => GitAiError::FromUtf8Error(e.clone()),
             GitAiError::PresetError(s) => GitAiError::PresetError(s.clone()),
             GitAiError::SqliteError(e) => GitAiError::Generic(format!("SQLite error: {}", e)),
+            GitAiError::AuthRequired(s) => GitAiError::AuthRequired(s.clone()),
+            GitAiError::CorruptNotes(s) => GitAiError::CorruptNotes(s.clone()),
+            GitAiError::GitUnavailable(s) => GitAiError::GitUnavailable(s.clone()),
+            GitAiError::CorruptCredentials(s) => GitAiError::CorruptCredentials(s.clone()),
+            GitAiError::UnsupportedSchema { found } => GitAiError::UnsupportedSchema {
+                found: found.clone(),
+            },
             GitAiError::Generic(s) => GitAiError::Generic(s.clone()),
             GitAiError::GixError(e) => GitAiError::Generic(format!("Gix error: {}", e)),
         }
@@ -150,6 +195,26 @@ mod tests {
         assert!(display.contains("git push"));
     }
 
+    #[test]
+    fn test_error_display_git_cli_error_trims_stderr() {
+        let err = GitAiError::GitCliError {
+            code: Some(128),
+            stderr: "fatal: bad object\n".to_string(),
+            args: vec!["git".to_string(), "show".to_string()],
+        };
+        assert_eq!(
+            format!("{}", err),
+            "Git CLI (git show) failed with exit code 128: fatal: bad object"
+        );
+
+        let err = GitAiError::GitCliError {
+            code: Some(1),
+            stderr: String::new(),
+            args: vec!["git".to_string(), "diff".to_string()],
+        };
+        assert!(format!("{}", err).ends_with("(no stderr output)"));
+    }
+
     #[test]
     fn test_error_display_json_error() {
         let json_str = "{invalid json";
@@ -184,6 +249,49 @@ mod tests {
         assert_eq!(display, "invalid preset configuration");
     }
 
+    #[test]
+    fn test_error_display_auth_required() {
+        let err = GitAiError::AuthRequired("session expired".to_string());
+        let display = format!("{}", err);
+        assert_eq!(display, "Authentication required: session expired");
+    }
+
+    #[test]
+    fn test_error_display_corrupt_notes() {
+        let err = GitAiError::CorruptNotes("refs/notes/ai".to_string());
+        let display = format!("{}", err);
+        assert_eq!(display, "Notes ref refs/notes/ai is corrupt or unreadable");
+    }
+
+    #[test]
+    fn test_error_display_unsupported_schema() {
+        let err = GitAiError::UnsupportedSchema {
+            found: "authorship/4.0.0".to_string(),
+        };
+        assert_eq!(
+            format!("{}", err),
+            "Unsupported authorship log version: authorship/4.0.0. Upgrade git-ai to read this note."
+        );
+
+        let missing = GitAiError::UnsupportedSchema {
+            found: String::new(),
+        };
+        assert_eq!(
+            format!("{}", missing),
+            "Authorship log metadata is missing schema_version"
+        );
+    }
+
+    #[test]
+    fn test_error_display_corrupt_credentials() {
+        let err = GitAiError::CorruptCredentials("access token is empty".to_string());
+        let display = format!("{}", err);
+        assert_eq!(
+            display,
+            "Stored credentials are corrupt (access token is empty). Run `git-ai login` to sign in again."
+        );
+    }
+
     #[test]
     fn test_error_display_sqlite_error() {
         use rusqlite::Connection;
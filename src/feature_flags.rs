@@ -1,4 +1,14 @@
+use crate::api::ApiContext;
+use crate::error::GitAiError;
 use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// API endpoint serving the remote feature flags document
+const REMOTE_FLAGS_ENDPOINT: &str = "/worker/feature-flags";
+
+/// How long a fetched remote flags document is used before asking the API again
+const REMOTE_FLAGS_CACHE_TTL_SECS: u64 = 60 * 60;
 
 macro_rules! define_feature_flags {
     (
@@ -38,6 +48,9 @@ macro_rules! define_feature_flags {
             )*
         }
 
+        /// Keys accepted in the `feature_flags` section of the config file
+        const KNOWN_FLAG_KEYS: &[&str] = &[$(stringify!($file_name),)*];
+
         impl FeatureFlags {
             /// Merge flags with a base, applying any Some values as overrides
             fn merge_with(base: Self, overrides: DeserializableFeatureFlags) -> Self {
@@ -45,6 +58,19 @@ macro_rules! define_feature_flags {
                     $($field: overrides.$file_name.unwrap_or(base.$field),)*
                 }
             }
+
+            /// Apply `GIT_AI_FEATURE_<FLAG>` overrides, reading variables through `lookup`
+            fn apply_feature_env_overrides(mut self, lookup: impl Fn(&str) -> Option<String>) -> Self {
+                $(
+                    let name = concat!("GIT_AI_FEATURE_", stringify!($file_name)).to_ascii_uppercase();
+                    if let Some(value) = lookup(&name)
+                        && let Some(enabled) = parse_env_flag(&name, &value)
+                    {
+                        self.$field = enabled;
+                    }
+                )*
+                self
+            }
         }
     };
 }
@@ -76,6 +102,33 @@ impl FeatureFlags {
         }
     }
 
+    /// Deserialize the config file's `feature_flags` section, returning the parsed flags along
+    /// with any top-level keys that don't name a flag. Unknown keys are ignored (and logged)
+    /// rather than rejected so older binaries keep working with newer config files.
+    pub(crate) fn parse_file_flags(
+        value: serde_json::Value,
+    ) -> (Option<DeserializableFeatureFlags>, Vec<String>) {
+        let unknown_keys: Vec<String> = value
+            .as_object()
+            .map(|flags| {
+                flags
+                    .keys()
+                    .filter(|key| !KNOWN_FLAG_KEYS.contains(&key.as_str()))
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default();
+        for key in &unknown_keys {
+            crate::utils::debug_log(&format!(
+                "Ignoring unknown feature flag '{}' in config file",
+                key
+            ));
+        }
+
+        // Ignore deserialization errors and fall back to defaults
+        (serde_json::from_value(value).ok(), unknown_keys)
+    }
+
     /// Build FeatureFlags from environment variables
     /// Reads from GIT_AI_* prefixed environment variables
     /// Example: GIT_AI_REWRITE_STASH=true, GIT_AI_CHECKPOINT_INTER_COMMIT_MOVE=false
@@ -87,12 +140,12 @@ impl FeatureFlags {
         Self::from_deserializable(env_flags)
     }
 
-    /// Build FeatureFlags from both file and environment variables
-    /// Precedence: Environment > File > Default
-    /// - Starts with defaults
-    /// - Applies file config overrides if present
-    /// - Applies environment variable overrides if present (highest priority)
-    pub(crate) fn from_env_and_file(file_flags: Option<DeserializableFeatureFlags>) -> Self {
+    /// Build FeatureFlags from file, remote and environment overrides
+    /// Precedence: Environment > Remote > File > Default
+    pub(crate) fn from_env_remote_and_file(
+        file_flags: Option<DeserializableFeatureFlags>,
+        remote_flags: Option<DeserializableFeatureFlags>,
+    ) -> Self {
         // Start with defaults
         let mut result = FeatureFlags::default();
 
@@ -101,13 +154,145 @@ impl FeatureFlags {
             result = Self::merge_with(result, file);
         }
 
+        // Apply remote overrides
+        if let Some(remote) = remote_flags {
+            result = Self::merge_with(result, remote);
+        }
+
         // Apply env var overrides (highest priority)
         let env_flags: DeserializableFeatureFlags =
             envy::prefixed("GIT_AI_").from_env().unwrap_or_default();
         result = Self::merge_with(result, env_flags);
 
-        result
+        result.with_env_overrides()
+    }
+
+    /// Force individual flags via `GIT_AI_FEATURE_<FLAG>=1|0|true|false`
+    /// (e.g. `GIT_AI_FEATURE_REWRITE_STASH=0`). These take precedence over every other source.
+    pub fn with_env_overrides(self) -> Self {
+        self.apply_feature_env_overrides(|name| std::env::var(name).ok())
+    }
+}
+
+/// Parse a `GIT_AI_FEATURE_*` value, ignoring unrecognized spellings
+fn parse_env_flag(name: &str, value: &str) -> Option<bool> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Some(true),
+        "0" | "false" | "no" | "off" => Some(false),
+        _ => {
+            crate::utils::debug_log(&format!(
+                "Ignoring {}={:?}: expected 1, 0, true or false",
+                name, value
+            ));
+            None
+        }
+    }
+}
+
+/// Remote flags document as cached on disk
+#[derive(Serialize, Deserialize)]
+struct RemoteFlagsCache {
+    fetched_at: u64,
+    flags: serde_json::Value,
+}
+
+/// File in `~/.git-ai/internal` caching the remote flags document
+pub(crate) const REMOTE_FLAGS_CACHE_FILE: &str = "remote_feature_flags.json";
+
+/// Path of the remote flags cache (~/.git-ai/internal/remote_feature_flags.json)
+fn remote_flags_cache_path() -> Option<PathBuf> {
+    crate::config::internal_dir_path().map(|dir| dir.join(REMOTE_FLAGS_CACHE_FILE))
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// Remote flags from the last successful fetch, regardless of age, without touching the network
+pub(crate) fn cached_remote_flags() -> Option<DeserializableFeatureFlags> {
+    read_remote_flags_cache(&remote_flags_cache_path()?, now_secs(), None)
+}
+
+/// Refresh the remote flags cache through `ctx` if it is older than the TTL
+pub fn refresh_remote_flags(ctx: &ApiContext) {
+    let _ = load_remote_flags(remote_flags_cache_path().as_deref(), now_secs(), || {
+        fetch_remote_flags(ctx)
+    });
+}
+
+/// Cached flags no older than `max_age_secs` (any age if `None`)
+fn read_remote_flags_cache(
+    path: &Path,
+    now: u64,
+    max_age_secs: Option<u64>,
+) -> Option<DeserializableFeatureFlags> {
+    let content = std::fs::read_to_string(path).ok()?;
+    let cache: RemoteFlagsCache = serde_json::from_str(&content).ok()?;
+    if let Some(max_age) = max_age_secs
+        && now.saturating_sub(cache.fetched_at) >= max_age
+    {
+        return None;
+    }
+    serde_json::from_value(cache.flags).ok()
+}
+
+/// Remote flags from a fresh cache, otherwise from `fetch` (caching the result).
+/// If the fetch fails, falls back to a stale cached copy.
+fn load_remote_flags(
+    cache_path: Option<&Path>,
+    now: u64,
+    fetch: impl FnOnce() -> Result<serde_json::Value, GitAiError>,
+) -> Option<DeserializableFeatureFlags> {
+    if let Some(path) = cache_path
+        && let Some(flags) = read_remote_flags_cache(path, now, Some(REMOTE_FLAGS_CACHE_TTL_SECS))
+    {
+        return Some(flags);
+    }
+
+    let fetched = fetch().and_then(|flags| {
+        let parsed: DeserializableFeatureFlags =
+            serde_json::from_value(flags.clone()).map_err(GitAiError::JsonError)?;
+        Ok((flags, parsed))
+    });
+    match fetched {
+        Ok((flags, parsed)) => {
+            if let Some(path) = cache_path {
+                let cache = RemoteFlagsCache {
+                    fetched_at: now,
+                    flags,
+                };
+                if let Some(parent) = path.parent() {
+                    let _ = std::fs::create_dir_all(parent);
+                }
+                if let Ok(json) = serde_json::to_string(&cache) {
+                    let _ = std::fs::write(path, json);
+                }
+            }
+            Some(parsed)
+        }
+        Err(e) => {
+            crate::utils::debug_log(&format!("Failed to fetch remote feature flags: {}", e));
+            cache_path.and_then(|path| read_remote_flags_cache(path, now, None))
+        }
+    }
+}
+
+/// GET the remote flags document
+fn fetch_remote_flags(ctx: &ApiContext) -> Result<serde_json::Value, GitAiError> {
+    let response = ctx.get(REMOTE_FLAGS_ENDPOINT)?;
+    if response.status_code != 200 {
+        return Err(GitAiError::Generic(format!(
+            "Feature flags request failed with status {}",
+            response.status_code
+        )));
     }
+    let body = response
+        .as_str()
+        .map_err(|e| GitAiError::Generic(format!("Failed to read response body: {}", e)))?;
+    serde_json::from_str(body).map_err(GitAiError::JsonError)
 }
 
 #[cfg(test)]
@@ -194,7 +379,7 @@ mod tests {
 
     #[test]
     #[serial_test::serial]
-    fn test_from_env_and_file_defaults_only() {
+    fn test_from_env_remote_and_file_defaults_only() {
         // No file flags, env should be empty
         unsafe {
             std::env::remove_var("GIT_AI_REWRITE_STASH");
@@ -202,7 +387,7 @@ mod tests {
             std::env::remove_var("GIT_AI_AUTH_KEYRING");
         }
 
-        let flags = FeatureFlags::from_env_and_file(None);
+        let flags = FeatureFlags::from_env_remote_and_file(None, None);
         let defaults = FeatureFlags::default();
         assert_eq!(flags.rewrite_stash, defaults.rewrite_stash);
         assert_eq!(flags.inter_commit_move, defaults.inter_commit_move);
@@ -211,7 +396,7 @@ mod tests {
 
     #[test]
     #[serial_test::serial]
-    fn test_from_env_and_file_file_overrides() {
+    fn test_from_env_remote_and_file_file_overrides() {
         unsafe {
             std::env::remove_var("GIT_AI_REWRITE_STASH");
             std::env::remove_var("GIT_AI_CHECKPOINT_INTER_COMMIT_MOVE");
@@ -224,7 +409,7 @@ mod tests {
             ..Default::default()
         };
 
-        let flags = FeatureFlags::from_env_and_file(Some(file_flags));
+        let flags = FeatureFlags::from_env_remote_and_file(Some(file_flags), None);
         assert!(flags.rewrite_stash);
         assert!(flags.auth_keyring);
     }
@@ -273,4 +458,223 @@ mod tests {
         let debug_str = format!("{:?}", flags);
         assert!(debug_str.contains("FeatureFlags"));
     }
+
+    fn clear_flag_env_vars() {
+        unsafe {
+            std::env::remove_var("GIT_AI_REWRITE_STASH");
+            std::env::remove_var("GIT_AI_CHECKPOINT_INTER_COMMIT_MOVE");
+            std::env::remove_var("GIT_AI_AUTH_KEYRING");
+        }
+    }
+
+    fn write_cache(path: &Path, fetched_at: u64, flags: serde_json::Value) {
+        let cache = RemoteFlagsCache { fetched_at, flags };
+        std::fs::write(path, serde_json::to_string(&cache).unwrap()).unwrap();
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_remote_overrides_file_overrides_default() {
+        clear_flag_env_vars();
+
+        let file_flags = DeserializableFeatureFlags {
+            rewrite_stash: Some(!FeatureFlags::default().rewrite_stash),
+            checkpoint_inter_commit_move: Some(true),
+            auth_keyring: Some(true),
+            ..Default::default()
+        };
+        let remote_flags = DeserializableFeatureFlags {
+            auth_keyring: Some(false),
+            git_hooks_enabled: Some(true),
+            ..Default::default()
+        };
+
+        let flags = FeatureFlags::from_env_remote_and_file(Some(file_flags), Some(remote_flags));
+        // File only
+        assert_eq!(flags.rewrite_stash, !FeatureFlags::default().rewrite_stash);
+        assert!(flags.inter_commit_move);
+        // Remote over file
+        assert!(!flags.auth_keyring);
+        // Remote over default
+        assert!(flags.git_hooks_enabled);
+        // Default only
+        assert_eq!(
+            flags.git_hooks_externally_managed,
+            FeatureFlags::default().git_hooks_externally_managed
+        );
+    }
+
+    #[test]
+    fn test_load_remote_flags_cache_hit_skips_fetch() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("remote_feature_flags.json");
+        write_cache(&path, 1_000, serde_json::json!({ "auth_keyring": true }));
+
+        let flags = load_remote_flags(Some(&path), 1_000 + REMOTE_FLAGS_CACHE_TTL_SECS - 1, || {
+            panic!("fresh cache should not be refetched")
+        })
+        .unwrap();
+        assert_eq!(flags.auth_keyring, Some(true));
+    }
+
+    #[test]
+    fn test_load_remote_flags_cache_miss_fetches_and_caches() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir
+            .path()
+            .join("internal")
+            .join("remote_feature_flags.json");
+
+        let flags = load_remote_flags(Some(&path), 5_000, || {
+            Ok(serde_json::json!({ "rewrite_stash": false, "unknown_flag": true }))
+        })
+        .unwrap();
+        assert_eq!(flags.rewrite_stash, Some(false));
+
+        let cached = read_remote_flags_cache(&path, 5_000, Some(REMOTE_FLAGS_CACHE_TTL_SECS))
+            .expect("fetched flags should be cached");
+        assert_eq!(cached.rewrite_stash, Some(false));
+    }
+
+    #[test]
+    fn test_load_remote_flags_stale_cache_refetches() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("remote_feature_flags.json");
+        write_cache(&path, 1_000, serde_json::json!({ "auth_keyring": true }));
+
+        let now = 1_000 + REMOTE_FLAGS_CACHE_TTL_SECS;
+        let flags = load_remote_flags(Some(&path), now, || {
+            Ok(serde_json::json!({ "auth_keyring": false }))
+        })
+        .unwrap();
+        assert_eq!(flags.auth_keyring, Some(false));
+        assert_eq!(
+            read_remote_flags_cache(&path, now, None)
+                .unwrap()
+                .auth_keyring,
+            Some(false)
+        );
+    }
+
+    #[test]
+    fn test_load_remote_flags_fetch_error_falls_back_to_stale_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("remote_feature_flags.json");
+        write_cache(&path, 1_000, serde_json::json!({ "auth_keyring": true }));
+
+        let flags = load_remote_flags(Some(&path), 1_000 + 2 * REMOTE_FLAGS_CACHE_TTL_SECS, || {
+            Err(GitAiError::Generic("offline".to_string()))
+        })
+        .unwrap();
+        assert_eq!(flags.auth_keyring, Some(true));
+
+        let missing = dir.path().join("missing.json");
+        assert!(
+            load_remote_flags(Some(&missing), 0, || Err(GitAiError::Generic(
+                "offline".to_string()
+            )))
+            .is_none()
+        );
+    }
+
+    #[test]
+    fn test_feature_env_overrides_truthy_and_falsy_spellings() {
+        for (value, expected) in [
+            ("1", true),
+            ("true", true),
+            ("TRUE", true),
+            (" yes ", true),
+            ("on", true),
+            ("0", false),
+            ("false", false),
+            ("False", false),
+            ("no", false),
+            ("off", false),
+        ] {
+            let base = FeatureFlags {
+                rewrite_stash: !expected,
+                ..FeatureFlags::default()
+            };
+            let flags = base.apply_feature_env_overrides(|name| {
+                (name == "GIT_AI_FEATURE_REWRITE_STASH").then(|| value.to_string())
+            });
+            assert_eq!(flags.rewrite_stash, expected, "value {:?}", value);
+        }
+    }
+
+    #[test]
+    fn test_feature_env_overrides_ignore_unrecognized_values() {
+        for value in ["", "maybe", "2", "enabled"] {
+            let base = FeatureFlags {
+                rewrite_stash: true,
+                ..FeatureFlags::default()
+            };
+            let flags = base.apply_feature_env_overrides(|name| {
+                (name == "GIT_AI_FEATURE_REWRITE_STASH").then(|| value.to_string())
+            });
+            assert!(flags.rewrite_stash, "value {:?}", value);
+        }
+    }
+
+    #[test]
+    fn test_feature_env_overrides_use_flag_file_names() {
+        let flags = FeatureFlags {
+            inter_commit_move: false,
+            git_hooks_externally_managed: false,
+            ..FeatureFlags::default()
+        }
+        .apply_feature_env_overrides(|name| match name {
+            "GIT_AI_FEATURE_CHECKPOINT_INTER_COMMIT_MOVE" => Some("1".to_string()),
+            "GIT_AI_FEATURE_GIT_HOOKS_EXTERNALLY_MANAGED" => Some("true".to_string()),
+            _ => None,
+        });
+        assert!(flags.inter_commit_move);
+        assert!(flags.git_hooks_externally_managed);
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_feature_env_override_beats_env_remote_and_file() {
+        clear_flag_env_vars();
+        unsafe {
+            std::env::set_var("GIT_AI_REWRITE_STASH", "true");
+            std::env::set_var("GIT_AI_FEATURE_REWRITE_STASH", "0");
+        }
+
+        let enabled = || DeserializableFeatureFlags {
+            rewrite_stash: Some(true),
+            ..Default::default()
+        };
+        let flags = FeatureFlags::from_env_remote_and_file(Some(enabled()), Some(enabled()));
+
+        unsafe {
+            std::env::remove_var("GIT_AI_REWRITE_STASH");
+            std::env::remove_var("GIT_AI_FEATURE_REWRITE_STASH");
+        }
+        assert!(!flags.rewrite_stash);
+    }
+
+    #[test]
+    fn test_parse_file_flags_reports_misspelled_keys() {
+        let (flags, unknown) = FeatureFlags::parse_file_flags(serde_json::json!({
+            "rewrite_stsah": true,
+            "auth_keyring": true,
+            "rewrite.stash": false,
+        }));
+
+        let flags = flags.expect("unknown keys should not fail deserialization");
+        assert_eq!(flags.auth_keyring, Some(true));
+        assert_eq!(flags.rewrite_stash, None);
+        assert_eq!(unknown, vec!["rewrite.stash", "rewrite_stsah"]);
+    }
+
+    #[test]
+    fn test_parse_file_flags_known_keys_only() {
+        let (flags, unknown) = FeatureFlags::parse_file_flags(serde_json::json!({
+            "rewrite_stash": false,
+            "checkpoint_inter_commit_move": true,
+        }));
+        assert!(flags.is_some());
+        assert!(unknown.is_empty());
+    }
 }
@@ -1,49 +1,1174 @@
-use std::collections::HashSet;
-
-use crate::authorship::authorship_log_serialization::AuthorshipLog;
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::io::{BufRead, Read, Write};
+use std::path::Path;
+use std::process::{Child, ChildStdin, ChildStdout};
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::authorship::authorship_log::LineRange;
+use crate::authorship::authorship_log_serialization::{
+    AttestationEntry, AuthorshipLog, FileAttestation,
+};
+use crate::authorship::transcript::Message;
 use crate::error::GitAiError;
 use crate::git::refs::{commits_with_authorship_notes, note_blob_oids_for_commits};
-#[cfg(test)]
-use crate::git::repository::exec_git;
-use crate::git::repository::{Repository, exec_git_stdin};
+use crate::git::repository::{
+    CatFileBatchHeader, Repository, parse_cat_file_batch_header, parse_git_var_identity,
+    spawn_git_piped,
+};
+use crate::utils::debug_log;
+
+/// Above this many note blobs, blobs are read by several `cat-file --batch` processes at once.
+const PARALLEL_BLOB_READ_THRESHOLD: usize = 2000;
+
+/// Maximum number of blob ids handed to a single `cat-file --batch` process.
+const BLOB_READ_WINDOW: usize = 5000;
 
 pub async fn load_ai_touched_files_for_commits(
     repo: &Repository,
     commit_shas: Vec<String>,
 ) -> Result<HashSet<String>, GitAiError> {
+    let concurrency = repo.notes_read_concurrency();
+
+    let blob_oids = {
+        let repo = repo.clone();
+        smol::unblock(move || unique_note_blob_oids(&repo, &commit_shas)).await?
+    };
+
+    let repo = repo.clone();
+    if blob_oids.len() > PARALLEL_BLOB_READ_THRESHOLD && concurrency > 1 {
+        read_touched_files_parallel(repo, blob_oids, concurrency).await
+    } else {
+        smol::unblock(move || read_touched_files(&repo, &blob_oids)).await
+    }
+}
+
+/// Load every file attestation recorded in the notes ref, with its line ranges and prompt
+/// references intact.
+pub async fn load_all_ai_attestations(
+    repo: &Repository,
+) -> Result<Vec<FileAttestation>, GitAiError> {
+    let repo = repo.clone();
+
+    smol::unblock(move || {
+        let blob_oids = get_note_blob_shas(&repo)?;
+        let mut attestations = Vec::new();
+        for_each_note_attestation(&repo, &blob_oids, |attestation| {
+            attestations.push(attestation);
+        })?;
+        Ok(attestations)
+    })
+    .await
+}
+
+/// Attestations anywhere in the notes ref that came from a prompt whose user messages contain
+/// `substring`, ignoring case, each paired with the SHA of the commit it annotates and sorted by
+/// that SHA. Each attestation keeps only the entries of matching prompts, and commits sharing a
+/// note blob each get their own copy.
+///
+/// Notes are read and parsed one at a time, and prompt text is searched in place, so memory is
+/// bounded by the largest single note however long its transcripts are.
+pub async fn search_attestations_by_prompt(
+    repo: &Repository,
+    substring: &str,
+) -> Result<Vec<(String, FileAttestation)>, GitAiError> {
     let repo = repo.clone();
+    let needle = substring.to_lowercase();
 
     smol::unblock(move || {
-        if commit_shas.is_empty() {
+        let mut commits_by_blob: HashMap<String, Vec<String>> = HashMap::new();
+        for (commit_sha, blob_sha) in get_note_entries(&repo)? {
+            commits_by_blob
+                .entry(blob_sha)
+                .or_default()
+                .push(commit_sha);
+        }
+        let blob_oids: Vec<String> = commits_by_blob.keys().cloned().collect();
+
+        let mut matches = Vec::new();
+        for_each_note_blob(&repo, &blob_oids, |oid, content| {
+            let Some(AuthorshipLog {
+                attestations,
+                metadata,
+            }) = parse_note_log(content)
+            else {
+                return;
+            };
+            let matching_prompts: HashSet<&str> = metadata
+                .prompts
+                .iter()
+                .filter(|(_, prompt)| {
+                    prompt.messages.iter().any(|message| match message {
+                        Message::User { text, .. } => contains_ignore_case(text, &needle),
+                        _ => false,
+                    })
+                })
+                .map(|(hash, _)| hash.as_str())
+                .collect();
+            if matching_prompts.is_empty() {
+                return;
+            }
+            let commits = commits_by_blob
+                .get(oid)
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+            for mut attestation in attestations {
+                attestation
+                    .entries
+                    .retain(|entry| matching_prompts.contains(entry.hash.as_str()));
+                if !attestation.entries.is_empty() {
+                    for commit_sha in commits {
+                        matches.push((commit_sha.clone(), attestation.clone()));
+                    }
+                }
+            }
+        })?;
+        matches.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(matches)
+    })
+    .await
+}
+
+/// Whether `haystack` contains `needle_lower` (already lowercased), ignoring case. ASCII needles
+/// are compared in place so long transcripts aren't copied just to be lowercased.
+fn contains_ignore_case(haystack: &str, needle_lower: &str) -> bool {
+    if needle_lower.is_ascii() {
+        let needle = needle_lower.as_bytes();
+        return needle.is_empty()
+            || haystack
+                .as_bytes()
+                .windows(needle.len())
+                .any(|window| window.eq_ignore_ascii_case(needle));
+    }
+    haystack.to_lowercase().contains(needle_lower)
+}
+
+/// Every file path touched by an AI attestation anywhere in the notes ref.
+///
+/// The result is cached in the repo's git-ai storage keyed on the notes ref tip, so repeated
+/// calls only re-read the notes after they change.
+pub async fn load_all_ai_touched_files(repo: &Repository) -> Result<HashSet<String>, GitAiError> {
+    load_all_ai_touched_files_with_progress(repo, |_processed, _total| {}).await
+}
+
+/// Like [`load_all_ai_touched_files`], keeping only paths that match `glob`, e.g. `src/**/*.rs`.
+///
+/// `**` matches any number of directories while `*` stays within one. A pattern without a `/`,
+/// such as `*.rs`, is matched against file names, so like a `.gitignore` entry it selects
+/// matching files at any depth.
+pub async fn load_ai_touched_files_matching(
+    repo: &Repository,
+    glob: &str,
+) -> Result<HashSet<String>, GitAiError> {
+    let matches_glob = path_glob_matcher(glob)?;
+    let files = load_all_ai_touched_files(repo).await?;
+    Ok(files
+        .into_iter()
+        .filter(|path| matches_glob(path))
+        .collect())
+}
+
+fn path_glob_matcher(glob: &str) -> Result<impl Fn(&str) -> bool, GitAiError> {
+    let pattern = glob::Pattern::new(glob)
+        .map_err(|e| GitAiError::Generic(format!("Invalid glob pattern '{}': {}", glob, e)))?;
+    let options = glob::MatchOptions {
+        require_literal_separator: true,
+        ..Default::default()
+    };
+    let match_file_name = !glob.contains('/');
+    Ok(move |path: &str| {
+        let candidate = if match_file_name {
+            path.rsplit('/').next().unwrap_or(path)
+        } else {
+            path
+        };
+        pattern.matches_with(candidate, options)
+    })
+}
+
+/// Like [`load_all_ai_touched_files`], calling `on_progress(processed, total)` after each
+/// `cat-file --batch` window of note blobs is read.
+///
+/// The callback is always invoked at least once so a UI can finalize; it gets `(0, 0)` when
+/// there are no notes or the result comes from the cache.
+pub async fn load_all_ai_touched_files_with_progress<P>(
+    repo: &Repository,
+    mut on_progress: P,
+) -> Result<HashSet<String>, GitAiError>
+where
+    P: FnMut(usize, usize) + Send + 'static,
+{
+    let cache_path = repo.storage.ai_dir.join(AI_TOUCHED_FILES_CACHE_FILE);
+    let notes_tip = {
+        let repo = repo.clone();
+        smol::unblock(move || notes_ref_tip(&repo)).await
+    };
+    let Some(notes_tip) = notes_tip else {
+        let _ = std::fs::remove_file(&cache_path);
+        on_progress(0, 0);
+        return Ok(HashSet::new());
+    };
+
+    if let Some(files) = read_touched_files_cache(&cache_path, &notes_tip) {
+        on_progress(0, 0);
+        return Ok(files);
+    }
+
+    let files = {
+        let repo = repo.clone();
+        smol::unblock(move || {
+            let blob_oids = get_note_blob_shas(&repo)?;
+            read_touched_files_with_progress(&repo, &blob_oids, BLOB_READ_WINDOW, on_progress)
+        })
+        .await?
+    };
+
+    if let Err(e) = write_touched_files_cache(&cache_path, &notes_tip, &files) {
+        debug_log(&format!("Failed to write AI touched files cache: {}", e));
+    }
+    Ok(files)
+}
+
+/// File name of the [`load_all_ai_touched_files`] cache inside the repo's git-ai storage dir
+const AI_TOUCHED_FILES_CACHE_FILE: &str = "ai_touched_files_cache.json";
+
+#[derive(Serialize, Deserialize)]
+struct TouchedFilesCache {
+    notes_tip: String,
+    files: Vec<String>,
+}
+
+fn read_touched_files_cache(path: &Path, notes_tip: &str) -> Option<HashSet<String>> {
+    let content = std::fs::read_to_string(path).ok()?;
+    let cache: TouchedFilesCache = serde_json::from_str(&content).ok()?;
+    (cache.notes_tip == notes_tip).then(|| cache.files.into_iter().collect())
+}
+
+fn write_touched_files_cache(
+    path: &Path,
+    notes_tip: &str,
+    files: &HashSet<String>,
+) -> Result<(), GitAiError> {
+    let mut files: Vec<String> = files.iter().cloned().collect();
+    files.sort();
+    let cache = TouchedFilesCache {
+        notes_tip: notes_tip.to_string(),
+        files,
+    };
+    // Write a sibling temp file and rename it into place, so a concurrent reader or an
+    // interrupted write never sees a truncated cache
+    let parent = path.parent().unwrap_or_else(|| Path::new("."));
+    let mut tmp = tempfile::NamedTempFile::new_in(parent)?;
+    tmp.write_all(serde_json::to_string(&cache)?.as_bytes())?;
+    tmp.persist(path).map_err(|e| e.error)?;
+    Ok(())
+}
+
+/// Current SHA of the notes ref (see [`Repository::notes_ref`]), or `None` if there are no notes yet
+pub(crate) fn notes_ref_tip(repo: &Repository) -> Option<String> {
+    verify_rev(repo, &repo.notes_ref())
+}
+
+/// SHA that `rev` resolves to, or `None` if it doesn't exist
+fn verify_rev(repo: &Repository, rev: &str) -> Option<String> {
+    let mut args = repo.global_args_for_exec();
+    args.push("rev-parse".to_string());
+    args.push("--verify".to_string());
+    args.push("--quiet".to_string());
+    args.push(rev.to_string());
+
+    let output = repo.exec_git(&args).ok()?;
+    let tip = String::from_utf8(output.stdout).ok()?.trim().to_string();
+    (!tip.is_empty()).then_some(tip)
+}
+
+/// Like [`load_all_ai_touched_files`], but with every path remapped to its current name at HEAD.
+///
+/// Each path is followed through the renames HEAD's history made after the commit it was
+/// attributed on, so a file attributed as `src/old.rs` and later moved to `src/new.rs` is
+/// reported as `src/new.rs`, while a later file that reuses the name `src/old.rs` is left alone.
+/// Paths that no longer exist at HEAD (deleted, or renamed to a path that was later deleted)
+/// are dropped.
+pub async fn load_all_ai_touched_files_current_names(
+    repo: &Repository,
+) -> Result<HashSet<String>, GitAiError> {
+    let repo = repo.clone();
+    smol::unblock(move || {
+        let attributed: HashSet<(String, String)> = load_attestations_with_commits(&repo)?
+            .into_iter()
+            .map(|(commit_sha, attestation)| (commit_sha, attestation.file_path))
+            .collect();
+        if attributed.is_empty() {
             return Ok(HashSet::new());
         }
 
-        let note_blob_map = note_blob_oids_for_commits(&repo, &commit_shas)?;
-        if note_blob_map.is_empty() {
+        let head_paths = head_tree_paths(&repo)?;
+        if head_paths.is_empty() {
             return Ok(HashSet::new());
         }
 
-        let mut unique_blob_oids = HashSet::new();
-        for blob_oid in note_blob_map.values() {
-            unique_blob_oids.insert(blob_oid.clone());
+        let positions = head_history_positions(&repo)?;
+        let attributed = attributed
+            .into_iter()
+            .map(|(commit_sha, path)| (positions.get(&commit_sha).copied(), path))
+            .collect();
+        let renames = rename_history(&repo)?
+            .into_iter()
+            .filter_map(|(commit_sha, old_path, new_path)| {
+                positions
+                    .get(&commit_sha)
+                    .map(|&position| (position, old_path, new_path))
+            })
+            .collect::<Vec<_>>();
+        Ok(remap_to_current_names(attributed, &renames, &head_paths))
+    })
+    .await
+}
+
+/// Follow each attributed path through the renames made after the commit it was attributed on
+/// and keep the ones whose final name is in `head_paths`.
+///
+/// `attributed` pairs each path with the position of its annotated commit in HEAD's history;
+/// commits outside that history (`None`) are treated as older than every rename. `renames` is
+/// `(position, old_path, new_path)` in history order.
+fn remap_to_current_names(
+    mut attributed: Vec<(Option<usize>, String)>,
+    renames: &[(usize, String, String)],
+    head_paths: &HashSet<String>,
+) -> HashSet<String> {
+    attributed.sort();
+    let mut pending = attributed.into_iter().peekable();
+    let mut current = HashSet::new();
+
+    for (position, old_path, new_path) in renames {
+        // Start following paths attributed before this rename
+        while let Some((_, path)) = pending.next_if(|(attributed_at, _)| {
+            attributed_at.is_none_or(|attributed_at| attributed_at < *position)
+        }) {
+            current.insert(path);
+        }
+        if current.remove(old_path) {
+            current.insert(new_path.clone());
+        }
+    }
+    current.extend(pending.map(|(_, path)| path));
+
+    current.retain(|path| head_paths.contains(path));
+    current
+}
+
+/// Position of every commit reachable from HEAD, oldest first in topological order
+fn head_history_positions(repo: &Repository) -> Result<HashMap<String, usize>, GitAiError> {
+    let mut args = repo.global_args_for_exec();
+    args.extend(["rev-list", "--reverse", "--topo-order", "HEAD"].map(String::from));
+
+    let output = repo.exec_git(&args)?;
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .enumerate()
+        .map(|(position, sha)| (sha.to_string(), position))
+        .collect())
+}
+
+/// Every `(commit_sha, old_path, new_path)` rename reachable from HEAD, oldest first
+fn rename_history(repo: &Repository) -> Result<Vec<(String, String, String)>, GitAiError> {
+    let mut args = repo.global_args_for_exec();
+    args.extend(
+        [
+            "log",
+            "--reverse",
+            "--topo-order",
+            "-M",
+            "--diff-filter=R",
+            "--name-status",
+            "--format=%H",
+            "-z",
+            "HEAD",
+        ]
+        .map(String::from),
+    );
+
+    let output = repo.exec_git(&args)?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    // -z output is the commit SHA followed by `R<score>\0<old>\0<new>\0` per rename
+    let mut renames = Vec::new();
+    let mut commit_sha = None;
+    let mut fields = stdout
+        .split('\0')
+        .map(|field| field.trim_start_matches('\n'))
+        .filter(|field| !field.is_empty());
+    while let Some(field) = fields.next() {
+        if !is_rename_status(field) {
+            commit_sha = Some(field);
+            continue;
+        }
+        let (Some(sha), Some(old_path), Some(new_path)) =
+            (commit_sha, fields.next(), fields.next())
+        else {
+            break;
+        };
+        renames.push((sha.to_string(), old_path.to_string(), new_path.to_string()));
+    }
+    Ok(renames)
+}
+
+/// Whether a `--name-status` field is a rename status such as `R100`
+fn is_rename_status(field: &str) -> bool {
+    field
+        .strip_prefix('R')
+        .is_some_and(|score| score.bytes().all(|b| b.is_ascii_digit()))
+}
+
+/// Every file path in the HEAD tree, or an empty set when HEAD is unborn
+fn head_tree_paths(repo: &Repository) -> Result<HashSet<String>, GitAiError> {
+    if verify_rev(repo, "HEAD").is_none() {
+        return Ok(HashSet::new());
+    }
+
+    let mut args = repo.global_args_for_exec();
+    args.extend(["ls-tree", "-r", "--name-only", "-z", "HEAD"].map(String::from));
+
+    let output = repo.exec_git(&args)?;
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .split('\0')
+        .filter(|path| !path.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+/// Like [`load_all_ai_touched_files`], but only counting notes on commits whose committer date
+/// falls between `since` and `until`.
+///
+/// Both bounds accept any date `git rev-list --since` does (`2024-01-01`, `3.weeks.ago`, ...)
+/// and either can be `None` to leave that end open. Notes on commits missing from the local
+/// object store are skipped, since their date can't be known.
+pub async fn load_ai_touched_files_in_range(
+    repo: &Repository,
+    since: Option<&str>,
+    until: Option<&str>,
+) -> Result<HashSet<String>, GitAiError> {
+    let repo = repo.clone();
+    let since = since.map(str::to_string);
+    let until = until.map(str::to_string);
+
+    smol::unblock(move || {
+        let note_entries = get_note_entries(&repo)?;
+        let commit_shas: Vec<String> = note_entries
+            .iter()
+            .map(|(commit_sha, _blob_sha)| commit_sha.clone())
+            .collect();
+        let in_range =
+            commits_in_date_range(&repo, &commit_shas, since.as_deref(), until.as_deref())?;
+
+        let blob_oids: Vec<String> = note_entries
+            .into_iter()
+            .filter(|(commit_sha, _blob_sha)| in_range.contains(commit_sha))
+            .map(|(_commit_sha, blob_sha)| blob_sha)
+            .collect();
+        read_touched_files(&repo, &blob_oids)
+    })
+    .await
+}
+
+/// The subset of `commit_shas` whose committer date passes `rev-list --since/--until`
+fn commits_in_date_range(
+    repo: &Repository,
+    commit_shas: &[String],
+    since: Option<&str>,
+    until: Option<&str>,
+) -> Result<HashSet<String>, GitAiError> {
+    // rev-list aborts on unknown objects, and notes can outlive (or arrive before) their commits
+    let commit_shas = existing_commits(repo, commit_shas)?;
+    if commit_shas.is_empty() {
+        return Ok(HashSet::new());
+    }
+
+    let mut args = repo.global_args_for_exec();
+    args.extend(["rev-list", "--no-walk", "--stdin"].map(String::from));
+    if let Some(since) = since {
+        args.push(format!("--since={}", since));
+    }
+    if let Some(until) = until {
+        args.push(format!("--until={}", until));
+    }
+
+    let stdin_data = commit_shas.join("\n") + "\n";
+    let output = repo.exec_git_stdin(&args, stdin_data.as_bytes())?;
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+/// The subset of `shas` that name commits present in the object store
+fn existing_commits(repo: &Repository, shas: &[String]) -> Result<Vec<String>, GitAiError> {
+    if shas.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut args = repo.global_args_for_exec();
+    args.push("cat-file".to_string());
+    args.push("--batch-check=%(objectname) %(objecttype)".to_string());
+
+    // Written from the writer thread so git's answers are read while the SHAs are still
+    // going in; with everything written up front both sides block once its stdout pipe fills
+    let shas = shas.to_vec();
+    let output = repo.exec_git_stdin_writer(&args, move |stdin| {
+        for sha in &shas {
+            writeln!(stdin, "{}", sha)?;
+        }
+        Ok(())
+    })?;
+    // Missing objects are reported as "<sha> missing"
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| line.strip_suffix(" commit"))
+        .map(str::to_string)
+        .collect())
+}
+
+/// Every authorship note as `(annotated_commit_sha, log)`, sorted by commit SHA.
+///
+/// Commits sharing a note blob each get their own copy of the parsed log. Notes that can't be
+/// parsed at all are skipped.
+pub fn load_all_note_logs(repo: &Repository) -> Result<Vec<(String, AuthorshipLog)>, GitAiError> {
+    let note_entries = get_note_entries(repo)?;
+    let blob_oids: Vec<String> = note_entries
+        .iter()
+        .map(|(_commit_sha, blob_sha)| blob_sha.clone())
+        .collect();
+
+    let mut logs_by_blob = HashMap::new();
+    for_each_note_blob(repo, &blob_oids, |oid, content| {
+        if let Some(log) = parse_note_log(content) {
+            logs_by_blob.insert(oid.to_string(), log);
         }
-        let mut blob_oids: Vec<String> = unique_blob_oids.into_iter().collect();
-        blob_oids.sort();
+    })?;
+
+    let mut logs: Vec<(String, AuthorshipLog)> = note_entries
+        .into_iter()
+        .filter_map(|(commit_sha, blob_sha)| {
+            logs_by_blob
+                .get(&blob_sha)
+                .map(|log| (commit_sha, log.clone()))
+        })
+        .collect();
+    logs.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(logs)
+}
 
-        let blob_contents = batch_read_blobs_with_oids(&repo.global_args_for_exec(), &blob_oids)?;
+/// Every file attestation in the notes ref paired with the SHA of the commit it annotates,
+/// sorted by commit SHA and then by position in the note.
+///
+/// Commits sharing a note blob each get their own copy of its attestations.
+pub fn load_attestations_with_commits(
+    repo: &Repository,
+) -> Result<Vec<(String, FileAttestation)>, GitAiError> {
+    Ok(load_all_note_logs(repo)?
+        .into_iter()
+        .flat_map(|(commit_sha, log)| {
+            log.attestations
+                .into_iter()
+                .map(move |attestation| (commit_sha.clone(), attestation))
+        })
+        .collect())
+}
+
+/// Commits whose authorship note has an attestation for `path`, sorted by SHA.
+pub fn commits_touching_file(repo: &Repository, path: &str) -> Result<Vec<String>, GitAiError> {
+    let note_entries = get_note_entries(repo)?;
+    let mut blob_shas: Vec<String> = note_entries
+        .iter()
+        .map(|(_commit_sha, blob_sha)| blob_sha.clone())
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    blob_shas.sort();
+
+    let mut matching_blobs = HashSet::new();
+    for_each_note_blob(repo, &blob_shas, |oid, content| {
+        if parse_note_attestations(content)
+            .iter()
+            .any(|attestation| attestation.file_path == path)
+        {
+            matching_blobs.insert(oid.to_string());
+        }
+    })?;
+
+    let mut commits: Vec<String> = note_entries
+        .into_iter()
+        .filter(|(_commit_sha, blob_sha)| matching_blobs.contains(blob_sha))
+        .map(|(commit_sha, _blob_sha)| commit_sha)
+        .collect();
+    commits.sort();
+    Ok(commits)
+}
+
+/// Per-file `(ai_lines, total_attributed_lines)` across every note in the notes ref.
+///
+/// `total_attributed_lines` counts every line covered by an attestation entry, and `ai_lines`
+/// the subset whose entry resolves to a prompt recorded in any note. Ranges for the same file
+/// are unioned, so lines attested by several commits are only counted once.
+pub async fn ai_line_stats(
+    repo: &Repository,
+) -> Result<HashMap<String, (usize, usize)>, GitAiError> {
+    let repo = repo.clone();
+
+    smol::unblock(move || {
+        let blob_oids = get_note_blob_shas(&repo)?;
+        let mut known_prompts = HashSet::new();
+        let mut entries_by_file: HashMap<String, Vec<AttestationEntry>> = HashMap::new();
+
+        for_each_note_blob(&repo, &blob_oids, |_oid, content| {
+            let Some(log) = parse_note_log(content) else {
+                return;
+            };
+            known_prompts.extend(log.metadata.prompts.into_keys());
+            for attestation in log.attestations {
+                entries_by_file
+                    .entry(attestation.file_path)
+                    .or_default()
+                    .extend(attestation.entries);
+            }
+        })?;
+
+        Ok(entries_by_file
+            .into_iter()
+            .map(|(file_path, entries)| {
+                let ai_ranges = entries
+                    .iter()
+                    .filter(|entry| known_prompts.contains(&entry.hash))
+                    .flat_map(|entry| entry.line_ranges.iter());
+                let all_ranges = entries.iter().flat_map(|entry| entry.line_ranges.iter());
+                (
+                    file_path,
+                    (
+                        count_unioned_lines(ai_ranges),
+                        count_unioned_lines(all_ranges),
+                    ),
+                )
+            })
+            .collect())
+    })
+    .await
+}
 
-        let mut all_files = HashSet::new();
-        for blob_oid in note_blob_map.into_values() {
-            if let Some(content) = blob_contents.get(&blob_oid) {
-                extract_file_paths_from_note(content, &mut all_files);
+/// Number of distinct lines covered by `ranges`
+fn count_unioned_lines<'a>(ranges: impl Iterator<Item = &'a LineRange>) -> usize {
+    let mut spans: Vec<(u32, u32)> = ranges
+        .map(|range| match *range {
+            LineRange::Single(line) => (line, line),
+            LineRange::Range(start, end) => (start.min(end), start.max(end)),
+        })
+        .collect();
+    spans.sort_unstable();
+
+    let mut total = 0usize;
+    let mut current: Option<(u32, u32)> = None;
+    for (start, end) in spans {
+        match current {
+            Some((cur_start, cur_end)) if start <= cur_end.saturating_add(1) => {
+                current = Some((cur_start, cur_end.max(end)));
+            }
+            _ => {
+                if let Some((cur_start, cur_end)) = current {
+                    total += (cur_end - cur_start) as usize + 1;
+                }
+                current = Some((start, end));
             }
         }
+    }
+    if let Some((cur_start, cur_end)) = current {
+        total += (cur_end - cur_start) as usize + 1;
+    }
+    total
+}
 
-        Ok(all_files)
+/// Every file in the notes ref with an attestation entry whose prompt was recorded for the
+/// human author with the given email.
+pub async fn load_ai_touched_files_by_author(
+    repo: &Repository,
+    email: &str,
+) -> Result<HashSet<String>, GitAiError> {
+    let repo = repo.clone();
+    let email = email.to_string();
+
+    smol::unblock(move || {
+        let blob_oids = get_note_blob_shas(&repo)?;
+        let mut files = HashSet::new();
+        for_each_note_blob(&repo, &blob_oids, |_oid, content| {
+            // Author matching needs the real prompts metadata, so skip notes that don't fully parse
+            let Ok(AuthorshipLog {
+                attestations,
+                metadata,
+            }) = AuthorshipLog::deserialize_from_string(&String::from_utf8_lossy(content))
+            else {
+                return;
+            };
+            for attestation in attestations {
+                let by_author = attestation.entries.iter().any(|entry| {
+                    metadata
+                        .prompts
+                        .get(&entry.hash)
+                        .and_then(|prompt| prompt.human_author.as_deref())
+                        .is_some_and(|author| human_author_has_email(author, &email))
+                });
+                if by_author {
+                    files.insert(attestation.file_path);
+                }
+            }
+        })?;
+        Ok(files)
     })
     .await
 }
 
+/// Match a prompt's `human_author` (`Name <email>`, or a bare email) against `email`, ignoring case
+fn human_author_has_email(human_author: &str, email: &str) -> bool {
+    let identity = parse_git_var_identity(human_author);
+    identity
+        .email
+        .as_deref()
+        .unwrap_or(human_author.trim())
+        .eq_ignore_ascii_case(email.trim())
+}
+
+/// Invoke `on_attestation` for every file attestation in the authorship notes of `commit_shas`.
+///
+/// Note blobs are read through `cat-file --batch` and parsed one at a time as they arrive, so
+/// memory stays bounded by the largest note rather than the sum of all notes.
+/// Commits sharing a note blob are only visited once.
+pub fn stream_ai_attestations<F>(
+    repo: &Repository,
+    commit_shas: &[String],
+    on_attestation: F,
+) -> Result<(), GitAiError>
+where
+    F: FnMut(FileAttestation),
+{
+    let blob_oids = unique_note_blob_oids(repo, commit_shas)?;
+    for_each_note_attestation(repo, &blob_oids, on_attestation)
+}
+
+/// Sorted, de-duplicated note blob ids for the commits that have authorship notes
+fn unique_note_blob_oids(
+    repo: &Repository,
+    commit_shas: &[String],
+) -> Result<Vec<String>, GitAiError> {
+    if commit_shas.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let note_blob_map = note_blob_oids_for_commits(repo, commit_shas)?;
+    let mut blob_oids: Vec<String> = note_blob_map
+        .into_values()
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    blob_oids.sort();
+    Ok(blob_oids)
+}
+
+/// Blob SHAs of every note in the notes ref, sorted and de-duplicated
+pub(crate) fn get_note_blob_shas(repo: &Repository) -> Result<Vec<String>, GitAiError> {
+    let mut blob_shas: Vec<String> = get_note_entries(repo)?
+        .into_iter()
+        .map(|(_commit_sha, blob_sha)| blob_sha)
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    blob_shas.sort();
+    Ok(blob_shas)
+}
+
+/// Commits with a note in the notes ref that no ref reaches (`git rev-list --all`), e.g. commits
+/// rebased away or left on deleted branches, sorted.
+///
+/// Only commits whose objects are in this clone count. Notes fetched for commits we never
+/// fetched (a teammate's unpushed branch, a shallow or partial clone) aren't ours to judge.
+pub fn unreachable_annotated_commits(repo: &Repository) -> Result<Vec<String>, GitAiError> {
+    let annotated = get_note_entries(repo)?;
+    if annotated.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut args = repo.global_args_for_exec();
+    args.push("rev-list".to_string());
+    args.push("--all".to_string());
+    let reachable: HashSet<String> = repo.exec_git_lines(&args, true)?.into_iter().collect();
+
+    let candidates: Vec<String> = annotated
+        .into_iter()
+        .map(|(commit_sha, _blob_sha)| commit_sha)
+        .filter(|commit_sha| !reachable.contains(commit_sha))
+        .collect();
+    let mut unreachable = existing_commits(repo, &candidates)?;
+    unreachable.sort();
+    unreachable.dedup();
+    Ok(unreachable)
+}
+
+/// Every note in the notes ref as (annotated_commit_sha, note_blob_sha) pairs.
+///
+/// Returns no entries when the ref is missing, and [`GitAiError::CorruptNotes`] when it exists
+/// but its tree can't be read.
+fn get_note_entries(repo: &Repository) -> Result<Vec<(String, String)>, GitAiError> {
+    let Some(notes_tip) = notes_ref_tip(repo) else {
+        return Ok(Vec::new());
+    };
+
+    let mut args = repo.global_args_for_exec();
+    args.push("ls-tree".to_string());
+    args.push("-r".to_string());
+    args.push(notes_tip);
+
+    // The ref resolved above, so a fatal ls-tree means its tree can't be read
+    let lines = repo.exec_git_lines(&args, false).map_err(|e| match e {
+        GitAiError::GitCliError {
+            code: Some(128), ..
+        } => GitAiError::CorruptNotes(repo.notes_ref()),
+        other => other,
+    })?;
+
+    // Parse ls-tree output: "<mode> blob <blob_sha>\t<path>", where the path is the annotated
+    // commit's SHA, possibly split into fanout directories ("ab/cdef...")
+    Ok(lines
+        .iter()
+        .filter_map(|line| {
+            let (meta, path) = line.split_once('\t')?;
+            let mut parts = meta.split_whitespace();
+            let _mode = parts.next()?;
+            if parts.next()? != "blob" {
+                return None;
+            }
+            let blob_sha = parts.next()?.to_string();
+            Some((commit_sha_from_note_path(path)?, blob_sha))
+        })
+        .collect())
+}
+
+/// Reassemble the annotated commit SHA from a notes tree path such as `ab/cd/ef01...`.
+///
+/// Returns `None` for paths that aren't a full SHA-1 or SHA-256 object id once the fanout
+/// separators are removed, e.g. stray non-note files in the notes tree.
+fn commit_sha_from_note_path(path: &str) -> Option<String> {
+    let sha = path.replace('/', "");
+    let is_full_sha =
+        matches!(sha.len(), 40 | 64) && sha.bytes().all(|byte| byte.is_ascii_hexdigit());
+    is_full_sha.then_some(sha)
+}
+
+/// Every note in the notes ref as `(annotated_commit_sha, raw_note_bytes)`, in notes tree order.
+///
+/// Nothing runs until the first item is requested: the notes tree is listed then, and each
+/// blob is only read from a single long-lived `cat-file --batch` process when its item is
+/// requested, so callers can stop early without reading the rest. Commits sharing a note blob
+/// each get their own item. A failure is yielded as an `Err` item and ends the iteration.
+pub fn iter_notes(
+    repo: &Repository,
+) -> impl Iterator<Item = Result<(String, Vec<u8>), GitAiError>> {
+    NotesIter {
+        repo: repo.clone(),
+        entries: None,
+        cat_file: None,
+    }
+}
+
+struct NotesIter {
+    repo: Repository,
+    /// Remaining `(commit_sha, blob_sha)` entries, listed on the first `next`
+    entries: Option<std::vec::IntoIter<(String, String)>>,
+    cat_file: Option<CatFileBatch>,
+}
+
+impl NotesIter {
+    fn next_note(&mut self) -> Result<Option<(String, Vec<u8>)>, GitAiError> {
+        if self.entries.is_none() {
+            self.entries = Some(get_note_entries(&self.repo)?.into_iter());
+        }
+        let Some((commit_sha, blob_sha)) = self.entries.as_mut().and_then(Iterator::next) else {
+            return Ok(None);
+        };
+
+        if self.cat_file.is_none() {
+            self.cat_file = Some(CatFileBatch::spawn(&self.repo.global_args_for_exec())?);
+        }
+        let content = self
+            .cat_file
+            .as_mut()
+            .expect("cat-file process was just started")
+            .read_blob(&blob_sha)?
+            .ok_or_else(|| {
+                GitAiError::Generic(format!(
+                    "note blob {} for commit {} is missing",
+                    blob_sha, commit_sha
+                ))
+            })?;
+        Ok(Some((commit_sha, content)))
+    }
+}
+
+impl Iterator for NotesIter {
+    type Item = Result<(String, Vec<u8>), GitAiError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.next_note() {
+            Ok(note) => note.map(Ok),
+            Err(e) => {
+                // The cat-file stream may be out of step now, so don't try to continue
+                self.entries = Some(Vec::new().into_iter());
+                self.cat_file = None;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+/// A `git cat-file --batch` process answering one object request at a time
+struct CatFileBatch {
+    child: Child,
+    stdin: Option<ChildStdin>,
+    stdout: std::io::BufReader<ChildStdout>,
+}
+
+impl CatFileBatch {
+    fn spawn(global_args: &[String]) -> Result<Self, GitAiError> {
+        let mut args = global_args.to_vec();
+        args.push("cat-file".to_string());
+        args.push("--batch".to_string());
+
+        let mut child = spawn_git_piped(&args)?;
+        let stdin = child.stdin.take();
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| GitAiError::Generic("Failed to capture git stdout".to_string()))?;
+        Ok(Self {
+            child,
+            stdin,
+            stdout: std::io::BufReader::new(stdout),
+        })
+    }
+
+    /// Contents of object `oid`, or `None` if git reports it missing or ambiguous
+    fn read_blob(&mut self, oid: &str) -> Result<Option<Vec<u8>>, GitAiError> {
+        let stdin = self
+            .stdin
+            .as_mut()
+            .ok_or_else(|| GitAiError::Generic("cat-file stdin is closed".to_string()))?;
+        writeln!(stdin, "{}", oid)?;
+        stdin.flush()?;
+
+        let mut header = String::new();
+        if self.stdout.read_line(&mut header)? == 0 {
+            return Err(GitAiError::Generic(
+                "cat-file --batch exited unexpectedly".to_string(),
+            ));
+        }
+        let size = match parse_cat_file_batch_header(header.trim_end())? {
+            Some(CatFileBatchHeader::Object { size, .. }) => size,
+            Some(CatFileBatchHeader::Status { .. }) => return Ok(None),
+            None => {
+                return Err(GitAiError::Generic(format!(
+                    "Invalid cat-file header: {:?}",
+                    header.trim_end()
+                )));
+            }
+        };
+
+        // Content is followed by a newline
+        let mut content = vec![0u8; size + 1];
+        self.stdout.read_exact(&mut content)?;
+        content.truncate(size);
+        Ok(Some(content))
+    }
+}
+
+impl Drop for CatFileBatch {
+    fn drop(&mut self) {
+        // Closing stdin lets cat-file exit on its own
+        drop(self.stdin.take());
+        let _ = self.child.wait();
+    }
+}
+
+fn for_each_note_attestation<F>(
+    repo: &Repository,
+    blob_oids: &[String],
+    mut on_attestation: F,
+) -> Result<(), GitAiError>
+where
+    F: FnMut(FileAttestation),
+{
+    for_each_note_blob(repo, blob_oids, |_oid, content| {
+        for attestation in parse_note_attestations(content) {
+            on_attestation(attestation);
+        }
+    })
+}
+
+/// Stream the raw contents of each note blob through `cat-file --batch`, in windows of
+/// [`BLOB_READ_WINDOW`] ids. Each distinct blob is read once, even if `blob_oids` repeats it.
+fn for_each_note_blob<F>(
+    repo: &Repository,
+    blob_oids: &[String],
+    on_note: F,
+) -> Result<(), GitAiError>
+where
+    F: FnMut(&str, &[u8]),
+{
+    let blob_oids = dedup_blob_oids(blob_oids);
+    batch_read_blobs_chunked(repo, &blob_oids, BLOB_READ_WINDOW, on_note)
+}
+
+/// `blob_oids` with repeats dropped, keeping first-seen order.
+///
+/// Several notes can share one blob when their content is identical; callers that need to know
+/// which commits share a blob should keep the `(commit, blob)` pairs from [`get_note_entries`].
+fn dedup_blob_oids(blob_oids: &[String]) -> Cow<'_, [String]> {
+    let mut seen = HashSet::with_capacity(blob_oids.len());
+    if blob_oids.iter().all(|oid| seen.insert(oid.as_str())) {
+        return Cow::Borrowed(blob_oids);
+    }
+
+    seen.clear();
+    Cow::Owned(
+        blob_oids
+            .iter()
+            .filter(|oid| seen.insert(oid.as_str()))
+            .cloned()
+            .collect(),
+    )
+}
+
+/// Read blobs with one `cat-file --batch` process per `window` ids, fully consuming each
+/// window's output before requesting the next. This bounds both the stdin buffer and any
+/// in-flight output to a single window regardless of how many notes the repo has.
+fn batch_read_blobs_chunked<F>(
+    repo: &Repository,
+    blob_oids: &[String],
+    window: usize,
+    mut on_blob: F,
+) -> Result<(), GitAiError>
+where
+    F: FnMut(&str, &[u8]),
+{
+    let mut args = repo.global_args_for_exec();
+    args.push("cat-file".to_string());
+    args.push("--batch".to_string());
+
+    for window_oids in blob_oids.chunks(window.max(1)) {
+        let stdin_data = (window_oids.join("\n") + "\n").into_bytes();
+        repo.exec_git_stdin_streaming(&args, stdin_data, &mut |stdout| {
+            for_each_cat_file_batch_blob(stdout, |oid, content| {
+                on_blob(oid, content);
+            })
+        })?;
+    }
+
+    Ok(())
+}
+
+fn read_touched_files(
+    repo: &Repository,
+    blob_oids: &[String],
+) -> Result<HashSet<String>, GitAiError> {
+    let mut files = HashSet::new();
+    for_each_note_attestation(repo, blob_oids, |attestation| {
+        files.insert(attestation.file_path);
+    })?;
+    Ok(files)
+}
+
+/// [`read_touched_files`] one `window` of blobs at a time, reporting the running count after each
+fn read_touched_files_with_progress<P>(
+    repo: &Repository,
+    blob_oids: &[String],
+    window: usize,
+    mut on_progress: P,
+) -> Result<HashSet<String>, GitAiError>
+where
+    P: FnMut(usize, usize),
+{
+    let total = blob_oids.len();
+    if total == 0 {
+        on_progress(0, 0);
+        return Ok(HashSet::new());
+    }
+
+    let mut files = HashSet::new();
+    let mut processed = 0;
+    for window_oids in blob_oids.chunks(window.max(1)) {
+        files.extend(read_touched_files(repo, window_oids)?);
+        processed += window_oids.len();
+        on_progress(processed, total);
+    }
+    Ok(files)
+}
+
+/// Split `blob_oids` into chunks and read each chunk with its own `cat-file --batch` process,
+/// running at most `concurrency` processes at a time.
+async fn read_touched_files_parallel(
+    repo: Repository,
+    blob_oids: Vec<String>,
+    concurrency: usize,
+) -> Result<HashSet<String>, GitAiError> {
+    let chunk_results = for_each_blob_chunk_bounded(blob_oids, concurrency, move |chunk| {
+        read_touched_files(&repo, chunk)
+    })
+    .await?;
+
+    let mut all_files = HashSet::new();
+    for files in chunk_results {
+        all_files.extend(files);
+    }
+    Ok(all_files)
+}
+
+/// Run `read` over chunks of `blob_oids` on blocking threads, with a semaphore keeping at
+/// most `concurrency` of them running at once.
+///
+/// Chunks are sized to spread the blobs across `concurrency` workers, but never exceed
+/// [`BLOB_READ_WINDOW`], so a large note set yields more chunks than permits and waits its
+/// turn instead of forking one process per core.
+async fn for_each_blob_chunk_bounded<T, F>(
+    blob_oids: Vec<String>,
+    concurrency: usize,
+    read: F,
+) -> Result<Vec<T>, GitAiError>
+where
+    T: Send + 'static,
+    F: Fn(&[String]) -> Result<T, GitAiError> + Send + Sync + 'static,
+{
+    let concurrency = concurrency.max(1);
+    let chunk_size = blob_oids
+        .len()
+        .div_ceil(concurrency)
+        .clamp(1, BLOB_READ_WINDOW);
+    let semaphore = Arc::new(smol::lock::Semaphore::new(concurrency));
+    let read = Arc::new(read);
+
+    let tasks: Vec<_> = blob_oids
+        .chunks(chunk_size)
+        .map(|chunk| {
+            let chunk = chunk.to_vec();
+            let semaphore = Arc::clone(&semaphore);
+            let read = Arc::clone(&read);
+            smol::spawn(async move {
+                let _permit = semaphore.acquire().await;
+                smol::unblock(move || read(&chunk)).await
+            })
+        })
+        .collect();
+
+    futures::future::join_all(tasks).await.into_iter().collect()
+}
+
 /// Return true if any of the provided commits has an authorship note attached.
 pub fn commits_have_authorship_notes(
     repo: &Repository,
@@ -58,13 +1183,13 @@ pub fn commits_have_authorship_notes(
 
 /// Get all notes as (note_blob_sha, commit_sha) pairs
 #[cfg(test)]
-fn get_notes_list(global_args: &[String]) -> Result<Vec<(String, String)>, GitAiError> {
-    let mut args = global_args.to_vec();
+fn get_notes_list(repo: &Repository) -> Result<Vec<(String, String)>, GitAiError> {
+    let mut args = repo.global_args_for_exec();
     args.push("notes".to_string());
     args.push("--ref=ai".to_string());
     args.push("list".to_string());
 
-    let output = match exec_git(&args) {
+    let output = match repo.exec_git(&args) {
         Ok(output) => output,
         Err(GitAiError::GitCliError { code: Some(1), .. }) => {
             // No notes exist yet
@@ -90,93 +1215,83 @@ fn get_notes_list(global_args: &[String]) -> Result<Vec<(String, String)>, GitAi
     Ok(mappings)
 }
 
-fn batch_read_blobs_with_oids(
-    global_args: &[String],
-    blob_oids: &[String],
-) -> Result<std::collections::HashMap<String, String>, GitAiError> {
-    if blob_oids.is_empty() {
-        return Ok(std::collections::HashMap::new());
-    }
-
-    let mut args = global_args.to_vec();
-    args.push("cat-file".to_string());
-    args.push("--batch".to_string());
-
-    let stdin_data = blob_oids.join("\n") + "\n";
-    let output = exec_git_stdin(&args, stdin_data.as_bytes())?;
-
-    parse_cat_file_batch_output_with_oids(&output.stdout)
-}
-
-fn parse_cat_file_batch_output_with_oids(
-    data: &[u8],
-) -> Result<std::collections::HashMap<String, String>, GitAiError> {
-    let mut results = std::collections::HashMap::new();
-    let mut pos = 0usize;
-
-    while pos < data.len() {
-        let header_end = match data[pos..].iter().position(|&b| b == b'\n') {
-            Some(idx) => pos + idx,
-            None => break,
-        };
-
-        let header = std::str::from_utf8(&data[pos..header_end])?;
-        let parts: Vec<&str> = header.split_whitespace().collect();
-        if parts.len() < 2 {
-            pos = header_end + 1;
-            continue;
+/// Read `cat-file --batch` output blob by blob, calling `on_blob` with each object's id and
+/// content. The content buffer is reused between blobs, so nothing is retained past the callback.
+fn for_each_cat_file_batch_blob<R, F>(reader: &mut R, mut on_blob: F) -> Result<(), GitAiError>
+where
+    R: BufRead + ?Sized,
+    F: FnMut(&str, &[u8]),
+{
+    let mut header = Vec::new();
+    let mut content = Vec::new();
+
+    loop {
+        header.clear();
+        if reader.read_until(b'\n', &mut header)? == 0 {
+            break;
         }
-
-        let oid = parts[0].to_string();
-        if parts[1] == "missing" {
-            pos = header_end + 1;
-            continue;
-        }
-
-        if parts.len() < 3 {
-            pos = header_end + 1;
-            continue;
+        if header.last() == Some(&b'\n') {
+            header.pop();
         }
 
-        let size: usize = parts[2]
-            .parse()
-            .map_err(|e| GitAiError::Generic(format!("Invalid size in cat-file output: {}", e)))?;
-
-        let content_start = header_end + 1;
-        let content_end = content_start + size;
-        if content_end > data.len() {
-            return Err(GitAiError::Generic(
-                "Malformed cat-file --batch output: truncated content".to_string(),
-            ));
-        }
+        let header_str = std::str::from_utf8(&header)?;
+        let (oid, size) = match parse_cat_file_batch_header(header_str)? {
+            Some(CatFileBatchHeader::Object { oid, size, .. }) => (oid, size),
+            // `missing`, `ambiguous`, ... lines have no content to skip over
+            _ => continue,
+        };
 
-        let content = String::from_utf8_lossy(&data[content_start..content_end]).to_string();
-        results.insert(oid, content);
+        content.clear();
+        content.resize(size, 0);
+        reader.read_exact(&mut content).map_err(|e| {
+            if e.kind() == std::io::ErrorKind::UnexpectedEof {
+                GitAiError::Generic(
+                    "Malformed cat-file --batch output: truncated content".to_string(),
+                )
+            } else {
+                GitAiError::IoError(e)
+            }
+        })?;
+        on_blob(oid, &content);
 
-        pos = content_end;
-        if pos < data.len() && data[pos] == b'\n' {
-            pos += 1;
+        // Each object's content is followed by a newline
+        let mut newline = [0u8; 1];
+        if reader.fill_buf()?.first() == Some(&b'\n') {
+            reader.read_exact(&mut newline)?;
         }
     }
 
-    Ok(results)
+    Ok(())
+}
+
+/// Parse the attestations of a note blob. Notes that fail to parse contribute nothing.
+fn parse_note_attestations(content: &[u8]) -> Vec<FileAttestation> {
+    parse_note_log(content)
+        .map(|log| log.attestations)
+        .unwrap_or_default()
 }
 
-/// Extract file paths from a note blob content
-fn extract_file_paths_from_note(content: &str, files: &mut HashSet<String>) {
-    // Find the divider and slice before it, then add minimal metadata to make it parseable
-    if let Some(divider_pos) = content.find("\n---\n") {
-        let attestation_section = &content[..divider_pos];
-        // Create a complete parseable format with empty metadata
-        let parseable = format!(
-            "{}\n---\n{{\"schema_version\":\"authorship/3.0.0\",\"base_commit_sha\":\"\",\"prompts\":{{}}}}",
-            attestation_section
-        );
+/// Parse a note blob into an [`AuthorshipLog`]. Notes that can't be parsed, including notes
+/// written in an unsupported schema, are skipped rather than returned with made-up metadata.
+fn parse_note_log(content: &[u8]) -> Option<AuthorshipLog> {
+    // Decode lossily so a stray invalid byte doesn't cost us the whole note
+    let text = String::from_utf8_lossy(content);
+    if let Cow::Owned(_) = text {
+        debug_log("Authorship note contains invalid UTF-8; decoding lossily");
+    }
 
-        if let Ok(log) = AuthorshipLog::deserialize_from_string(&parseable) {
-            for attestation in log.attestations {
-                files.insert(attestation.file_path);
+    match AuthorshipLog::deserialize_from_string(&text) {
+        Ok(log) => Some(log),
+        Err(e) => {
+            if let Some(GitAiError::UnsupportedSchema { found }) = e.downcast_ref() {
+                debug_log(&format!(
+                    "Skipping authorship note with unsupported schema '{}'",
+                    found
+                ));
+            } else {
+                debug_log(&format!("Skipping unparseable authorship note: {}", e));
             }
+            None
         }
     }
 }
@@ -184,6 +1299,9 @@ fn extract_file_paths_from_note(content: &str, files: &mut HashSet<String>) {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::git::refs::{DEFAULT_MAX_NOTES_READ_CONCURRENCY, DEFAULT_NOTES_REF, notes_add};
+    use crate::git::repository::{exec_git, exec_git_stdin};
+    use crate::git::test_utils::{TmpRepo, note_with_attestations, test_prompt_record};
     use crate::git::{find_repository_in_path, sync_authorship::fetch_authorship_notes};
     use std::time::Instant;
 
@@ -195,8 +1313,7 @@ mod tests {
             fetch_authorship_notes(&repo, "origin").unwrap();
 
             // Get all notes to find commits that have notes attached
-            let global_args = repo.global_args_for_exec();
-            let all_notes = get_notes_list(&global_args).unwrap();
+            let all_notes = get_notes_list(&repo).unwrap();
 
             if all_notes.len() < 3 {
                 println!(
@@ -302,16 +1419,32 @@ mod tests {
         assert!(!result);
     }
 
+    /// Collect [`for_each_cat_file_batch_blob`] output into an oid -> content map
+    fn read_cat_file_batch(data: &[u8]) -> Result<HashMap<String, Vec<u8>>, GitAiError> {
+        let mut blobs = HashMap::new();
+        for_each_cat_file_batch_blob(&mut &data[..], |oid, content| {
+            blobs.insert(oid.to_string(), content.to_vec());
+        })?;
+        Ok(blobs)
+    }
+
+    fn note_file_paths(content: &[u8]) -> HashSet<String> {
+        parse_note_attestations(content)
+            .into_iter()
+            .map(|attestation| attestation.file_path)
+            .collect()
+    }
+
     #[test]
     fn test_parse_cat_file_batch_output_empty() {
-        let result = parse_cat_file_batch_output_with_oids(b"").unwrap();
+        let result = read_cat_file_batch(b"").unwrap();
         assert!(result.is_empty(), "Empty input should return empty map");
     }
 
     #[test]
     fn test_parse_cat_file_batch_output_missing() {
         let data = b"abc123 missing\n";
-        let result = parse_cat_file_batch_output_with_oids(data).unwrap();
+        let result = read_cat_file_batch(data).unwrap();
         assert!(
             result.is_empty(),
             "Missing blobs should not be included in result"
@@ -321,60 +1454,59 @@ mod tests {
     #[test]
     fn test_parse_cat_file_batch_output_single_blob() {
         let data = b"abc123 blob 11\nhello world\n";
-        let result = parse_cat_file_batch_output_with_oids(data).unwrap();
+        let result = read_cat_file_batch(data).unwrap();
         assert_eq!(result.len(), 1);
-        assert_eq!(result.get("abc123"), Some(&"hello world".to_string()));
+        assert_eq!(result.get("abc123"), Some(&b"hello world".to_vec()));
     }
 
     #[test]
     fn test_parse_cat_file_batch_output_multiple_blobs() {
         let data = b"abc123 blob 5\nhello\ndef456 blob 5\nworld\n";
-        let result = parse_cat_file_batch_output_with_oids(data).unwrap();
+        let result = read_cat_file_batch(data).unwrap();
         assert_eq!(result.len(), 2);
-        assert_eq!(result.get("abc123"), Some(&"hello".to_string()));
-        assert_eq!(result.get("def456"), Some(&"world".to_string()));
+        assert_eq!(result.get("abc123"), Some(&b"hello".to_vec()));
+        assert_eq!(result.get("def456"), Some(&b"world".to_vec()));
     }
 
     #[test]
     fn test_parse_cat_file_batch_output_truncated() {
         // Size says 20 bytes but only 5 provided
         let data = b"abc123 blob 20\nhello";
-        let result = parse_cat_file_batch_output_with_oids(data);
+        let result = read_cat_file_batch(data);
         assert!(result.is_err(), "Truncated content should return error");
     }
 
     #[test]
     fn test_parse_cat_file_batch_output_invalid_size() {
         let data = b"abc123 blob notanumber\n";
-        let result = parse_cat_file_batch_output_with_oids(data);
+        let result = read_cat_file_batch(data);
         assert!(result.is_err(), "Invalid size should return error");
     }
 
     #[test]
     fn test_parse_cat_file_batch_output_malformed_header() {
         let data = b"abc123\n";
-        let result = parse_cat_file_batch_output_with_oids(data).unwrap();
+        let result = read_cat_file_batch(data).unwrap();
         assert!(result.is_empty(), "Malformed header should skip that entry");
     }
 
     #[test]
-    fn test_batch_read_blobs_with_oids_empty() {
+    fn test_batch_read_blobs_chunked_empty() {
         let repo = find_repository_in_path(".").unwrap();
-        let result = batch_read_blobs_with_oids(&repo.global_args_for_exec(), &[]).unwrap();
-        assert!(result.is_empty(), "Empty OID list should return empty map");
+        let mut blobs_seen = 0;
+        batch_read_blobs_chunked(&repo, &[], 16, |_, _| blobs_seen += 1).unwrap();
+        assert_eq!(blobs_seen, 0, "Empty OID list should read no blobs");
     }
 
     #[test]
-    fn test_extract_file_paths_from_note_empty() {
-        let mut files = HashSet::new();
-        extract_file_paths_from_note("", &mut files);
+    fn test_note_file_paths_empty() {
+        let files = note_file_paths(b"");
         assert!(files.is_empty(), "Empty note should extract no files");
     }
 
     #[test]
-    fn test_extract_file_paths_from_note_no_divider() {
-        let mut files = HashSet::new();
-        extract_file_paths_from_note("some content without divider", &mut files);
+    fn test_note_file_paths_no_divider() {
+        let files = note_file_paths(b"some content without divider");
         assert!(
             files.is_empty(),
             "Note without divider should extract no files"
@@ -382,11 +1514,1306 @@ mod tests {
     }
 
     #[test]
-    fn test_extract_file_paths_from_note_invalid_format() {
-        let mut files = HashSet::new();
-        let content = "invalid attestation\n---\n{\"metadata\":\"test\"}";
-        extract_file_paths_from_note(content, &mut files);
-        // Should not crash, might extract nothing or handle gracefully
-        // This tests error handling path
+    fn test_note_file_paths_invalid_format() {
+        let content = b"invalid attestation\n---\n{\"metadata\":\"test\"}";
+        let files = note_file_paths(content);
+        assert!(files.is_empty(), "Unparseable note should extract no files");
+    }
+
+    /// Metadata section for hand-written note fixtures
+    const NOTE_METADATA: &str =
+        r#"{"schema_version":"authorship/3.0.0","base_commit_sha":"","prompts":{}}"#;
+
+    fn cat_file_batch_entry(oid: &str, content: &str) -> Vec<u8> {
+        format!("{} blob {}\n{}\n", oid, content.len(), content).into_bytes()
+    }
+
+    #[test]
+    fn test_streaming_collects_note_file_set() {
+        let mut data = Vec::new();
+        data.extend(cat_file_batch_entry(
+            "aaa111",
+            &format!(
+                "src/lib.rs\n  abc1234 1-10\nsrc/main.rs\n  abc1234 3\n---\n{}",
+                NOTE_METADATA
+            ),
+        ));
+        data.extend(b"bbb222 missing\n");
+        data.extend(cat_file_batch_entry(
+            "ccc333",
+            &format!(
+                "\"docs/read me.md\"\n  def5678 2-4\nsrc/lib.rs\n  def5678 20\n---\n{}",
+                NOTE_METADATA
+            ),
+        ));
+        data.extend(cat_file_batch_entry("ddd444", "not a note"));
+
+        let mut streamed_files = HashSet::new();
+        let mut blobs_seen = 0;
+        for_each_cat_file_batch_blob(&mut data.as_slice(), |_oid, content| {
+            blobs_seen += 1;
+            for attestation in parse_note_attestations(content) {
+                streamed_files.insert(attestation.file_path);
+            }
+        })
+        .unwrap();
+
+        assert_eq!(blobs_seen, 3, "missing objects should be skipped");
+        assert_eq!(
+            streamed_files,
+            HashSet::from([
+                "src/lib.rs".to_string(),
+                "src/main.rs".to_string(),
+                "docs/read me.md".to_string(),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_for_each_cat_file_batch_blob_truncated() {
+        let data = b"abc123 blob 20\nhello";
+        let result = for_each_cat_file_batch_blob(&mut data.as_slice(), |_, _| {});
+        assert!(result.is_err(), "Truncated content should return error");
+    }
+
+    #[test]
+    fn test_for_each_cat_file_batch_blob_skips_ambiguous_entries() {
+        let mut data = cat_file_batch_entry("aaa111", "first");
+        data.extend(b"abc ambiguous\n");
+        data.extend(cat_file_batch_entry("ccc333", "third"));
+
+        let mut blobs = Vec::new();
+        for_each_cat_file_batch_blob(&mut data.as_slice(), |oid, content| {
+            blobs.push((oid.to_string(), content.to_vec()));
+        })
+        .unwrap();
+
+        assert_eq!(
+            blobs,
+            vec![
+                ("aaa111".to_string(), b"first".to_vec()),
+                ("ccc333".to_string(), b"third".to_vec()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_stream_ai_attestations_empty_commits() {
+        let repo = find_repository_in_path(".").unwrap();
+
+        let mut calls = 0;
+        stream_ai_attestations(&repo, &[], |_| calls += 1).unwrap();
+
+        assert_eq!(calls, 0, "Empty input should not produce attestations");
+    }
+
+    #[test]
+    fn test_parallel_read_matches_serial_file_set() {
+        let temp = tempfile::tempdir().unwrap();
+        let repo_path = temp.path().to_str().unwrap().to_string();
+        exec_git(&["init".to_string(), "-q".to_string(), repo_path.clone()]).unwrap();
+        let repo = find_repository_in_path(&repo_path).unwrap();
+        let global_args = repo.global_args_for_exec();
+
+        let mut blob_oids = Vec::new();
+        for i in 0..40 {
+            let note = format!(
+                "src/file_{}.rs\n  abc1234 1-{}\nshared/common.rs\n  abc1234 {}\n---\n{}",
+                i % 25,
+                i + 1,
+                i + 1,
+                NOTE_METADATA
+            );
+            let mut args = global_args.clone();
+            args.extend(["hash-object", "-w", "--stdin"].map(String::from));
+            let output = exec_git_stdin(&args, note.as_bytes()).unwrap();
+            blob_oids.push(String::from_utf8(output.stdout).unwrap().trim().to_string());
+        }
+
+        let serial = read_touched_files(&repo, &blob_oids).unwrap();
+        let parallel = smol::block_on(read_touched_files_parallel(
+            repo.clone(),
+            blob_oids.clone(),
+            4,
+        ))
+        .unwrap();
+
+        assert_eq!(serial.len(), 26);
+        assert_eq!(parallel, serial);
+
+        // More workers than blobs still covers every blob
+        let oversubscribed =
+            smol::block_on(read_touched_files_parallel(repo, blob_oids, 64)).unwrap();
+        assert_eq!(oversubscribed, serial);
+    }
+
+    fn max_concurrent_chunk_reads(blob_count: usize, concurrency: usize) -> usize {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let active = Arc::new(AtomicUsize::new(0));
+        let max_active = Arc::new(AtomicUsize::new(0));
+        let blob_oids: Vec<String> = (0..blob_count).map(|i| format!("{:040x}", i)).collect();
+
+        let chunk_lens = {
+            let active = Arc::clone(&active);
+            let max_active = Arc::clone(&max_active);
+            smol::block_on(for_each_blob_chunk_bounded(
+                blob_oids,
+                concurrency,
+                move |chunk| {
+                    let now = active.fetch_add(1, Ordering::SeqCst) + 1;
+                    max_active.fetch_max(now, Ordering::SeqCst);
+                    std::thread::sleep(std::time::Duration::from_millis(20));
+                    active.fetch_sub(1, Ordering::SeqCst);
+                    Ok(chunk.len())
+                },
+            ))
+            .unwrap()
+        };
+        assert_eq!(chunk_lens.iter().sum::<usize>(), blob_count);
+        max_active.load(Ordering::SeqCst)
+    }
+
+    #[test]
+    fn test_bounded_chunk_reads_respect_concurrency() {
+        // Enough blobs for several windows, so there are more chunks than permits
+        let blob_count = BLOB_READ_WINDOW * 4;
+        assert_eq!(max_concurrent_chunk_reads(blob_count, 1), 1);
+        assert!(max_concurrent_chunk_reads(blob_count, 2) <= 2);
+    }
+
+    #[test]
+    fn test_notes_read_concurrency_config() {
+        let temp = tempfile::tempdir().unwrap();
+        let repo_path = temp.path().to_str().unwrap().to_string();
+        exec_git(&["init".to_string(), "-q".to_string(), repo_path.clone()]).unwrap();
+        let set = |value: &str| {
+            exec_git(&[
+                "-C".to_string(),
+                repo_path.clone(),
+                "config".to_string(),
+                "git-ai.notesReadConcurrency".to_string(),
+                value.to_string(),
+            ])
+            .unwrap();
+            find_repository_in_path(&repo_path)
+                .unwrap()
+                .notes_read_concurrency()
+        };
+
+        let default = find_repository_in_path(&repo_path)
+            .unwrap()
+            .notes_read_concurrency();
+        assert!((1..=DEFAULT_MAX_NOTES_READ_CONCURRENCY).contains(&default));
+        assert_eq!(set("1"), 1);
+        assert_eq!(set("32"), 32);
+        assert_eq!(set("4k"), 4096);
+        assert_eq!(set("0"), default);
+        assert_eq!(set("-2"), default);
+        assert_eq!(set("lots"), default);
+    }
+
+    #[test]
+    fn test_existing_commits_handles_more_input_than_a_pipe_holds() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        tmp_repo.write_file("a.txt", "a\n", true).unwrap();
+        tmp_repo.commit_with_message("Commit A").unwrap();
+        let commit_a = tmp_repo.get_head_commit_sha().unwrap();
+
+        // Each missing SHA is echoed back, so 12k of them overflow git's stdout pipe well
+        // before all of stdin has been written
+        let mut shas: Vec<String> = (0..12_000u64).map(|i| format!("{:040x}", i + 1)).collect();
+        shas.insert(6_000, commit_a.clone());
+
+        let existing = existing_commits(tmp_repo.gitai_repo(), &shas).unwrap();
+
+        assert_eq!(existing, vec![commit_a]);
+    }
+
+    #[test]
+    fn test_search_attestations_by_prompt_matches_user_messages() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        let repo = tmp_repo.gitai_repo();
+        tmp_repo.write_file("a.txt", "a\n", true).unwrap();
+        tmp_repo.commit_with_message("Commit A").unwrap();
+        let commit_a = tmp_repo.get_head_commit_sha().unwrap();
+
+        let mut log = AuthorshipLog::new();
+        let prompts = [
+            (
+                "aaaaaaa",
+                "src/login.rs",
+                "Add OAuth token REFRESH to the login flow",
+            ),
+            ("bbbbbbb", "src/ui.rs", "Tweak the button colours"),
+        ];
+        for (hash, file_path, prompt_text) in prompts {
+            let mut attestation = FileAttestation::new(file_path.to_string());
+            attestation.add_entry(AttestationEntry::new(
+                hash.to_string(),
+                vec![LineRange::Range(1, 3)],
+            ));
+            log.attestations.push(attestation);
+            let mut prompt = test_prompt_record(hash, "Alice <alice@example.com>");
+            prompt.messages = vec![
+                Message::user(prompt_text.to_string(), None),
+                // Assistant replies don't count as prompt text
+                Message::assistant("Sure, updating the oauth code".to_string(), None),
+            ];
+            log.metadata.prompts.insert(hash.to_string(), prompt);
+        }
+        // One file edited under both prompts keeps only the matching entry
+        let mut shared = FileAttestation::new("src/shared.rs".to_string());
+        shared.add_entry(AttestationEntry::new(
+            "aaaaaaa".to_string(),
+            vec![LineRange::Single(1)],
+        ));
+        shared.add_entry(AttestationEntry::new(
+            "bbbbbbb".to_string(),
+            vec![LineRange::Single(2)],
+        ));
+        log.attestations.push(shared);
+        notes_add(repo, &commit_a, &log.serialize_to_string().unwrap()).unwrap();
+
+        let mut matches =
+            smol::block_on(search_attestations_by_prompt(repo, "token refresh")).unwrap();
+        matches.sort_by(|a, b| a.1.file_path.cmp(&b.1.file_path));
+        let summary: Vec<(&str, &str, Vec<&str>)> = matches
+            .iter()
+            .map(|(commit_sha, a)| {
+                (
+                    commit_sha.as_str(),
+                    a.file_path.as_str(),
+                    a.entries.iter().map(|e| e.hash.as_str()).collect(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (commit_a.as_str(), "src/login.rs", vec!["aaaaaaa"]),
+                (commit_a.as_str(), "src/shared.rs", vec!["aaaaaaa"]),
+            ]
+        );
+
+        assert!(
+            smol::block_on(search_attestations_by_prompt(repo, "updating the oauth"))
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_contains_ignore_case() {
+        assert!(contains_ignore_case("Fix the AUTH middleware", "auth"));
+        assert!(contains_ignore_case("anything", ""));
+        assert!(!contains_ignore_case("au", "auth"));
+        assert!(contains_ignore_case("Überprüfe die Anmeldung", "überprüfe"));
+    }
+
+    #[test]
+    fn test_load_ai_touched_files_by_author_filters_to_author() {
+        let tmp_repo = TmpRepo::new().unwrap();
+
+        tmp_repo.write_file("a.txt", "a\n", true).unwrap();
+        tmp_repo.commit_with_message("Commit A").unwrap();
+        let commit_a = tmp_repo.get_head_commit_sha().unwrap();
+        notes_add(
+            tmp_repo.gitai_repo(),
+            &commit_a,
+            &note_with_attestations(&[
+                ("src/alice.rs", "aaaaaaa", "Alice <alice@example.com>"),
+                ("src/shared.rs", "bbbbbbb", "Bob <bob@example.com>"),
+            ]),
+        )
+        .unwrap();
+
+        tmp_repo.write_file("b.txt", "b\n", true).unwrap();
+        tmp_repo.commit_with_message("Commit B").unwrap();
+        let commit_b = tmp_repo.get_head_commit_sha().unwrap();
+        notes_add(
+            tmp_repo.gitai_repo(),
+            &commit_b,
+            &note_with_attestations(&[
+                ("src/bob.rs", "ccccccc", "Bob <bob@example.com>"),
+                ("src/shared.rs", "ddddddd", "Alice <Alice@Example.com>"),
+            ]),
+        )
+        .unwrap();
+
+        let alice_files = smol::block_on(load_ai_touched_files_by_author(
+            tmp_repo.gitai_repo(),
+            "alice@example.com",
+        ))
+        .unwrap();
+        let bob_files = smol::block_on(load_ai_touched_files_by_author(
+            tmp_repo.gitai_repo(),
+            "bob@example.com",
+        ))
+        .unwrap();
+        let nobody_files = smol::block_on(load_ai_touched_files_by_author(
+            tmp_repo.gitai_repo(),
+            "carol@example.com",
+        ))
+        .unwrap();
+
+        assert_eq!(
+            alice_files,
+            HashSet::from(["src/alice.rs".to_string(), "src/shared.rs".to_string()])
+        );
+        assert_eq!(
+            bob_files,
+            HashSet::from(["src/bob.rs".to_string(), "src/shared.rs".to_string()])
+        );
+        assert!(nobody_files.is_empty());
+    }
+
+    #[test]
+    fn test_human_author_has_email() {
+        assert!(human_author_has_email(
+            "Alice <alice@example.com>",
+            "alice@example.com"
+        ));
+        assert!(human_author_has_email(
+            "alice@example.com",
+            "ALICE@example.com"
+        ));
+        assert!(!human_author_has_email(
+            "Alice <alice@example.com>",
+            "bob@example.com"
+        ));
+        assert!(!human_author_has_email("Alice", "alice@example.com"));
+    }
+
+    #[test]
+    fn test_load_all_ai_attestations_preserves_line_ranges() {
+        let tmp_repo = TmpRepo::new().unwrap();
+
+        tmp_repo.write_file("a.txt", "a\n", true).unwrap();
+        tmp_repo.commit_with_message("Commit A").unwrap();
+        let commit_a = tmp_repo.get_head_commit_sha().unwrap();
+
+        let mut log = AuthorshipLog::new();
+        let mut attestation = FileAttestation::new("src/lib.rs".to_string());
+        attestation.add_entry(AttestationEntry::new(
+            "abc1234".to_string(),
+            vec![LineRange::Range(1, 3), LineRange::Single(7)],
+        ));
+        attestation.add_entry(AttestationEntry::new(
+            "def5678".to_string(),
+            vec![LineRange::Range(10, 12)],
+        ));
+        log.attestations.push(attestation.clone());
+        notes_add(
+            tmp_repo.gitai_repo(),
+            &commit_a,
+            &log.serialize_to_string().unwrap(),
+        )
+        .unwrap();
+
+        let attestations = smol::block_on(load_all_ai_attestations(tmp_repo.gitai_repo())).unwrap();
+        assert_eq!(attestations, vec![attestation]);
+
+        let files = smol::block_on(load_all_ai_touched_files(tmp_repo.gitai_repo())).unwrap();
+        assert_eq!(files, HashSet::from(["src/lib.rs".to_string()]));
+    }
+
+    #[test]
+    fn test_load_all_ai_attestations_reads_configured_notes_ref() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        tmp_repo.write_file("a.txt", "a\n", true).unwrap();
+        tmp_repo.commit_with_message("Commit A").unwrap();
+        let commit_a = tmp_repo.get_head_commit_sha().unwrap();
+
+        let note_for = |file_path: &str| {
+            let mut log = AuthorshipLog::new();
+            let mut attestation = FileAttestation::new(file_path.to_string());
+            attestation.add_entry(AttestationEntry::new(
+                "abc1234".to_string(),
+                vec![LineRange::Range(1, 2)],
+            ));
+            log.attestations.push(attestation);
+            log.serialize_to_string().unwrap()
+        };
+        notes_add(
+            tmp_repo.gitai_repo(),
+            &commit_a,
+            &note_for("src/default.rs"),
+        )
+        .unwrap();
+
+        tmp_repo
+            .git_command(&["config", "git-ai.notesRef", "refs/notes/myorg/ai"])
+            .unwrap();
+        let repo = tmp_repo.gitai_repo();
+        assert_eq!(repo.notes_ref(), "refs/notes/myorg/ai");
+        notes_add(repo, &commit_a, &note_for("src/custom.rs")).unwrap();
+
+        assert!(crate::git::refs::ref_exists(repo, "refs/notes/myorg/ai"));
+        let files = smol::block_on(load_all_ai_touched_files(repo)).unwrap();
+        assert_eq!(files, HashSet::from(["src/custom.rs".to_string()]));
+    }
+
+    #[test]
+    fn test_load_attestations_with_commits_reassembles_fanout_paths() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        tmp_repo.write_file("a.txt", "a\n", true).unwrap();
+        tmp_repo.commit_with_message("Commit A").unwrap();
+        let commit_a = tmp_repo.get_head_commit_sha().unwrap();
+        tmp_repo.write_file("b.txt", "b\n", true).unwrap();
+        tmp_repo.commit_with_message("Commit B").unwrap();
+        let commit_b = tmp_repo.get_head_commit_sha().unwrap();
+
+        let repo = tmp_repo.gitai_repo();
+        notes_add(
+            repo,
+            &commit_a,
+            &note_with_attestations(&[("src/a.rs", "aaaaaaa", "Alice <alice@example.com>")]),
+        )
+        .unwrap();
+        notes_add(
+            repo,
+            &commit_b,
+            &note_with_attestations(&[("src/b.rs", "bbbbbbb", "Bob <bob@example.com>")]),
+        )
+        .unwrap();
+
+        // Rewrite the notes tree so commit B's note sits under a fanout directory ("ab/cdef...")
+        let git = |args: &[&str], stdin: &str| {
+            let mut full_args = repo.global_args_for_exec();
+            full_args.extend(args.iter().map(|arg| arg.to_string()));
+            let output = exec_git_stdin(&full_args, stdin.as_bytes()).unwrap();
+            String::from_utf8(output.stdout).unwrap().trim().to_string()
+        };
+        let blob_for = |commit_sha: &str| {
+            git(
+                &[
+                    "rev-parse",
+                    &format!("{}:{}", DEFAULT_NOTES_REF, commit_sha),
+                ],
+                "",
+            )
+        };
+        let (fanout_dir, fanout_name) = commit_b.split_at(2);
+        let inner_tree = git(
+            &["mktree"],
+            &format!("100644 blob {}\t{}\n", blob_for(&commit_b), fanout_name),
+        );
+        let notes_tree = git(
+            &["mktree"],
+            &format!(
+                "100644 blob {}\t{}\n040000 tree {}\t{}\n",
+                blob_for(&commit_a),
+                commit_a,
+                inner_tree,
+                fanout_dir
+            ),
+        );
+        let notes_commit = git(&["commit-tree", &notes_tree, "-m", "fanout"], "");
+        git(&["update-ref", DEFAULT_NOTES_REF, &notes_commit], "");
+
+        let attestations = load_attestations_with_commits(repo).unwrap();
+        let mut pairs: Vec<(String, String)> = attestations
+            .into_iter()
+            .map(|(commit_sha, attestation)| (commit_sha, attestation.file_path))
+            .collect();
+        pairs.sort();
+        let mut expected = vec![
+            (commit_a.clone(), "src/a.rs".to_string()),
+            (commit_b.clone(), "src/b.rs".to_string()),
+        ];
+        expected.sort();
+        assert_eq!(pairs, expected);
+    }
+
+    #[test]
+    fn test_traversal_in_sha256_repository() {
+        let temp = tempfile::tempdir().unwrap();
+        let git = |args: &[&str]| {
+            let output = std::process::Command::new(crate::config::Config::get().git_cmd())
+                .current_dir(temp.path())
+                .args([
+                    "-c",
+                    "user.name=Test User",
+                    "-c",
+                    "user.email=test@example.com",
+                ])
+                .args(args)
+                .output()
+                .unwrap();
+            assert!(output.status.success(), "{:?}", output);
+            String::from_utf8(output.stdout).unwrap().trim().to_string()
+        };
+        git(&["init", "-q", "--object-format=sha256"]);
+        git(&["config", "user.name", "Test User"]);
+        git(&["config", "user.email", "test@example.com"]);
+        std::fs::write(temp.path().join("a.txt"), "a\n").unwrap();
+        git(&["add", "a.txt"]);
+        git(&["commit", "-q", "-m", "Commit A"]);
+        let commit = git(&["rev-parse", "HEAD"]);
+        assert_eq!(commit.len(), 64);
+
+        let repo = find_repository_in_path(temp.path().to_str().unwrap()).unwrap();
+        notes_add(
+            &repo,
+            &commit,
+            &note_with_attestations(&[("src/a.rs", "aaaaaaa", "Alice <alice@example.com>")]),
+        )
+        .unwrap();
+
+        let attestations = load_attestations_with_commits(&repo).unwrap();
+        assert_eq!(attestations.len(), 1);
+        assert_eq!(attestations[0].0, commit);
+        assert_eq!(attestations[0].1.file_path, "src/a.rs");
+        assert_eq!(
+            smol::block_on(load_all_ai_touched_files(&repo)).unwrap(),
+            HashSet::from(["src/a.rs".to_string()])
+        );
+        assert!(commits_have_authorship_notes(&repo, std::slice::from_ref(&commit)).unwrap());
+    }
+
+    #[test]
+    fn test_iter_notes_visits_every_note_once() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        let repo = tmp_repo.gitai_repo();
+        let mut expected = HashMap::new();
+        for (name, content) in [
+            ("a", "note a\n"),
+            ("b", "shared note\n"),
+            ("c", "shared note\n"),
+        ] {
+            tmp_repo
+                .write_file(&format!("{}.txt", name), "x\n", true)
+                .unwrap();
+            tmp_repo.commit_with_message(name).unwrap();
+            let commit = tmp_repo.get_head_commit_sha().unwrap();
+            notes_add(repo, &commit, content).unwrap();
+            expected.insert(commit, content.as_bytes().to_vec());
+        }
+
+        let mut visited = HashMap::new();
+        for note in iter_notes(repo) {
+            let (commit_sha, content) = note.unwrap();
+            assert!(
+                visited.insert(commit_sha.clone(), content).is_none(),
+                "{} visited twice",
+                commit_sha
+            );
+        }
+
+        assert_eq!(visited, expected);
+    }
+
+    #[test]
+    fn test_iter_notes_without_notes_ref_is_empty() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        assert_eq!(iter_notes(tmp_repo.gitai_repo()).count(), 0);
+    }
+
+    #[test]
+    fn test_commit_sha_from_note_path() {
+        let sha1 = "0123456789abcdef0123456789abcdef01234567";
+        assert_eq!(commit_sha_from_note_path(sha1).as_deref(), Some(sha1));
+        assert_eq!(
+            commit_sha_from_note_path("01/23/456789abcdef0123456789abcdef01234567").as_deref(),
+            Some(sha1)
+        );
+        let sha256 = "ab".repeat(32);
+        assert_eq!(
+            commit_sha_from_note_path(&format!("{}/{}", &sha256[..2], &sha256[2..])),
+            Some(sha256)
+        );
+        assert_eq!(commit_sha_from_note_path("README"), None);
+        assert_eq!(commit_sha_from_note_path("01/23"), None);
+    }
+
+    #[test]
+    fn test_load_all_ai_attestations_without_notes_ref() {
+        let tmp_repo = TmpRepo::new().unwrap();
+
+        let attestations = smol::block_on(load_all_ai_attestations(tmp_repo.gitai_repo())).unwrap();
+
+        assert!(attestations.is_empty());
+    }
+
+    #[test]
+    fn test_parse_cat_file_batch_output_keeps_invalid_utf8_blob() {
+        let mut data = b"abc123 blob 4\nsrc".to_vec();
+        data.push(0xff);
+        data.push(b'\n');
+
+        let result = read_cat_file_batch(&data).unwrap();
+
+        assert_eq!(result.get("abc123"), Some(&vec![b's', b'r', b'c', 0xff]));
+    }
+
+    #[test]
+    fn test_note_file_paths_with_invalid_utf8() {
+        // Invalid byte inside a metadata string: the note still parses
+        let mut note = b"src/lib.rs\n  abc1234 1-3\nsrc/main.rs\n  abc1234 5\n---\n{\"schema_version\":\"authorship/3.0.0\",\"base_commit_sha\":\"".to_vec();
+        note.push(0xff);
+        note.extend(b"\",\"prompts\":{}}");
+        let files = note_file_paths(&note);
+        assert_eq!(
+            files,
+            HashSet::from(["src/lib.rs".to_string(), "src/main.rs".to_string()])
+        );
+
+        // Invalid byte inside the attestation section: other paths are still kept
+        let mut note = b"src/caf".to_vec();
+        note.push(0xe9);
+        note.extend(b".rs\n  abc1234 1-3\nsrc/ok.rs\n  abc1234 4\n---\n");
+        note.extend(NOTE_METADATA.as_bytes());
+        let files = note_file_paths(&note);
+        assert_eq!(
+            files,
+            HashSet::from(["src/caf\u{FFFD}.rs".to_string(), "src/ok.rs".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_parse_note_log_drops_notes_with_unparseable_metadata() {
+        // No metadata is invented for notes whose metadata section is broken
+        let note = b"src/lib.rs\n  abc1234 1-3\n---\n{\"schema_version\":\"authorship/3.0.0\",\"prompts\":\"oops\"}";
+        assert!(parse_note_log(note).is_none());
+        assert!(parse_note_attestations(note).is_empty());
+    }
+
+    #[test]
+    fn test_parse_note_log_skips_unsupported_schema() {
+        let future = b"src/lib.rs\n  abc1234 1-3\n---\n{\"schema_version\":\"authorship/4.0.0\",\"base_commit_sha\":\"\",\"prompts\":{}}";
+        assert!(parse_note_log(future).is_none());
+
+        // A future note that also fails to parse must not be salvaged as the current schema
+        let malformed = b"src/lib.rs\n  abc1234 1-3\n---\n{\"schema_version\":\"authorship/4.0.0\",\"prompts\":[]}";
+        assert!(parse_note_log(malformed).is_none());
+    }
+
+    #[test]
+    fn test_commits_touching_file_maps_notes_back_to_commits() {
+        let tmp_repo = TmpRepo::new().unwrap();
+
+        tmp_repo.write_file("a.txt", "a\n", true).unwrap();
+        tmp_repo.commit_with_message("Commit A").unwrap();
+        let commit_a = tmp_repo.get_head_commit_sha().unwrap();
+        notes_add(
+            tmp_repo.gitai_repo(),
+            &commit_a,
+            &note_with_attestations(&[
+                ("src/only_a.rs", "aaaaaaa", "Alice <alice@example.com>"),
+                ("src/shared.rs", "aaaaaaa", "Alice <alice@example.com>"),
+            ]),
+        )
+        .unwrap();
+
+        tmp_repo.write_file("b.txt", "b\n", true).unwrap();
+        tmp_repo.commit_with_message("Commit B").unwrap();
+        let commit_b = tmp_repo.get_head_commit_sha().unwrap();
+        notes_add(
+            tmp_repo.gitai_repo(),
+            &commit_b,
+            &note_with_attestations(&[
+                ("src/only_b.rs", "bbbbbbb", "Bob <bob@example.com>"),
+                ("src/shared.rs", "bbbbbbb", "Bob <bob@example.com>"),
+            ]),
+        )
+        .unwrap();
+
+        let repo = tmp_repo.gitai_repo();
+        assert_eq!(
+            commits_touching_file(repo, "src/only_a.rs").unwrap(),
+            vec![commit_a.clone()]
+        );
+        assert_eq!(
+            commits_touching_file(repo, "src/only_b.rs").unwrap(),
+            vec![commit_b.clone()]
+        );
+        let mut both = vec![commit_a, commit_b];
+        both.sort();
+        assert_eq!(commits_touching_file(repo, "src/shared.rs").unwrap(), both);
+        assert!(
+            commits_touching_file(repo, "src/untouched.rs")
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_load_ai_touched_files_matching_globs() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        let repo = tmp_repo.gitai_repo();
+        tmp_repo.write_file("a.txt", "a\n", true).unwrap();
+        tmp_repo.commit_with_message("Commit A").unwrap();
+        let commit_a = tmp_repo.get_head_commit_sha().unwrap();
+        notes_add(
+            repo,
+            &commit_a,
+            &note_with_attestations(&[
+                ("lib.rs", "aaaaaaa", "Alice <alice@example.com>"),
+                ("src/main.rs", "bbbbbbb", "Alice <alice@example.com>"),
+                (
+                    "src/auth/deep/token.rs",
+                    "ccccccc",
+                    "Alice <alice@example.com>",
+                ),
+                ("src/auth/README.md", "ddddddd", "Alice <alice@example.com>"),
+                (
+                    "tests/src/fixture.rs",
+                    "eeeeeee",
+                    "Alice <alice@example.com>",
+                ),
+            ]),
+        )
+        .unwrap();
+
+        let matching = |glob: &str| {
+            let mut files: Vec<String> = smol::block_on(load_ai_touched_files_matching(repo, glob))
+                .unwrap()
+                .into_iter()
+                .collect();
+            files.sort();
+            files
+        };
+
+        assert_eq!(
+            matching("src/**/*.rs"),
+            vec!["src/auth/deep/token.rs", "src/main.rs"]
+        );
+        assert_eq!(matching("src/*.rs"), vec!["src/main.rs"]);
+        assert_eq!(matching("**/README.md"), vec!["src/auth/README.md"]);
+        assert_eq!(
+            matching("*.rs"),
+            vec![
+                "lib.rs",
+                "src/auth/deep/token.rs",
+                "src/main.rs",
+                "tests/src/fixture.rs"
+            ]
+        );
+        assert!(matching("*.py").is_empty());
+        assert!(smol::block_on(load_ai_touched_files_matching(repo, "src/[")).is_err());
+    }
+
+    #[test]
+    fn test_load_all_ai_touched_files_cache_tracks_notes_tip() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        let repo = tmp_repo.gitai_repo();
+        let cache_path = repo.storage.ai_dir.join(AI_TOUCHED_FILES_CACHE_FILE);
+
+        tmp_repo.write_file("a.txt", "a\n", true).unwrap();
+        tmp_repo.commit_with_message("Commit A").unwrap();
+        let commit_a = tmp_repo.get_head_commit_sha().unwrap();
+        notes_add(
+            repo,
+            &commit_a,
+            &note_with_attestations(&[("src/a.rs", "aaaaaaa", "Alice <alice@example.com>")]),
+        )
+        .unwrap();
+
+        let files = smol::block_on(load_all_ai_touched_files(repo)).unwrap();
+        assert!(files.contains("src/a.rs"));
+        let first_tip = notes_ref_tip(repo).unwrap();
+        assert_eq!(
+            read_touched_files_cache(&cache_path, &first_tip),
+            Some(files.clone())
+        );
+
+        // A cache entry for the current tip is trusted without re-reading notes
+        write_touched_files_cache(
+            &cache_path,
+            &first_tip,
+            &HashSet::from(["cached/only.rs".to_string()]),
+        )
+        .unwrap();
+        assert_eq!(
+            smol::block_on(load_all_ai_touched_files(repo)).unwrap(),
+            HashSet::from(["cached/only.rs".to_string()])
+        );
+
+        // Adding a note moves the tip, which forces a recompute
+        tmp_repo.write_file("b.txt", "b\n", true).unwrap();
+        tmp_repo.commit_with_message("Commit B").unwrap();
+        let commit_b = tmp_repo.get_head_commit_sha().unwrap();
+        notes_add(
+            repo,
+            &commit_b,
+            &note_with_attestations(&[("src/b.rs", "bbbbbbb", "Bob <bob@example.com>")]),
+        )
+        .unwrap();
+        let second_tip = notes_ref_tip(repo).unwrap();
+        assert_ne!(first_tip, second_tip);
+
+        let files = smol::block_on(load_all_ai_touched_files(repo)).unwrap();
+        assert!(files.contains("src/a.rs"));
+        assert!(files.contains("src/b.rs"));
+        assert!(!files.contains("cached/only.rs"));
+        assert_eq!(
+            read_touched_files_cache(&cache_path, &second_tip),
+            Some(files)
+        );
+    }
+
+    #[test]
+    fn test_read_touched_files_with_progress_reports_each_window() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        let repo = tmp_repo.gitai_repo();
+
+        for name in ["a", "b", "c"] {
+            tmp_repo
+                .write_file(&format!("{}.txt", name), "x\n", true)
+                .unwrap();
+            tmp_repo
+                .commit_with_message(&format!("Commit {}", name))
+                .unwrap();
+            let commit = tmp_repo.get_head_commit_sha().unwrap();
+            notes_add(
+                repo,
+                &commit,
+                &note_with_attestations(&[(
+                    &format!("src/{}.rs", name),
+                    "aaaaaaa",
+                    "Alice <alice@example.com>",
+                )]),
+            )
+            .unwrap();
+        }
+
+        let blob_oids = get_note_blob_shas(repo).unwrap();
+        let mut reports = Vec::new();
+        let files = read_touched_files_with_progress(repo, &blob_oids, 2, |processed, total| {
+            reports.push((processed, total))
+        })
+        .unwrap();
+
+        let total = blob_oids.len();
+        assert!(files.contains("src/a.rs") && files.contains("src/c.rs"));
+        assert!(reports.windows(2).all(|pair| pair[0].0 < pair[1].0));
+        assert!(
+            reports
+                .iter()
+                .all(|&(_, reported_total)| reported_total == total)
+        );
+        assert_eq!(reports.last(), Some(&(total, total)));
+        assert_eq!(reports.len(), total.div_ceil(2));
+    }
+
+    #[test]
+    fn test_load_all_ai_touched_files_with_progress_reports_empty_repo() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        let reports = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+
+        let sink = reports.clone();
+        let files = smol::block_on(load_all_ai_touched_files_with_progress(
+            tmp_repo.gitai_repo(),
+            move |processed, total| sink.lock().unwrap().push((processed, total)),
+        ))
+        .unwrap();
+
+        assert!(files.is_empty());
+        assert_eq!(*reports.lock().unwrap(), vec![(0, 0)]);
+    }
+
+    #[test]
+    fn test_shared_note_blob_is_read_once() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        let repo = tmp_repo.gitai_repo();
+        let note = note_with_attestations(&[("src/shared.rs", "aaaaaaa", "Alice <a@example.com>")]);
+
+        let mut commits = Vec::new();
+        for name in ["a", "b"] {
+            tmp_repo
+                .write_file(&format!("{}.txt", name), "x\n", true)
+                .unwrap();
+            tmp_repo
+                .commit_with_message(&format!("Commit {}", name))
+                .unwrap();
+            let commit = tmp_repo.get_head_commit_sha().unwrap();
+            notes_add(repo, &commit, &note).unwrap();
+            commits.push(commit);
+        }
+
+        let entries = get_note_entries(repo).unwrap();
+        let blob_for = |commit: &str| {
+            entries
+                .iter()
+                .find(|(commit_sha, _)| commit_sha == commit)
+                .map(|(_, blob)| blob.clone())
+                .unwrap()
+        };
+        let shared_blob = blob_for(&commits[0]);
+        assert_eq!(blob_for(&commits[1]), shared_blob);
+
+        let blob_oids = vec![shared_blob.clone(), shared_blob.clone()];
+        let mut reads = Vec::new();
+        for_each_note_blob(repo, &blob_oids, |oid, _content| {
+            reads.push(oid.to_string());
+        })
+        .unwrap();
+        assert_eq!(reads, vec![shared_blob]);
+    }
+
+    #[test]
+    fn test_dedup_blob_oids_keeps_first_seen_order() {
+        let oids: Vec<String> = ["c", "a", "c", "b", "a"].map(String::from).to_vec();
+        assert_eq!(
+            dedup_blob_oids(&oids).as_ref(),
+            ["c", "a", "b"].map(String::from)
+        );
+
+        let unique: Vec<String> = ["b", "a"].map(String::from).to_vec();
+        assert!(matches!(dedup_blob_oids(&unique), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn test_load_ai_touched_files_in_range_filters_by_committer_date() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        let repo = tmp_repo.gitai_repo();
+
+        for (name, date) in [
+            ("jan", "2024-01-15T12:00:00Z"),
+            ("mar", "2024-03-15T12:00:00Z"),
+            ("jun", "2024-06-15T12:00:00Z"),
+        ] {
+            tmp_repo
+                .write_file(&format!("{}.txt", name), "x\n", true)
+                .unwrap();
+            let output = std::process::Command::new(crate::config::Config::get().git_cmd())
+                .current_dir(tmp_repo.path())
+                .env("GIT_AUTHOR_DATE", date)
+                .env("GIT_COMMITTER_DATE", date)
+                .args([
+                    "-c",
+                    "user.name=Test User",
+                    "-c",
+                    "user.email=test@example.com",
+                    "commit",
+                    "-q",
+                    "-m",
+                    name,
+                ])
+                .output()
+                .unwrap();
+            assert!(output.status.success(), "{:?}", output);
+            let commit = tmp_repo.get_head_commit_sha().unwrap();
+            notes_add(
+                repo,
+                &commit,
+                &note_with_attestations(&[(
+                    &format!("src/{}.rs", name),
+                    "aaaaaaa",
+                    "Alice <alice@example.com>",
+                )]),
+            )
+            .unwrap();
+        }
+
+        let in_range = |since: Option<&str>, until: Option<&str>| {
+            smol::block_on(load_ai_touched_files_in_range(repo, since, until)).unwrap()
+        };
+
+        assert_eq!(
+            in_range(Some("2024-02-01"), Some("2024-04-01")),
+            HashSet::from(["src/mar.rs".to_string()])
+        );
+        assert_eq!(
+            in_range(Some("2024-02-01"), None),
+            HashSet::from(["src/mar.rs".to_string(), "src/jun.rs".to_string()])
+        );
+        assert_eq!(
+            in_range(None, Some("2024-02-01")),
+            HashSet::from(["src/jan.rs".to_string()])
+        );
+        assert!(in_range(Some("2025-01-01"), None).is_empty());
+    }
+
+    #[test]
+    fn test_streamed_note_reads_go_through_the_repository_executor() {
+        use crate::git::git_executor::MockGitExecutor;
+        use crate::git::repository::from_bare_repository;
+
+        let temp = tempfile::tempdir().unwrap();
+        let executor = Arc::new(
+            MockGitExecutor::new()
+                .on(
+                    &["--batch-check=%(objectname) %(objecttype)"],
+                    "aaa commit\nbbb missing\n",
+                )
+                .on(&["cat-file", "--batch"], "ccc blob 5\nhello\n"),
+        );
+        let repo = from_bare_repository(temp.path())
+            .unwrap()
+            .with_executor(executor.clone());
+
+        let existing = existing_commits(&repo, &["aaa".to_string(), "bbb".to_string()]).unwrap();
+        let mut blobs = Vec::new();
+        batch_read_blobs_chunked(&repo, &["ccc".to_string()], 1, |oid, content| {
+            blobs.push((oid.to_string(), content.to_vec()));
+        })
+        .unwrap();
+
+        assert_eq!(existing, vec!["aaa".to_string()]);
+        assert_eq!(blobs, vec![("ccc".to_string(), b"hello".to_vec())]);
+        assert_eq!(executor.calls().len(), 2);
+    }
+
+    #[test]
+    fn test_batch_read_blobs_chunked_keeps_framing_across_windows() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        let repo = tmp_repo.gitai_repo();
+        let global_args = repo.global_args_for_exec();
+
+        // Contents that look like cat-file headers or lack trailing newlines must not confuse
+        // the framing at window boundaries
+        let contents = [
+            "plain".to_string(),
+            "line one\nline two\n".to_string(),
+            "0123456789abcdef blob 5\nfake header\n".to_string(),
+            "\n\n".to_string(),
+            "no trailing newline".to_string(),
+            "deadbeef missing\n".to_string(),
+            "x".repeat(10_000),
+        ];
+        let mut expected = std::collections::HashMap::new();
+        for content in &contents {
+            let mut args = global_args.clone();
+            args.extend(["hash-object", "-w", "--stdin"].map(String::from));
+            let output = exec_git_stdin(&args, content.as_bytes()).unwrap();
+            let oid = String::from_utf8(output.stdout).unwrap().trim().to_string();
+            expected.insert(oid, content.as_bytes().to_vec());
+        }
+        let oids: Vec<String> = expected.keys().cloned().collect();
+
+        for window in [1, 2, 3, oids.len(), oids.len() + 5] {
+            let mut seen = std::collections::HashMap::new();
+            batch_read_blobs_chunked(repo, &oids, window, |oid, content| {
+                seen.insert(oid.to_string(), content.to_vec());
+            })
+            .unwrap();
+            assert_eq!(seen, expected, "window size {}", window);
+        }
+    }
+
+    #[test]
+    fn test_count_unioned_lines_merges_overlaps() {
+        let ranges = [
+            LineRange::Range(1, 5),
+            LineRange::Range(3, 8),
+            LineRange::Single(8),
+            LineRange::Single(10),
+            LineRange::Range(11, 12),
+            LineRange::Range(20, 18),
+        ];
+        // 1-8, 10-12 and 18-20
+        assert_eq!(count_unioned_lines(ranges.iter()), 8 + 3 + 3);
+        assert_eq!(count_unioned_lines(std::iter::empty()), 0);
+    }
+
+    #[test]
+    fn test_ai_line_stats_unions_overlapping_ranges_across_commits() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        let repo = tmp_repo.gitai_repo();
+
+        let note_for = |ranges: Vec<(&str, &str, Vec<LineRange>)>, prompts: &[&str]| {
+            let mut log = AuthorshipLog::new();
+            for (file_path, hash, line_ranges) in ranges {
+                let mut attestation = FileAttestation::new(file_path.to_string());
+                attestation.add_entry(AttestationEntry::new(hash.to_string(), line_ranges));
+                log.attestations.push(attestation);
+            }
+            for hash in prompts {
+                log.metadata.prompts.insert(
+                    hash.to_string(),
+                    test_prompt_record(hash, "Test <test@example.com>"),
+                );
+            }
+            log.serialize_to_string().unwrap()
+        };
+
+        tmp_repo.write_file("a.txt", "a\n", true).unwrap();
+        tmp_repo.commit_with_message("Commit A").unwrap();
+        let commit_a = tmp_repo.get_head_commit_sha().unwrap();
+        notes_add(
+            repo,
+            &commit_a,
+            &note_for(
+                vec![
+                    ("src/lib.rs", "aaaaaaa", vec![LineRange::Range(1, 10)]),
+                    ("src/other.rs", "aaaaaaa", vec![LineRange::Single(4)]),
+                ],
+                &["aaaaaaa"],
+            ),
+        )
+        .unwrap();
+
+        tmp_repo.write_file("b.txt", "b\n", true).unwrap();
+        tmp_repo.commit_with_message("Commit B").unwrap();
+        let commit_b = tmp_repo.get_head_commit_sha().unwrap();
+        notes_add(
+            repo,
+            &commit_b,
+            &note_for(
+                vec![
+                    (
+                        "src/lib.rs",
+                        "bbbbbbb",
+                        vec![LineRange::Range(5, 15), LineRange::Single(30)],
+                    ),
+                    // Entry whose prompt isn't recorded in any note
+                    ("src/lib.rs", "ccccccc", vec![LineRange::Range(14, 20)]),
+                ],
+                &["bbbbbbb"],
+            ),
+        )
+        .unwrap();
+
+        let stats = smol::block_on(ai_line_stats(repo)).unwrap();
+
+        // AI: 1-15 and 30; all attributed: 1-20 and 30
+        assert_eq!(stats.get("src/lib.rs"), Some(&(16, 21)));
+        assert_eq!(stats.get("src/other.rs"), Some(&(1, 1)));
+        assert_eq!(stats.len(), 2);
+    }
+
+    #[test]
+    fn test_notes_ref_pointing_at_blob_is_corrupt_notes() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        tmp_repo.write_file("a.txt", "a\n", true).unwrap();
+        tmp_repo.commit_with_message("Commit A").unwrap();
+        tmp_repo
+            .git_command(&["update-ref", DEFAULT_NOTES_REF, "HEAD:a.txt"])
+            .unwrap();
+
+        let result = smol::block_on(load_all_ai_touched_files(tmp_repo.gitai_repo()));
+        match result {
+            Err(GitAiError::CorruptNotes(ref_name)) => assert_eq!(ref_name, DEFAULT_NOTES_REF),
+            other => panic!("expected CorruptNotes, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_missing_notes_ref_is_empty() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        tmp_repo.write_file("a.txt", "a\n", true).unwrap();
+        tmp_repo.commit_with_message("Commit A").unwrap();
+        tmp_repo
+            .git_command(&["update-ref", "-d", DEFAULT_NOTES_REF])
+            .unwrap();
+
+        assert!(
+            get_note_blob_shas(tmp_repo.gitai_repo())
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_load_all_ai_touched_files_current_names_follows_renames() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        let repo = tmp_repo.gitai_repo();
+        let author = "Test <test@example.com>";
+
+        tmp_repo
+            .write_file("src/old.rs", "fn old() {}\n", true)
+            .unwrap();
+        tmp_repo
+            .write_file("src/gone.rs", "fn gone() {}\n", true)
+            .unwrap();
+        tmp_repo
+            .write_file("src/stays.rs", "fn stays() {}\n", true)
+            .unwrap();
+        tmp_repo.commit_with_message("Add files").unwrap();
+        let commit_sha = tmp_repo.get_head_commit_sha().unwrap();
+        notes_add(
+            repo,
+            &commit_sha,
+            &note_with_attestations(&[
+                ("src/old.rs", "aaaaaaa", author),
+                ("src/gone.rs", "aaaaaaa", author),
+                ("src/stays.rs", "aaaaaaa", author),
+            ]),
+        )
+        .unwrap();
+
+        tmp_repo
+            .git_command(&["mv", "src/old.rs", "src/new.rs"])
+            .unwrap();
+        tmp_repo.git_command(&["rm", "-q", "src/gone.rs"]).unwrap();
+        tmp_repo.commit_with_message("Rename and delete").unwrap();
+
+        let files = smol::block_on(load_all_ai_touched_files_current_names(repo)).unwrap();
+
+        let expected: HashSet<String> = ["src/new.rs", "src/stays.rs"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        assert_eq!(files, expected);
+    }
+
+    #[test]
+    fn test_load_all_ai_touched_files_current_names_keeps_reused_names() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        let repo = tmp_repo.gitai_repo();
+        let author = "Test <test@example.com>";
+
+        tmp_repo
+            .write_file("old.rs", "fn old() {}\n", true)
+            .unwrap();
+        tmp_repo.commit_with_message("Add old.rs").unwrap();
+        tmp_repo.git_command(&["mv", "old.rs", "new.rs"]).unwrap();
+        tmp_repo.commit_with_message("Rename").unwrap();
+
+        // A new file reuses the old name after the rename and is attributed there
+        tmp_repo
+            .write_file("old.rs", "fn reused() {}\n", true)
+            .unwrap();
+        tmp_repo.commit_with_message("Reuse old.rs").unwrap();
+        let commit_sha = tmp_repo.get_head_commit_sha().unwrap();
+        notes_add(
+            repo,
+            &commit_sha,
+            &note_with_attestations(&[("old.rs", "aaaaaaa", author)]),
+        )
+        .unwrap();
+
+        let files = smol::block_on(load_all_ai_touched_files_current_names(repo)).unwrap();
+
+        assert_eq!(files, HashSet::from(["old.rs".to_string()]));
+    }
+
+    #[test]
+    fn test_remap_to_current_names_follows_rename_chains() {
+        let attributed = vec![(Some(0), "a.rs".to_string()), (Some(0), "b.rs".to_string())];
+        let renames = vec![
+            (1, "a.rs".to_string(), "c.rs".to_string()),
+            (2, "c.rs".to_string(), "d.rs".to_string()),
+            (3, "b.rs".to_string(), "e.rs".to_string()),
+        ];
+        let head_paths: HashSet<String> = ["d.rs"].into_iter().map(String::from).collect();
+
+        let current = remap_to_current_names(attributed, &renames, &head_paths);
+
+        assert_eq!(current, HashSet::from(["d.rs".to_string()]));
+    }
+
+    #[test]
+    fn test_remap_to_current_names_ignores_renames_before_attribution() {
+        // old.rs was moved to new.rs, then a new old.rs was attributed afterwards
+        let attributed = vec![
+            (Some(0), "old.rs".to_string()),
+            (Some(2), "old.rs".to_string()),
+            (None, "other.rs".to_string()),
+        ];
+        let renames = vec![
+            (1, "old.rs".to_string(), "new.rs".to_string()),
+            (3, "other.rs".to_string(), "moved.rs".to_string()),
+        ];
+        let head_paths: HashSet<String> = ["old.rs", "new.rs", "moved.rs"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+
+        let current = remap_to_current_names(attributed, &renames, &head_paths);
+
+        assert_eq!(current, head_paths);
     }
 }
@@ -481,6 +481,12 @@ pub fn is_dry_run(args: &[String]) -> bool {
     args.iter().any(|arg| arg == "--dry-run")
 }
 
+/// Whether `GIT_AI_DRY_RUN=1` asks git-ai to preview its own changes (notes, working-log
+/// renames) without making them, while the git command itself still runs
+pub fn is_git_ai_dry_run_env() -> bool {
+    std::env::var("GIT_AI_DRY_RUN").is_ok_and(|value| value == "1")
+}
+
 /// Extract the target directory from git clone command arguments.
 /// Returns the directory where the repository was cloned to.
 ///
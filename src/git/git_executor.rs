@@ -0,0 +1,172 @@
+use crate::error::GitAiError;
+use crate::git::repository::{
+    exec_git, exec_git_stdin, exec_git_stdin_streaming, exec_git_stdin_writer,
+};
+use std::process::Output;
+
+/// Produces a command's stdin incrementally, see [`GitExecutor::exec_stdin_writer`]
+pub type StdinWriter = Box<dyn FnOnce(&mut dyn std::io::Write) -> std::io::Result<()> + Send>;
+
+/// Runs git commands on behalf of a [`Repository`](crate::git::repository::Repository).
+///
+/// Implementations follow the contract of [`exec_git`]: a non-zero exit is returned as
+/// [`GitAiError::GitCliError`] rather than as an `Output`, so callers can match on exit codes.
+///
+/// Long-running request/response processes started with
+/// [`spawn_git_piped`](crate::git::repository::spawn_git_piped) (e.g. `cat-file --batch`) hand
+/// back a live child process and always spawn git.
+pub trait GitExecutor: Send + Sync + std::fmt::Debug {
+    /// Run git with `args` (global args included) and collect its output
+    fn exec(&self, args: &[String]) -> Result<Output, GitAiError>;
+
+    /// Like [`GitExecutor::exec`], with `stdin_data` written to git's stdin
+    fn exec_stdin(&self, args: &[String], stdin_data: &[u8]) -> Result<Output, GitAiError>;
+
+    /// Like [`GitExecutor::exec_stdin`], with stdin produced by `write_stdin` as git runs
+    fn exec_stdin_writer(
+        &self,
+        args: &[String],
+        write_stdin: StdinWriter,
+    ) -> Result<Output, GitAiError>;
+
+    /// Like [`GitExecutor::exec_stdin`], handing stdout to `on_stdout` as it is produced
+    fn exec_stdin_streaming(
+        &self,
+        args: &[String],
+        stdin_data: Vec<u8>,
+        on_stdout: &mut dyn FnMut(&mut dyn std::io::BufRead) -> Result<(), GitAiError>,
+    ) -> Result<(), GitAiError>;
+}
+
+/// Default executor that spawns the configured git binary
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ProcessGitExecutor;
+
+impl GitExecutor for ProcessGitExecutor {
+    fn exec(&self, args: &[String]) -> Result<Output, GitAiError> {
+        exec_git(args)
+    }
+
+    fn exec_stdin(&self, args: &[String], stdin_data: &[u8]) -> Result<Output, GitAiError> {
+        exec_git_stdin(args, stdin_data)
+    }
+
+    fn exec_stdin_writer(
+        &self,
+        args: &[String],
+        write_stdin: StdinWriter,
+    ) -> Result<Output, GitAiError> {
+        exec_git_stdin_writer(args, write_stdin)
+    }
+
+    fn exec_stdin_streaming(
+        &self,
+        args: &[String],
+        stdin_data: Vec<u8>,
+        on_stdout: &mut dyn FnMut(&mut dyn std::io::BufRead) -> Result<(), GitAiError>,
+    ) -> Result<(), GitAiError> {
+        exec_git_stdin_streaming(args, stdin_data, on_stdout)
+    }
+}
+
+#[cfg(test)]
+pub use mock::MockGitExecutor;
+
+#[cfg(test)]
+mod mock {
+    use super::*;
+    use std::process::ExitStatus;
+    use std::sync::Mutex;
+
+    /// Successful stdout, or a failing exit code and stderr
+    type CannedResponse = Result<String, (i32, String)>;
+
+    /// Executor returning canned responses, for unit tests that shouldn't spawn git.
+    ///
+    /// A response is picked by the first registered pattern that appears as a contiguous run
+    /// of the command's args, so `&["reflog", "-1"]` matches regardless of global args.
+    /// Commands with no matching pattern fail with exit code 128, like an unknown ref would.
+    #[derive(Debug, Default)]
+    pub struct MockGitExecutor {
+        responses: Vec<(Vec<String>, CannedResponse)>,
+        calls: Mutex<Vec<Vec<String>>>,
+    }
+
+    impl MockGitExecutor {
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        /// Answer commands matching `pattern` with a successful exit and `stdout`
+        pub fn on(mut self, pattern: &[&str], stdout: &str) -> Self {
+            self.responses
+                .push((to_strings(pattern), Ok(stdout.to_string())));
+            self
+        }
+
+        /// Answer commands matching `pattern` with a failing exit `code` and `stderr`
+        pub fn on_fail(mut self, pattern: &[&str], code: i32, stderr: &str) -> Self {
+            self.responses
+                .push((to_strings(pattern), Err((code, stderr.to_string()))));
+            self
+        }
+
+        /// Args of every command run so far, in order
+        pub fn calls(&self) -> Vec<Vec<String>> {
+            self.calls.lock().unwrap().clone()
+        }
+    }
+
+    impl GitExecutor for MockGitExecutor {
+        fn exec(&self, args: &[String]) -> Result<Output, GitAiError> {
+            self.calls.lock().unwrap().push(args.to_vec());
+            let response = self.responses.iter().find(|(pattern, _)| {
+                !pattern.is_empty() && args.windows(pattern.len()).any(|w| w == pattern.as_slice())
+            });
+            let (code, stderr) = match response {
+                Some((_, Ok(stdout))) => {
+                    return Ok(Output {
+                        status: ExitStatus::default(),
+                        stdout: stdout.clone().into_bytes(),
+                        stderr: Vec::new(),
+                    });
+                }
+                Some((_, Err((code, stderr)))) => (*code, stderr.clone()),
+                None => (128, "fatal: no canned response".to_string()),
+            };
+            Err(GitAiError::GitCliError {
+                code: Some(code),
+                stderr,
+                args: args.to_vec(),
+            })
+        }
+
+        fn exec_stdin(&self, args: &[String], _stdin_data: &[u8]) -> Result<Output, GitAiError> {
+            self.exec(args)
+        }
+
+        fn exec_stdin_writer(
+            &self,
+            args: &[String],
+            write_stdin: StdinWriter,
+        ) -> Result<Output, GitAiError> {
+            let mut stdin = Vec::new();
+            write_stdin(&mut stdin)?;
+            self.exec(args)
+        }
+
+        fn exec_stdin_streaming(
+            &self,
+            args: &[String],
+            _stdin_data: Vec<u8>,
+            on_stdout: &mut dyn FnMut(&mut dyn std::io::BufRead) -> Result<(), GitAiError>,
+        ) -> Result<(), GitAiError> {
+            let output = self.exec(args)?;
+            on_stdout(&mut std::io::Cursor::new(output.stdout))
+        }
+    }
+
+    fn to_strings(args: &[&str]) -> Vec<String> {
+        args.iter().map(|s| s.to_string()).collect()
+    }
+}
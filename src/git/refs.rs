@@ -1,14 +1,17 @@
-use crate::authorship::authorship_log_serialization::{AUTHORSHIP_LOG_VERSION, AuthorshipLog};
+use crate::authorship::authorship_log_serialization::{AuthorshipLog, is_supported_schema_version};
 use crate::authorship::working_log::Checkpoint;
 use crate::error::GitAiError;
-use crate::git::repository::{Repository, exec_git, exec_git_stdin};
+use crate::git::repository::{CatFileBatchHeader, Repository, parse_cat_file_batch_header};
 use crate::utils::debug_log;
 use serde_json;
 use std::collections::{HashMap, HashSet};
 
-// Modern refspecs without force to enable proper merging
-pub const AI_AUTHORSHIP_REFNAME: &str = "ai";
-pub const AI_AUTHORSHIP_PUSH_REFSPEC: &str = "refs/notes/ai:refs/notes/ai";
+/// Notes ref used when `git-ai.notesRef` is unset; see [`Repository::notes_ref`]
+pub const DEFAULT_NOTES_REF: &str = "refs/notes/ai";
+
+/// Upper bound on the default of `git-ai.notesReadConcurrency`; see
+/// [`Repository::notes_read_concurrency`]
+pub const DEFAULT_MAX_NOTES_READ_CONCURRENCY: usize = 8;
 
 pub fn notes_add(
     repo: &Repository,
@@ -17,7 +20,7 @@ pub fn notes_add(
 ) -> Result<(), GitAiError> {
     let mut args = repo.global_args_for_exec();
     args.push("notes".to_string());
-    args.push("--ref=ai".to_string());
+    args.push(format!("--ref={}", repo.notes_ref()));
     args.push("add".to_string());
     args.push("-f".to_string()); // Always force overwrite
     args.push("-F".to_string());
@@ -25,11 +28,31 @@ pub fn notes_add(
     args.push(commit_sha.to_string());
 
     // Use stdin to provide the note content to avoid command line length limits
-    exec_git_stdin(&args, note_content.as_bytes())?;
+    repo.exec_git_stdin(&args, note_content.as_bytes())?;
     crate::authorship::git_ai_hooks::post_notes_updated_single(repo, commit_sha, note_content);
     Ok(())
 }
 
+/// Remove the authorship notes of `commit_shas` in one `git notes remove --stdin` call.
+/// Commits without a note are ignored.
+pub fn notes_remove_batch(repo: &Repository, commit_shas: &[String]) -> Result<(), GitAiError> {
+    if commit_shas.is_empty() {
+        return Ok(());
+    }
+
+    let mut args = repo.global_args_for_exec();
+    args.push("notes".to_string());
+    args.push(format!("--ref={}", repo.notes_ref()));
+    args.push("remove".to_string());
+    args.push("--ignore-missing".to_string());
+    args.push("--stdin".to_string());
+
+    let mut stdin = commit_shas.join("\n");
+    stdin.push('\n');
+    repo.exec_git_stdin(&args, stdin.as_bytes())?;
+    Ok(())
+}
+
 fn notes_path_for_object(oid: &str) -> String {
     if oid.len() <= 2 {
         oid.to_string()
@@ -38,12 +61,12 @@ fn notes_path_for_object(oid: &str) -> String {
     }
 }
 
-fn flat_note_pathspec_for_commit(commit_sha: &str) -> String {
-    format!("refs/notes/ai:{}", commit_sha)
+fn flat_note_pathspec_for_commit(notes_ref: &str, commit_sha: &str) -> String {
+    format!("{}:{}", notes_ref, commit_sha)
 }
 
-fn fanout_note_pathspec_for_commit(commit_sha: &str) -> String {
-    format!("refs/notes/ai:{}", notes_path_for_object(commit_sha))
+fn fanout_note_pathspec_for_commit(notes_ref: &str, commit_sha: &str) -> String {
+    format!("{}:{}", notes_ref, notes_path_for_object(commit_sha))
 }
 
 fn parse_batch_check_blob_oid(line: &str) -> Option<String> {
@@ -74,26 +97,14 @@ fn parse_cat_file_batch_output_with_oids(
         };
 
         let header = std::str::from_utf8(&data[pos..header_end])?;
-        let parts: Vec<&str> = header.split_whitespace().collect();
-        if parts.len() < 2 {
-            pos = header_end + 1;
-            continue;
-        }
-
-        let oid = parts[0].to_string();
-        if parts[1] == "missing" {
-            pos = header_end + 1;
-            continue;
-        }
-
-        if parts.len() < 3 {
-            pos = header_end + 1;
-            continue;
-        }
-
-        let size: usize = parts[2]
-            .parse()
-            .map_err(|e| GitAiError::Generic(format!("Invalid size in cat-file output: {}", e)))?;
+        let (oid, size) = match parse_cat_file_batch_header(header)? {
+            Some(CatFileBatchHeader::Object { oid, size, .. }) => (oid.to_string(), size),
+            // `missing`, `ambiguous`, ... lines have no content to skip over
+            _ => {
+                pos = header_end + 1;
+                continue;
+            }
+        };
 
         let content_start = header_end + 1;
         let content_end = content_start + size;
@@ -128,7 +139,7 @@ fn batch_read_blob_contents(
     args.push("--batch".to_string());
 
     let stdin_data = blob_oids.join("\n") + "\n";
-    let output = exec_git_stdin(&args, stdin_data.as_bytes())?;
+    let output = repo.exec_git_stdin(&args, stdin_data.as_bytes())?;
     parse_cat_file_batch_output_with_oids(&output.stdout)
 }
 
@@ -147,17 +158,25 @@ pub fn note_blob_oids_for_commits(
     args.push("cat-file".to_string());
     args.push("--batch-check".to_string());
 
-    let mut stdin_data = String::new();
-    for commit_sha in commit_shas {
-        // Notes can be stored with either flat paths (<sha>) or fanout paths (<aa>/<bb...>).
-        // Query both forms so this works regardless of repository note fanout state.
-        stdin_data.push_str(&flat_note_pathspec_for_commit(commit_sha));
-        stdin_data.push('\n');
-        stdin_data.push_str(&fanout_note_pathspec_for_commit(commit_sha));
-        stdin_data.push('\n');
-    }
-
-    let output = exec_git_stdin(&args, stdin_data.as_bytes())?;
+    let notes_ref = repo.notes_ref();
+    let queried_shas = commit_shas.to_vec();
+    let output = repo.exec_git_stdin_writer(&args, move |stdin| {
+        for commit_sha in &queried_shas {
+            // Notes can be stored with either flat paths (<sha>) or fanout paths (<aa>/<bb...>).
+            // Query both forms so this works regardless of repository note fanout state.
+            writeln!(
+                stdin,
+                "{}",
+                flat_note_pathspec_for_commit(&notes_ref, commit_sha)
+            )?;
+            writeln!(
+                stdin,
+                "{}",
+                fanout_note_pathspec_for_commit(&notes_ref, commit_sha)
+            )?;
+        }
+        Ok(())
+    })?;
     let stdout = String::from_utf8(output.stdout)?;
     let mut lines = stdout.lines();
     let mut result = HashMap::new();
@@ -183,11 +202,12 @@ pub fn notes_add_batch(repo: &Repository, entries: &[(String, String)]) -> Resul
         return Ok(());
     }
 
+    let notes_ref = repo.notes_ref();
     let mut args = repo.global_args_for_exec();
     args.push("rev-parse".to_string());
     args.push("--verify".to_string());
-    args.push("refs/notes/ai".to_string());
-    let existing_notes_tip = match exec_git(&args) {
+    args.push(notes_ref.clone());
+    let existing_notes_tip = match repo.exec_git(&args) {
         Ok(output) => Some(String::from_utf8(output.stdout)?.trim().to_string()),
         Err(GitAiError::GitCliError {
             code: Some(128), ..
@@ -220,7 +240,7 @@ pub fn notes_add_batch(repo: &Repository, entries: &[(String, String)]) -> Resul
         script.extend_from_slice(b"\n");
     }
 
-    script.extend_from_slice(b"commit refs/notes/ai\n");
+    script.extend_from_slice(format!("commit {}\n", notes_ref).as_bytes());
     script.extend_from_slice(format!("committer git-ai <git-ai@local> {} +0000\n", now).as_bytes());
     script.extend_from_slice(b"data 0\n");
     if let Some(existing_tip) = existing_notes_tip {
@@ -241,7 +261,7 @@ pub fn notes_add_batch(repo: &Repository, entries: &[(String, String)]) -> Resul
     let mut fast_import_args = repo.global_args_for_exec();
     fast_import_args.push("fast-import".to_string());
     fast_import_args.push("--quiet".to_string());
-    exec_git_stdin(&fast_import_args, &script)?;
+    repo.exec_git_stdin(&fast_import_args, &script)?;
     crate::authorship::git_ai_hooks::post_notes_updated(repo, &deduped_entries);
 
     Ok(())
@@ -259,11 +279,12 @@ pub fn notes_add_blob_batch(
         return Ok(());
     }
 
+    let notes_ref = repo.notes_ref();
     let mut args = repo.global_args_for_exec();
     args.push("rev-parse".to_string());
     args.push("--verify".to_string());
-    args.push("refs/notes/ai".to_string());
-    let existing_notes_tip = match exec_git(&args) {
+    args.push(notes_ref.clone());
+    let existing_notes_tip = match repo.exec_git(&args) {
         Ok(output) => Some(String::from_utf8(output.stdout)?.trim().to_string()),
         Err(GitAiError::GitCliError {
             code: Some(128), ..
@@ -287,7 +308,7 @@ pub fn notes_add_blob_batch(
         .as_secs();
 
     let mut script = Vec::<u8>::new();
-    script.extend_from_slice(b"commit refs/notes/ai\n");
+    script.extend_from_slice(format!("commit {}\n", notes_ref).as_bytes());
     script.extend_from_slice(format!("committer git-ai <git-ai@local> {} +0000\n", now).as_bytes());
     script.extend_from_slice(b"data 0\n");
     if let Some(existing_tip) = existing_notes_tip {
@@ -308,7 +329,7 @@ pub fn notes_add_blob_batch(
     let mut fast_import_args = repo.global_args_for_exec();
     fast_import_args.push("fast-import".to_string());
     fast_import_args.push("--quiet".to_string());
-    exec_git_stdin(&fast_import_args, &script)?;
+    repo.exec_git_stdin(&fast_import_args, &script)?;
 
     let has_post_notes_updated_hooks = crate::config::Config::get()
         .git_ai_hook_commands("post_notes_updated")
@@ -382,7 +403,7 @@ pub fn get_commits_with_notes_from_list(
         args.push(sha.clone());
     }
 
-    let output = exec_git(&args)?;
+    let output = repo.exec_git(&args)?;
     let stdout = String::from_utf8(output.stdout)
         .map_err(|_| GitAiError::Generic("Failed to parse git rev-list output".to_string()))?;
 
@@ -439,11 +460,11 @@ pub fn get_commits_with_notes_from_list(
 pub fn show_authorship_note(repo: &Repository, commit_sha: &str) -> Option<String> {
     let mut args = repo.global_args_for_exec();
     args.push("notes".to_string());
-    args.push("--ref=ai".to_string());
+    args.push(format!("--ref={}", repo.notes_ref()));
     args.push("show".to_string());
     args.push(commit_sha.to_string());
 
-    match exec_git(&args) {
+    match repo.exec_git(&args) {
         Ok(output) => String::from_utf8(output.stdout)
             .ok()
             .map(|s| s.trim().to_string())
@@ -455,7 +476,7 @@ pub fn show_authorship_note(repo: &Repository, commit_sha: &str) -> Option<Strin
 
 /// Return the subset of `commit_shas` that currently has an authorship note.
 ///
-/// This uses a single `git notes --ref=ai list` invocation instead of one
+/// This uses a single batched `git cat-file` invocation instead of one
 /// `git notes show` call per commit.
 pub fn commits_with_authorship_notes(
     repo: &Repository,
@@ -496,7 +517,12 @@ pub fn get_reference_as_authorship_log_v3(
     // Try to deserialize as AuthorshipLog
     let mut authorship_log = match AuthorshipLog::deserialize_from_string(&content) {
         Ok(log) => log,
-        Err(_) => {
+        Err(e) => {
+            if let Some(GitAiError::UnsupportedSchema { found }) = e.downcast_ref::<GitAiError>() {
+                return Err(GitAiError::UnsupportedSchema {
+                    found: found.clone(),
+                });
+            }
             return Err(GitAiError::Generic(
                 "Failed to parse authorship log".to_string(),
             ));
@@ -504,11 +530,10 @@ pub fn get_reference_as_authorship_log_v3(
     };
 
     // Check version compatibility
-    if authorship_log.metadata.schema_version != AUTHORSHIP_LOG_VERSION {
-        return Err(GitAiError::Generic(format!(
-            "Unsupported authorship log version: {} (expected: {})",
-            authorship_log.metadata.schema_version, AUTHORSHIP_LOG_VERSION
-        )));
+    if !is_supported_schema_version(&authorship_log.metadata.schema_version) {
+        return Err(GitAiError::UnsupportedSchema {
+            found: authorship_log.metadata.schema_version,
+        });
     }
 
     // Keep metadata aligned with the commit where this note is attached.
@@ -553,27 +578,202 @@ pub fn ref_exists(repo: &Repository, ref_name: &str) -> bool {
     args.push("--quiet".to_string());
     args.push(ref_name.to_string());
 
-    exec_git(&args).is_ok()
+    repo.exec_git(&args).is_ok()
+}
+
+/// `git notes merge` strategy used when not configured; see [`notes_merge_strategy`]
+const DEFAULT_NOTES_MERGE_STRATEGY: &str = "union";
+
+/// Strategy for resolving notes that both sides changed, from `git-ai.notesMergeStrategy`:
+/// `ours` keeps the local note, `theirs` takes the fetched one and `union` combines both
+/// (see [`merge_notes_from_ref`]). Defaults to `union` when unset or not one of those.
+fn notes_merge_strategy(repo: &Repository) -> &'static str {
+    match repo.config_get_str("git-ai.notesMergeStrategy") {
+        Ok(Some(value)) => match value.trim().to_ascii_lowercase().as_str() {
+            "ours" => "ours",
+            "theirs" => "theirs",
+            "union" => "union",
+            _ => {
+                debug_log(&format!(
+                    "ignoring git-ai.notesMergeStrategy: expected ours, theirs or union, got {:?}",
+                    value
+                ));
+                DEFAULT_NOTES_MERGE_STRATEGY
+            }
+        },
+        Ok(None) => DEFAULT_NOTES_MERGE_STRATEGY,
+        Err(e) => {
+            debug_log(&format!("ignoring git-ai.notesMergeStrategy: {}", e));
+            DEFAULT_NOTES_MERGE_STRATEGY
+        }
+    }
 }
 
-/// Merge notes from a source ref into refs/notes/ai
-/// Uses the 'ours' strategy to combine notes without data loss
+/// Merge notes from a source ref into the repository's notes ref.
+/// Notes changed on both sides are resolved with the configured [`notes_merge_strategy`], so
+/// divergent note histories merge without stopping for manual conflict resolution.
+///
+/// git's `union` concatenates the two texts, which for authorship logs yields two metadata
+/// sections that no longer parse. So with `union`, each note both sides changed is rewritten
+/// afterwards as one log holding both sides' attestations and prompts
+/// ([`AuthorshipLog::union_with`]). Notes that aren't authorship logs keep git's result.
 pub fn merge_notes_from_ref(repo: &Repository, source_ref: &str) -> Result<(), GitAiError> {
+    let notes_ref = repo.notes_ref();
+
+    // Nothing to merge when the source is already in the local notes history, and nothing can
+    // conflict when the local notes just fast-forward to it
+    if repo.is_ancestor(source_ref, &notes_ref).unwrap_or(false) {
+        debug_log(&format!(
+            "Notes in {} are already in {}",
+            source_ref, notes_ref
+        ));
+        return Ok(());
+    }
+    let fast_forward = repo.is_ancestor(&notes_ref, source_ref).unwrap_or(false);
+
+    let strategy = notes_merge_strategy(repo);
+    let conflicts = if strategy == "union" && !fast_forward {
+        conflicting_notes(repo, &notes_ref, source_ref)?
+    } else {
+        Vec::new()
+    };
+
     let mut args = repo.global_args_for_exec();
     args.push("notes".to_string());
-    args.push(format!("--ref={}", AI_AUTHORSHIP_REFNAME));
+    args.push(format!("--ref={}", notes_ref));
     args.push("merge".to_string());
     args.push("-s".to_string());
-    args.push("ours".to_string());
+    args.push(strategy.to_string());
     args.push("--quiet".to_string());
     args.push(source_ref.to_string());
 
     debug_log(&format!(
-        "Merging notes from {} into refs/notes/ai",
-        source_ref
+        "Merging notes from {} into {}",
+        source_ref, notes_ref
     ));
-    exec_git(&args)?;
-    Ok(())
+    repo.exec_git(&args)?;
+
+    let mut merged = Vec::new();
+    for (commit_sha, ours_blob, theirs_blob) in conflicts {
+        let ours = read_blob(repo, &ours_blob)?;
+        let theirs = read_blob(repo, &theirs_blob)?;
+        match (
+            AuthorshipLog::deserialize_from_string(&ours),
+            AuthorshipLog::deserialize_from_string(&theirs),
+        ) {
+            (Ok(mut log), Ok(theirs_log)) => {
+                log.union_with(theirs_log);
+                let content = log.serialize_to_string().map_err(|_| {
+                    GitAiError::Generic("Failed to serialize authorship log".to_string())
+                })?;
+                merged.push((commit_sha, content));
+            }
+            _ => debug_log(&format!(
+                "Note for {} isn't an authorship log on both sides; keeping git's union",
+                commit_sha
+            )),
+        }
+    }
+    notes_add_batch(repo, &merged)
+}
+
+/// Commits whose note both `notes_ref` and `source_ref` changed, relative to their merge
+/// base, to different contents; as `(commit, our blob, their blob)`
+fn conflicting_notes(
+    repo: &Repository,
+    notes_ref: &str,
+    source_ref: &str,
+) -> Result<Vec<(String, String, String)>, GitAiError> {
+    let (ours, theirs) = match repo.merge_base(notes_ref, source_ref)? {
+        Some(base) => (
+            changed_notes(repo, &base, notes_ref)?,
+            changed_notes(repo, &base, source_ref)?,
+        ),
+        // Unrelated histories: every note present on both sides was added on both
+        None => (
+            note_blobs_at(repo, notes_ref)?,
+            note_blobs_at(repo, source_ref)?,
+        ),
+    };
+
+    let mut conflicts: Vec<(String, String, String)> = ours
+        .into_iter()
+        .filter_map(|(commit_sha, ours_blob)| {
+            let theirs_blob = theirs.get(&commit_sha)?;
+            (ours_blob != *theirs_blob).then(|| (commit_sha, ours_blob, theirs_blob.clone()))
+        })
+        .collect();
+    conflicts.sort();
+    Ok(conflicts)
+}
+
+/// Notes added or modified between the notes trees of `base` and `tip`, as commit SHA to new
+/// note blob. Only the changed paths are listed (`diff-tree`), and a note that merely moved
+/// between fanout directories doesn't count as changed.
+fn changed_notes(
+    repo: &Repository,
+    base: &str,
+    tip: &str,
+) -> Result<HashMap<String, String>, GitAiError> {
+    let mut args = repo.global_args_for_exec();
+    args.push("diff-tree".to_string());
+    args.push("-r".to_string());
+    args.push("--no-renames".to_string());
+    args.push(base.to_string());
+    args.push(tip.to_string());
+    let output = repo.exec_git(&args)?;
+    Ok(parse_changed_notes(&String::from_utf8(output.stdout)?))
+}
+
+/// Parse `diff-tree -r` raw output into the notes it adds or modifies, keyed by commit SHA
+fn parse_changed_notes(output: &str) -> HashMap<String, String> {
+    let mut removed: HashMap<String, String> = HashMap::new();
+    let mut changed: HashMap<String, String> = HashMap::new();
+    for line in output.lines() {
+        // ":<old mode> <new mode> <old oid> <new oid> <status>\t<path>"
+        let Some((meta, path)) = line.split_once('\t') else {
+            continue;
+        };
+        let fields: Vec<&str> = meta.split_whitespace().collect();
+        let [_, _, old_oid, new_oid, status] = fields[..] else {
+            continue;
+        };
+        let commit_sha = path.replace('/', "");
+        if status.starts_with('D') {
+            removed.insert(commit_sha, old_oid.to_string());
+        } else {
+            changed.insert(commit_sha, new_oid.to_string());
+        }
+    }
+    changed.retain(|commit_sha, blob| removed.get(commit_sha) != Some(blob));
+    changed
+}
+
+/// Note blob of each annotated commit in the notes tree of `treeish`, keyed by commit SHA.
+/// Fanout directories are folded back into the SHA.
+fn note_blobs_at(repo: &Repository, treeish: &str) -> Result<HashMap<String, String>, GitAiError> {
+    let mut args = repo.global_args_for_exec();
+    args.push("ls-tree".to_string());
+    args.push("-r".to_string());
+    args.push(treeish.to_string());
+    let output = repo.exec_git(&args)?;
+    Ok(String::from_utf8(output.stdout)?
+        .lines()
+        .filter_map(|line| {
+            // "<mode> blob <oid>\t<path>"
+            let (meta, path) = line.split_once('\t')?;
+            let oid = meta.split_whitespace().nth(2)?;
+            Some((path.replace('/', ""), oid.to_string()))
+        })
+        .collect())
+}
+
+fn read_blob(repo: &Repository, oid: &str) -> Result<String, GitAiError> {
+    let mut args = repo.global_args_for_exec();
+    args.push("cat-file".to_string());
+    args.push("blob".to_string());
+    args.push(oid.to_string());
+    Ok(String::from_utf8(repo.exec_git(&args)?.stdout)?)
 }
 
 /// Copy a ref to another location (used for initial setup of local notes from tracking ref)
@@ -584,29 +784,32 @@ pub fn copy_ref(repo: &Repository, source_ref: &str, dest_ref: &str) -> Result<(
     args.push(source_ref.to_string());
 
     debug_log(&format!("Copying ref {} to {}", source_ref, dest_ref));
-    exec_git(&args)?;
+    repo.exec_git(&args)?;
     Ok(())
 }
 
 /// Search AI notes for a pattern and return matching commit SHAs ordered by commit date (newest first)
-/// Uses git grep to search through refs/notes/ai
+/// Uses git grep to search through the repository's notes ref
 pub fn grep_ai_notes(repo: &Repository, pattern: &str) -> Result<Vec<String>, GitAiError> {
+    let notes_ref = repo.notes_ref();
     let mut args = repo.global_args_for_exec();
     args.push("--no-pager".to_string());
     args.push("grep".to_string());
     args.push("-nI".to_string());
     args.push(pattern.to_string());
-    args.push("refs/notes/ai".to_string());
+    args.push(notes_ref.clone());
 
-    let output = exec_git(&args)?;
+    let output = repo.exec_git(&args)?;
     let stdout = String::from_utf8(output.stdout)
         .map_err(|_| GitAiError::Generic("Failed to parse git grep output".to_string()))?;
 
-    // Parse output format: refs/notes/ai:ab/cdef123...:line_number:matched_content
+    // Parse output format: <notes_ref>:ab/cdef123...:line_number:matched_content
     // Extract the commit SHA from the path
     let mut shas = HashSet::new();
     for line in stdout.lines() {
-        if let Some(path_and_rest) = line.strip_prefix("refs/notes/ai:")
+        if let Some(path_and_rest) = line
+            .strip_prefix(notes_ref.as_str())
+            .and_then(|rest| rest.strip_prefix(':'))
             && let Some(path_end) = path_and_rest.find(':')
         {
             let path = &path_and_rest[..path_end];
@@ -628,7 +831,7 @@ pub fn grep_ai_notes(repo: &Repository, pattern: &str) -> Result<Vec<String>, Gi
             args.push(sha.clone());
         }
 
-        let output = exec_git(&args)?;
+        let output = repo.exec_git(&args)?;
         let stdout = String::from_utf8(output.stdout)
             .map_err(|_| GitAiError::Generic("Failed to parse git log output".to_string()))?;
 
@@ -883,6 +1086,106 @@ mod tests {
         assert!(final_note_c.is_some() || initial_note_c.is_some());
     }
 
+    #[test]
+    fn test_parse_changed_notes_ignores_fanout_moves() {
+        let a = "a".repeat(40);
+        let b = "b".repeat(40);
+        let moved = format!("ab{}", "1".repeat(38));
+        let edited = format!("cd{}", "2".repeat(38));
+        let added = format!("ef{}", "3".repeat(38));
+        let zero = "0".repeat(40);
+        let output = format!(
+            ":100644 000000 {a} {zero} D\t{moved}\n\
+             :000000 100644 {zero} {a} A\tab/{rest_moved}\n\
+             :100644 100644 {a} {b} M\t{edited}\n\
+             :000000 100644 {zero} {b} A\tef/{rest_added}\n",
+            rest_moved = &moved[2..],
+            rest_added = &added[2..],
+        );
+
+        let changed = parse_changed_notes(&output);
+        assert_eq!(
+            changed,
+            HashMap::from([(edited, b.clone()), (added, b.clone())])
+        );
+    }
+
+    #[test]
+    fn test_merge_notes_from_ref_unions_diverged_notes() {
+        use crate::git::test_utils::note_with_attestations;
+
+        let tmp_repo = TmpRepo::new().unwrap();
+        let repo = tmp_repo.gitai_repo();
+        tmp_repo.write_file("a.txt", "a\n", true).unwrap();
+        tmp_repo.commit_with_message("first").unwrap();
+        let first = tmp_repo.get_head_commit_sha().unwrap();
+        tmp_repo.write_file("b.txt", "b\n", true).unwrap();
+        tmp_repo.commit_with_message("second").unwrap();
+        let second = tmp_repo.get_head_commit_sha().unwrap();
+
+        let base = note_with_attestations(&[("base.rs", "0000000000000000", "Dev <dev@x>")]);
+        notes_add(repo, &first, &base).unwrap();
+        copy_ref(repo, &repo.notes_ref(), "refs/notes/theirs").unwrap();
+
+        // Both sides rewrite the first commit's note
+        let ours = note_with_attestations(&[("ours.rs", "1111111111111111", "Dev <dev@x>")]);
+        notes_add(repo, &first, &ours).unwrap();
+        let theirs = note_with_attestations(&[("theirs.rs", "2222222222222222", "Dev <dev@x>")]);
+        tmp_repo
+            .git_command(&["notes", "--ref=theirs", "add", "-f", "-m", &theirs, &first])
+            .unwrap();
+
+        merge_notes_from_ref(repo, "refs/notes/theirs").unwrap();
+        let merged =
+            AuthorshipLog::deserialize_from_string(&show_authorship_note(repo, &first).unwrap())
+                .unwrap();
+        let mut files: Vec<&str> = merged
+            .attestations
+            .iter()
+            .map(|file| file.file_path.as_str())
+            .collect();
+        files.sort();
+        assert_eq!(files, vec!["ours.rs", "theirs.rs"]);
+
+        // Source ahead of the local notes: a plain fast-forward
+        let ahead = note_with_attestations(&[("ahead.rs", "3333333333333333", "Dev <dev@x>")]);
+        copy_ref(repo, &repo.notes_ref(), "refs/notes/ahead").unwrap();
+        tmp_repo
+            .git_command(&["notes", "--ref=ahead", "add", "-f", "-m", &ahead, &second])
+            .unwrap();
+        merge_notes_from_ref(repo, "refs/notes/ahead").unwrap();
+        let rev_parse = |name: &str| repo.revparse_single(name).unwrap().id();
+        assert_eq!(rev_parse(&repo.notes_ref()), rev_parse("refs/notes/ahead"));
+
+        // Source already merged: the local notes stay put
+        let tip = rev_parse(&repo.notes_ref());
+        merge_notes_from_ref(repo, "refs/notes/theirs").unwrap();
+        assert_eq!(rev_parse(&repo.notes_ref()), tip);
+    }
+
+    #[test]
+    fn test_notes_merge_strategy_config() {
+        let tmp_repo = TmpRepo::new().expect("Failed to create tmp repo");
+        assert_eq!(notes_merge_strategy(tmp_repo.gitai_repo()), "union");
+
+        for (value, expected) in [
+            ("ours", "ours"),
+            ("Theirs", "theirs"),
+            ("union", "union"),
+            ("manual", "union"),
+        ] {
+            tmp_repo
+                .git_command(&["config", "git-ai.notesMergeStrategy", value])
+                .unwrap();
+            assert_eq!(
+                notes_merge_strategy(tmp_repo.gitai_repo()),
+                expected,
+                "{}",
+                value
+            );
+        }
+    }
+
     #[test]
     fn test_copy_ref() {
         let tmp_repo = TmpRepo::new().expect("Failed to create tmp repo");
@@ -1110,7 +1413,7 @@ mod tests {
     #[test]
     fn test_flat_note_pathspec_for_commit() {
         let sha = "abcdef1234567890abcdef1234567890abcdef12";
-        let pathspec = flat_note_pathspec_for_commit(sha);
+        let pathspec = flat_note_pathspec_for_commit(DEFAULT_NOTES_REF, sha);
         assert_eq!(
             pathspec,
             "refs/notes/ai:abcdef1234567890abcdef1234567890abcdef12"
@@ -1120,7 +1423,7 @@ mod tests {
     #[test]
     fn test_fanout_note_pathspec_for_commit() {
         let sha = "abcdef1234567890abcdef1234567890abcdef12";
-        let pathspec = fanout_note_pathspec_for_commit(sha);
+        let pathspec = fanout_note_pathspec_for_commit(DEFAULT_NOTES_REF, sha);
         assert_eq!(
             pathspec,
             "refs/notes/ai:ab/cdef1234567890abcdef1234567890abcdef12"
@@ -1223,12 +1526,44 @@ mod tests {
 
         // Should fail with version mismatch error
         let result = get_reference_as_authorship_log_v3(tmp_repo.gitai_repo(), &commit_sha);
-        assert!(result.is_err());
+        match result {
+            Err(GitAiError::UnsupportedSchema { found }) => assert_eq!(found, "999"),
+            other => panic!("Expected unsupported schema error, got {:?}", other),
+        }
+    }
 
-        if let Err(GitAiError::Generic(msg)) = result {
-            assert!(msg.contains("Unsupported authorship log version"));
-        } else {
-            panic!("Expected version mismatch error");
+    #[test]
+    fn test_get_reference_as_authorship_log_v3_future_version() {
+        let tmp_repo = TmpRepo::new().expect("Failed to create tmp repo");
+        tmp_repo
+            .write_file("test.txt", "content\n", true)
+            .expect("write file");
+        tmp_repo.commit_with_message("Commit").expect("commit");
+        let commit_sha = tmp_repo.get_head_commit_sha().expect("head");
+
+        let note = "test.txt\n  abc1234 1\n---\n{\"schema_version\":\"authorship/4.0.0\",\"base_commit_sha\":\"\",\"prompts\":{}}";
+        notes_add(tmp_repo.gitai_repo(), &commit_sha, note).expect("add note");
+
+        match get_reference_as_authorship_log_v3(tmp_repo.gitai_repo(), &commit_sha) {
+            Err(GitAiError::UnsupportedSchema { found }) => assert_eq!(found, "authorship/4.0.0"),
+            other => panic!("Expected unsupported schema error, got {:?}", other),
         }
     }
+
+    #[test]
+    fn test_get_reference_as_authorship_log_v3_newer_minor_version() {
+        let tmp_repo = TmpRepo::new().expect("Failed to create tmp repo");
+        tmp_repo
+            .write_file("test.txt", "content\n", true)
+            .expect("write file");
+        tmp_repo.commit_with_message("Commit").expect("commit");
+        let commit_sha = tmp_repo.get_head_commit_sha().expect("head");
+
+        let note = "test.txt\n  abc1234 1\n---\n{\"schema_version\":\"authorship/3.1.0\",\"base_commit_sha\":\"\",\"prompts\":{}}";
+        notes_add(tmp_repo.gitai_repo(), &commit_sha, note).expect("add note");
+
+        let log = get_reference_as_authorship_log_v3(tmp_repo.gitai_repo(), &commit_sha)
+            .expect("3.x notes are readable");
+        assert_eq!(log.metadata.schema_version, "authorship/3.1.0");
+    }
 }
@@ -4,12 +4,14 @@ use crate::authorship::authorship_log_serialization::generate_short_hash;
 use crate::authorship::working_log::{CHECKPOINT_API_VERSION, Checkpoint, CheckpointKind};
 use crate::error::GitAiError;
 use crate::git::rewrite_log::{RewriteLogEvent, append_event_to_file};
-use crate::utils::{debug_log, normalize_to_posix};
+use crate::utils::{LockFile, debug_log, normalize_to_posix};
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use std::collections::{HashMap, HashSet};
 use std::fs;
+use std::io::Write;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 /// Initial attributions data structure stored in the INITIAL file
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -20,6 +22,31 @@ pub struct InitialAttributions {
     pub prompts: HashMap<String, PromptRecord>,
 }
 
+/// Outcome of [`RepoStorage::rename_working_log`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WorkingLogRename {
+    Renamed,
+    /// There was no working log for the old commit
+    SourceMissing,
+    /// The new commit already has a working log, so the old one was left in place
+    DestinationExists,
+}
+
+/// A working log rename that was skipped because `GIT_AI_DRY_RUN` was set
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlannedWorkingLogRename {
+    pub old_sha: String,
+    pub new_sha: String,
+    /// What triggered the rename, e.g. "fast-forward pull"
+    pub reason: String,
+    pub recorded_at: String,
+}
+
+/// How many times [`RepoStorage::lock_working_logs`] tries the lock before giving up
+const WORKING_LOG_LOCK_ATTEMPTS: u32 = 20;
+/// Pause between working-log lock attempts
+const WORKING_LOG_LOCK_RETRY_DELAY: Duration = Duration::from_millis(25);
+
 #[derive(Debug, Clone)]
 pub struct RepoStorage {
     pub ai_dir: PathBuf,
@@ -116,22 +143,136 @@ impl RepoStorage {
         Ok(())
     }
 
+    /// Take the advisory lock that serializes working-log mutations across git-ai processes,
+    /// retrying briefly if another process holds it. Returns `None` if it is still held after
+    /// the retries or the lock file can't be created; the lock is released when the returned
+    /// guard is dropped.
+    pub fn lock_working_logs(&self) -> Option<LockFile> {
+        self.lock_working_logs_with_retry(WORKING_LOG_LOCK_ATTEMPTS, WORKING_LOG_LOCK_RETRY_DELAY)
+    }
+
+    fn lock_working_logs_with_retry(&self, attempts: u32, delay: Duration) -> Option<LockFile> {
+        fs::create_dir_all(&self.ai_dir).ok()?;
+        let lock_path = self.ai_dir.join("working_logs.lock");
+        for attempt in 0..attempts {
+            if let Some(lock) = LockFile::try_acquire(&lock_path) {
+                return Some(lock);
+            }
+            if attempt + 1 < attempts {
+                std::thread::sleep(delay);
+            }
+        }
+        None
+    }
+
     /// Rename a working log directory from one commit SHA to another.
     /// Used when fast-forward pull changes HEAD but preserves working directory state.
-    /// Only renames if old directory exists and new directory doesn't exist.
-    pub fn rename_working_log(&self, old_sha: &str, new_sha: &str) -> Result<(), GitAiError> {
+    /// Only renames if old directory exists and new directory doesn't exist; otherwise the
+    /// returned outcome says why nothing moved.
+    pub fn rename_working_log(
+        &self,
+        old_sha: &str,
+        new_sha: &str,
+    ) -> Result<WorkingLogRename, GitAiError> {
         let old_dir = self.working_logs.join(old_sha);
         let new_dir = self.working_logs.join(new_sha);
-        if old_dir.exists() && !new_dir.exists() {
-            fs::rename(&old_dir, &new_dir)?;
-            debug_log(&format!(
-                "Renamed working log from {} to {}",
-                old_sha, new_sha
-            ));
+        if !old_dir.exists() {
+            return Ok(WorkingLogRename::SourceMissing);
+        }
+        if new_dir.exists() {
+            return Ok(WorkingLogRename::DestinationExists);
+        }
+        fs::rename(&old_dir, &new_dir)?;
+        debug_log(&format!(
+            "Renamed working log from {} to {}",
+            old_sha, new_sha
+        ));
+        Ok(WorkingLogRename::Renamed)
+    }
+
+    /// Fold the working log for `old_sha` into the one for `new_sha` and remove the old one.
+    ///
+    /// Checkpoints from both logs are kept in timestamp order, file blobs are copied over, and
+    /// INITIAL attributions are combined, with the destination winning for files both logs seed.
+    pub fn merge_working_logs(&self, old_sha: &str, new_sha: &str) -> Result<(), GitAiError> {
+        if old_sha == new_sha || !self.has_working_log(old_sha) {
+            return Ok(());
+        }
+        let source = self.working_log_for_base_commit(old_sha);
+        let destination = self.working_log_for_base_commit(new_sha);
+
+        let mut checkpoints = destination.read_all_checkpoints()?;
+        checkpoints.extend(source.read_all_checkpoints()?);
+        checkpoints.sort_by_key(|checkpoint| checkpoint.timestamp);
+        destination.write_all_checkpoints(&checkpoints)?;
+
+        let source_blobs = source.dir.join("blobs");
+        if source_blobs.is_dir() {
+            let destination_blobs = destination.dir.join("blobs");
+            fs::create_dir_all(&destination_blobs)?;
+            for entry in fs::read_dir(&source_blobs)? {
+                let entry = entry?;
+                let target = destination_blobs.join(entry.file_name());
+                if !target.exists() {
+                    fs::copy(entry.path(), target)?;
+                }
+            }
+        }
+
+        let source_initial = source.read_initial_attributions();
+        if !source_initial.files.is_empty() {
+            let mut initial = destination.read_initial_attributions();
+            for (file, attributions) in source_initial.files {
+                initial.files.entry(file).or_insert(attributions);
+            }
+            for (id, prompt) in source_initial.prompts {
+                initial.prompts.entry(id).or_insert(prompt);
+            }
+            destination.write_initial_attributions(initial.files, initial.prompts)?;
         }
+
+        self.delete_working_log_for_base_commit(old_sha)?;
+        debug_log(&format!(
+            "Merged working log from {} into {}",
+            old_sha, new_sha
+        ));
+        Ok(())
+    }
+
+    /// Record a working log rename that a dry run skipped, so it can be reviewed later.
+    pub fn record_planned_working_log_rename(
+        &self,
+        old_sha: &str,
+        new_sha: &str,
+        reason: &str,
+    ) -> Result<(), GitAiError> {
+        let planned = PlannedWorkingLogRename {
+            old_sha: old_sha.to_string(),
+            new_sha: new_sha.to_string(),
+            reason: reason.to_string(),
+            recorded_at: chrono::Utc::now().to_rfc3339(),
+        };
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.planned_renames_path())?;
+        writeln!(file, "{}", serde_json::to_string(&planned)?)?;
         Ok(())
     }
 
+    /// Working log renames recorded by dry runs, oldest first. Unreadable lines are skipped.
+    pub fn read_planned_working_log_renames(&self) -> Vec<PlannedWorkingLogRename> {
+        fs::read_to_string(self.planned_renames_path())
+            .unwrap_or_default()
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect()
+    }
+
+    fn planned_renames_path(&self) -> PathBuf {
+        self.ai_dir.join("planned_working_log_renames")
+    }
+
     /* Rewrite Log Persistance */
 
     /// Append a rewrite event to the rewrite log file and return the full log
@@ -942,4 +1083,155 @@ mod tests {
             "Working log directory should be in correct location"
         );
     }
+
+    #[test]
+    fn test_working_log_lock_serializes_contending_threads() {
+        use std::sync::Arc;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let tmp_repo = TmpRepo::new().expect("Failed to create tmp repo");
+        let repo_storage =
+            RepoStorage::for_repo_path(tmp_repo.repo().path(), tmp_repo.repo().workdir().unwrap());
+        let holders = Arc::new(AtomicUsize::new(0));
+        let max_holders = Arc::new(AtomicUsize::new(0));
+
+        let threads: Vec<_> = (0..2)
+            .map(|_| {
+                let repo_storage = repo_storage.clone();
+                let holders = Arc::clone(&holders);
+                let max_holders = Arc::clone(&max_holders);
+                std::thread::spawn(move || {
+                    let _lock = repo_storage
+                        .lock_working_logs()
+                        .expect("lock should be acquired within the retry window");
+                    let now_holding = holders.fetch_add(1, Ordering::SeqCst) + 1;
+                    max_holders.fetch_max(now_holding, Ordering::SeqCst);
+                    std::thread::sleep(Duration::from_millis(100));
+                    holders.fetch_sub(1, Ordering::SeqCst);
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+
+        assert_eq!(max_holders.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_working_log_lock_gives_up_while_held() {
+        let tmp_repo = TmpRepo::new().expect("Failed to create tmp repo");
+        let repo_storage =
+            RepoStorage::for_repo_path(tmp_repo.repo().path(), tmp_repo.repo().workdir().unwrap());
+
+        let held = repo_storage.lock_working_logs().unwrap();
+        assert!(
+            repo_storage
+                .lock_working_logs_with_retry(3, Duration::from_millis(1))
+                .is_none()
+        );
+        drop(held);
+        assert!(repo_storage.lock_working_logs().is_some());
+    }
+
+    #[test]
+    fn test_rename_working_log_merges_when_destination_exists() {
+        use crate::authorship::working_log::CheckpointKind;
+
+        let tmp_repo = TmpRepo::new().expect("Failed to create tmp repo");
+        let repo_storage =
+            RepoStorage::for_repo_path(tmp_repo.repo().path(), tmp_repo.repo().workdir().unwrap());
+
+        let old_log = repo_storage.working_log_for_base_commit("old-sha");
+        let mut old_checkpoint = Checkpoint::new(
+            CheckpointKind::Human,
+            "old-diff".to_string(),
+            "old-author".to_string(),
+            vec![],
+        );
+        old_checkpoint.timestamp = 1;
+        old_log.append_checkpoint(&old_checkpoint).unwrap();
+        let old_blob = old_log.persist_file_version("old content").unwrap();
+        old_log
+            .write_initial_attributions(
+                HashMap::from([
+                    (
+                        "old.txt".to_string(),
+                        vec![LineAttribution::new(1, 1, "old-ai".to_string(), None)],
+                    ),
+                    (
+                        "shared.txt".to_string(),
+                        vec![LineAttribution::new(1, 1, "old-ai".to_string(), None)],
+                    ),
+                ]),
+                HashMap::new(),
+            )
+            .unwrap();
+
+        let new_log = repo_storage.working_log_for_base_commit("new-sha");
+        let mut new_checkpoint = Checkpoint::new(
+            CheckpointKind::Human,
+            "new-diff".to_string(),
+            "new-author".to_string(),
+            vec![],
+        );
+        new_checkpoint.timestamp = 2;
+        new_log.append_checkpoint(&new_checkpoint).unwrap();
+        new_log
+            .write_initial_attributions(
+                HashMap::from([(
+                    "shared.txt".to_string(),
+                    vec![LineAttribution::new(1, 1, "new-ai".to_string(), None)],
+                )]),
+                HashMap::new(),
+            )
+            .unwrap();
+
+        assert_eq!(
+            repo_storage
+                .rename_working_log("old-sha", "new-sha")
+                .unwrap(),
+            WorkingLogRename::DestinationExists
+        );
+        repo_storage
+            .merge_working_logs("old-sha", "new-sha")
+            .unwrap();
+
+        assert!(!repo_storage.working_logs.join("old-sha").exists());
+        let merged = repo_storage.working_log_for_base_commit("new-sha");
+        let authors: Vec<String> = merged
+            .read_all_checkpoints()
+            .unwrap()
+            .into_iter()
+            .map(|checkpoint| checkpoint.author)
+            .collect();
+        assert_eq!(authors, vec!["old-author", "new-author"]);
+        assert_eq!(merged.get_file_version(&old_blob).unwrap(), "old content");
+
+        let initial = merged.read_initial_attributions();
+        assert_eq!(initial.files["old.txt"][0].author_id, "old-ai");
+        assert_eq!(initial.files["shared.txt"][0].author_id, "new-ai");
+    }
+
+    #[test]
+    fn test_rename_working_log_reports_missing_source() {
+        let tmp_repo = TmpRepo::new().expect("Failed to create tmp repo");
+        let repo_storage =
+            RepoStorage::for_repo_path(tmp_repo.repo().path(), tmp_repo.repo().workdir().unwrap());
+
+        assert_eq!(
+            repo_storage
+                .rename_working_log("missing", "new-sha")
+                .unwrap(),
+            WorkingLogRename::SourceMissing
+        );
+        repo_storage.working_log_for_base_commit("present");
+        assert_eq!(
+            repo_storage
+                .rename_working_log("present", "new-sha")
+                .unwrap(),
+            WorkingLogRename::Renamed
+        );
+        assert!(repo_storage.has_working_log("new-sha"));
+    }
 }
@@ -2,11 +2,13 @@ use crate::authorship::authorship_log_serialization::AuthorshipLog;
 use crate::authorship::rebase_authorship::rewrite_authorship_if_needed;
 use crate::config;
 use crate::error::GitAiError;
-use crate::git::refs::get_authorship;
+use crate::git::git_executor::{GitExecutor, ProcessGitExecutor};
+use crate::git::refs::{DEFAULT_MAX_NOTES_READ_CONCURRENCY, DEFAULT_NOTES_REF, get_authorship};
 use crate::git::repo_storage::RepoStorage;
 use crate::git::rewrite_log::RewriteLogEvent;
 use crate::git::status::MAX_PATHSPEC_ARGS;
 use crate::git::sync_authorship::{fetch_authorship_notes, push_authorship_notes};
+use crate::utils::debug_log;
 #[cfg(windows)]
 use crate::utils::is_interactive_terminal;
 
@@ -287,7 +289,7 @@ impl<'a> Object<'a> {
         // args.push("-q".to_string());
         args.push("--verify".to_string());
         args.push(format!("{}^{}", self.oid, "{commit}"));
-        let output = exec_git(&args)?;
+        let output = self.repo.exec_git(&args)?;
         Ok(Commit {
             repo: self.repo,
             oid: String::from_utf8(output.stdout)?.trim().to_string(),
@@ -359,18 +361,7 @@ impl<'a> CommitRange<'a> {
                 args.push(resolved_end.clone());
                 args.push("--format=%(refname)".to_string());
 
-                let refs = match exec_git(&args) {
-                    Ok(output) => {
-                        let stdout = String::from_utf8(output.stdout).unwrap_or_default();
-                        let refs: Vec<String> = stdout
-                            .lines()
-                            .map(|s| s.trim().to_string())
-                            .filter(|s| !s.is_empty())
-                            .collect();
-                        refs
-                    }
-                    Err(_) => Vec::new(),
-                };
+                let refs = exec_git_lines(&args, false).unwrap_or_default();
 
                 // If exactly one ref found, use it
                 if refs.len() == 1 {
@@ -417,7 +408,7 @@ impl<'a> CommitRange<'a> {
             args.push(self.start_oid.clone());
             args.push(self.refname.clone());
 
-            exec_git(&args).map_err(|_| {
+            self.repo.exec_git(&args).map_err(|_| {
                 GitAiError::Generic(format!(
                     "Commit {} is not reachable from refname {}",
                     self.start_oid, self.refname
@@ -431,7 +422,7 @@ impl<'a> CommitRange<'a> {
         args.push(self.end_oid.clone());
         args.push(self.refname.clone());
 
-        exec_git(&args).map_err(|_| {
+        self.repo.exec_git(&args).map_err(|_| {
             GitAiError::Generic(format!(
                 "Commit {} is not reachable from refname {}",
                 self.end_oid, self.refname
@@ -447,7 +438,7 @@ impl<'a> CommitRange<'a> {
             args.push(self.start_oid.clone());
             args.push(self.end_oid.clone());
 
-            exec_git(&args).map_err(|_| {
+            self.repo.exec_git(&args).map_err(|_| {
                 GitAiError::Generic(format!(
                     "Commit {} is not an ancestor of {}",
                     self.start_oid, self.end_oid
@@ -467,7 +458,7 @@ impl<'a> CommitRange<'a> {
         args.push("--count".to_string());
         args.push(format!("{}..{}", self.start_oid, self.end_oid));
 
-        match exec_git(&args) {
+        match self.repo.exec_git(&args) {
             Ok(output) => {
                 let count_str = String::from_utf8(output.stdout).unwrap_or_default();
                 count_str.trim().parse().unwrap_or(0)
@@ -516,17 +507,8 @@ impl<'a> IntoIterator for CommitRange<'a> {
         args.push("rev-list".to_string());
         args.push(format!("{}..{}", self.start_oid, self.end_oid));
 
-        let commit_oids: Vec<String> = match exec_git(&args) {
-            Ok(output) => {
-                let stdout = String::from_utf8(output.stdout).unwrap_or_default();
-                stdout
-                    .lines()
-                    .map(|s| s.trim().to_string())
-                    .filter(|s| !s.is_empty())
-                    .collect()
-            }
-            Err(_) => Vec::new(), // If they don't share lineage or error occurs, return empty
-        };
+        // If they don't share lineage or an error occurs, the range is empty
+        let commit_oids = exec_git_lines(&args, false).unwrap_or_default();
 
         CommitRangeIterator {
             repo: self.repo,
@@ -642,7 +624,7 @@ impl<'a> Commit<'a> {
         // args.push("-q".to_string());
         args.push("--verify".to_string());
         args.push(format!("{}^{}", self.oid, "{tree}"));
-        let output = exec_git(&args)?;
+        let output = self.repo.exec_git(&args)?;
         Ok(Tree {
             repo: self.repo,
             oid: String::from_utf8(output.stdout)?.trim().to_string(),
@@ -656,7 +638,7 @@ impl<'a> Commit<'a> {
         args.push("--verify".to_string());
         // libgit2 uses 0-based indexing; Git's rev syntax uses 1-based parent selectors.
         args.push(format!("{}^{}", self.oid, i + 1));
-        let output = exec_git(&args)?;
+        let output = self.repo.exec_git(&args)?;
         Ok(Commit {
             repo: self.repo,
             oid: String::from_utf8(output.stdout)?.trim().to_string(),
@@ -673,7 +655,7 @@ impl<'a> Commit<'a> {
         args.push("--format=%P".to_string());
         args.push(self.oid.clone());
 
-        let parent_oids: Vec<String> = match exec_git(&args) {
+        let parent_oids: Vec<String> = match self.repo.exec_git(&args) {
             Ok(output) => {
                 let stdout = String::from_utf8(output.stdout).unwrap_or_default();
                 stdout.split_whitespace().map(|s| s.to_string()).collect()
@@ -704,7 +686,7 @@ impl<'a> Commit<'a> {
         args.push("--encoding=UTF-8".to_string());
         args.push("--format=%s".to_string());
         args.push(self.oid.clone());
-        let output = exec_git(&args)?;
+        let output = self.repo.exec_git(&args)?;
         Ok(String::from_utf8(output.stdout)?.trim().to_string())
     }
 
@@ -718,7 +700,7 @@ impl<'a> Commit<'a> {
         args.push("--encoding=UTF-8".to_string());
         args.push("--format=%b".to_string());
         args.push(self.oid.clone());
-        let output = exec_git(&args)?;
+        let output = self.repo.exec_git(&args)?;
         Ok(String::from_utf8(output.stdout)?.trim().to_string())
     }
 
@@ -732,7 +714,7 @@ impl<'a> Commit<'a> {
         args.push("--encoding=UTF-8".to_string());
         args.push("--format=%an%n%ae%n%aI".to_string());
         args.push(self.oid.clone());
-        let output = exec_git(&args)?;
+        let output = self.repo.exec_git(&args)?;
         let stdout = String::from_utf8(output.stdout)?;
         let mut lines = stdout.lines();
         let name = lines.next().unwrap_or("").trim().to_string();
@@ -756,7 +738,7 @@ impl<'a> Commit<'a> {
         args.push("--encoding=UTF-8".to_string());
         args.push("--format=%cn%n%ce%n%cI".to_string());
         args.push(self.oid.clone());
-        let output = exec_git(&args)?;
+        let output = self.repo.exec_git(&args)?;
         let stdout = String::from_utf8(output.stdout)?;
         let mut lines = stdout.lines();
         let name = lines.next().unwrap_or("").trim().to_string();
@@ -809,7 +791,7 @@ impl<'a> Commit<'a> {
             rp_args.push("--symbolic-full-name".to_string());
             rp_args.push(refname.to_string());
 
-            match exec_git(&rp_args) {
+            match self.repo.exec_git(&rp_args) {
                 Ok(output) => {
                     let s = String::from_utf8(output.stdout).unwrap_or_default();
                     let s = s.trim();
@@ -845,7 +827,7 @@ impl<'a> Commit<'a> {
             args.push(parent_sha.clone());
             args.push(fq_refname.clone());
 
-            if exec_git(&args).is_ok() {
+            if self.repo.exec_git(&args).is_ok() {
                 return Ok(parent);
             }
         }
@@ -915,7 +897,7 @@ impl<'a> Tree<'a> {
         let path_str = path.to_string_lossy().to_string();
         args.push(path_str.clone());
 
-        let output = exec_git(&args)?;
+        let output = self.repo.exec_git(&args)?;
         let bytes = output.stdout;
 
         // Each record: "<mode> <type> <object>\t<file>\0"
@@ -983,7 +965,7 @@ impl<'a> Blob<'a> {
         args.push("cat-file".to_string());
         args.push("blob".to_string());
         args.push(self.oid.clone());
-        let output = exec_git(&args)?;
+        let output = self.repo.exec_git(&args)?;
         Ok(output.stdout)
     }
 }
@@ -1008,7 +990,7 @@ impl<'a> Reference<'a> {
         args.push("rev-parse".to_string());
         args.push("--abbrev-ref".to_string());
         args.push(self.ref_name.clone());
-        let output = exec_git(&args)?;
+        let output = self.repo.exec_git(&args)?;
         Ok(String::from_utf8(output.stdout)?.trim().to_string())
     }
 
@@ -1016,7 +998,7 @@ impl<'a> Reference<'a> {
         let mut args = self.repo.global_args_for_exec();
         args.push("rev-parse".to_string());
         args.push(self.ref_name.clone());
-        let output = exec_git(&args)?;
+        let output = self.repo.exec_git(&args)?;
         Ok(String::from_utf8(output.stdout)?.trim().to_string())
     }
 
@@ -1029,7 +1011,7 @@ impl<'a> Reference<'a> {
         // args.push("-q".to_string());
         args.push("--verify".to_string());
         args.push(format!("{}^{}", self.ref_name, "{blob}"));
-        let output = exec_git(&args)?;
+        let output = self.repo.exec_git(&args)?;
         Ok(Blob {
             repo: self.repo,
             oid: String::from_utf8(output.stdout)?.trim().to_string(),
@@ -1044,7 +1026,7 @@ impl<'a> Reference<'a> {
         // args.push("-q".to_string());
         args.push("--verify".to_string());
         args.push(format!("{}^{}", self.ref_name, "{commit}"));
-        let output = exec_git(&args)?;
+        let output = self.repo.exec_git(&args)?;
         Ok(Commit {
             repo: self.repo,
             oid: String::from_utf8(output.stdout)?.trim().to_string(),
@@ -1168,6 +1150,117 @@ pub fn parse_git_var_identity(output: &str) -> GitAuthorIdentity {
     }
 }
 
+/// A header line from `git cat-file --batch` output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CatFileBatchHeader<'a> {
+    /// `<oid> <type> <size>`, followed by `size` bytes of content and a newline.
+    Object {
+        oid: &'a str,
+        object_type: &'a str,
+        size: usize,
+    },
+    /// `<name> <status>` for a request that did not resolve to an object (`missing`,
+    /// `ambiguous`, ...). No content follows.
+    Status { name: &'a str, status: &'a str },
+}
+
+/// Parse one `git cat-file --batch` header line (without its trailing newline). Lines that
+/// are neither an object nor a status header yield `None`.
+pub fn parse_cat_file_batch_header(
+    header: &str,
+) -> Result<Option<CatFileBatchHeader<'_>>, GitAiError> {
+    let parts: Vec<&str> = header.split_whitespace().collect();
+    match parts.as_slice() {
+        [oid, object_type, size] => Ok(Some(CatFileBatchHeader::Object {
+            oid,
+            object_type,
+            size: size.parse().map_err(|e| {
+                GitAiError::Generic(format!("Invalid size in cat-file output: {}", e))
+            })?,
+        })),
+        [name, status] if status.bytes().all(|b| b.is_ascii_lowercase()) => {
+            Ok(Some(CatFileBatchHeader::Status { name, status }))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Which git config file a value comes from or a write targets, mirroring the scopes of
+/// `git config --show-scope`. Only `Local` and `Global` can be written to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConfigScope {
+    /// The system-wide config (`$GIT_CONFIG_SYSTEM`, `/etc/gitconfig`, or the git installation's config).
+    System,
+    /// The user's global config (`$GIT_CONFIG_GLOBAL`, `~/.gitconfig` or `$XDG_CONFIG_HOME/git/config`).
+    Global,
+    /// The repository's own config (`$GIT_COMMON_DIR/config`).
+    #[default]
+    Local,
+    /// The per-worktree config (`$GIT_DIR/config.worktree`) when `extensions.worktreeConfig` is set.
+    Worktree,
+    /// Values passed via `GIT_CONFIG_COUNT`/`GIT_CONFIG_PARAMETERS` or `git -c`.
+    Command,
+}
+
+impl ConfigScope {
+    fn from_source(source: gix_config::Source) -> Self {
+        match source {
+            gix_config::Source::GitInstallation | gix_config::Source::System => Self::System,
+            gix_config::Source::Git | gix_config::Source::User => Self::Global,
+            gix_config::Source::Local => Self::Local,
+            gix_config::Source::Worktree => Self::Worktree,
+            gix_config::Source::Env
+            | gix_config::Source::Cli
+            | gix_config::Source::Api
+            | gix_config::Source::EnvOverride => Self::Command,
+        }
+    }
+}
+
+/// Resolve the file `git config --global` writes to.
+///
+/// Git prefers `$GIT_CONFIG_GLOBAL`, then `~/.gitconfig`, and only falls back to the
+/// XDG location when `~/.gitconfig` does not exist but the XDG file does.
+fn global_config_write_path(
+    env_var: &mut dyn FnMut(&str) -> Option<std::ffi::OsString>,
+) -> Option<PathBuf> {
+    let user_path = gix_config::Source::User
+        .storage_location(env_var)
+        .map(|p| p.into_owned());
+    if env_var("GIT_CONFIG_GLOBAL").is_some() {
+        return user_path;
+    }
+
+    let xdg_path = gix_config::Source::Git
+        .storage_location(env_var)
+        .map(|p| p.into_owned());
+    match (user_path, xdg_path) {
+        (Some(user), Some(xdg)) if !user.exists() && xdg.exists() => Some(xdg),
+        (Some(user), _) => Some(user),
+        (None, xdg) => xdg,
+    }
+}
+
+/// Split a `section[.subsection].name` key the way git does: the first and last dots
+/// delimit the parts, so subsection names may themselves contain dots.
+fn parse_config_key(key: &str) -> Result<gix_config::KeyRef<'_>, GitAiError> {
+    gix_config::KeyRef::parse_unvalidated(key.into())
+        .ok_or_else(|| GitAiError::Generic(format!("Invalid config key: {}", key)))
+}
+
+/// One update recorded in a ref's reflog
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReflogEntry {
+    /// What the ref pointed at before the update; all zeros when the update created the ref
+    pub old_sha: String,
+    /// What the ref points at after the update
+    pub new_sha: String,
+    /// Reflog message, e.g. "pull: Fast-forward" or "commit (amend): fix typo"
+    pub subject: String,
+    /// Identity that made the update, as "Name <email>"
+    pub committer: String,
+}
+
 #[derive(Debug, Clone)]
 pub struct Repository {
     global_args: Vec<String>,
@@ -1186,6 +1279,11 @@ pub struct Repository {
     canonical_workdir: PathBuf,
     /// Cached git author identity resolved via `git var GIT_COMMITTER_IDENT`.
     cached_author_identity: std::sync::OnceLock<GitAuthorIdentity>,
+    /// Explicit environment for config lookups; `None` reads the process environment.
+    config_env: Option<std::sync::Arc<HashMap<String, std::ffi::OsString>>>,
+    /// Runs this repository's git commands (all but [`spawn_git_piped`] processes); spawns git
+    /// unless a test swaps it out
+    executor: std::sync::Arc<dyn GitExecutor>,
 }
 
 impl Repository {
@@ -1198,12 +1296,60 @@ impl Repository {
         args
     }
 
+    /// Run `args` (global args included) through this repository's [`GitExecutor`]
+    pub fn exec_git(&self, args: &[String]) -> Result<Output, GitAiError> {
+        self.executor.exec(args)
+    }
+
+    /// Like [`Repository::exec_git`], with `stdin_data` written to git's stdin
+    pub fn exec_git_stdin(&self, args: &[String], stdin_data: &[u8]) -> Result<Output, GitAiError> {
+        self.executor.exec_stdin(args, stdin_data)
+    }
+
+    /// [`exec_git_stdin_writer`] through this repository's [`GitExecutor`]
+    pub fn exec_git_stdin_writer<W>(
+        &self,
+        args: &[String],
+        write_stdin: W,
+    ) -> Result<Output, GitAiError>
+    where
+        W: FnOnce(&mut dyn std::io::Write) -> std::io::Result<()> + Send + 'static,
+    {
+        self.executor.exec_stdin_writer(args, Box::new(write_stdin))
+    }
+
+    /// [`exec_git_stdin_streaming`] through this repository's [`GitExecutor`]
+    pub fn exec_git_stdin_streaming(
+        &self,
+        args: &[String],
+        stdin_data: Vec<u8>,
+        on_stdout: &mut dyn FnMut(&mut dyn std::io::BufRead) -> Result<(), GitAiError>,
+    ) -> Result<(), GitAiError> {
+        self.executor
+            .exec_stdin_streaming(args, stdin_data, on_stdout)
+    }
+
+    /// [`exec_git_lines`] through this repository's [`GitExecutor`]
+    pub fn exec_git_lines(
+        &self,
+        args: &[String],
+        empty_on_128: bool,
+    ) -> Result<Vec<String>, GitAiError> {
+        output_lines(self.exec_git(args), empty_on_128)
+    }
+
+    /// Use `executor` for this repository's git commands, e.g. a mock in unit tests
+    pub fn with_executor(mut self, executor: std::sync::Arc<dyn GitExecutor>) -> Self {
+        self.executor = executor;
+        self
+    }
+
     /// Execute an arbitrary git command and return stdout as string
     #[allow(dead_code)]
     pub fn git(&self, args: &[&str]) -> Result<String, GitAiError> {
         let mut full_args = self.global_args_for_exec();
         full_args.extend(args.iter().map(|s| s.to_string()));
-        let output = exec_git(&full_args)?;
+        let output = self.exec_git(&full_args)?;
         Ok(String::from_utf8(output.stdout)?)
     }
 
@@ -1252,10 +1398,26 @@ impl Repository {
         args.push("cat-file".to_string());
         args.push("-t".to_string());
         args.push(oid.to_string());
-        let output = exec_git(&args)?;
+        let output = self.exec_git(&args)?;
         Ok(String::from_utf8(output.stdout)?.trim().to_string())
     }
 
+    // Short name of the branch HEAD points at (e.g., "main"), or `None` when HEAD is detached.
+    // An unborn branch still has a name.
+    pub fn head_branch_name(&self) -> Result<Option<String>, GitAiError> {
+        let mut args = self.global_args_for_exec();
+        args.push("symbolic-ref".to_string());
+        args.push("--quiet".to_string());
+        args.push("--short".to_string());
+        args.push("HEAD".to_string());
+        match self.exec_git(&args) {
+            Ok(output) => Ok(Some(String::from_utf8(output.stdout)?.trim().to_string())),
+            // With --quiet, a detached HEAD exits 1 without printing an error
+            Err(GitAiError::GitCliError { code: Some(1), .. }) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
     // Retrieve and resolve the reference pointed at by HEAD.
     // If HEAD is a symbolic ref, return the refname (e.g., "refs/heads/main").
     // Otherwise, return "HEAD".
@@ -1265,7 +1427,7 @@ impl Repository {
         // args.push("-q".to_string());
         args.push("HEAD".to_string());
 
-        let output = exec_git(&args);
+        let output = self.exec_git(&args);
 
         match output {
             Ok(output) if output.status.success() => {
@@ -1306,7 +1468,7 @@ impl Repository {
         let mut args = self.global_args_for_exec();
         args.push("rev-parse".to_string());
         args.push("--is-bare-repository".to_string());
-        let output = exec_git(&args)?;
+        let output = self.exec_git(&args)?;
         let value = String::from_utf8(output.stdout)?;
         Ok(value.trim() == "true")
     }
@@ -1347,7 +1509,7 @@ impl Repository {
         let mut args = self.global_args_for_exec();
         args.push("remote".to_string());
 
-        let output = exec_git(&args)?;
+        let output = self.exec_git(&args)?;
         let remotes = String::from_utf8(output.stdout)?;
         Ok(remotes.trim().split("\n").map(|s| s.to_string()).collect())
     }
@@ -1358,7 +1520,7 @@ impl Repository {
         args.push("remote".to_string());
         args.push("-v".to_string());
 
-        let output = exec_git(&args)?;
+        let output = self.exec_git(&args)?;
         let remotes_output = String::from_utf8(output.stdout)?;
 
         let mut remotes = Vec::new();
@@ -1379,6 +1541,106 @@ impl Repository {
         Ok(remotes)
     }
 
+    /// Names of every remote with a `remote.<name>.url`, in config order and without duplicates.
+    /// Unlike [`Self::remotes`] this reads the config directly instead of running `git remote`,
+    /// and returns an empty list when there are no remotes.
+    pub fn list_remotes(&self) -> Result<Vec<String>, GitAiError> {
+        let mut seen = HashSet::new();
+        Ok(self
+            .config_get_regexp_multi(r"^remote\..*\.url$")?
+            .into_iter()
+            .filter_map(|(key, _url)| {
+                key.strip_prefix("remote.")?
+                    .strip_suffix(".url")
+                    .map(str::to_string)
+            })
+            .filter(|name| seen.insert(name.clone()))
+            .collect())
+    }
+
+    /// The configured `remote.<name>.url`, or `None` if there's no such remote.
+    /// When the URL is set more than once the last value wins, as with `git config --get`.
+    pub fn remote_url(&self, name: &str) -> Result<Option<String>, GitAiError> {
+        self.config_get_str(&format!("remote.{}.url", name))
+    }
+
+    /// Read git config from an explicit environment instead of the process environment.
+    ///
+    /// `vars` stands in for every variable config loading consults (`HOME`, `XDG_CONFIG_HOME`,
+    /// `GIT_CONFIG_GLOBAL`, `GIT_CONFIG_SYSTEM`, `GIT_CONFIG_NOSYSTEM`, and
+    /// `GIT_CONFIG_COUNT`/`GIT_CONFIG_KEY_<n>`/`GIT_CONFIG_VALUE_<n>`); anything missing from it
+    /// is treated as unset. Config reads then can't race with threads that change the process
+    /// environment.
+    pub fn with_config_env<K, V>(mut self, vars: impl IntoIterator<Item = (K, V)>) -> Self
+    where
+        K: Into<String>,
+        V: Into<std::ffi::OsString>,
+    {
+        let vars = vars
+            .into_iter()
+            .map(|(name, value)| (name.into(), value.into()))
+            .collect();
+        self.config_env = Some(std::sync::Arc::new(vars));
+        self
+    }
+
+    /// Snapshot of the process environment to hand to [`Repository::with_config_env`] on
+    /// another thread, so that thread reads the same config sources as the one that took it
+    pub fn process_config_env() -> Vec<(String, std::ffi::OsString)> {
+        std::env::vars_os()
+            .filter_map(|(name, value)| Some((name.into_string().ok()?, value)))
+            .collect()
+    }
+
+    /// Environment variable as seen by config loading
+    fn config_env_var(&self, name: &str) -> Option<std::ffi::OsString> {
+        match &self.config_env {
+            Some(vars) => vars.get(name).cloned(),
+            None => std::env::var_os(name),
+        }
+    }
+
+    /// `GIT_CONFIG_COUNT`/`GIT_CONFIG_KEY_<n>`/`GIT_CONFIG_VALUE_<n>` overrides from the
+    /// explicit config environment, mirroring `gix_config::File::from_env`
+    fn config_env_overrides(
+        vars: &HashMap<String, std::ffi::OsString>,
+    ) -> Result<gix_config::File<'static>, GitAiError> {
+        let mut config =
+            gix_config::File::new(gix_config::file::Metadata::from(gix_config::Source::Env));
+        let Some(count) = vars.get("GIT_CONFIG_COUNT") else {
+            return Ok(config);
+        };
+        let count: usize =
+            count.to_string_lossy().trim().parse().map_err(|_| {
+                GitAiError::GixError(format!("Invalid GIT_CONFIG_COUNT: {:?}", count))
+            })?;
+        for i in 0..count {
+            let invalid = || {
+                GitAiError::GixError(format!(
+                    "Invalid GIT_CONFIG_KEY_{} or GIT_CONFIG_VALUE_{}",
+                    i, i
+                ))
+            };
+            let key = vars
+                .get(&format!("GIT_CONFIG_KEY_{}", i))
+                .and_then(|key| key.to_str())
+                .ok_or_else(invalid)?;
+            let value = vars
+                .get(&format!("GIT_CONFIG_VALUE_{}", i))
+                .and_then(|value| value.to_str())
+                .ok_or_else(invalid)?;
+            let key = parse_config_key(key)?;
+            let value_name =
+                gix_config::parse::section::ValueName::try_from(key.value_name.to_owned())
+                    .map_err(|e| GitAiError::GixError(e.to_string()))?;
+            config
+                .section_mut_or_create_new(key.section_name, key.subsection_name)
+                .map_err(|e| GitAiError::GixError(e.to_string()))?
+                .push(value_name, Some(value.into()));
+        }
+        Ok(config)
+    }
+
     fn load_optional_config_file(
         path: &Path,
         source: gix_config::Source,
@@ -1392,27 +1654,29 @@ impl Repository {
     }
 
     fn get_git_config_file(&self) -> Result<gix_config::File<'static>, GitAiError> {
-        let mut config =
-            gix_config::File::from_globals().map_err(|e| GitAiError::GixError(e.to_string()))?;
-
-        let home = dirs::home_dir();
-        let options = gix_config::file::init::Options {
-            includes: gix_config::file::includes::Options::follow(
-                gix_config::path::interpolate::Context {
-                    home_dir: home.as_deref(),
-                    ..Default::default()
-                },
-                gix_config::file::includes::conditional::Context {
-                    git_dir: Some(self.path()),
-                    branch_name: None,
-                },
-            ),
-            ..Default::default()
-        };
-
-        config
-            .resolve_includes(options)
-            .map_err(|e| GitAiError::GixError(e.to_string()))?;
+        // Load every file in the stack without includes first, then resolve includes once over the
+        // combined config. `hasconfig:remote.*.url` conditions in global config must be able to see
+        // remotes defined in the local config, and resolving per-file would also apply the
+        // unconditional includes that `File::from_globals()` already follows a second time.
+        let metas = [
+            gix_config::source::Kind::GitInstallation,
+            gix_config::source::Kind::System,
+            gix_config::source::Kind::Global,
+        ]
+        .iter()
+        .flat_map(|kind| kind.sources())
+        .filter_map(|source| {
+            let path = source
+                .storage_location(&mut |name| self.config_env_var(name))
+                .filter(|p| p.is_file())?;
+            Some(gix_config::file::Metadata::from(*source).at(path.into_owned()))
+        });
+        let mut config = gix_config::File::from_paths_metadata(
+            metas,
+            gix_config::file::init::Options::default(),
+        )
+        .map_err(|e| GitAiError::GixError(e.to_string()))?
+        .unwrap_or_default();
 
         let local_config_path = self.common_dir().join("config");
         let local_config =
@@ -1423,40 +1687,349 @@ impl Repository {
             .and_then(Result::ok)
             .unwrap_or(false);
 
-        if let Some(mut local_config) = local_config {
-            local_config
-                .resolve_includes(options)
-                .map_err(|e| GitAiError::GixError(e.to_string()))?;
+        if let Some(local_config) = local_config {
             config.append(local_config);
         }
 
         if worktree_config_enabled {
             let worktree_config_path = self.path().join("config.worktree");
-            if let Some(mut worktree_config) = Self::load_optional_config_file(
+            if let Some(worktree_config) = Self::load_optional_config_file(
                 &worktree_config_path,
                 gix_config::Source::Worktree,
             )? {
-                worktree_config
-                    .resolve_includes(options)
-                    .map_err(|e| GitAiError::GixError(e.to_string()))?;
                 config.append(worktree_config);
             }
         }
 
-        config.append(
-            gix_config::File::from_environment_overrides()
+        let home = match &self.config_env {
+            Some(_) => self.config_env_var("HOME").map(PathBuf::from),
+            None => dirs::home_dir(),
+        };
+        let branch_name = self.head_branch_for_config_includes();
+        let options = gix_config::file::init::Options {
+            includes: gix_config::file::includes::Options::follow(
+                gix_config::path::interpolate::Context {
+                    home_dir: home.as_deref(),
+                    home_for_user: Some(gix_config::path::interpolate::home_for_user),
+                    ..Default::default()
+                },
+                gix_config::file::includes::conditional::Context {
+                    git_dir: Some(self.path()),
+                    branch_name: branch_name.as_ref().map(|name| name.as_ref()),
+                },
+            ),
+            ..Default::default()
+        };
+
+        config
+            .resolve_includes(options)
+            .map_err(|e| GitAiError::GixError(e.to_string()))?;
+
+        let env_overrides = match &self.config_env {
+            Some(vars) => Self::config_env_overrides(vars)?,
+            None => gix_config::File::from_environment_overrides()
                 .map_err(|e| GitAiError::GixError(e.to_string()))?,
-        );
+        };
+        config.append(env_overrides);
 
         Ok(config)
     }
 
+    /// Read the checked-out branch straight from `HEAD` so `includeIf "onbranch:..."` can be
+    /// evaluated without spawning git. Detached or unreadable HEADs yield `None`, which makes
+    /// `onbranch` conditions never match, as in git.
+    fn head_branch_for_config_includes(&self) -> Option<gix_ref::FullName> {
+        let head = std::fs::read_to_string(self.path().join("HEAD")).ok()?;
+        let target = head.strip_prefix("ref:")?.trim();
+        gix_ref::FullName::try_from(target).ok()
+    }
+
     /// Get config value for a given key as a String.
+    ///
+    /// As in git, only the first and last dots of `key` delimit its parts, so a subsection
+    /// may contain dots, e.g. `url.https://github.com/.insteadOf`.
     pub fn config_get_str(&self, key: &str) -> Result<Option<String>, GitAiError> {
         self.get_git_config_file()
             .map(|cfg| cfg.string(key).map(|cow| cow.to_string()))
     }
 
+    /// Get config value for a given key along with the scope it was read from, matching
+    /// `git config --show-scope --get`. Values pulled in via `include`/`includeIf` report the
+    /// scope of the file that included them.
+    pub fn config_get_str_with_scope(
+        &self,
+        key: &str,
+    ) -> Result<Option<(String, ConfigScope)>, GitAiError> {
+        let parsed_key = parse_config_key(key)?;
+        let config = self.get_git_config_file()?;
+        let Some(value) = config.string(key).map(|cow| cow.to_string()) else {
+            return Ok(None);
+        };
+
+        // The effective value is the last one, so its scope is that of the last section defining it.
+        let scope = config
+            .sections()
+            .filter(|section| {
+                let header = section.header();
+                header
+                    .name()
+                    .eq_ignore_ascii_case(parsed_key.section_name.as_bytes())
+                    && header.subsection_name() == parsed_key.subsection_name
+                    && section.body().contains_value_name(parsed_key.value_name)
+            })
+            .last()
+            .map(|section| ConfigScope::from_source(section.meta().source))
+            .unwrap_or_default();
+
+        Ok(Some((value, scope)))
+    }
+
+    /// Get config value for a given key, falling back to `default` when the key is unset.
+    /// Errors reading the config are still surfaced rather than replaced by the default.
+    pub fn config_get_str_or(&self, key: &str, default: &str) -> Result<String, GitAiError> {
+        self.config_get_str(key)
+            .map(|value| value.unwrap_or_else(|| default.to_string()))
+    }
+
+    /// Get every value of a multi-valued key (e.g. `remote.origin.fetch`) in file order,
+    /// matching `git config --get-all`. A missing key yields an empty list.
+    pub fn config_get_all(&self, key: &str) -> Result<Vec<String>, GitAiError> {
+        self.get_git_config_file().map(|cfg| {
+            cfg.strings(key)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|cow| cow.to_string())
+                .collect()
+        })
+    }
+
+    /// Get config value for a given key as a bool, matching `git config --type=bool --get`.
+    ///
+    /// Accepts `true/yes/on` and `false/no/off` case-insensitively, any integer (non-zero is
+    /// true), an empty value as false, and a valueless key (`[section]\n\tflag`) as true.
+    /// Returns `Ok(None)` when the key is missing and an error for a non-boolean value.
+    pub fn config_get_bool(&self, key: &str) -> Result<Option<bool>, GitAiError> {
+        let config = self.get_git_config_file()?;
+        config
+            .boolean(key)
+            .transpose()
+            .map_err(|e| GitAiError::Generic(format!("Invalid boolean value for {}: {}", key, e)))
+    }
+
+    /// Get config value for a given key as a bool, falling back to `default` when the key is
+    /// unset, the value isn't a valid boolean, or the config can't be read. Invalid values and
+    /// read errors are debug logged before falling back.
+    pub fn config_get_bool_or(&self, key: &str, default: bool) -> bool {
+        match self.config_get_bool(key) {
+            Ok(value) => value.unwrap_or(default),
+            Err(e) => {
+                debug_log(&format!("ignoring {}, using {}: {}", key, default, e));
+                default
+            }
+        }
+    }
+
+    /// Get config value for a given key as an integer, matching `git config --type=int --get`.
+    ///
+    /// A case-insensitive `k`, `m` or `g` suffix multiplies the value by 1024, 1024² or 1024³.
+    /// Returns `Ok(None)` when the key is missing and an error for a non-integer value.
+    pub fn config_get_int(&self, key: &str) -> Result<Option<i64>, GitAiError> {
+        let config = self.get_git_config_file()?;
+        config
+            .integer(key)
+            .transpose()
+            .map_err(|e| GitAiError::Generic(format!("Invalid integer value for {}: {}", key, e)))
+    }
+
+    /// Set a single-valued config key, matching `git config [--local|--global] key value`.
+    ///
+    /// The section is created if it doesn't exist yet and the last existing value is
+    /// overwritten. The target file is rewritten via a `.lock` sibling like git does.
+    pub fn config_set_str(
+        &self,
+        key: &str,
+        value: &str,
+        scope: ConfigScope,
+    ) -> Result<(), GitAiError> {
+        let parsed_key = parse_config_key(key)?;
+        let (path, mut config) = self.load_config_file_for_write(scope)?;
+
+        config
+            .set_raw_value_by(
+                parsed_key.section_name,
+                parsed_key.subsection_name,
+                parsed_key.value_name.to_string(),
+                value,
+            )
+            .map_err(|e| GitAiError::GixError(e.to_string()))?;
+
+        Self::write_config_file(&path, &config)
+    }
+
+    /// Remove a single-valued config key, matching `git config [--local|--global] --unset key`.
+    ///
+    /// Returns whether a value was removed. Like git, this refuses to touch a key with
+    /// multiple values; use [`Self::config_unset_all`] for those.
+    pub fn config_unset(&self, key: &str, scope: ConfigScope) -> Result<bool, GitAiError> {
+        self.config_unset_values(key, scope, false)
+    }
+
+    /// Remove every value of a config key, matching `git config [--local|--global] --unset-all key`.
+    ///
+    /// Returns whether any value was removed.
+    pub fn config_unset_all(&self, key: &str, scope: ConfigScope) -> Result<bool, GitAiError> {
+        self.config_unset_values(key, scope, true)
+    }
+
+    fn config_unset_values(
+        &self,
+        key: &str,
+        scope: ConfigScope,
+        all: bool,
+    ) -> Result<bool, GitAiError> {
+        let parsed_key = parse_config_key(key)?;
+        let (path, mut config) = self.load_config_file_for_write(scope)?;
+
+        let Ok(mut values) = config.raw_values_mut_by(
+            parsed_key.section_name,
+            parsed_key.subsection_name,
+            parsed_key.value_name,
+        ) else {
+            return Ok(false);
+        };
+        if values.len() > 1 && !all {
+            return Err(GitAiError::Generic(format!("{} has multiple values", key)));
+        }
+        values.delete_all();
+
+        Self::write_config_file(&path, &config)?;
+        Ok(true)
+    }
+
+    /// Path of the global config file, honoring `GIT_CONFIG_GLOBAL`. Without the override this is
+    /// `~/.gitconfig`, or the XDG `git/config` file when only that one exists. The file itself
+    /// may not exist yet; `None` means no location could be resolved (e.g. `HOME` is unset).
+    pub fn global_config_path(&self) -> Option<PathBuf> {
+        global_config_write_path(&mut |name| self.config_env_var(name))
+    }
+
+    /// Path of the system config file, honoring `GIT_CONFIG_SYSTEM`, or `None` when
+    /// `GIT_CONFIG_NOSYSTEM` disables it.
+    pub fn system_config_path(&self) -> Option<PathBuf> {
+        gix_config::Source::System
+            .storage_location(&mut |name| self.config_env_var(name))
+            .map(|path| path.into_owned())
+    }
+
+    /// Ref holding the authorship notes, from `git-ai.notesRef` (default `refs/notes/ai`).
+    /// A short name like `ai-team` expands to `refs/notes/ai-team`, as `git notes --ref` does.
+    /// An unreadable config falls back to the default rather than failing note operations.
+    pub fn notes_ref(&self) -> String {
+        match self.config_get_str("git-ai.notesRef") {
+            Ok(Some(name)) if !name.trim().is_empty() => {
+                let name = name.trim();
+                if name.starts_with("refs/") {
+                    name.to_string()
+                } else {
+                    format!("refs/notes/{}", name)
+                }
+            }
+            _ => DEFAULT_NOTES_REF.to_string(),
+        }
+    }
+
+    /// Maximum number of `cat-file --batch` processes reading note blobs at once, from
+    /// `git-ai.notesReadConcurrency` (default: available parallelism, capped at
+    /// [`DEFAULT_MAX_NOTES_READ_CONCURRENCY`]). A value of 1 forces serial reads; zero or
+    /// unparseable values fall back to the default.
+    pub fn notes_read_concurrency(&self) -> usize {
+        let configured = match self.config_get_int("git-ai.notesReadConcurrency") {
+            Ok(value) => value.and_then(|n| usize::try_from(n).ok()),
+            Err(e) => {
+                debug_log(&format!("ignoring git-ai.notesReadConcurrency: {}", e));
+                None
+            }
+        };
+        configured.filter(|&n| n > 0).unwrap_or_else(|| {
+            std::thread::available_parallelism()
+                .map(|n| n.get())
+                .unwrap_or(1)
+                .min(DEFAULT_MAX_NOTES_READ_CONCURRENCY)
+        })
+    }
+
+    /// Path of the config file a write with the given scope should modify.
+    fn config_file_path_for_scope(&self, scope: ConfigScope) -> Result<PathBuf, GitAiError> {
+        match scope {
+            ConfigScope::Local => Ok(self.common_dir().join("config")),
+            ConfigScope::Global => self.global_config_path().ok_or_else(|| {
+                GitAiError::Generic(
+                    "Unable to resolve global git config path: HOME is not set".to_string(),
+                )
+            }),
+            ConfigScope::System | ConfigScope::Worktree | ConfigScope::Command => Err(
+                GitAiError::Generic(format!("Writing {:?} git config is not supported", scope)),
+            ),
+        }
+    }
+
+    /// Load a single config file (without includes) for modification.
+    /// A missing file yields an empty config that will be created on write.
+    fn load_config_file_for_write(
+        &self,
+        scope: ConfigScope,
+    ) -> Result<(PathBuf, gix_config::File<'static>), GitAiError> {
+        let path = self.config_file_path_for_scope(scope)?;
+        let source = match scope {
+            ConfigScope::Global => gix_config::Source::User,
+            _ => gix_config::Source::Local,
+        };
+        let config = match Self::load_optional_config_file(&path, source)? {
+            Some(config) => config,
+            None => gix_config::File::new(gix_config::file::Metadata::from(source).at(&path)),
+        };
+        Ok((path, config))
+    }
+
+    fn write_config_file(path: &Path, config: &gix_config::File<'_>) -> Result<(), GitAiError> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut lock_path = path.as_os_str().to_owned();
+        lock_path.push(".lock");
+        let lock_path = PathBuf::from(lock_path);
+
+        // Like git, refuse to write while another writer holds the lock
+        let mut lock_file = match std::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&lock_path)
+        {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                return Err(GitAiError::Generic(format!(
+                    "could not lock config file {}: File exists",
+                    path.display()
+                )));
+            }
+            Err(e) => return Err(e.into()),
+        };
+        let written = {
+            use std::io::Write;
+            lock_file.write_all(&config.to_bstring())
+        };
+        drop(lock_file);
+        if let Err(e) = written {
+            let _ = std::fs::remove_file(&lock_path);
+            return Err(e.into());
+        }
+        if let Err(e) = std::fs::rename(&lock_path, path) {
+            let _ = std::fs::remove_file(&lock_path);
+            return Err(e.into());
+        }
+        Ok(())
+    }
+
     /// Get the effective git user identity for this repository.
     ///
     /// Uses `git var GIT_COMMITTER_IDENT` which respects the full git identity precedence:
@@ -1493,7 +2066,7 @@ impl Repository {
         args.push("var".to_string());
         args.push(git_var.to_string());
 
-        if let Ok(output) = exec_git(&args)
+        if let Ok(output) = self.exec_git(&args)
             && let Ok(stdout) = String::from_utf8(output.stdout)
         {
             let identity = parse_git_var_identity(&stdout);
@@ -1525,34 +2098,104 @@ impl Repository {
     /// and done against a canonicalized version of the key
     /// in which section and variable names are lowercased, but subsection names are not.
     ///
-    /// Returns a HashMap of key -> value for all matching config entries.
+    /// Returns a HashMap of key -> value for all matching config entries. When a
+    /// multi-valued key matches, only its last value is kept; use
+    /// [`Self::config_get_regexp_multi`] to see every occurrence.
     pub fn config_get_regexp(
         &self,
         pattern: &str,
     ) -> Result<std::collections::HashMap<String, String>, GitAiError> {
-        let re = Regex::new(pattern)
+        Ok(self.config_get_regexp_multi(pattern)?.into_iter().collect())
+    }
+
+    /// Like [`Self::config_get_regexp`], but `pattern` must match the whole canonicalized
+    /// key, as if written `^(?:pattern)$`.
+    ///
+    /// `git config --get-regexp` (and so [`Self::config_get_regexp`]) matches anywhere in
+    /// the key, so `alias\.` also finds `alias.st`. Use this when listing specific keys,
+    /// e.g. `pull\.rebase|branch\..*\.rebase`, to avoid picking up longer keys by accident.
+    pub fn config_get_regexp_exact(
+        &self,
+        pattern: &str,
+    ) -> Result<std::collections::HashMap<String, String>, GitAiError> {
+        self.config_get_regexp(&format!("^(?:{})$", pattern))
+    }
+
+    /// Get all config entries matching a regex pattern, like `git config --get-regexp`.
+    ///
+    /// Uses the same key canonicalization as [`Self::config_get_regexp`], but returns
+    /// `(key, value)` pairs in file order and keeps every value of multi-valued keys.
+    pub fn config_get_regexp_multi(
+        &self,
+        pattern: &str,
+    ) -> Result<Vec<(String, String)>, GitAiError> {
+        self.config_get_regexp_filtered(pattern, None)
+    }
+
+    /// Get all config entries whose key matches `key_pattern` and, when given, whose value
+    /// matches `value_pattern`, like `git config --get-regexp <key_pattern> <value_pattern>`.
+    ///
+    /// As with git, a value pattern starting with `!` selects values that do *not* match
+    /// the remainder of the pattern. Entries are returned in file order. Values come from the
+    /// parsed config rather than line-oriented `git config` output, so embedded newlines are
+    /// kept verbatim.
+    pub fn config_get_regexp_filtered(
+        &self,
+        key_pattern: &str,
+        value_pattern: Option<&str>,
+    ) -> Result<Vec<(String, String)>, GitAiError> {
+        let re = Regex::new(key_pattern)
             .map_err(|e| GitAiError::Generic(format!("Invalid regex pattern: {}", e)))?;
+        let value_filter = value_pattern
+            .map(|pattern| {
+                let (negate, pattern) = match pattern.strip_prefix('!') {
+                    Some(rest) => (true, rest),
+                    None => (false, pattern),
+                };
+                Regex::new(pattern)
+                    .map(|value_re| (negate, value_re))
+                    .map_err(|e| GitAiError::Generic(format!("Invalid value regex pattern: {}", e)))
+            })
+            .transpose()?;
 
         let config = self.get_git_config_file()?;
-        let mut matches: HashMap<String, String> = HashMap::new();
+        let mut matches: Vec<(String, String)> = Vec::new();
 
         for section in config.sections() {
             let section_name = section.header().name().to_string().to_lowercase();
             let subsection = section.header().subsection_name();
+            // A value name can repeat within a section; track which occurrence we're at.
+            let mut occurrences: HashMap<String, usize> = HashMap::new();
 
             for value_name in section.body().value_names() {
                 let value_name_str = value_name.to_string().to_lowercase();
+                let occurrence = occurrences.entry(value_name_str.clone()).or_insert(0);
+                let index = *occurrence;
+                *occurrence += 1;
+
                 let full_key = if let Some(sub) = subsection {
                     format!("{}.{}.{}", section_name, sub, value_name_str)
                 } else {
                     format!("{}.{}", section_name, value_name_str)
                 };
 
-                if re.is_match(&full_key)
-                    && let Some(value) = section.body().value(value_name).map(|c| c.to_string())
+                if !re.is_match(&full_key) {
+                    continue;
+                }
+                let Some(value) = section
+                    .body()
+                    .values(&value_name_str)
+                    .get(index)
+                    .map(|v| v.to_string())
+                else {
+                    continue;
+                };
+                if let Some((negate, value_re)) = &value_filter
+                    && value_re.is_match(&value) == *negate
                 {
-                    matches.insert(full_key, value);
+                    continue;
                 }
+                matches.push((full_key, value));
             }
         }
 
@@ -1563,7 +2206,7 @@ impl Repository {
     /// Returns None if the version cannot be parsed.
     pub fn git_version(&self) -> Option<(u32, u32, u32)> {
         let args = vec!["--version".to_string()];
-        let output = exec_git(&args).ok()?;
+        let output = self.exec_git(&args).ok()?;
         let version_str = String::from_utf8(output.stdout).ok()?;
         parse_git_version(&version_str)
     }
@@ -1589,7 +2232,7 @@ impl Repository {
         args.push("hash-object".to_string());
         args.push("-w".to_string());
         args.push("--stdin".to_string());
-        let output = exec_git_stdin(&args, data)?;
+        let output = self.exec_git_stdin(&args, data)?;
         Ok(String::from_utf8(output.stdout)?.trim().to_string())
     }
 
@@ -1611,7 +2254,7 @@ impl Repository {
 
         let verb = if force { "update" } else { "create" };
         let stdin_line = format!("{} {} {}\n", verb, name, id.trim());
-        exec_git_stdin(&args, stdin_line.as_bytes())?;
+        self.exec_git_stdin(&args, stdin_line.as_bytes())?;
 
         Ok(Reference {
             repo: self,
@@ -1626,7 +2269,7 @@ impl Repository {
         args.push(format!("refs/remotes/{}/HEAD", remote_name));
         args.push("--short".to_string());
 
-        let output = exec_git(&args)?;
+        let output = self.exec_git(&args)?;
         Ok(String::from_utf8(output.stdout)?.trim().to_string())
     }
 
@@ -1638,20 +2281,83 @@ impl Repository {
         args.push("--verify".to_string());
         args.push("-s".to_string());
         args.push(name.to_string());
-        exec_git(&args)?;
+        self.exec_git(&args)?;
         Ok(Reference {
             repo: self,
             ref_name: name.to_string(),
         })
     }
-    // Find a merge base between two commits
-    pub fn merge_base(&self, one: String, two: String) -> Result<String, GitAiError> {
+    // Best common ancestor of `a` and `b`, or `None` if their histories are unrelated.
+    // Errors when git can't answer, e.g. because one of the commits doesn't exist.
+    pub fn merge_base(&self, a: &str, b: &str) -> Result<Option<String>, GitAiError> {
         let mut args = self.global_args_for_exec();
         args.push("merge-base".to_string());
-        args.push(one.to_string());
-        args.push(two.to_string());
-        let output = exec_git(&args)?;
-        Ok(String::from_utf8(output.stdout)?.trim().to_string())
+        args.push(a.to_string());
+        args.push(b.to_string());
+        match self.exec_git(&args) {
+            Ok(output) => Ok(Some(String::from_utf8(output.stdout)?.trim().to_string())),
+            Err(GitAiError::GitCliError { code: Some(1), .. }) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    // Whether `ancestor` is reachable from `descendant` (a commit is its own ancestor).
+    // Errors only when git can't answer, e.g. because one of the commits doesn't exist.
+    pub fn is_ancestor(&self, ancestor: &str, descendant: &str) -> Result<bool, GitAiError> {
+        let mut args = self.global_args_for_exec();
+        args.push("merge-base".to_string());
+        args.push("--is-ancestor".to_string());
+        args.push(ancestor.to_string());
+        args.push(descendant.to_string());
+        match self.exec_git(&args) {
+            Ok(_) => Ok(true),
+            Err(GitAiError::GitCliError { code: Some(1), .. }) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    // Whether moving a branch from `old` to `new` could have been a fast-forward, i.e. `old` is a
+    // proper ancestor of `new` (`merge-base --is-ancestor`). The shape of `new` doesn't matter: an
+    // upstream merge commit whose first parent is `old` is still a fast-forward target. Works from
+    // history alone, so it doesn't depend on the (possibly localized) reflog message.
+    pub fn is_fast_forward(&self, old: &str, new: &str) -> bool {
+        old != new && self.is_ancestor(old, new).unwrap_or(false)
+    }
+
+    /// The newest `limit` entries of `ref_name`'s reflog, newest first. `ref_name` is "HEAD" or
+    /// a full ref name such as "refs/heads/main"; a ref without a reflog has no entries.
+    ///
+    /// Entries come from `git reflog show`, so any ref backend works. Each entry's `old_sha` is
+    /// the new value of the entry before it, or all zeros for the oldest entry in the reflog.
+    pub fn reflog_entries(
+        &self,
+        ref_name: &str,
+        limit: usize,
+    ) -> Result<Vec<ReflogEntry>, GitAiError> {
+        if limit == 0 {
+            return Ok(Vec::new());
+        }
+
+        let mut args = self.global_args_for_exec();
+        args.push("reflog".to_string());
+        args.push("show".to_string());
+        // One extra entry supplies the old value of the oldest entry returned
+        args.push(format!("-n{}", limit + 1));
+        args.push("--no-show-signature".to_string());
+        args.push("--format=%H%x00%gn <%ge>%x00%gs".to_string());
+        args.push(ref_name.to_string());
+        args.push("--".to_string());
+        let output = match self.exec_git(&args) {
+            Ok(output) => output,
+            Err(GitAiError::GitCliError {
+                code: Some(128), ..
+            }) => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+        Ok(parse_reflog_entries(
+            &String::from_utf8_lossy(&output.stdout),
+            limit,
+        ))
     }
 
     // Merge two trees, producing an index that reflects the result of the merge. The index may be written as-is to the working directory or checked out. If the index is to be converted to a tree, the caller should resolve any conflicts that arose as part of the merge.
@@ -1670,7 +2376,7 @@ impl Repository {
         args.push("ours".to_string());
         args.push(our_tree.oid.to_string());
         args.push(their_tree.oid.to_string());
-        let output = exec_git(&args)?;
+        let output = self.exec_git(&args)?;
         Ok(String::from_utf8(output.stdout)?.trim().to_string())
     }
 
@@ -1688,7 +2394,7 @@ impl Repository {
             rp_args.push("--symbolic-full-name".to_string());
             rp_args.push(branch_refname.to_string());
 
-            match exec_git(&rp_args) {
+            match self.exec_git(&rp_args) {
                 Ok(output) => {
                     let s = String::from_utf8(output.stdout).unwrap_or_default();
                     let s = s.trim();
@@ -1719,7 +2425,7 @@ impl Repository {
             rp_args.push("--symbolic-full-name".to_string());
             rp_args.push(merge_target_refname.to_string());
 
-            match exec_git(&rp_args) {
+            match self.exec_git(&rp_args) {
                 Ok(output) => {
                     let s = String::from_utf8(output.stdout).unwrap_or_default();
                     let s = s.trim();
@@ -1757,7 +2463,7 @@ impl Repository {
         log_args.push("--not".to_string());
         log_args.push(fq_merge_target.to_string());
 
-        let log_output = exec_git(&log_args).map_err(|e| {
+        let log_output = self.exec_git(&log_args).map_err(|e| {
             GitAiError::Generic(format!(
                 "Failed to get commit log for {}: {:?}",
                 branch_refname, e
@@ -1893,7 +2599,7 @@ impl Repository {
             if let Some(tip) = old_tip {
                 ur_args.push(tip);
             }
-            exec_git(&ur_args)?;
+            self.exec_git(&ur_args)?;
         }
 
         Ok(new_commit)
@@ -1906,7 +2612,7 @@ impl Repository {
         // args.push("-q".to_string());
         args.push("--verify".to_string());
         args.push(spec.to_string());
-        let output = exec_git(&args)?;
+        let output = self.exec_git(&args)?;
         Ok(Object {
             repo: self,
             oid: String::from_utf8(output.stdout)?.trim().to_string(),
@@ -1947,7 +2653,7 @@ impl Repository {
         let mut args = self.global_args_for_exec();
         args.push("branch".to_string());
         args.push("--show-current".to_string());
-        let output = exec_git(&args)?;
+        let output = self.exec_git(&args)?;
         let branch = String::from_utf8(output.stdout)?.trim().to_string();
         if branch.is_empty() {
             return Ok(None);
@@ -1964,7 +2670,7 @@ impl Repository {
         args.push("-i".to_string());
         args.push("--max-count=1".to_string());
         args.push(format!("--author={}", author_spec));
-        let output = match exec_git(&args) {
+        let output = match self.exec_git(&args) {
             Ok(output) => output,
             Err(GitAiError::GitCliError { code: Some(1), .. }) => {
                 // No commit found
@@ -1983,7 +2689,7 @@ impl Repository {
         show_args.push("-s".to_string());
         show_args.push("--format=%an <%ae>".to_string());
         show_args.push(commit_oid);
-        let show_output = exec_git(&show_args)?;
+        let show_output = self.exec_git(&show_args)?;
         let author_line = String::from_utf8(show_output.stdout)?.trim().to_string();
         if author_line.is_empty() {
             Ok(None)
@@ -1999,7 +2705,7 @@ impl Repository {
         args.push("for-each-ref".to_string());
         args.push("--format=%(refname)".to_string());
 
-        let output = exec_git(&args)?;
+        let output = self.exec_git(&args)?;
         let stdout = String::from_utf8(output.stdout)?;
         let refs: Vec<String> = stdout
             .lines()
@@ -2066,7 +2772,7 @@ impl Repository {
         let mut args = self.global_args_for_exec();
         args.push("show".to_string());
         args.push(format!("{}:{}", commit_hash, file_path));
-        let output = exec_git(&args)?;
+        let output = self.exec_git(&args)?;
         Ok(output.stdout)
     }
 
@@ -2096,7 +2802,7 @@ impl Repository {
 
                 async move {
                     let _permit = semaphore.acquire().await;
-                    let result = exec_git(&args).and_then(|output| {
+                    let result = self.exec_git(&args).and_then(|output| {
                         String::from_utf8(output.stdout)
                             .map_err(|e| GitAiError::Utf8Error(e.utf8_error()))
                     });
@@ -2162,7 +2868,7 @@ impl Repository {
             false
         };
 
-        let output = exec_git(&args)?;
+        let output = self.exec_git(&args)?;
 
         // With -z, output is NUL-separated. The output may contain a trailing NUL.
         let mut files: HashSet<String> = output
@@ -2365,7 +3071,7 @@ impl Repository {
         args.push("fetch".to_string());
         args.push(remote_name.to_string());
         args.push(branch_name.to_string());
-        exec_git(&args)?;
+        self.exec_git(&args)?;
         Ok(())
     }
 }
@@ -2505,6 +3211,8 @@ pub fn find_repository(global_args: &[String]) -> Result<Repository, GitAiError>
         workdir,
         canonical_workdir,
         cached_author_identity: std::sync::OnceLock::new(),
+        config_env: None,
+        executor: std::sync::Arc::new(ProcessGitExecutor),
     })
 }
 
@@ -2597,6 +3305,8 @@ pub fn from_bare_repository(git_dir: &Path) -> Result<Repository, GitAiError> {
         workdir,
         canonical_workdir,
         cached_author_identity: std::sync::OnceLock::new(),
+        config_env: None,
+        executor: std::sync::Arc::new(ProcessGitExecutor),
     })
 }
 
@@ -2774,6 +3484,33 @@ pub fn exec_git_with_profile(
     Ok(output)
 }
 
+/// Helper to execute a git command and return its stdout as trimmed, non-empty lines.
+///
+/// With `empty_on_128`, a fatal exit (code 128, e.g. a missing ref or path) yields no lines
+/// instead of an error.
+pub fn exec_git_lines(args: &[String], empty_on_128: bool) -> Result<Vec<String>, GitAiError> {
+    output_lines(exec_git(args), empty_on_128)
+}
+
+fn output_lines(
+    result: Result<Output, GitAiError>,
+    empty_on_128: bool,
+) -> Result<Vec<String>, GitAiError> {
+    let output = match result {
+        Ok(output) => output,
+        Err(GitAiError::GitCliError {
+            code: Some(128), ..
+        }) if empty_on_128 => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    Ok(String::from_utf8(output.stdout)?
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
 /// Helper to execute a git command with data provided on stdin
 pub fn exec_git_stdin(args: &[String], stdin_data: &[u8]) -> Result<Output, GitAiError> {
     exec_git_stdin_with_profile(args, stdin_data, InternalGitProfile::General)
@@ -2785,6 +3522,129 @@ pub fn exec_git_stdin_with_profile(
     stdin_data: &[u8],
     profile: InternalGitProfile,
 ) -> Result<Output, GitAiError> {
+    exec_git_stdin_with_env_with_profile(args, &[], stdin_data, profile)
+}
+
+/// Helper to execute a git command whose stdin is produced incrementally by `write_stdin`.
+///
+/// `write_stdin` runs on its own thread against a buffered pipe writer while stdout and stderr
+/// are collected, so callers can stream arbitrarily large inputs without building them in memory
+/// first and without deadlocking against git's output.
+pub fn exec_git_stdin_writer<W>(args: &[String], write_stdin: W) -> Result<Output, GitAiError>
+where
+    W: FnOnce(&mut dyn std::io::Write) -> std::io::Result<()> + Send + 'static,
+{
+    use std::io::Write;
+
+    let (mut child, effective_args) = spawn_git(
+        args,
+        &[],
+        InternalGitProfile::General,
+        std::process::Stdio::piped(),
+    )?;
+
+    let stdin_writer = child.stdin.take().map(|stdin| {
+        std::thread::spawn(move || -> std::io::Result<()> {
+            let mut writer = std::io::BufWriter::new(stdin);
+            write_stdin(&mut writer)?;
+            writer.flush()
+            // Dropping the writer closes stdin so git sees EOF
+        })
+    });
+
+    let output = child.wait_with_output().map_err(GitAiError::IoError)?;
+    let stdin_result = stdin_writer.map(|handle| handle.join());
+
+    let output = checked_output(output, effective_args)?;
+    match stdin_result {
+        Some(Ok(Err(e))) => Err(GitAiError::IoError(e)),
+        Some(Err(_)) => Err(GitAiError::Generic(
+            "git stdin writer thread panicked".to_string(),
+        )),
+        _ => Ok(output),
+    }
+}
+
+/// Helper to execute a git command with data provided on stdin, handing its stdout to `on_stdout`
+/// as a reader while the command runs instead of buffering the whole output in memory.
+///
+/// Stdin is fed from a separate thread so large inputs can't deadlock against a full stdout pipe.
+pub fn exec_git_stdin_streaming(
+    args: &[String],
+    stdin_data: Vec<u8>,
+    on_stdout: &mut dyn FnMut(&mut dyn std::io::BufRead) -> Result<(), GitAiError>,
+) -> Result<(), GitAiError> {
+    use std::io::{Read, Write};
+
+    let (mut child, effective_args) = spawn_git(
+        args,
+        &[],
+        InternalGitProfile::General,
+        std::process::Stdio::piped(),
+    )?;
+
+    let stdin_writer = child.stdin.take().map(|mut stdin| {
+        std::thread::spawn(move || -> std::io::Result<()> { stdin.write_all(&stdin_data) })
+    });
+    let stderr_reader = child.stderr.take().map(|mut stderr| {
+        std::thread::spawn(move || {
+            let mut buf = Vec::new();
+            let _ = stderr.read_to_end(&mut buf);
+            buf
+        })
+    });
+
+    let stdout = child
+        .stdout
+        .take()
+        .ok_or_else(|| GitAiError::Generic("Failed to capture git stdout".to_string()))?;
+    let mut reader = std::io::BufReader::new(stdout);
+    let callback_result = on_stdout(&mut reader);
+    // Drain anything the callback didn't consume so git never blocks on a full pipe.
+    let _ = std::io::copy(&mut reader, &mut std::io::sink());
+
+    let status = child.wait().map_err(GitAiError::IoError)?;
+    let stdin_result = stdin_writer.map(|handle| handle.join());
+    let stderr = stderr_reader
+        .and_then(|handle| handle.join().ok())
+        .unwrap_or_default();
+
+    if !status.success() {
+        return Err(GitAiError::GitCliError {
+            code: status.code(),
+            stderr: String::from_utf8_lossy(&stderr).to_string(),
+            args: effective_args,
+        });
+    }
+    callback_result?;
+    if let Some(Ok(Err(e))) = stdin_result {
+        return Err(GitAiError::IoError(e));
+    }
+
+    Ok(())
+}
+
+/// Spawn a long-running git command with piped stdin and stdout, for request/response
+/// protocols such as `cat-file --batch` where each answer is read before the next request is
+/// written. Stderr is discarded so it can never fill up and stall git.
+pub fn spawn_git_piped(args: &[String]) -> Result<std::process::Child, GitAiError> {
+    spawn_git(
+        args,
+        &[],
+        InternalGitProfile::General,
+        std::process::Stdio::null(),
+    )
+    .map(|(child, _)| child)
+}
+
+/// Spawn git with piped stdin and stdout, the given stderr handling and env overrides, returning
+/// the child together with the effective args for error reporting.
+fn spawn_git(
+    args: &[String],
+    env: &[(String, String)],
+    profile: InternalGitProfile,
+    stderr: std::process::Stdio,
+) -> Result<(std::process::Child, Vec<String>), GitAiError> {
     // TODO Make sure to handle process signals, etc.
     let effective_args =
         args_with_internal_git_profile(&args_with_disabled_hooks_if_needed(args), profile);
@@ -2792,7 +3652,12 @@ pub fn exec_git_stdin_with_profile(
     cmd.args(&effective_args)
         .stdin(std::process::Stdio::piped())
         .stdout(std::process::Stdio::piped())
-        .stderr(std::process::Stdio::piped());
+        .stderr(stderr);
+
+    // Apply env overrides
+    for (k, v) in env.iter() {
+        cmd.env(k, v);
+    }
     cmd.env_remove("GIT_EXTERNAL_DIFF");
     cmd.env_remove("GIT_DIFF_OPTS");
 
@@ -2803,17 +3668,12 @@ pub fn exec_git_stdin_with_profile(
         }
     }
 
-    let mut child = cmd.spawn().map_err(GitAiError::IoError)?;
-
-    if let Some(mut stdin) = child.stdin.take() {
-        use std::io::Write;
-        if let Err(e) = stdin.write_all(stdin_data) {
-            return Err(GitAiError::IoError(e));
-        }
-    }
-
-    let output = child.wait_with_output().map_err(GitAiError::IoError)?;
+    let child = cmd.spawn().map_err(GitAiError::IoError)?;
+    Ok((child, effective_args))
+}
 
+/// Turn a finished git command's output into a `GitCliError` when it exited unsuccessfully.
+fn checked_output(output: Output, effective_args: Vec<String>) -> Result<Output, GitAiError> {
     if !output.status.success() {
         let code = output.status.code();
         let stderr = String::from_utf8_lossy(&output.stderr).to_string();
@@ -2838,37 +3698,13 @@ pub fn exec_git_stdin_with_env(
 }
 
 /// Helper to execute a git command with data provided on stdin, env overrides, and profile.
-#[allow(dead_code)]
 pub fn exec_git_stdin_with_env_with_profile(
     args: &[String],
     env: &[(String, String)],
     stdin_data: &[u8],
     profile: InternalGitProfile,
 ) -> Result<Output, GitAiError> {
-    // TODO Make sure to handle process signals, etc.
-    let effective_args =
-        args_with_internal_git_profile(&args_with_disabled_hooks_if_needed(args), profile);
-    let mut cmd = Command::new(config::Config::get().git_cmd());
-    cmd.args(&effective_args)
-        .stdin(std::process::Stdio::piped())
-        .stdout(std::process::Stdio::piped())
-        .stderr(std::process::Stdio::piped());
-
-    // Apply env overrides
-    for (k, v) in env.iter() {
-        cmd.env(k, v);
-    }
-    cmd.env_remove("GIT_EXTERNAL_DIFF");
-    cmd.env_remove("GIT_DIFF_OPTS");
-
-    #[cfg(windows)]
-    {
-        if !is_interactive_terminal() {
-            cmd.creation_flags(CREATE_NO_WINDOW);
-        }
-    }
-
-    let mut child = cmd.spawn().map_err(GitAiError::IoError)?;
+    let (mut child, effective_args) = spawn_git(args, env, profile, std::process::Stdio::piped())?;
 
     if let Some(mut stdin) = child.stdin.take() {
         use std::io::Write;
@@ -2878,18 +3714,79 @@ pub fn exec_git_stdin_with_env_with_profile(
     }
 
     let output = child.wait_with_output().map_err(GitAiError::IoError)?;
+    checked_output(output, effective_args)
+}
 
-    if !output.status.success() {
-        let code = output.status.code();
-        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-        return Err(GitAiError::GitCliError {
-            code,
-            stderr,
-            args: effective_args,
-        });
+/// Parse `reflog show --format=%H%x00%gn <%ge>%x00%gs` output (newest first) into at most
+/// `limit` entries. Each entry's old value is the new value of the entry after it, so the oldest
+/// line only contributes its new value unless the reflog ends there.
+fn parse_reflog_entries(output: &str, limit: usize) -> Vec<ReflogEntry> {
+    let mut entries: Vec<ReflogEntry> = output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(3, '\0');
+            let new_sha = fields.next()?.trim();
+            if new_sha.is_empty() {
+                return None;
+            }
+            Some(ReflogEntry {
+                old_sha: String::new(),
+                new_sha: new_sha.to_string(),
+                committer: fields.next()?.to_string(),
+                subject: fields.next().unwrap_or_default().to_string(),
+            })
+        })
+        .collect();
+
+    let mut older_new_sha: Option<String> = None;
+    for entry in entries.iter_mut().rev() {
+        entry.old_sha = older_new_sha
+            .take()
+            .unwrap_or_else(|| "0".repeat(entry.new_sha.len()));
+        older_new_sha = Some(entry.new_sha.clone());
     }
+    entries.truncate(limit);
+    entries
+}
 
-    Ok(output)
+/// Oldest git we support; `git branch --show-current` first shipped in 2.22.
+pub const MIN_GIT_VERSION: (u32, u32, u32) = (2, 22, 0);
+
+/// Check once per process that the configured git binary runs and is at least
+/// [`MIN_GIT_VERSION`], returning [`GitAiError::GitUnavailable`] otherwise.
+pub fn ensure_git_available() -> Result<(), GitAiError> {
+    static GIT_AVAILABLE: std::sync::OnceLock<Result<(), GitAiError>> = std::sync::OnceLock::new();
+    GIT_AVAILABLE
+        .get_or_init(|| {
+            let git_cmd = config::Config::get().git_cmd();
+            match exec_git(&["--version".to_string()]) {
+                Ok(output) => check_git_version(&String::from_utf8_lossy(&output.stdout)),
+                Err(GitAiError::IoError(e)) if e.kind() == std::io::ErrorKind::NotFound => Err(
+                    GitAiError::GitUnavailable(format!("`{}` was not found on PATH", git_cmd)),
+                ),
+                Err(e) => Err(GitAiError::GitUnavailable(format!(
+                    "`{} --version` failed: {}",
+                    git_cmd, e
+                ))),
+            }
+        })
+        .clone()
+}
+
+/// Check `git --version` output against [`MIN_GIT_VERSION`]
+fn check_git_version(version_output: &str) -> Result<(), GitAiError> {
+    let (min_major, min_minor, min_patch) = MIN_GIT_VERSION;
+    match parse_git_version(version_output) {
+        Some(version) if version >= MIN_GIT_VERSION => Ok(()),
+        Some((major, minor, patch)) => Err(GitAiError::GitUnavailable(format!(
+            "git {}.{}.{} is too old; git-ai needs {}.{}.{} or newer",
+            major, minor, patch, min_major, min_minor, min_patch
+        ))),
+        None => Err(GitAiError::GitUnavailable(format!(
+            "couldn't read a version from `git --version` output: {}",
+            version_output.trim()
+        ))),
+    }
 }
 
 /// Parse git version string (e.g., "git version 2.39.3 (Apple Git-146)") to extract major, minor, patch.
@@ -3152,6 +4049,108 @@ mod tests {
         assert_eq!(parse_git_version("git version 2.39"), Some((2, 39, 0)));
     }
 
+    #[test]
+    fn test_parse_reflog_entries_multiple_operations() {
+        let a = "a".repeat(40);
+        let b = "b".repeat(40);
+        let c = "c".repeat(40);
+        let d = "d".repeat(40);
+        let output = format!(
+            "{d}\0Dev <dev@example.com>\0pull: Fast-forward\n\
+             {c}\0Dev <dev@example.com>\0reset: moving to HEAD~1\n\
+             {b}\0CI Bot <ci@example.com>\0merge feature: Merge made by the 'ort' strategy.\n\
+             {a}\0Dev <dev@example.com>\0commit (initial): first\n"
+        );
+
+        let entries = parse_reflog_entries(&output, 10);
+        let summary: Vec<(&str, &str, &str)> = entries
+            .iter()
+            .map(|e| (e.old_sha.as_str(), e.new_sha.as_str(), e.subject.as_str()))
+            .collect();
+        let zero = "0".repeat(40);
+        assert_eq!(
+            summary,
+            vec![
+                (c.as_str(), d.as_str(), "pull: Fast-forward"),
+                (b.as_str(), c.as_str(), "reset: moving to HEAD~1"),
+                (
+                    a.as_str(),
+                    b.as_str(),
+                    "merge feature: Merge made by the 'ort' strategy."
+                ),
+                (zero.as_str(), a.as_str(), "commit (initial): first"),
+            ]
+        );
+        assert_eq!(entries[2].committer, "CI Bot <ci@example.com>");
+
+        // The extra entry git was asked for only supplies the last old value
+        let limited = parse_reflog_entries(&output, 2);
+        assert_eq!(limited.len(), 2);
+        assert_eq!(limited[1].old_sha, b);
+        assert!(parse_reflog_entries("", 3).is_empty());
+
+        // An update without a message has an empty subject
+        let no_message = format!("{a}\0Dev <dev@example.com>\0\n");
+        assert_eq!(parse_reflog_entries(&no_message, 1)[0].subject, "");
+    }
+
+    #[test]
+    fn test_reflog_entries_reads_head_reflog() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        run_git(temp.path(), &["init"]);
+        run_git(temp.path(), &["config", "user.name", "Test User"]);
+        run_git(temp.path(), &["config", "user.email", "test@example.com"]);
+        run_git(temp.path(), &["commit", "--allow-empty", "-m", "first"]);
+        run_git(temp.path(), &["commit", "--allow-empty", "-m", "second"]);
+        run_git(
+            temp.path(),
+            &["commit", "--amend", "--allow-empty", "-m", "amended"],
+        );
+        let repo = find_repository_in_path(temp.path().to_str().unwrap()).unwrap();
+
+        let entries = repo.reflog_entries("HEAD", 2).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].subject, "commit (amend): amended");
+        assert_eq!(entries[0].old_sha, entries[1].new_sha);
+        assert_eq!(entries[0].new_sha, repo.head().unwrap().target().unwrap());
+        assert_eq!(entries[1].subject, "commit: second");
+        assert_eq!(entries[0].committer, "Test User <test@example.com>");
+
+        assert!(
+            repo.reflog_entries("refs/heads/missing", 5)
+                .unwrap()
+                .is_empty()
+        );
+
+        // Reset back to the first commit: the old value is the amended commit
+        let amended = entries[0].new_sha.clone();
+        run_git(temp.path(), &["reset", "--hard", "HEAD~1"]);
+        let reset = &repo.reflog_entries("HEAD", 1).unwrap()[0];
+        assert_eq!(reset.old_sha, amended);
+        assert_eq!(reset.new_sha, repo.head().unwrap().target().unwrap());
+
+        let branch = repo.head().unwrap().name().unwrap().to_string();
+        assert_eq!(
+            repo.reflog_entries(&branch, 1).unwrap()[0].new_sha,
+            reset.new_sha
+        );
+    }
+
+    #[test]
+    fn test_check_git_version_rejects_old_git() {
+        assert!(check_git_version("git version 2.39.3 (Apple Git-146)\n").is_ok());
+        assert!(check_git_version("git version 2.22.0").is_ok());
+
+        let err = check_git_version("git version 2.17.1\n").unwrap_err();
+        assert!(
+            matches!(&err, GitAiError::GitUnavailable(msg) if msg.contains("2.17.1 is too old"))
+        );
+        assert!(matches!(
+            check_git_version("not git at all"),
+            Err(GitAiError::GitUnavailable(_))
+        ));
+    }
+
     #[test]
     fn test_parse_git_version_with_newline() {
         // Version string with trailing newline
@@ -3167,6 +4166,33 @@ mod tests {
         assert_eq!(parse_git_version("git version x.y.z"), None);
     }
 
+    #[test]
+    fn test_parse_cat_file_batch_header() {
+        assert_eq!(
+            parse_cat_file_batch_header("abc123 blob 42").unwrap(),
+            Some(CatFileBatchHeader::Object {
+                oid: "abc123",
+                object_type: "blob",
+                size: 42,
+            })
+        );
+        for (line, status) in [
+            ("abc123 missing", "missing"),
+            ("abc ambiguous", "ambiguous"),
+        ] {
+            assert_eq!(
+                parse_cat_file_batch_header(line).unwrap(),
+                Some(CatFileBatchHeader::Status {
+                    name: line.split(' ').next().unwrap(),
+                    status,
+                })
+            );
+        }
+        assert!(parse_cat_file_batch_header("abc123 blob many").is_err());
+        assert_eq!(parse_cat_file_batch_header("abc123 42").unwrap(), None);
+        assert_eq!(parse_cat_file_batch_header("abc123").unwrap(), None);
+    }
+
     #[test]
     fn disable_internal_git_hooks_guard_applies_to_spawned_threads() {
         let args = vec!["status".to_string()];
@@ -3325,6 +4351,146 @@ mod tests {
         assert!(rewritten.iter().any(|arg| arg == "--no-relative"));
     }
 
+    #[test]
+    fn test_exec_git_stdin_writer_streams_more_than_a_pipe_buffer() {
+        use crate::git::test_utils::TmpRepo;
+
+        let tmp_repo = TmpRepo::new().unwrap();
+        let mut args = tmp_repo.gitai_repo().global_args_for_exec();
+        args.push("cat-file".to_string());
+        args.push("--batch-check".to_string());
+
+        // ~4MB in and ~5MB out, far past the 64KiB pipe buffer in both directions
+        const LINES: usize = 100_000;
+        let output = exec_git_stdin_writer(&args, |stdin| {
+            for i in 0..LINES {
+                writeln!(stdin, "{:040x}", i)?;
+            }
+            Ok(())
+        })
+        .unwrap();
+
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert_eq!(stdout.lines().count(), LINES);
+        assert!(stdout.lines().all(|line| line.ends_with(" missing")));
+    }
+
+    #[test]
+    fn test_head_branch_name_on_branch_and_detached() {
+        use crate::git::test_utils::TmpRepo;
+
+        let tmp_repo = TmpRepo::new().unwrap();
+        tmp_repo
+            .git_command(&["checkout", "-b", "feature/nested"])
+            .unwrap();
+        // Unborn branch
+        assert_eq!(
+            tmp_repo.gitai_repo().head_branch_name().unwrap().as_deref(),
+            Some("feature/nested")
+        );
+
+        tmp_repo.write_file("file.txt", "base\n", true).unwrap();
+        tmp_repo.commit_with_message("base").unwrap();
+        assert_eq!(
+            tmp_repo.gitai_repo().head_branch_name().unwrap().as_deref(),
+            Some("feature/nested")
+        );
+
+        let sha = tmp_repo.get_head_commit_sha().unwrap();
+        tmp_repo
+            .git_command(&["checkout", "--detach", &sha])
+            .unwrap();
+        assert_eq!(tmp_repo.gitai_repo().head_branch_name().unwrap(), None);
+    }
+
+    #[test]
+    fn test_merge_base_and_is_ancestor_over_small_graph() {
+        use crate::git::test_utils::TmpRepo;
+
+        // base -- main
+        //     \-- feature        orphan (unrelated root)
+        let tmp_repo = TmpRepo::new().unwrap();
+        tmp_repo.write_file("file.txt", "base\n", true).unwrap();
+        tmp_repo.commit_with_message("base").unwrap();
+        let base = tmp_repo.get_head_commit_sha().unwrap();
+        let main_branch = tmp_repo.current_branch().unwrap();
+
+        tmp_repo
+            .git_command(&["checkout", "-b", "feature"])
+            .unwrap();
+        tmp_repo
+            .write_file("feature.txt", "feature\n", true)
+            .unwrap();
+        tmp_repo.commit_with_message("feature").unwrap();
+        let feature = tmp_repo.get_head_commit_sha().unwrap();
+
+        tmp_repo.git_command(&["checkout", &main_branch]).unwrap();
+        tmp_repo.write_file("main.txt", "main\n", true).unwrap();
+        tmp_repo.commit_with_message("main").unwrap();
+        let main = tmp_repo.get_head_commit_sha().unwrap();
+
+        tmp_repo
+            .git_command(&["checkout", "--orphan", "orphan"])
+            .unwrap();
+        tmp_repo.write_file("orphan.txt", "orphan\n", true).unwrap();
+        tmp_repo.commit_with_message("orphan").unwrap();
+        let orphan = tmp_repo.get_head_commit_sha().unwrap();
+
+        let repo = tmp_repo.gitai_repo();
+        assert_eq!(
+            repo.merge_base(&main, &feature).unwrap(),
+            Some(base.clone())
+        );
+        assert_eq!(repo.merge_base(&base, &main).unwrap(), Some(base.clone()));
+        assert_eq!(repo.merge_base(&main, &orphan).unwrap(), None);
+        assert!(repo.merge_base(&main, &"0".repeat(40)).is_err());
+
+        assert!(repo.is_ancestor(&base, &main).unwrap());
+        assert!(repo.is_ancestor(&main, &main).unwrap());
+        assert!(!repo.is_ancestor(&main, &base).unwrap());
+        assert!(!repo.is_ancestor(&feature, &main).unwrap());
+        assert!(!repo.is_ancestor(&orphan, &main).unwrap());
+        assert!(repo.is_ancestor(&"0".repeat(40), &main).is_err());
+    }
+
+    #[test]
+    fn test_is_fast_forward_onto_merge_commit_with_old_as_first_parent() {
+        use crate::git::test_utils::TmpRepo;
+
+        let tmp_repo = TmpRepo::new().unwrap();
+        tmp_repo.write_file("base.txt", "base\n", true).unwrap();
+        tmp_repo.commit_with_message("base").unwrap();
+        let old = tmp_repo.get_head_commit_sha().unwrap();
+        let main_branch = tmp_repo.current_branch().unwrap();
+
+        tmp_repo
+            .git_command(&["checkout", "-b", "feature"])
+            .unwrap();
+        tmp_repo
+            .write_file("feature.txt", "feature\n", true)
+            .unwrap();
+        tmp_repo.commit_with_message("feature").unwrap();
+        let feature = tmp_repo.get_head_commit_sha().unwrap();
+
+        // Upstream merged a branch with --no-ff on top of `old`
+        tmp_repo.git_command(&["checkout", &main_branch]).unwrap();
+        tmp_repo
+            .git_command(&["merge", "--no-ff", "-m", "merge feature", "feature"])
+            .unwrap();
+        let merge = tmp_repo.get_head_commit_sha().unwrap();
+
+        let repo = tmp_repo.gitai_repo();
+        let merge_commit = repo.find_commit(merge.clone()).unwrap();
+        let parents: Vec<String> = merge_commit.parents().map(|p| p.id()).collect();
+        assert_eq!(parents, vec![old.clone(), feature.clone()]);
+
+        assert!(repo.is_fast_forward(&old, &merge));
+        assert!(repo.is_fast_forward(&feature, &merge));
+        assert!(!repo.is_fast_forward(&merge, &merge));
+        assert!(!repo.is_fast_forward(&merge, &old));
+        assert!(!repo.is_fast_forward(&old, &"0".repeat(40)));
+    }
+
     #[test]
     fn test_list_commit_files_with_utf8_filename() {
         use crate::git::test_utils::TmpRepo;
@@ -3517,6 +4683,91 @@ index 0000000..abc1234 100644
         assert_eq!(resolved, base.join("nested").join("..").join("repo"));
     }
 
+    fn git_dir_args(repo: &Path, args: &[&str]) -> Vec<String> {
+        let mut full = vec!["-C".to_string(), repo.to_string_lossy().to_string()];
+        full.extend(args.iter().map(|arg| arg.to_string()));
+        full
+    }
+
+    #[test]
+    fn exec_git_lines_returns_trimmed_non_empty_lines() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        run_git(temp.path(), &["init"]);
+        run_git(temp.path(), &["config", "user.name", "Test User"]);
+        run_git(temp.path(), &["config", "user.email", "test@example.com"]);
+        run_git(temp.path(), &["commit", "--allow-empty", "-m", "initial"]);
+        run_git(temp.path(), &["branch", "feature"]);
+
+        let lines = exec_git_lines(
+            &git_dir_args(
+                temp.path(),
+                &[
+                    "for-each-ref",
+                    "--format=  %(refname)  ",
+                    "refs/heads/feature",
+                ],
+            ),
+            false,
+        )
+        .expect("for-each-ref");
+        assert_eq!(lines, vec!["refs/heads/feature".to_string()]);
+
+        let lines = exec_git_lines(
+            &git_dir_args(temp.path(), &["for-each-ref", "refs/tags"]),
+            false,
+        )
+        .expect("for-each-ref without matches");
+        assert!(lines.is_empty());
+    }
+
+    #[test]
+    fn exec_git_lines_maps_non_zero_exit() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        run_git(temp.path(), &["init"]);
+        let args = git_dir_args(
+            temp.path(),
+            &["rev-parse", "--verify", "refs/heads/missing"],
+        );
+
+        match exec_git_lines(&args, false) {
+            Err(GitAiError::GitCliError { code, .. }) => assert_eq!(code, Some(128)),
+            other => panic!("expected GitCliError, got {:?}", other),
+        }
+        assert!(
+            exec_git_lines(&args, true)
+                .expect("128 as empty")
+                .is_empty()
+        );
+
+        // Exit codes other than 128 are still errors with the flag set
+        let args = git_dir_args(temp.path(), &["config", "--get", "custom.missing"]);
+        match exec_git_lines(&args, true) {
+            Err(GitAiError::GitCliError { code, .. }) => assert_eq!(code, Some(1)),
+            other => panic!("expected GitCliError, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn exec_git_failure_reports_stderr() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        run_git(temp.path(), &["init"]);
+
+        let err = exec_git(&git_dir_args(temp.path(), &["show", "no-such-ref"]))
+            .expect_err("bad ref should fail");
+        let message = err.to_string();
+        assert!(message.contains("exit code 128"), "{}", message);
+        assert!(message.contains("no-such-ref"), "{}", message);
+        assert!(message.contains("unknown revision"), "{}", message);
+
+        let err = exec_git_stdin(
+            &git_dir_args(temp.path(), &["apply", "--check"]),
+            b"not a patch\n",
+        )
+        .expect_err("garbage patch should fail");
+        let message = err.to_string();
+        assert!(message.contains("No valid patches in input"), "{}", message);
+    }
+
     #[test]
     fn find_repository_in_path_supports_bare_repositories() {
         let temp = tempfile::tempdir().expect("tempdir");
@@ -3618,4 +4869,46 @@ index 0000000..abc1234 100644
             repo.storage.working_logs.display()
         );
     }
+
+    #[test]
+    fn global_config_write_path_prefers_git_config_global_override() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let override_path = temp.path().join("custom-global.gitconfig");
+        let home = temp.path().join("home");
+
+        let path = global_config_write_path(&mut |name| match name {
+            "GIT_CONFIG_GLOBAL" => Some(override_path.clone().into_os_string()),
+            "HOME" => Some(home.clone().into_os_string()),
+            _ => None,
+        });
+
+        assert_eq!(path, Some(override_path));
+    }
+
+    #[test]
+    fn global_config_write_path_falls_back_to_xdg_only_when_home_config_missing() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let home = temp.path().join("home");
+        let xdg = temp.path().join("xdg");
+        fs::create_dir_all(xdg.join("git")).expect("create xdg git dir");
+        fs::create_dir_all(&home).expect("create home dir");
+        fs::write(xdg.join("git").join("config"), "").expect("write xdg config");
+
+        let mut env = |name: &str| match name {
+            "HOME" => Some(home.clone().into_os_string()),
+            "XDG_CONFIG_HOME" => Some(xdg.clone().into_os_string()),
+            _ => None,
+        };
+
+        assert_eq!(
+            global_config_write_path(&mut env),
+            Some(xdg.join("git").join("config"))
+        );
+
+        fs::write(home.join(".gitconfig"), "").expect("write home gitconfig");
+        assert_eq!(
+            global_config_write_path(&mut env),
+            Some(home.join(".gitconfig"))
+        );
+    }
 }
@@ -1,13 +1,18 @@
-use crate::git::refs::{
-    AI_AUTHORSHIP_PUSH_REFSPEC, copy_ref, merge_notes_from_ref, ref_exists, tracking_ref_for_remote,
-};
+use crate::git::refs::{copy_ref, merge_notes_from_ref, ref_exists, tracking_ref_for_remote};
 use crate::{
     error::GitAiError,
-    git::{cli_parser::ParsedGitInvocation, repository::exec_git},
-    utils::debug_log,
+    git::cli_parser::ParsedGitInvocation,
+    utils::{debug_log, info_log},
 };
 
 use super::repository::Repository;
+use std::process::Output;
+use std::time::Duration;
+
+/// Attempts at an authorship notes fetch before giving up on transient remote errors
+const NOTES_FETCH_ATTEMPTS: u32 = 3;
+/// Pause before the first notes fetch retry; doubles for each later one
+const NOTES_FETCH_RETRY_DELAY: Duration = Duration::from_millis(500);
 
 #[cfg(windows)]
 fn disabled_hooks_config() -> &'static str {
@@ -28,6 +33,9 @@ pub enum NotesExistence {
     NotFound,
 }
 
+/// The remote a fetch/pull reads from: a positional URL or path as given, else a named
+/// remote in the args, else the upstream or default remote. A URL is returned verbatim so the
+/// authorship fetch targets it directly, with a tracking ref derived from the URL.
 pub fn fetch_remote_from_args(
     repository: &Repository,
     parsed_args: &ParsedGitInvocation,
@@ -67,25 +75,80 @@ pub fn fetch_remote_from_args(
     })
 }
 
+/// Every remote a fetch/pull touches. `--all` covers every configured remote, and `--multiple`
+/// the positional args that name configured remotes (or every remote if none do). Otherwise this
+/// is the single remote from [`fetch_remote_from_args`].
+pub fn fetch_remotes_from_args(
+    repository: &Repository,
+    parsed_args: &ParsedGitInvocation,
+) -> Result<Vec<String>, GitAiError> {
+    let args = &parsed_args.command_args;
+    let fetch_all = args.iter().any(|a| a == "--all");
+    let fetch_multiple = args.iter().any(|a| a == "--multiple");
+    if !fetch_all && !fetch_multiple {
+        return fetch_remote_from_args(repository, parsed_args).map(|remote| vec![remote]);
+    }
+
+    let all_remotes = repository.list_remotes()?;
+    let mut remotes: Vec<String> = if fetch_all {
+        all_remotes.clone()
+    } else {
+        args.iter()
+            .filter(|a| all_remotes.iter().any(|r| r == *a))
+            .cloned()
+            .collect()
+    };
+    if remotes.is_empty() {
+        remotes = all_remotes;
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    remotes.retain(|remote| seen.insert(remote.clone()));
+
+    if remotes.is_empty() {
+        return Err(GitAiError::Generic(
+            "No remotes are configured for fetch --all/--multiple.".to_string(),
+        ));
+    }
+    Ok(remotes)
+}
+
 // for use with post-fetch and post-pull and post-clone hooks
 // Returns Ok(NotesExistence::Found) if notes were found and fetched,
 // Ok(NotesExistence::NotFound) if confirmed no notes exist on remote,
 // Err(...) for actual errors (network, permissions, etc.)
+// Transient remote errors (see `is_transient_fetch_error`) are retried a couple of times with
+// backoff before giving up.
 pub fn fetch_authorship_notes(
     repository: &Repository,
     remote_name: &str,
 ) -> Result<NotesExistence, GitAiError> {
+    fetch_authorship_notes_with(repository, remote_name, NOTES_FETCH_RETRY_DELAY, |args| {
+        repository.exec_git(args)
+    })
+}
+
+fn fetch_authorship_notes_with<F>(
+    repository: &Repository,
+    remote_name: &str,
+    retry_delay: Duration,
+    mut run_fetch: F,
+) -> Result<NotesExistence, GitAiError>
+where
+    F: FnMut(&[String]) -> Result<Output, GitAiError>,
+{
     // Generate tracking ref for this remote
     let tracking_ref = tracking_ref_for_remote(remote_name);
 
-    debug_log(&format!(
+    info_log(&format!(
         "fetching authorship notes for remote '{}' to tracking ref '{}'",
         remote_name, tracking_ref
     ));
 
     // Fetch notes to tracking ref with explicit refspec.
-    // If the remote does not have refs/notes/ai yet, treat that as NotFound.
-    let fetch_refspec = format!("+refs/notes/ai:{}", tracking_ref);
+    // If the remote does not have the notes ref yet, treat that as NotFound.
+    let local_notes_ref = repository.notes_ref();
+    let fetch_refspec = format!("+{}:{}", local_notes_ref, tracking_ref);
 
     // Build the internal authorship fetch with explicit flags and disabled hooks.
     // IMPORTANT: use repository.global_args_for_exec() to ensure -C flag is present for bare repos.
@@ -97,52 +160,64 @@ pub fn fetch_authorship_notes(
 
     debug_log(&format!("fetch command: {:?}", fetch_authorship));
 
-    match exec_git(&fetch_authorship) {
-        Ok(output) => {
-            debug_log(&format!(
-                "fetch stdout: '{}'",
-                String::from_utf8_lossy(&output.stdout)
-            ));
-            debug_log(&format!(
-                "fetch stderr: '{}'",
-                String::from_utf8_lossy(&output.stderr)
-            ));
-        }
-        Err(e) => {
-            if is_missing_remote_notes_ref_error(&e) {
+    let mut attempt = 1;
+    loop {
+        match run_fetch(&fetch_authorship) {
+            Ok(output) => {
+                debug_log(&format!(
+                    "fetch stdout: '{}'",
+                    String::from_utf8_lossy(&output.stdout)
+                ));
                 debug_log(&format!(
-                    "no authorship notes found on remote '{}', nothing to sync",
-                    remote_name
+                    "fetch stderr: '{}'",
+                    String::from_utf8_lossy(&output.stderr)
                 ));
-                return Ok(NotesExistence::NotFound);
+                break;
+            }
+            Err(e) => {
+                if is_missing_remote_notes_ref_error(&e, &local_notes_ref) {
+                    info_log(&format!(
+                        "no authorship notes found on remote '{}', nothing to sync",
+                        remote_name
+                    ));
+                    return Ok(NotesExistence::NotFound);
+                }
+                if attempt < NOTES_FETCH_ATTEMPTS && is_transient_fetch_error(&e) {
+                    let delay = retry_delay * 2u32.pow(attempt - 1);
+                    debug_log(&format!(
+                        "authorship fetch attempt {} failed, retrying in {:?}: {}",
+                        attempt, delay, e
+                    ));
+                    std::thread::sleep(delay);
+                    attempt += 1;
+                    continue;
+                }
+                info_log(&format!("authorship fetch failed: {}", e));
+                return Err(e);
             }
-            debug_log(&format!("authorship fetch failed: {}", e));
-            return Err(e);
         }
     }
 
-    // After successful fetch, merge the tracking ref into refs/notes/ai
-    let local_notes_ref = "refs/notes/ai";
-
+    // After successful fetch, merge the tracking ref into the local notes ref
     if crate::git::refs::ref_exists(repository, &tracking_ref) {
-        if crate::git::refs::ref_exists(repository, local_notes_ref) {
+        if crate::git::refs::ref_exists(repository, &local_notes_ref) {
             // Both exist - merge them
-            debug_log(&format!(
+            info_log(&format!(
                 "merging authorship notes from {} into {}",
                 tracking_ref, local_notes_ref
             ));
             if let Err(e) = merge_notes_from_ref(repository, &tracking_ref) {
-                debug_log(&format!("notes merge failed: {}", e));
+                info_log(&format!("notes merge failed: {}", e));
                 // Don't fail on merge errors, just log and continue
             }
         } else {
             // Only tracking ref exists - copy it to local
-            debug_log(&format!(
+            info_log(&format!(
                 "initializing {} from tracking ref {}",
                 local_notes_ref, tracking_ref
             ));
-            if let Err(e) = copy_ref(repository, &tracking_ref, local_notes_ref) {
-                debug_log(&format!("notes copy failed: {}", e));
+            if let Err(e) = copy_ref(repository, &tracking_ref, &local_notes_ref) {
+                info_log(&format!("notes copy failed: {}", e));
                 // Don't fail on copy errors, just log and continue
             }
         }
@@ -156,24 +231,53 @@ pub fn fetch_authorship_notes(
     Ok(NotesExistence::Found)
 }
 
-fn is_missing_remote_notes_ref_error(error: &GitAiError) -> bool {
+fn is_missing_remote_notes_ref_error(error: &GitAiError, notes_ref: &str) -> bool {
     let GitAiError::GitCliError { stderr, .. } = error else {
         return false;
     };
 
     let stderr_lower = stderr.to_ascii_lowercase();
-    stderr_lower.contains("refs/notes/ai")
+    stderr_lower.contains(&notes_ref.to_ascii_lowercase())
         && (stderr_lower.contains("couldn't find remote ref")
             || stderr_lower.contains("could not find remote ref")
             || stderr_lower.contains("remote ref does not exist")
             || stderr_lower.contains("not our ref"))
 }
+
+/// Whether a failed fetch looks like a network hiccup worth retrying, as opposed to a problem
+/// that will fail the same way again (bad remote, authentication, permissions)
+fn is_transient_fetch_error(error: &GitAiError) -> bool {
+    let GitAiError::GitCliError { stderr, .. } = error else {
+        return false;
+    };
+
+    let stderr_lower = stderr.to_ascii_lowercase();
+    [
+        "could not resolve host",
+        "temporary failure in name resolution",
+        "connection timed out",
+        "operation timed out",
+        "connection reset",
+        "connection refused",
+        "failed to connect",
+        "the remote end hung up unexpectedly",
+        "early eof",
+        "rpc failed",
+        "http 502",
+        "http 503",
+        "http 504",
+    ]
+    .iter()
+    .any(|marker| stderr_lower.contains(marker))
+}
+
 // for use with post-push hook
 pub fn push_authorship_notes(repository: &Repository, remote_name: &str) -> Result<(), GitAiError> {
     // STEP 1: Fetch remote notes into tracking ref and merge before pushing
     // This ensures we don't lose notes from other branches/clones
     let tracking_ref = tracking_ref_for_remote(remote_name);
-    let fetch_refspec = format!("+refs/notes/ai:{}", tracking_ref);
+    let local_notes_ref = repository.notes_ref();
+    let fetch_refspec = format!("+{}:{}", local_notes_ref, tracking_ref);
 
     let fetch_before_push = build_authorship_fetch_args(
         repository.global_args_for_exec(),
@@ -187,44 +291,45 @@ pub fn push_authorship_notes(repository: &Repository, remote_name: &str) -> Resu
     ));
 
     // Fetch is best-effort; if it fails (e.g., no remote notes yet), continue
-    if exec_git(&fetch_before_push).is_ok() {
-        // Merge fetched notes into local refs/notes/ai
-        let local_notes_ref = "refs/notes/ai";
-
+    if repository.exec_git(&fetch_before_push).is_ok() {
+        // Merge fetched notes into the local notes ref
         if ref_exists(repository, &tracking_ref) {
-            if ref_exists(repository, local_notes_ref) {
+            if ref_exists(repository, &local_notes_ref) {
                 // Both exist - merge them
-                debug_log(&format!(
+                info_log(&format!(
                     "pre-push: merging {} into {}",
                     tracking_ref, local_notes_ref
                 ));
                 if let Err(e) = merge_notes_from_ref(repository, &tracking_ref) {
-                    debug_log(&format!("pre-push notes merge failed: {}", e));
+                    info_log(&format!("pre-push notes merge failed: {}", e));
                 }
             } else {
                 // Only tracking ref exists - copy it to local
-                debug_log(&format!(
+                info_log(&format!(
                     "pre-push: initializing {} from {}",
                     local_notes_ref, tracking_ref
                 ));
-                if let Err(e) = copy_ref(repository, &tracking_ref, local_notes_ref) {
-                    debug_log(&format!("pre-push notes copy failed: {}", e));
+                if let Err(e) = copy_ref(repository, &tracking_ref, &local_notes_ref) {
+                    info_log(&format!("pre-push notes copy failed: {}", e));
                 }
             }
         }
     }
 
     // STEP 2: Push notes without force (requires fast-forward)
-    let push_authorship =
-        build_authorship_push_args(repository.global_args_for_exec(), remote_name);
+    let push_authorship = build_authorship_push_args(
+        repository.global_args_for_exec(),
+        remote_name,
+        &local_notes_ref,
+    );
 
-    debug_log(&format!(
+    info_log(&format!(
         "pushing authorship refs (no force): {:?}",
         &push_authorship
     ));
-    if let Err(e) = exec_git(&push_authorship) {
+    if let Err(e) = repository.exec_git(&push_authorship) {
         // Best-effort; don't fail user operation due to authorship sync issues
-        debug_log(&format!("authorship push skipped due to error: {}", e));
+        info_log(&format!("authorship push skipped due to error: {}", e));
         return Err(e);
     }
 
@@ -305,7 +410,11 @@ fn build_authorship_fetch_args(
     args
 }
 
-fn build_authorship_push_args(global_args: Vec<String>, remote_name: &str) -> Vec<String> {
+fn build_authorship_push_args(
+    global_args: Vec<String>,
+    remote_name: &str,
+    notes_ref: &str,
+) -> Vec<String> {
     let mut args = with_disabled_hooks(global_args);
     args.push("push".to_string());
     args.push("--quiet".to_string());
@@ -313,13 +422,369 @@ fn build_authorship_push_args(global_args: Vec<String>, remote_name: &str) -> Ve
     args.push("--no-verify".to_string());
     args.push("--no-signed".to_string());
     args.push(remote_name.to_string());
-    args.push(AI_AUTHORSHIP_PUSH_REFSPEC.to_string());
+    args.push(format!("{}:{}", notes_ref, notes_ref));
     args
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::git::test_utils::TmpRepo;
+
+    fn fetch_invocation(args: &[&str]) -> ParsedGitInvocation {
+        ParsedGitInvocation {
+            global_args: Vec::new(),
+            command: Some("fetch".to_string()),
+            command_args: args.iter().map(|s| s.to_string()).collect(),
+            saw_end_of_opts: false,
+            is_help: false,
+        }
+    }
+
+    #[test]
+    fn fetch_remotes_from_args_expands_all_and_multiple() {
+        let repo = TmpRepo::new().unwrap();
+        for (name, url) in [
+            ("origin", "https://example.com/a.git"),
+            ("upstream", "https://example.com/b.git"),
+            ("fork", "https://example.com/c.git"),
+        ] {
+            repo.git_command(&["remote", "add", name, url]).unwrap();
+        }
+        let gitai_repo = repo.gitai_repo();
+
+        assert_eq!(
+            fetch_remotes_from_args(gitai_repo, &fetch_invocation(&["--all"])).unwrap(),
+            vec!["origin", "upstream", "fork"]
+        );
+        assert_eq!(
+            fetch_remotes_from_args(
+                gitai_repo,
+                &fetch_invocation(&["--multiple", "fork", "upstream", "fork"])
+            )
+            .unwrap(),
+            vec!["fork", "upstream"]
+        );
+        assert_eq!(
+            fetch_remotes_from_args(gitai_repo, &fetch_invocation(&["upstream"])).unwrap(),
+            vec!["upstream"]
+        );
+    }
+
+    #[test]
+    fn fetch_remote_from_args_returns_url_remotes_verbatim() {
+        let repo = TmpRepo::new().unwrap();
+        repo.git_command(&["remote", "add", "origin", "https://example.com/a.git"])
+            .unwrap();
+        let gitai_repo = repo.gitai_repo();
+
+        for url in [
+            "https://host.example/team/repo.git",
+            "ssh://git@host.example/team/repo",
+            "git@host.example:team/repo.git",
+        ] {
+            assert_eq!(
+                fetch_remote_from_args(gitai_repo, &fetch_invocation(&["--prune", url, "main"]))
+                    .unwrap(),
+                url
+            );
+        }
+    }
+
+    #[test]
+    fn fetch_authorship_notes_goes_through_the_repository_executor() {
+        use crate::git::git_executor::MockGitExecutor;
+        use crate::git::repository::from_bare_repository;
+        use std::sync::Arc;
+
+        let temp = tempfile::tempdir().unwrap();
+        let executor = Arc::new(MockGitExecutor::new().on_fail(
+            &["fetch"],
+            128,
+            "fatal: couldn't find remote ref refs/notes/ai",
+        ));
+        let repo = from_bare_repository(temp.path())
+            .unwrap()
+            .with_executor(executor.clone());
+
+        let result = fetch_authorship_notes(&repo, "origin").unwrap();
+
+        assert!(matches!(result, NotesExistence::NotFound));
+        assert!(
+            executor
+                .calls()
+                .iter()
+                .any(|call| call.contains(&"fetch".to_string()))
+        );
+    }
+
+    #[test]
+    fn fetch_authorship_notes_from_url_fetches_notes_ref_from_that_url() {
+        use crate::git::refs::{notes_add, show_authorship_note};
+
+        let url = "https://host.example/team/repo.git";
+        let repo = TmpRepo::new().unwrap();
+        let mut fetch_args = Vec::new();
+        fetch_authorship_notes_with(repo.gitai_repo(), url, Duration::ZERO, |args| {
+            fetch_args = args.to_vec();
+            Err(git_cli_error(
+                "fatal: couldn't find remote ref refs/notes/ai",
+            ))
+        })
+        .unwrap();
+        assert_eq!(
+            &fetch_args[fetch_args.len() - 2..],
+            [
+                url.to_string(),
+                format!("+refs/notes/ai:{}", tracking_ref_for_remote(url))
+            ]
+        );
+        assert!(fetch_args.contains(&"fetch".to_string()));
+
+        // An anonymous file:// URL works end to end without a configured remote
+        repo.write_file("a.txt", "a\n", true).unwrap();
+        repo.commit_with_message("Commit A").unwrap();
+        let commit_a = repo.get_head_commit_sha().unwrap();
+        let source_dir = tempfile::tempdir().unwrap();
+        let output = std::process::Command::new(crate::config::Config::get().git_cmd())
+            .args(["clone", "-q"])
+            .arg(repo.path())
+            .arg(source_dir.path())
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{:?}", output);
+        let source =
+            crate::git::find_repository_in_path(source_dir.path().to_str().unwrap()).unwrap();
+        notes_add(&source, &commit_a, "note from url").unwrap();
+        repo.git_command(&["notes", "--ref=ai", "remove", &commit_a])
+            .unwrap();
+
+        let source_path = source_dir.path().to_str().unwrap().replace('\\', "/");
+        let source_url = format!("file:///{}", source_path.trim_start_matches('/'));
+        assert_eq!(
+            fetch_authorship_notes(repo.gitai_repo(), &source_url).unwrap(),
+            NotesExistence::Found
+        );
+        assert_eq!(
+            show_authorship_note(repo.gitai_repo(), &commit_a).as_deref(),
+            Some("note from url")
+        );
+    }
+
+    fn git_cli_error(stderr: &str) -> GitAiError {
+        GitAiError::GitCliError {
+            code: Some(128),
+            stderr: stderr.to_string(),
+            args: vec![],
+        }
+    }
+
+    /// A clone of `repo` registered as its `origin`, plus a handle to the clone
+    fn clone_as_origin(repo: &TmpRepo) -> (tempfile::TempDir, Repository) {
+        let remote_dir = tempfile::tempdir().unwrap();
+        let output = std::process::Command::new(crate::config::Config::get().git_cmd())
+            .args(["clone", "-q"])
+            .arg(repo.path())
+            .arg(remote_dir.path())
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{:?}", output);
+        repo.git_command(&[
+            "remote",
+            "add",
+            "origin",
+            remote_dir.path().to_str().unwrap(),
+        ])
+        .unwrap();
+        let remote =
+            crate::git::find_repository_in_path(remote_dir.path().to_str().unwrap()).unwrap();
+        (remote_dir, remote)
+    }
+
+    #[test]
+    fn fetch_authorship_notes_union_merges_divergent_notes() {
+        use crate::git::refs::{notes_add, show_authorship_note};
+
+        let repo = TmpRepo::new().unwrap();
+        repo.write_file("a.txt", "a\n", true).unwrap();
+        repo.commit_with_message("Commit A").unwrap();
+        let commit_a = repo.get_head_commit_sha().unwrap();
+        repo.write_file("b.txt", "b\n", true).unwrap();
+        repo.commit_with_message("Commit B").unwrap();
+        let commit_b = repo.get_head_commit_sha().unwrap();
+        let (_remote_dir, remote) = clone_as_origin(&repo);
+
+        // Each side annotates A differently, and only the remote re-annotates B
+        notes_add(repo.gitai_repo(), &commit_a, "local note for A").unwrap();
+        notes_add(&remote, &commit_a, "remote note for A").unwrap();
+        notes_add(&remote, &commit_b, "remote note for B").unwrap();
+
+        let result = fetch_authorship_notes(repo.gitai_repo(), "origin").unwrap();
+
+        assert_eq!(result, NotesExistence::Found);
+        let note_a = show_authorship_note(repo.gitai_repo(), &commit_a).unwrap();
+        assert!(note_a.contains("local note for A"), "{}", note_a);
+        assert!(note_a.contains("remote note for A"), "{}", note_a);
+        let note_b = show_authorship_note(repo.gitai_repo(), &commit_b).unwrap();
+        assert!(note_b.contains("remote note for B"), "{}", note_b);
+    }
+
+    #[test]
+    fn fetch_authorship_notes_union_combines_divergent_authorship_logs() {
+        use crate::authorship::authorship_log_serialization::AuthorshipLog;
+        use crate::git::refs::{notes_add, show_authorship_note};
+        use crate::git::test_utils::note_with_attestations;
+
+        let repo = TmpRepo::new().unwrap();
+        repo.write_file("a.txt", "a\n", true).unwrap();
+        repo.commit_with_message("Commit A").unwrap();
+        let commit_a = repo.get_head_commit_sha().unwrap();
+        let (_remote_dir, remote) = clone_as_origin(&repo);
+
+        notes_add(
+            repo.gitai_repo(),
+            &commit_a,
+            &note_with_attestations(&[("src/a.rs", "aaaaaaa", "Alice <alice@example.com>")]),
+        )
+        .unwrap();
+        notes_add(
+            &remote,
+            &commit_a,
+            &note_with_attestations(&[
+                ("src/a.rs", "bbbbbbb", "Bob <bob@example.com>"),
+                ("src/b.rs", "bbbbbbb", "Bob <bob@example.com>"),
+            ]),
+        )
+        .unwrap();
+
+        fetch_authorship_notes(repo.gitai_repo(), "origin").unwrap();
+
+        let note = show_authorship_note(repo.gitai_repo(), &commit_a).unwrap();
+        let log = AuthorshipLog::deserialize_from_string(&note)
+            .unwrap_or_else(|e| panic!("merged note doesn't parse ({}): {}", e, note));
+        let mut entries: Vec<(String, String)> = log
+            .attestations
+            .iter()
+            .flat_map(|file| {
+                file.entries
+                    .iter()
+                    .map(|entry| (file.file_path.clone(), entry.hash.clone()))
+            })
+            .collect();
+        entries.sort();
+        assert_eq!(
+            entries,
+            vec![
+                ("src/a.rs".to_string(), "aaaaaaa".to_string()),
+                ("src/a.rs".to_string(), "bbbbbbb".to_string()),
+                ("src/b.rs".to_string(), "bbbbbbb".to_string()),
+            ]
+        );
+        assert_eq!(
+            log.metadata.prompts.keys().collect::<Vec<_>>(),
+            vec!["aaaaaaa", "bbbbbbb"]
+        );
+    }
+
+    #[test]
+    fn fetch_authorship_notes_ours_keeps_local_note_on_conflict() {
+        use crate::git::refs::{notes_add, show_authorship_note};
+
+        let repo = TmpRepo::new().unwrap();
+        repo.write_file("a.txt", "a\n", true).unwrap();
+        repo.commit_with_message("Commit A").unwrap();
+        let commit_a = repo.get_head_commit_sha().unwrap();
+        let (_remote_dir, remote) = clone_as_origin(&repo);
+        repo.git_command(&["config", "git-ai.notesMergeStrategy", "ours"])
+            .unwrap();
+
+        notes_add(repo.gitai_repo(), &commit_a, "local note for A").unwrap();
+        notes_add(&remote, &commit_a, "remote note for A").unwrap();
+
+        fetch_authorship_notes(repo.gitai_repo(), "origin").unwrap();
+
+        assert_eq!(
+            show_authorship_note(repo.gitai_repo(), &commit_a).as_deref(),
+            Some("local note for A")
+        );
+    }
+
+    #[test]
+    fn fetch_authorship_notes_retries_transient_failure() {
+        let repo = TmpRepo::new().unwrap();
+        let mut attempts = 0;
+
+        let result = fetch_authorship_notes_with(
+            repo.gitai_repo(),
+            "origin",
+            Duration::ZERO,
+            |_args| {
+                attempts += 1;
+                if attempts == 1 {
+                    Err(git_cli_error(
+                        "fatal: unable to access 'https://example.com/repo.git/': Could not resolve host: example.com",
+                    ))
+                } else {
+                    Ok(Output {
+                        status: std::process::ExitStatus::default(),
+                        stdout: Vec::new(),
+                        stderr: Vec::new(),
+                    })
+                }
+            },
+        );
+
+        assert_eq!(result.unwrap(), NotesExistence::Found);
+        assert_eq!(attempts, 2);
+    }
+
+    #[test]
+    fn fetch_authorship_notes_does_not_retry_permanent_failures() {
+        let repo = TmpRepo::new().unwrap();
+
+        let mut attempts = 0;
+        let result =
+            fetch_authorship_notes_with(repo.gitai_repo(), "origin", Duration::ZERO, |_args| {
+                attempts += 1;
+                Err(git_cli_error(
+                    "fatal: couldn't find remote ref refs/notes/ai",
+                ))
+            });
+        assert_eq!(result.unwrap(), NotesExistence::NotFound);
+        assert_eq!(attempts, 1);
+
+        let mut attempts = 0;
+        let result =
+            fetch_authorship_notes_with(repo.gitai_repo(), "origin", Duration::ZERO, |_args| {
+                attempts += 1;
+                Err(git_cli_error(
+                    "fatal: Authentication failed for 'https://example.com/repo.git/'",
+                ))
+            });
+        assert!(result.is_err());
+        assert_eq!(attempts, 1);
+    }
+
+    #[test]
+    fn fetch_authorship_notes_gives_up_after_bounded_retries() {
+        let repo = TmpRepo::new().unwrap();
+        let mut attempts = 0;
+
+        let result =
+            fetch_authorship_notes_with(repo.gitai_repo(), "origin", Duration::ZERO, |_args| {
+                attempts += 1;
+                Err(git_cli_error("fatal: the remote end hung up unexpectedly"))
+            });
+
+        assert!(result.is_err());
+        assert_eq!(attempts, NOTES_FETCH_ATTEMPTS);
+    }
+
+    #[test]
+    fn fetch_remotes_from_args_all_without_remotes_errors() {
+        let repo = TmpRepo::new().unwrap();
+        assert!(fetch_remotes_from_args(repo.gitai_repo(), &fetch_invocation(&["--all"])).is_err());
+    }
 
     #[test]
     fn authorship_fetch_args_always_disable_hooks() {
@@ -340,8 +805,11 @@ mod tests {
     #[test]
     fn authorship_push_args_always_disable_hooks() {
         let disabled_hooks = disabled_hooks_config();
-        let args =
-            build_authorship_push_args(vec!["-C".to_string(), "/tmp/repo".to_string()], "origin");
+        let args = build_authorship_push_args(
+            vec!["-C".to_string(), "/tmp/repo".to_string()],
+            "origin",
+            "refs/notes/ai",
+        );
 
         assert!(
             args.windows(2)
@@ -357,7 +825,7 @@ mod tests {
             stderr: "fatal: couldn't find remote ref refs/notes/ai".to_string(),
             args: vec!["fetch".to_string(), "origin".to_string()],
         };
-        assert!(is_missing_remote_notes_ref_error(&err));
+        assert!(is_missing_remote_notes_ref_error(&err, "refs/notes/ai"));
     }
 
     #[test]
@@ -368,6 +836,6 @@ mod tests {
                 .to_string(),
             args: vec!["fetch".to_string(), "origin".to_string()],
         };
-        assert!(!is_missing_remote_notes_ref_error(&err));
+        assert!(!is_missing_remote_notes_ref_error(&err, "refs/notes/ai"));
     }
 }
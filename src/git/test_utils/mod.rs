@@ -1,13 +1,16 @@
-use crate::authorship::attribution_tracker::Attribution;
-use crate::authorship::authorship_log_serialization::AuthorshipLog;
+use crate::authorship::attribution_tracker::{Attribution, LineAttribution};
+use crate::authorship::authorship_log::{LineRange, PromptRecord};
+use crate::authorship::authorship_log_serialization::{
+    AttestationEntry, AuthorshipLog, FileAttestation,
+};
 use crate::authorship::post_commit::post_commit;
-use crate::authorship::working_log::{Checkpoint, CheckpointKind};
+use crate::authorship::working_log::{AgentId, Checkpoint, CheckpointKind};
 use crate::commands::checkpoint_agent::agent_presets::AgentRunResult;
 use crate::commands::{blame, checkpoint::run as checkpoint};
 use crate::error::GitAiError;
 use crate::git::repository::Repository as GitAiRepository;
 use git2::{Repository, Signature};
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
 use std::path::PathBuf;
 use std::process::Command;
@@ -1427,6 +1430,75 @@ pub fn snapshot_checkpoints(checkpoints: &[Checkpoint]) -> Vec<SnapshotCheckpoin
     snapshots
 }
 
+/// A successful exit status to hand to post-command hooks without running a command
+#[cfg(unix)]
+pub fn success_exit_status() -> std::process::ExitStatus {
+    use std::os::unix::process::ExitStatusExt;
+    std::process::ExitStatus::from_raw(0)
+}
+
+/// A successful exit status to hand to post-command hooks without running a command
+#[cfg(windows)]
+pub fn success_exit_status() -> std::process::ExitStatus {
+    use std::os::windows::process::ExitStatusExt;
+    std::process::ExitStatus::from_raw(0)
+}
+
+/// Prompt record for `prompt_hash` from a Cursor session, with three accepted lines
+#[allow(dead_code)]
+pub fn test_prompt_record(prompt_hash: &str, human_author: &str) -> PromptRecord {
+    PromptRecord {
+        agent_id: AgentId {
+            tool: "cursor".to_string(),
+            id: format!("session-{}", prompt_hash),
+            model: "test-model".to_string(),
+        },
+        human_author: Some(human_author.to_string()),
+        messages: vec![],
+        total_additions: 3,
+        total_deletions: 0,
+        accepted_lines: 3,
+        overriden_lines: 0,
+        messages_url: None,
+        custom_attributes: None,
+    }
+}
+
+/// Serialized authorship note attributing lines 1-3 of each `(file, prompt hash, human author)`
+/// to that prompt, with a [`test_prompt_record`] per prompt
+#[allow(dead_code)]
+pub fn note_with_attestations(files_and_authors: &[(&str, &str, &str)]) -> String {
+    let mut log = AuthorshipLog::new();
+    for (file_path, prompt_hash, human_author) in files_and_authors {
+        let mut attestation = FileAttestation::new(file_path.to_string());
+        attestation.add_entry(AttestationEntry::new(
+            prompt_hash.to_string(),
+            vec![LineRange::Range(1, 3)],
+        ));
+        log.attestations.push(attestation);
+        log.metadata.prompts.insert(
+            prompt_hash.to_string(),
+            test_prompt_record(prompt_hash, human_author),
+        );
+    }
+    log.serialize_to_string().unwrap()
+}
+
+/// Seed `repo`'s working log for `base_commit` with one AI-attributed line in `file.txt`
+#[allow(dead_code)]
+pub fn seed_working_log(repo: &GitAiRepository, base_commit: &str) {
+    repo.storage
+        .working_log_for_base_commit(base_commit)
+        .write_initial_attributions(
+            HashMap::from([(
+                "file.txt".to_string(),
+                vec![LineAttribution::new(1, 1, "ai-author".to_string(), None)],
+            )]),
+            HashMap::new(),
+        )
+        .unwrap();
+}
+
 /// Reset mode for git reset command
 #[allow(dead_code)]
 #[derive(Debug, Clone, Copy)]
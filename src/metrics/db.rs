@@ -4,6 +4,7 @@
 //! Server handles idempotency - no retry/queue logic needed.
 
 use crate::error::GitAiError;
+use crate::utils::error_log;
 use rusqlite::{Connection, OptionalExtension, params};
 use std::path::PathBuf;
 use std::sync::{Mutex, OnceLock};
@@ -51,7 +52,7 @@ impl MetricsDatabase {
             match Self::new() {
                 Ok(db) => Mutex::new(db),
                 Err(e) => {
-                    eprintln!("[Error] Failed to initialize metrics database: {}", e);
+                    error_log(&format!("Failed to initialize metrics database: {}", e));
                     // Create a dummy connection that will fail on any operation
                     let temp_path = std::env::temp_dir().join("git-ai-metrics-db-failed");
                     let conn = Connection::open(&temp_path).expect("Failed to create temp DB");
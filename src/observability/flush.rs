@@ -88,6 +88,13 @@ pub fn handle_flush_logs(args: &[String]) {
     // Initialize metrics uploader (metrics can always be stored in local DB even if upload isn't possible)
     let metrics_uploader = MetricsUploader::new();
 
+    // Keep the remote feature flags cache warm so regular commands never wait on the API
+    if metrics_uploader.should_upload
+        && let Some(client) = &metrics_uploader.client
+    {
+        crate::feature_flags::refresh_remote_flags(client.context());
+    }
+
     // Get current PID to exclude our own log file
     let current_pid = std::process::id();
     let current_log_file = format!("{}.log", current_pid);
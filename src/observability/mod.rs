@@ -11,6 +11,7 @@ use crate::metrics::METRICS_API_VERSION;
 use crate::metrics::MetricEvent;
 
 pub mod flush;
+pub mod sync_events;
 pub mod wrapper_performance_targets;
 
 /// Maximum events per metrics envelope
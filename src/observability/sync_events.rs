@@ -0,0 +1,140 @@
+use std::sync::Arc;
+
+use serde_json::json;
+
+use crate::error::GitAiError;
+use crate::git::repository::Repository;
+use crate::observability::log_message;
+
+/// Structured event describing one step of authorship sync
+#[derive(Debug, Clone, PartialEq)]
+pub enum SyncEvent {
+    NotesFetchStarted { remote: String },
+    NotesFetchCompleted { remote: String, found: bool },
+    NotesFetchFailed { remote: String, reason: String },
+    WorkingLogRenamed { reason: String, merged: bool },
+}
+
+impl SyncEvent {
+    pub fn name(&self) -> &'static str {
+        match self {
+            SyncEvent::NotesFetchStarted { .. } => "notes_fetch_started",
+            SyncEvent::NotesFetchCompleted { .. } => "notes_fetch_completed",
+            SyncEvent::NotesFetchFailed { .. } => "notes_fetch_failed",
+            SyncEvent::WorkingLogRenamed { .. } => "working_log_renamed",
+        }
+    }
+
+    fn attributes(&self) -> serde_json::Value {
+        match self {
+            SyncEvent::NotesFetchStarted { remote } => json!({ "remote": remote }),
+            SyncEvent::NotesFetchCompleted { remote, found } => {
+                json!({ "remote": remote, "found": found })
+            }
+            SyncEvent::NotesFetchFailed { remote, reason } => {
+                json!({ "remote": remote, "reason": reason })
+            }
+            SyncEvent::WorkingLogRenamed { reason, merged } => {
+                json!({ "reason": reason, "merged": merged })
+            }
+        }
+    }
+}
+
+/// Coarse, path-free category for a failed notes fetch, suitable as a telemetry dimension
+pub fn fetch_failure_reason(error: &GitAiError) -> String {
+    match error {
+        GitAiError::GitCliError { stderr, .. } => {
+            let stderr = stderr.to_ascii_lowercase();
+            if stderr.contains("authentication") || stderr.contains("permission denied") {
+                "auth".to_string()
+            } else if stderr.contains("could not read from remote")
+                || stderr.contains("does not appear to be a git repository")
+                || stderr.contains("could not resolve host")
+            {
+                "remote_unreachable".to_string()
+            } else {
+                "git_error".to_string()
+            }
+        }
+        GitAiError::IoError(_) => "io_error".to_string(),
+        _ => "other".to_string(),
+    }
+}
+
+/// Sends [`SyncEvent`]s to the observability log, unless `git-ai.telemetry` is false
+#[derive(Clone)]
+pub struct SyncEventEmitter {
+    enabled: bool,
+    sink: Arc<dyn Fn(&SyncEvent) + Send + Sync>,
+}
+
+impl SyncEventEmitter {
+    pub fn for_repository(repository: &Repository) -> Self {
+        Self {
+            enabled: telemetry_enabled(repository),
+            sink: Arc::new(log_sync_event),
+        }
+    }
+
+    /// Emitter honouring the repository's `git-ai.telemetry`, delivering to `sink` instead of
+    /// the observability log
+    #[cfg(test)]
+    pub fn with_sink<F>(repository: &Repository, sink: F) -> Self
+    where
+        F: Fn(&SyncEvent) + Send + Sync + 'static,
+    {
+        Self {
+            enabled: telemetry_enabled(repository),
+            sink: Arc::new(sink),
+        }
+    }
+
+    pub fn emit(&self, event: SyncEvent) {
+        if self.enabled {
+            (self.sink)(&event);
+        }
+    }
+}
+
+fn log_sync_event(event: &SyncEvent) {
+    log_message(event.name(), "info", Some(event.attributes()));
+}
+
+/// Whether `git-ai.telemetry` allows sync events. Defaults to true when unset or not a valid
+/// boolean.
+fn telemetry_enabled(repository: &Repository) -> bool {
+    repository.config_get_bool_or("git-ai.telemetry", true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fetch_failure_reason_hides_remote_details() {
+        let err = GitAiError::GitCliError {
+            code: Some(128),
+            stderr: "fatal: '/secret/path' does not appear to be a git repository".to_string(),
+            args: vec![],
+        };
+        assert_eq!(fetch_failure_reason(&err), "remote_unreachable");
+        assert_eq!(
+            fetch_failure_reason(&GitAiError::Generic("boom".to_string())),
+            "other"
+        );
+    }
+
+    #[test]
+    fn test_event_names_and_attributes() {
+        let event = SyncEvent::NotesFetchFailed {
+            remote: "origin".to_string(),
+            reason: "auth".to_string(),
+        };
+        assert_eq!(event.name(), "notes_fetch_failed");
+        assert_eq!(
+            event.attributes(),
+            json!({ "remote": "origin", "reason": "auth" })
+        );
+    }
+}
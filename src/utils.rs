@@ -9,6 +9,7 @@ use std::process::{Command, Stdio};
 /// This is checked once at module initialization to avoid repeated environment variable lookups.
 static DEBUG_ENABLED: std::sync::OnceLock<bool> = std::sync::OnceLock::new();
 static DEBUG_PERFORMANCE_LEVEL: std::sync::OnceLock<u8> = std::sync::OnceLock::new();
+static LOG_LEVEL: std::sync::OnceLock<LogLevel> = std::sync::OnceLock::new();
 static IS_TERMINAL: std::sync::OnceLock<bool> = std::sync::OnceLock::new();
 static IS_IN_BACKGROUND_AGENT: std::sync::OnceLock<bool> = std::sync::OnceLock::new();
 
@@ -46,18 +47,85 @@ pub fn debug_performance_log_structured(json: serde_json::Value) {
     }
 }
 
+/// Verbosity of git-ai's own stderr logging, from least to most verbose
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LogLevel {
+    Error,
+    Warn,
+    Info,
+    Debug,
+}
+
+impl LogLevel {
+    fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "error" => Some(LogLevel::Error),
+            "warn" | "warning" => Some(LogLevel::Warn),
+            "info" => Some(LogLevel::Info),
+            "debug" => Some(LogLevel::Debug),
+            _ => None,
+        }
+    }
+}
+
+/// Most verbose level that gets printed, from `GIT_AI_LOG=error|warn|info|debug`.
+///
+/// Without a valid `GIT_AI_LOG` this is `debug` wherever debug output was already on (debug
+/// builds or `GIT_AI_DEBUG=1`, see [`debug_log`]) and `warn` otherwise.
+fn log_level() -> LogLevel {
+    *LOG_LEVEL.get_or_init(|| {
+        std::env::var("GIT_AI_LOG")
+            .ok()
+            .and_then(|value| LogLevel::parse(&value))
+            .unwrap_or(if is_debug_enabled() {
+                LogLevel::Debug
+            } else {
+                LogLevel::Warn
+            })
+    })
+}
+
+/// The stderr line for `msg` logged at `level`, or `None` when `max_level` filters it out
+fn format_log_line(level: LogLevel, max_level: LogLevel, msg: &str) -> Option<String> {
+    if level > max_level {
+        return None;
+    }
+    Some(match level {
+        LogLevel::Debug => format!("\x1b[1;33m[git-ai]\x1b[0m {}", msg),
+        LogLevel::Info => format!("\x1b[1;33m[git-ai info]\x1b[0m {}", msg),
+        LogLevel::Warn => format!("\x1b[1;33m[git-ai warn]\x1b[0m {}", msg),
+        LogLevel::Error => format!("\x1b[1;31m[git-ai error]\x1b[0m {}", msg),
+    })
+}
+
+/// Print `msg` to stderr if `level` is enabled by `GIT_AI_LOG` (see [`LogLevel`])
+pub fn log(level: LogLevel, msg: &str) {
+    if let Some(line) = format_log_line(level, log_level(), msg) {
+        eprintln!("{}", line);
+    }
+}
+
+/// Error-level logging, for failures that leave git-ai unable to do its job
+pub fn error_log(msg: &str) {
+    log(LogLevel::Error, msg);
+}
+
+/// Info-level logging, for messages worth seeing without full debug output (e.g. notes sync)
+pub fn info_log(msg: &str) {
+    log(LogLevel::Info, msg);
+}
+
 /// Debug logging utility function
 ///
 /// Prints debug messages with a colored prefix when debug assertions are enabled or when
-/// the `GIT_AI_DEBUG` environment variable is set to "1".
+/// the `GIT_AI_DEBUG` environment variable is set to "1", unless `GIT_AI_LOG` picks a
+/// less verbose level.
 ///
 /// # Arguments
 ///
 /// * `msg` - The debug message to print
 pub fn debug_log(msg: &str) {
-    if is_debug_enabled() {
-        eprintln!("\x1b[1;33m[git-ai]\x1b[0m {}", msg);
-    }
+    log(LogLevel::Debug, msg);
 }
 
 /// Print a git diff in a readable format
@@ -371,6 +439,27 @@ pub fn unescape_git_path(path: &str) -> String {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_log_level_parse() {
+        assert_eq!(LogLevel::parse("error"), Some(LogLevel::Error));
+        assert_eq!(LogLevel::parse("WARN"), Some(LogLevel::Warn));
+        assert_eq!(LogLevel::parse(" info "), Some(LogLevel::Info));
+        assert_eq!(LogLevel::parse("debug"), Some(LogLevel::Debug));
+        assert_eq!(LogLevel::parse("trace"), None);
+    }
+
+    #[test]
+    fn test_format_log_line_suppresses_levels_below_configured() {
+        assert!(format_log_line(LogLevel::Debug, LogLevel::Info, "debug").is_none());
+        assert!(format_log_line(LogLevel::Info, LogLevel::Warn, "info").is_none());
+        assert!(format_log_line(LogLevel::Warn, LogLevel::Error, "warn").is_none());
+
+        let line = format_log_line(LogLevel::Info, LogLevel::Info, "synced notes").unwrap();
+        assert!(line.ends_with("[git-ai info]\x1b[0m synced notes"));
+        assert!(format_log_line(LogLevel::Error, LogLevel::Error, "error").is_some());
+        assert!(format_log_line(LogLevel::Info, LogLevel::Debug, "info").is_some());
+    }
+
     // =========================================================================
     // LockFile Tests
     // =========================================================================
@@ -1,7 +1,7 @@
 use git_ai::git::repository;
 
 use crate::repos::test_repo::TestRepo;
-use git_ai::commands::git_handlers::CommandHooksContext;
+use crate::test_utils::hooks_context;
 use git_ai::commands::hooks::checkout_hooks::{post_checkout_hook, pre_checkout_hook};
 use git_ai::git::cli_parser::ParsedGitInvocation;
 
@@ -36,15 +36,7 @@ fn test_pre_checkout_hook_normal() {
 
     let mut repository =
         repository::find_repository_in_path(repo.path().to_str().unwrap()).unwrap();
-    let mut context = CommandHooksContext {
-        pre_commit_hook_result: None,
-        rebase_original_head: None,
-        rebase_onto: None,
-        fetch_authorship_handle: None,
-        stash_sha: None,
-        push_authorship_handle: None,
-        stashed_va: None,
-    };
+    let mut context = hooks_context();
     let parsed_args = make_checkout_invocation(&["main"]);
 
     pre_checkout_hook(&parsed_args, &mut repository, &mut context);
@@ -73,15 +65,7 @@ fn test_pre_checkout_hook_with_merge_flag() {
 
     let mut repository =
         repository::find_repository_in_path(repo.path().to_str().unwrap()).unwrap();
-    let mut context = CommandHooksContext {
-        pre_commit_hook_result: None,
-        rebase_original_head: None,
-        rebase_onto: None,
-        fetch_authorship_handle: None,
-        stash_sha: None,
-        push_authorship_handle: None,
-        stashed_va: None,
-    };
+    let mut context = hooks_context();
     let parsed_args = make_checkout_invocation(&["--merge", "main"]);
 
     pre_checkout_hook(&parsed_args, &mut repository, &mut context);
@@ -103,15 +87,7 @@ fn test_pre_checkout_hook_merge_without_changes() {
 
     let mut repository =
         repository::find_repository_in_path(repo.path().to_str().unwrap()).unwrap();
-    let mut context = CommandHooksContext {
-        pre_commit_hook_result: None,
-        rebase_original_head: None,
-        rebase_onto: None,
-        fetch_authorship_handle: None,
-        stash_sha: None,
-        push_authorship_handle: None,
-        stashed_va: None,
-    };
+    let mut context = hooks_context();
     let parsed_args = make_checkout_invocation(&["--merge", "main"]);
 
     pre_checkout_hook(&parsed_args, &mut repository, &mut context);
@@ -131,15 +107,7 @@ fn test_pre_checkout_hook_merge_short_flag() {
 
     let mut repository =
         repository::find_repository_in_path(repo.path().to_str().unwrap()).unwrap();
-    let mut context = CommandHooksContext {
-        pre_commit_hook_result: None,
-        rebase_original_head: None,
-        rebase_onto: None,
-        fetch_authorship_handle: None,
-        stash_sha: None,
-        push_authorship_handle: None,
-        stashed_va: None,
-    };
+    let mut context = hooks_context();
     let parsed_args = make_checkout_invocation(&["-m", "main"]);
 
     pre_checkout_hook(&parsed_args, &mut repository, &mut context);
@@ -175,15 +143,7 @@ fn test_post_checkout_hook_success() {
     repo.git(&["checkout", &original_branch]).unwrap();
 
     let parsed_args = make_checkout_invocation(&[&original_branch]);
-    let mut context = CommandHooksContext {
-        pre_commit_hook_result: None,
-        rebase_original_head: None,
-        rebase_onto: None,
-        fetch_authorship_handle: None,
-        stash_sha: None,
-        push_authorship_handle: None,
-        stashed_va: None,
-    };
+    let mut context = hooks_context();
     let exit_status = std::process::Command::new("true").status().unwrap();
 
     post_checkout_hook(&parsed_args, &mut repository, exit_status, &mut context);
@@ -203,15 +163,7 @@ fn test_post_checkout_hook_failed() {
     let mut repository =
         repository::find_repository_in_path(repo.path().to_str().unwrap()).unwrap();
     let parsed_args = make_checkout_invocation(&["nonexistent"]);
-    let mut context = CommandHooksContext {
-        pre_commit_hook_result: None,
-        rebase_original_head: None,
-        rebase_onto: None,
-        fetch_authorship_handle: None,
-        stash_sha: None,
-        push_authorship_handle: None,
-        stashed_va: None,
-    };
+    let mut context = hooks_context();
     let exit_status = std::process::Command::new("false")
         .status()
         .unwrap_or_else(|_| {
@@ -241,15 +193,7 @@ fn test_post_checkout_hook_head_unchanged() {
     repository.pre_command_base_commit = Some(commit.commit_sha.clone());
 
     let parsed_args = make_checkout_invocation(&["main"]);
-    let mut context = CommandHooksContext {
-        pre_commit_hook_result: None,
-        rebase_original_head: None,
-        rebase_onto: None,
-        fetch_authorship_handle: None,
-        stash_sha: None,
-        push_authorship_handle: None,
-        stashed_va: None,
-    };
+    let mut context = hooks_context();
     let exit_status = std::process::Command::new("true").status().unwrap();
 
     post_checkout_hook(&parsed_args, &mut repository, exit_status, &mut context);
@@ -283,15 +227,7 @@ fn test_post_checkout_hook_pathspec() {
 
     // Checkout specific file (pathspec checkout)
     let parsed_args = make_checkout_invocation(&["HEAD", "--", "file1.txt"]);
-    let mut context = CommandHooksContext {
-        pre_commit_hook_result: None,
-        rebase_original_head: None,
-        rebase_onto: None,
-        fetch_authorship_handle: None,
-        stash_sha: None,
-        push_authorship_handle: None,
-        stashed_va: None,
-    };
+    let mut context = hooks_context();
     let exit_status = std::process::Command::new("true").status().unwrap();
 
     post_checkout_hook(&parsed_args, &mut repository, exit_status, &mut context);
@@ -325,15 +261,7 @@ fn test_post_checkout_hook_multiple_pathspecs() {
     repository.pre_command_base_commit = Some(commit_sha.clone());
 
     let parsed_args = make_checkout_invocation(&["HEAD", "--", "file1.txt", "file2.txt"]);
-    let mut context = CommandHooksContext {
-        pre_commit_hook_result: None,
-        rebase_original_head: None,
-        rebase_onto: None,
-        fetch_authorship_handle: None,
-        stash_sha: None,
-        push_authorship_handle: None,
-        stashed_va: None,
-    };
+    let mut context = hooks_context();
     let exit_status = std::process::Command::new("true").status().unwrap();
 
     post_checkout_hook(&parsed_args, &mut repository, exit_status, &mut context);
@@ -378,15 +306,7 @@ fn test_post_checkout_hook_force_checkout() {
     repository.pre_command_base_commit = Some(old_head.clone());
 
     let parsed_args = make_checkout_invocation(&["--force", &original_branch]);
-    let mut context = CommandHooksContext {
-        pre_commit_hook_result: None,
-        rebase_original_head: None,
-        rebase_onto: None,
-        fetch_authorship_handle: None,
-        stash_sha: None,
-        push_authorship_handle: None,
-        stashed_va: None,
-    };
+    let mut context = hooks_context();
     let exit_status = std::process::Command::new("true").status().unwrap();
 
     post_checkout_hook(&parsed_args, &mut repository, exit_status, &mut context);
@@ -420,15 +340,7 @@ fn test_post_checkout_hook_force_short_flag() {
     repository.pre_command_base_commit = Some(old_head.clone());
 
     let parsed_args = make_checkout_invocation(&["-f", &original_branch]);
-    let mut context = CommandHooksContext {
-        pre_commit_hook_result: None,
-        rebase_original_head: None,
-        rebase_onto: None,
-        fetch_authorship_handle: None,
-        stash_sha: None,
-        push_authorship_handle: None,
-        stashed_va: None,
-    };
+    let mut context = hooks_context();
     let exit_status = std::process::Command::new("true").status().unwrap();
 
     post_checkout_hook(&parsed_args, &mut repository, exit_status, &mut context);
@@ -461,15 +373,7 @@ fn test_post_checkout_hook_with_merge() {
         repository::find_repository_in_path(repo.path().to_str().unwrap()).unwrap();
     repository.pre_command_base_commit = Some(old_head.clone());
 
-    let mut context = CommandHooksContext {
-        pre_commit_hook_result: None,
-        rebase_original_head: None,
-        rebase_onto: None,
-        fetch_authorship_handle: None,
-        stash_sha: None,
-        push_authorship_handle: None,
-        stashed_va: None,
-    };
+    let mut context = hooks_context();
     // In real scenario, pre_checkout_hook would populate this
     // context.stashed_va = Some(...);
 
@@ -675,15 +579,7 @@ fn test_checkout_normal_flow() {
 
     let mut repository =
         repository::find_repository_in_path(repo.path().to_str().unwrap()).unwrap();
-    let mut context = CommandHooksContext {
-        pre_commit_hook_result: None,
-        rebase_original_head: None,
-        rebase_onto: None,
-        fetch_authorship_handle: None,
-        stash_sha: None,
-        push_authorship_handle: None,
-        stashed_va: None,
-    };
+    let mut context = hooks_context();
     let parsed_args = make_checkout_invocation(&[&original_branch]);
 
     // Pre-hook
@@ -726,15 +622,7 @@ fn test_checkout_force_flow() {
 
     let mut repository =
         repository::find_repository_in_path(repo.path().to_str().unwrap()).unwrap();
-    let mut context = CommandHooksContext {
-        pre_commit_hook_result: None,
-        rebase_original_head: None,
-        rebase_onto: None,
-        fetch_authorship_handle: None,
-        stash_sha: None,
-        push_authorship_handle: None,
-        stashed_va: None,
-    };
+    let mut context = hooks_context();
     let parsed_args = make_checkout_invocation(&["--force", &original_branch]);
 
     // Pre-hook
@@ -777,15 +665,7 @@ fn test_checkout_pathspec_flow() {
     let mut repository =
         repository::find_repository_in_path(repo.path().to_str().unwrap()).unwrap();
     repository.pre_command_base_commit = Some(commit.commit_sha.clone());
-    let mut context = CommandHooksContext {
-        pre_commit_hook_result: None,
-        rebase_original_head: None,
-        rebase_onto: None,
-        fetch_authorship_handle: None,
-        stash_sha: None,
-        push_authorship_handle: None,
-        stashed_va: None,
-    };
+    let mut context = hooks_context();
 
     // Checkout specific file
     let parsed_args = make_checkout_invocation(&["HEAD", "--", "file1.txt"]);
@@ -814,15 +694,7 @@ fn test_checkout_new_branch_creation() {
 
     let mut repository =
         repository::find_repository_in_path(repo.path().to_str().unwrap()).unwrap();
-    let mut context = CommandHooksContext {
-        pre_commit_hook_result: None,
-        rebase_original_head: None,
-        rebase_onto: None,
-        fetch_authorship_handle: None,
-        stash_sha: None,
-        push_authorship_handle: None,
-        stashed_va: None,
-    };
+    let mut context = hooks_context();
     let parsed_args = make_checkout_invocation(&["-b", "new-branch"]);
 
     pre_checkout_hook(&parsed_args, &mut repository, &mut context);
@@ -853,15 +725,7 @@ fn test_checkout_detached_head() {
 
     let mut repository =
         repository::find_repository_in_path(repo.path().to_str().unwrap()).unwrap();
-    let mut context = CommandHooksContext {
-        pre_commit_hook_result: None,
-        rebase_original_head: None,
-        rebase_onto: None,
-        fetch_authorship_handle: None,
-        stash_sha: None,
-        push_authorship_handle: None,
-        stashed_va: None,
-    };
+    let mut context = hooks_context();
     let parsed_args = make_checkout_invocation(&[&commit1.commit_sha]);
 
     pre_checkout_hook(&parsed_args, &mut repository, &mut context);
@@ -1,6 +1,7 @@
 use git_ai::git::repository;
 
 use crate::repos::test_repo::TestRepo;
+use crate::test_utils::hooks_context;
 use git_ai::commands::git_handlers::CommandHooksContext;
 use git_ai::commands::hooks::commit_hooks::{
     commit_post_command_hook, commit_pre_command_hook, get_commit_default_author,
@@ -117,15 +118,7 @@ fn test_post_commit_hook_success() {
     repository.pre_command_base_commit = None;
 
     let parsed_args = make_commit_invocation(&["-m", "test commit"]);
-    let mut context = CommandHooksContext {
-        pre_commit_hook_result: None,
-        rebase_original_head: None,
-        rebase_onto: None,
-        fetch_authorship_handle: None,
-        stash_sha: None,
-        push_authorship_handle: None,
-        stashed_va: None,
-    };
+    let mut context = hooks_context();
     context.pre_commit_hook_result = Some(true);
 
     let exit_status = std::process::Command::new("true").status().unwrap();
@@ -164,15 +157,7 @@ fn test_post_commit_hook_amend() {
     repository.pre_command_base_commit = Some(original_commit.commit_sha.clone());
 
     let parsed_args = make_commit_invocation(&["--amend", "-m", "amended commit"]);
-    let mut context = CommandHooksContext {
-        pre_commit_hook_result: None,
-        rebase_original_head: None,
-        rebase_onto: None,
-        fetch_authorship_handle: None,
-        stash_sha: None,
-        push_authorship_handle: None,
-        stashed_va: None,
-    };
+    let mut context = hooks_context();
     context.pre_commit_hook_result = Some(true);
 
     let exit_status = std::process::Command::new("true").status().unwrap();
@@ -200,13 +185,8 @@ fn test_post_commit_hook_dry_run() {
         repository::find_repository_in_path(repo.path().to_str().unwrap()).unwrap();
     let parsed_args = make_commit_invocation(&["--dry-run", "-m", "test"]);
     let mut context = CommandHooksContext {
-        pre_commit_hook_result: None,
-        rebase_original_head: None,
-        rebase_onto: None,
-        fetch_authorship_handle: None,
-        stash_sha: None,
-        push_authorship_handle: None,
-        stashed_va: None,
+        dry_run: true,
+        ..Default::default()
     };
 
     let exit_status = std::process::Command::new("true").status().unwrap();
@@ -233,15 +213,7 @@ fn test_post_commit_hook_failed_status() {
     let mut repository =
         repository::find_repository_in_path(repo.path().to_str().unwrap()).unwrap();
     let parsed_args = make_commit_invocation(&["-m", "test"]);
-    let mut context = CommandHooksContext {
-        pre_commit_hook_result: None,
-        rebase_original_head: None,
-        rebase_onto: None,
-        fetch_authorship_handle: None,
-        stash_sha: None,
-        push_authorship_handle: None,
-        stashed_va: None,
-    };
+    let mut context = hooks_context();
     context.pre_commit_hook_result = Some(true);
 
     let exit_status = std::process::Command::new("false")
@@ -277,15 +249,7 @@ fn test_post_commit_hook_pre_hook_failed() {
     let mut repository =
         repository::find_repository_in_path(repo.path().to_str().unwrap()).unwrap();
     let parsed_args = make_commit_invocation(&["-m", "test"]);
-    let mut context = CommandHooksContext {
-        pre_commit_hook_result: None,
-        rebase_original_head: None,
-        rebase_onto: None,
-        fetch_authorship_handle: None,
-        stash_sha: None,
-        push_authorship_handle: None,
-        stashed_va: None,
-    };
+    let mut context = hooks_context();
     context.pre_commit_hook_result = Some(false);
 
     let exit_status = std::process::Command::new("true").status().unwrap();
@@ -318,15 +282,7 @@ fn test_post_commit_hook_porcelain_suppresses_output() {
     repository.pre_command_base_commit = None;
 
     let parsed_args = make_commit_invocation(&["--porcelain", "-m", "test"]);
-    let mut context = CommandHooksContext {
-        pre_commit_hook_result: None,
-        rebase_original_head: None,
-        rebase_onto: None,
-        fetch_authorship_handle: None,
-        stash_sha: None,
-        push_authorship_handle: None,
-        stashed_va: None,
-    };
+    let mut context = hooks_context();
     context.pre_commit_hook_result = Some(true);
 
     let exit_status = std::process::Command::new("true").status().unwrap();
@@ -351,15 +307,7 @@ fn test_post_commit_hook_quiet_suppresses_output() {
     repository.pre_command_base_commit = None;
 
     let parsed_args = make_commit_invocation(&["--quiet", "-m", "test"]);
-    let mut context = CommandHooksContext {
-        pre_commit_hook_result: None,
-        rebase_original_head: None,
-        rebase_onto: None,
-        fetch_authorship_handle: None,
-        stash_sha: None,
-        push_authorship_handle: None,
-        stashed_va: None,
-    };
+    let mut context = hooks_context();
     context.pre_commit_hook_result = Some(true);
 
     let exit_status = std::process::Command::new("true").status().unwrap();
@@ -658,15 +606,7 @@ fn test_commit_full_flow() {
     let _commit = repo.commit("test commit").unwrap();
 
     // Post-hook
-    let mut context = CommandHooksContext {
-        pre_commit_hook_result: None,
-        rebase_original_head: None,
-        rebase_onto: None,
-        fetch_authorship_handle: None,
-        stash_sha: None,
-        push_authorship_handle: None,
-        stashed_va: None,
-    };
+    let mut context = hooks_context();
     context.pre_commit_hook_result = Some(true);
     let exit_status = std::process::Command::new("true").status().unwrap();
 
@@ -712,15 +652,7 @@ fn test_commit_amend_full_flow() {
         .unwrap();
 
     // Post-hook
-    let mut context = CommandHooksContext {
-        pre_commit_hook_result: None,
-        rebase_original_head: None,
-        rebase_onto: None,
-        fetch_authorship_handle: None,
-        stash_sha: None,
-        push_authorship_handle: None,
-        stashed_va: None,
-    };
+    let mut context = hooks_context();
     context.pre_commit_hook_result = Some(true);
     let exit_status = std::process::Command::new("true").status().unwrap();
 
@@ -1050,11 +1050,15 @@ fn test_merge_base_linear_history() {
     ])
     .unwrap();
 
-    let merge_base = repo.merge_base(first.commit_sha.clone(), second.commit_sha);
+    let merge_base = repo.merge_base(&first.commit_sha, &second.commit_sha);
     assert!(merge_base.is_ok(), "Should find merge base");
 
     let base = merge_base.unwrap();
-    assert_eq!(base, first.commit_sha, "Merge base should be first commit");
+    assert_eq!(
+        base,
+        Some(first.commit_sha),
+        "Merge base should be first commit"
+    );
 }
 
 #[test]
@@ -1085,12 +1089,13 @@ fn test_merge_base_with_branches() {
     ])
     .unwrap();
 
-    let merge_base = repo.merge_base(feature.commit_sha, main.commit_sha);
+    let merge_base = repo.merge_base(&feature.commit_sha, &main.commit_sha);
     assert!(merge_base.is_ok(), "Should find merge base");
 
     let merge_base_sha = merge_base.unwrap();
     assert_eq!(
-        merge_base_sha, base.commit_sha,
+        merge_base_sha,
+        Some(base.commit_sha),
         "Merge base should be base commit"
     );
 }
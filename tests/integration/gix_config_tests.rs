@@ -1,7 +1,39 @@
 use std::collections::HashMap;
 
 use crate::repos::test_repo::TestRepo;
+use git_ai::error::GitAiError;
 use git_ai::git::repository as GitAiRepository;
+use git_ai::git::repository::ConfigScope;
+use serial_test::serial;
+
+struct EnvVarGuard {
+    key: &'static str,
+    old: Option<String>,
+}
+
+impl EnvVarGuard {
+    fn set(key: &'static str, value: &str) -> Self {
+        let old = std::env::var(key).ok();
+        // SAFETY: tests marked `serial` avoid concurrent env mutation.
+        unsafe {
+            std::env::set_var(key, value);
+        }
+        Self { key, old }
+    }
+}
+
+impl Drop for EnvVarGuard {
+    fn drop(&mut self) {
+        // SAFETY: tests marked `serial` avoid concurrent env mutation.
+        unsafe {
+            if let Some(old) = &self.old {
+                std::env::set_var(self.key, old);
+            } else {
+                std::env::remove_var(self.key);
+            }
+        }
+    }
+}
 
 /// Helper to get git config via CLI for comparison
 fn get_git_config_cli(repo: &TestRepo, _command: &str, key: &str) -> Result<String, String> {
@@ -117,6 +149,313 @@ fn test_config_get_str_special_chars() {
     );
 }
 
+// ============================================================================
+// config_get_all tests
+// ============================================================================
+
+#[test]
+fn test_config_get_str_or_returns_configured_value() {
+    let repo = TestRepo::new();
+    let key = "pull.rebase";
+
+    repo.git(&["config", key, "merges"]).unwrap();
+
+    let git_ai_repo =
+        GitAiRepository::find_repository_in_path(repo.path().to_str().unwrap()).unwrap();
+    let result = git_ai_repo.config_get_str_or(key, "false").unwrap();
+
+    assert_eq!(result, "merges");
+    assert_eq!(
+        result,
+        get_git_config_cli(&repo, "--get", key).unwrap().trim()
+    );
+}
+
+#[test]
+fn test_config_get_str_or_missing_key_returns_default() {
+    let repo = TestRepo::new();
+
+    let git_ai_repo =
+        GitAiRepository::find_repository_in_path(repo.path().to_str().unwrap()).unwrap();
+    let result = git_ai_repo
+        .config_get_str_or("custom.missing", "fallback")
+        .unwrap();
+
+    assert_eq!(result, "fallback");
+}
+
+#[test]
+fn test_config_get_str_or_surfaces_read_errors() {
+    let repo = TestRepo::new();
+
+    let git_ai_repo =
+        GitAiRepository::find_repository_in_path(repo.path().to_str().unwrap()).unwrap();
+    std::fs::write(local_config_path(&repo), "[custom\n\tbroken = ").unwrap();
+
+    assert!(
+        git_ai_repo
+            .config_get_str_or("custom.broken", "fallback")
+            .is_err()
+    );
+}
+
+#[test]
+fn test_config_get_all_multi_valued_key_in_order() {
+    let repo = TestRepo::new();
+    let key = "remote.origin.fetch";
+
+    repo.git(&[
+        "config",
+        "--add",
+        key,
+        "+refs/heads/*:refs/remotes/origin/*",
+    ])
+    .unwrap();
+    repo.git(&["config", "--add", key, "+refs/notes/ai:refs/notes/ai"])
+        .unwrap();
+
+    let git_ai_repo =
+        GitAiRepository::find_repository_in_path(repo.path().to_str().unwrap()).unwrap();
+    let result = git_ai_repo.config_get_all(key).unwrap();
+
+    assert_eq!(
+        result,
+        vec![
+            "+refs/heads/*:refs/remotes/origin/*".to_string(),
+            "+refs/notes/ai:refs/notes/ai".to_string(),
+        ]
+    );
+
+    // compare with git config --get-all output
+    let git_config_result = repo.git_og(&["config", "--get-all", key]).unwrap();
+    let expected: Vec<String> = git_config_result.lines().map(str::to_string).collect();
+    assert_eq!(result, expected);
+
+    // config_get_str still only reports the last value
+    assert_eq!(
+        git_ai_repo.config_get_str(key).unwrap().as_deref(),
+        Some("+refs/notes/ai:refs/notes/ai")
+    );
+}
+
+#[test]
+fn test_config_get_all_missing_key_returns_empty() {
+    let repo = TestRepo::new();
+    let git_ai_repo =
+        GitAiRepository::find_repository_in_path(repo.path().to_str().unwrap()).unwrap();
+
+    assert!(
+        git_ai_repo
+            .config_get_all("http.extraHeader")
+            .unwrap()
+            .is_empty()
+    );
+}
+
+// ============================================================================
+// config_get_bool tests
+// ============================================================================
+
+/// Helper to get a boolean via `git config --type=bool --get` for comparison
+fn get_git_config_cli_bool(repo: &TestRepo, key: &str) -> Result<bool, String> {
+    let output = repo.git_og(&["config", "--type=bool", "--get", key])?;
+    match output.trim() {
+        "true" => Ok(true),
+        "false" => Ok(false),
+        other => Err(format!("unexpected git bool output: {}", other)),
+    }
+}
+
+/// Path to the repository-local config file, which is shared across worktrees
+fn local_config_path(repo: &TestRepo) -> std::path::PathBuf {
+    let common_dir = repo
+        .git_og(&["rev-parse", "--git-common-dir"])
+        .expect("rev-parse --git-common-dir should succeed");
+    repo.path().join(common_dir.trim()).join("config")
+}
+
+#[test]
+fn test_config_get_bool_git_vocabulary() {
+    let repo = TestRepo::new();
+    let git_ai_repo =
+        GitAiRepository::find_repository_in_path(repo.path().to_str().unwrap()).unwrap();
+
+    let cases = [
+        ("true", true),
+        ("TRUE", true),
+        ("yes", true),
+        ("On", true),
+        ("1", true),
+        ("42", true),
+        ("false", false),
+        ("No", false),
+        ("OFF", false),
+        ("0", false),
+        ("", false),
+    ];
+
+    for (raw, expected) in cases {
+        let key = "custom.flag";
+        repo.git(&["config", key, raw]).unwrap();
+
+        let result = git_ai_repo
+            .config_get_bool(key)
+            .unwrap_or_else(|e| panic!("Failed to parse {:?} as bool: {}", raw, e));
+
+        assert_eq!(result, Some(expected), "value {:?}", raw);
+        assert_eq!(
+            result,
+            Some(get_git_config_cli_bool(&repo, key).unwrap()),
+            "value {:?} should match git config --type=bool",
+            raw
+        );
+    }
+}
+
+#[test]
+fn test_config_get_bool_valueless_key_is_true() {
+    let repo = TestRepo::new();
+    let config_path = local_config_path(&repo);
+    let mut contents = std::fs::read_to_string(&config_path).unwrap();
+    contents.push_str("[custom]\n\tbareflag\n");
+    std::fs::write(&config_path, contents).unwrap();
+
+    let git_ai_repo =
+        GitAiRepository::find_repository_in_path(repo.path().to_str().unwrap()).unwrap();
+    let result = git_ai_repo.config_get_bool("custom.bareflag").unwrap();
+
+    assert_eq!(result, Some(true));
+    assert_eq!(
+        result,
+        Some(get_git_config_cli_bool(&repo, "custom.bareflag").unwrap())
+    );
+}
+
+#[test]
+fn test_config_get_bool_missing_key_returns_none() {
+    let repo = TestRepo::new();
+    let git_ai_repo =
+        GitAiRepository::find_repository_in_path(repo.path().to_str().unwrap()).unwrap();
+
+    assert_eq!(
+        git_ai_repo.config_get_bool("nonexistent.flag").unwrap(),
+        None
+    );
+}
+
+#[test]
+fn test_config_get_bool_invalid_value_errors() {
+    let repo = TestRepo::new();
+    let key = "custom.flag";
+    repo.git(&["config", key, "maybe"]).unwrap();
+
+    let git_ai_repo =
+        GitAiRepository::find_repository_in_path(repo.path().to_str().unwrap()).unwrap();
+    let result = git_ai_repo.config_get_bool(key);
+
+    assert!(
+        matches!(result, Err(GitAiError::Generic(_))),
+        "expected Generic error, got {:?}",
+        result
+    );
+    // git itself also refuses to interpret the value as a bool
+    assert!(get_git_config_cli_bool(&repo, key).is_err());
+}
+
+#[test]
+fn test_config_get_bool_or_falls_back_when_missing_or_invalid() {
+    let repo = TestRepo::new();
+    repo.git(&["config", "custom.off", "no"]).unwrap();
+    repo.git(&["config", "custom.broken", "maybe"]).unwrap();
+
+    let git_ai_repo =
+        GitAiRepository::find_repository_in_path(repo.path().to_str().unwrap()).unwrap();
+
+    assert!(!git_ai_repo.config_get_bool_or("custom.off", true));
+    assert!(git_ai_repo.config_get_bool_or("custom.missing", true));
+    assert!(!git_ai_repo.config_get_bool_or("custom.missing", false));
+    assert!(git_ai_repo.config_get_bool_or("custom.broken", true));
+}
+
+// ============================================================================
+// config_get_int tests
+// ============================================================================
+
+/// Helper to get an integer via `git config --type=int --get` for comparison
+fn get_git_config_cli_int(repo: &TestRepo, key: &str) -> Result<i64, String> {
+    let output = repo.git_og(&["config", "--type=int", "--get", key])?;
+    output
+        .trim()
+        .parse()
+        .map_err(|e| format!("unexpected git int output {:?}: {}", output, e))
+}
+
+#[test]
+fn test_config_get_int_with_suffixes() {
+    let repo = TestRepo::new();
+    let git_ai_repo =
+        GitAiRepository::find_repository_in_path(repo.path().to_str().unwrap()).unwrap();
+
+    let cases = [
+        ("42", 42),
+        ("-7", -7),
+        ("1k", 1024),
+        ("1K", 1024),
+        ("512m", 512 * 1024 * 1024),
+        ("2g", 2 * 1024 * 1024 * 1024),
+        ("2G", 2 * 1024 * 1024 * 1024),
+    ];
+
+    for (raw, expected) in cases {
+        let key = "http.postBuffer";
+        repo.git(&["config", key, raw]).unwrap();
+
+        let result = git_ai_repo
+            .config_get_int(key)
+            .unwrap_or_else(|e| panic!("Failed to parse {:?} as int: {}", raw, e));
+
+        assert_eq!(result, Some(expected), "value {:?}", raw);
+        assert_eq!(
+            result,
+            Some(get_git_config_cli_int(&repo, key).unwrap()),
+            "value {:?} should match git config --type=int",
+            raw
+        );
+    }
+}
+
+#[test]
+fn test_config_get_int_missing_key_returns_none() {
+    let repo = TestRepo::new();
+    let git_ai_repo =
+        GitAiRepository::find_repository_in_path(repo.path().to_str().unwrap()).unwrap();
+
+    assert_eq!(
+        git_ai_repo.config_get_int("core.bigFileThreshold").unwrap(),
+        None
+    );
+}
+
+#[test]
+fn test_config_get_int_invalid_value_errors() {
+    let repo = TestRepo::new();
+    // Not a key git itself parses, otherwise every git invocation would fail on it
+    let key = "custom.threshold";
+    repo.git(&["config", key, "12q"]).unwrap();
+
+    let git_ai_repo =
+        GitAiRepository::find_repository_in_path(repo.path().to_str().unwrap()).unwrap();
+    let result = git_ai_repo.config_get_int(key);
+
+    assert!(
+        matches!(result, Err(GitAiError::Generic(_))),
+        "expected Generic error, got {:?}",
+        result
+    );
+    // git itself also refuses to interpret the value as an int
+    assert!(get_git_config_cli_int(&repo, key).is_err());
+}
+
 // ============================================================================
 // config_get_regexp tests
 // ============================================================================
@@ -213,104 +552,1127 @@ fn test_config_get_regexp_case_insensitive_keys() {
 }
 
 // ============================================================================
-// Global config fallback tests
+// config_set_str tests
 // ============================================================================
 
 #[test]
-#[ignore] // Temporarily ignored: Permission denied on global git config
-fn test_config_falls_back_to_global() {
+fn test_config_set_str_new_key() {
     let repo = TestRepo::new();
+    let key = "custom.newkey";
 
-    // Use a unique key to avoid conflicts with real config
-    let test_key = "gitaici.globalcheck";
-    let global_value = "GLOBAL_CI_VALUE_12345";
+    let git_ai_repo =
+        GitAiRepository::find_repository_in_path(repo.path().to_str().unwrap()).unwrap();
+    git_ai_repo
+        .config_set_str(key, "new_value", ConfigScope::Local)
+        .expect("Failed to set custom.newkey");
 
-    // Set a global value for our test key
-    repo.git_og(&["config", "--global", test_key, global_value])
-        .expect("Failed to set global config");
+    // compare with trimmed git config --get output
+    let git_config_result = get_git_config_cli(&repo, "--get", key).unwrap();
+    assert_eq!(git_config_result.trim(), "new_value");
+    assert_eq!(
+        git_ai_repo.config_get_str(key).unwrap(),
+        Some("new_value".to_string())
+    );
+}
 
-    // Ensure no local value exists
-    let _ = repo.git(&["config", "--local", "--unset", test_key]);
+#[test]
+fn test_config_set_str_subsection() {
+    let repo = TestRepo::new();
+    let key = "git-ai.sub.Key";
 
     let git_ai_repo =
         GitAiRepository::find_repository_in_path(repo.path().to_str().unwrap()).unwrap();
-    let result = git_ai_repo.config_get_str(test_key).unwrap();
-
-    // Clean up global config
-    let _ = repo.git_og(&["config", "--global", "--unset", test_key]);
+    git_ai_repo
+        .config_set_str(key, "sub_value", ConfigScope::Local)
+        .expect("Failed to set git-ai.sub.Key");
 
-    assert_eq!(result, Some(global_value.to_string()));
+    let git_config_result = get_git_config_cli(&repo, "--get", key).unwrap();
+    assert_eq!(git_config_result.trim(), "sub_value");
 }
 
 #[test]
-fn test_config_local_overrides_global() {
+fn test_config_set_str_overwrites_existing_value() {
     let repo = TestRepo::new();
+    let key = "notes.rewriteRef";
 
-    // Get global value (may or may not exist)
-    let global_value = repo
-        .git_og(&["config", "--global", "--get", "user.name"])
-        .ok()
-        .map(|s| s.trim().to_string());
+    repo.git(&["config", key, "refs/notes/old"]).unwrap();
 
-    let local_value = "TEST_LOCAL_USER_12345";
+    let git_ai_repo =
+        GitAiRepository::find_repository_in_path(repo.path().to_str().unwrap()).unwrap();
+    git_ai_repo
+        .config_set_str(key, "refs/notes/ai", ConfigScope::Local)
+        .expect("Failed to overwrite notes.rewriteRef");
 
-    // Test is invalid if local happens to match global
-    if global_value.as_deref() == Some(local_value) {
-        panic!("Test invalid: local value matches global");
-    }
+    // Overwriting must not leave the old value behind as a second entry
+    let all_values = repo.git_og(&["config", "--get-all", key]).unwrap();
+    assert_eq!(all_values.trim(), "refs/notes/ai");
+    assert_eq!(
+        git_ai_repo.config_get_str(key).unwrap().as_deref(),
+        get_git_config_cli(&repo, "--get", key)
+            .ok()
+            .as_deref()
+            .map(str::trim)
+    );
+}
 
-    repo.git(&["config", "--local", "user.name", local_value])
-        .unwrap();
+#[test]
+fn test_config_set_str_refuses_when_config_is_locked() {
+    let repo = TestRepo::new();
+    let key = "custom.locked";
+    repo.git(&["config", key, "before"]).unwrap();
+    let config_path = local_config_path(&repo);
+    let mut lock_path = config_path.clone().into_os_string();
+    lock_path.push(".lock");
+    std::fs::write(&lock_path, "held by another writer").unwrap();
 
     let git_ai_repo =
         GitAiRepository::find_repository_in_path(repo.path().to_str().unwrap()).unwrap();
-    let result = git_ai_repo.config_get_str("user.name").unwrap();
+    let result = git_ai_repo.config_set_str(key, "after", ConfigScope::Local);
 
-    assert_eq!(result, Some(local_value.to_string()));
+    assert!(
+        matches!(&result, Err(GitAiError::Generic(msg)) if msg.contains("could not lock")),
+        "expected a lock error, got {:?}",
+        result
+    );
+    // Neither the config nor the other writer's lock file was touched
+    assert_eq!(
+        std::fs::read_to_string(&lock_path).unwrap(),
+        "held by another writer"
+    );
+    assert_eq!(
+        get_git_config_cli(&repo, "--get", key).unwrap().trim(),
+        "before"
+    );
 }
 
-// ============================================================================
-// Bare repository tests
-// ============================================================================
-
 #[test]
-fn test_config_get_str_bare_repo() {
-    let repo = TestRepo::new_bare();
-    let key = "custom.baretest";
-
-    repo.git(&["config", key, "bare_value"]).unwrap();
+fn test_config_set_str_value_with_special_chars() {
+    let repo = TestRepo::new();
+    let key = "alias.lg";
+    let value = "log --oneline \"--format=%h %s\" # not a comment";
 
-    let git_ai_repo = GitAiRepository::from_bare_repository(repo.path()).unwrap();
-    let result = git_ai_repo.config_get_str(key).unwrap();
+    let git_ai_repo =
+        GitAiRepository::find_repository_in_path(repo.path().to_str().unwrap()).unwrap();
+    git_ai_repo
+        .config_set_str(key, value, ConfigScope::Local)
+        .expect("Failed to set alias.lg");
 
-    assert_eq!(result, Some("bare_value".to_string()));
+    let git_config_result = get_git_config_cli(&repo, "--get", key).unwrap();
+    assert_eq!(git_config_result.trim(), value);
 }
 
 #[test]
-fn test_config_get_regexp_bare_repo() {
+fn test_config_set_str_bare_repo() {
     let repo = TestRepo::new_bare();
-
-    repo.git(&["config", "baretest.key1", "value1"]).unwrap();
-    repo.git(&["config", "baretest.key2", "value2"]).unwrap();
+    let key = "custom.bareset";
 
     let git_ai_repo = GitAiRepository::from_bare_repository(repo.path()).unwrap();
-    let result = git_ai_repo.config_get_regexp(r"^baretest\.").unwrap();
+    git_ai_repo
+        .config_set_str(key, "bare_value", ConfigScope::Local)
+        .unwrap();
 
-    assert_eq!(result.len(), 2);
-    assert_eq!(result.get("baretest.key1"), Some(&"value1".to_string()));
-    assert_eq!(result.get("baretest.key2"), Some(&"value2".to_string()));
+    let git_config_result = get_git_config_cli(&repo, "--get", key).unwrap();
+    assert_eq!(git_config_result.trim(), "bare_value");
 }
 
-crate::reuse_tests_in_worktree!(
-    test_config_get_str_simple_value,
-    test_config_get_str_subsection,
-    test_config_get_str_missing_key_returns_none,
-    test_config_get_str_special_chars,
-    test_config_get_regexp_subsection,
-    test_config_get_regexp_no_matches,
-    test_config_get_regexp_with_subsections,
-    test_config_get_regexp_case_insensitive_keys,
-    test_config_local_overrides_global,
+// ============================================================================
+// config_unset tests
+// ============================================================================
+
+#[test]
+fn test_config_unset_removes_single_value() {
+    let repo = TestRepo::new();
+    let key = "custom.removeme";
+
+    repo.git(&["config", key, "value"]).unwrap();
+    repo.git(&["config", "custom.keepme", "kept"]).unwrap();
+
+    let git_ai_repo =
+        GitAiRepository::find_repository_in_path(repo.path().to_str().unwrap()).unwrap();
+    let removed = git_ai_repo.config_unset(key, ConfigScope::Local).unwrap();
+
+    assert!(removed);
+    assert!(get_git_config_cli(&repo, "--get", key).is_err());
+    assert_eq!(git_ai_repo.config_get_str(key).unwrap(), None);
+    // Neighbouring keys in the same section are untouched
+    assert_eq!(
+        get_git_config_cli(&repo, "--get", "custom.keepme")
+            .unwrap()
+            .trim(),
+        "kept"
+    );
+}
+
+#[test]
+fn test_config_unset_missing_key_returns_false() {
+    let repo = TestRepo::new();
+    let key = "custom.neverset";
+
+    let git_ai_repo =
+        GitAiRepository::find_repository_in_path(repo.path().to_str().unwrap()).unwrap();
+
+    assert!(!git_ai_repo.config_unset(key, ConfigScope::Local).unwrap());
+    assert!(
+        !git_ai_repo
+            .config_unset_all(key, ConfigScope::Local)
+            .unwrap()
+    );
+    // git reports the same "nothing to unset" condition as a failure
+    assert!(repo.git_og(&["config", "--unset", key]).is_err());
+}
+
+#[test]
+fn test_config_unset_multi_valued_key_errors() {
+    let repo = TestRepo::new();
+    let key = "custom.multi";
+
+    repo.git_og(&["config", "--add", key, "one"]).unwrap();
+    repo.git_og(&["config", "--add", key, "two"]).unwrap();
+
+    let git_ai_repo =
+        GitAiRepository::find_repository_in_path(repo.path().to_str().unwrap()).unwrap();
+    let result = git_ai_repo.config_unset(key, ConfigScope::Local);
+
+    assert!(
+        matches!(result, Err(GitAiError::Generic(_))),
+        "expected Generic error, got {:?}",
+        result
+    );
+    assert!(repo.git_og(&["config", "--unset", key]).is_err());
+    // Nothing was dropped
+    assert_eq!(
+        git_ai_repo.config_get_all(key).unwrap(),
+        vec!["one".to_string(), "two".to_string()]
+    );
+}
+
+#[test]
+fn test_config_unset_all_removes_every_value() {
+    let repo = TestRepo::new();
+    let key = "custom.multi";
+
+    repo.git_og(&["config", "--add", key, "one"]).unwrap();
+    repo.git_og(&["config", "--add", key, "two"]).unwrap();
+    repo.git_og(&["config", "--add", key, "three"]).unwrap();
+
+    let git_ai_repo =
+        GitAiRepository::find_repository_in_path(repo.path().to_str().unwrap()).unwrap();
+    let removed = git_ai_repo
+        .config_unset_all(key, ConfigScope::Local)
+        .unwrap();
+
+    assert!(removed);
+    assert!(repo.git_og(&["config", "--get-all", key]).is_err());
+    assert!(git_ai_repo.config_get_all(key).unwrap().is_empty());
+}
+
+#[test]
+fn test_config_unset_subsection_key_after_set() {
+    let repo = TestRepo::new();
+    let key = "git-ai.sub.Key";
+
+    let git_ai_repo =
+        GitAiRepository::find_repository_in_path(repo.path().to_str().unwrap()).unwrap();
+    git_ai_repo
+        .config_set_str(key, "managed", ConfigScope::Local)
+        .unwrap();
+    assert!(git_ai_repo.config_unset(key, ConfigScope::Local).unwrap());
+
+    assert!(get_git_config_cli(&repo, "--get", key).is_err());
+    assert!(!git_ai_repo.config_unset(key, ConfigScope::Local).unwrap());
+}
+
+#[test]
+fn test_config_get_regexp_exact_anchors_pattern() {
+    let repo = TestRepo::new();
+    repo.git(&["config", "alias.st", "status"]).unwrap();
+    repo.git(&["config", "myalias.co", "checkout"]).unwrap();
+
+    let git_ai_repo =
+        GitAiRepository::find_repository_in_path(repo.path().to_str().unwrap()).unwrap();
+
+    // Unanchored, like git config --get-regexp: matches anywhere in the key
+    let result = git_ai_repo.config_get_regexp(r"alias\.").unwrap();
+    assert!(result.contains_key("alias.st"));
+    assert!(result.contains_key("myalias.co"));
+
+    // Exact: the pattern has to match the whole key
+    let result = git_ai_repo.config_get_regexp_exact(r"alias\.").unwrap();
+    assert!(!result.contains_key("alias.st"));
+    assert!(!result.contains_key("myalias.co"));
+
+    let result = git_ai_repo.config_get_regexp_exact(r"alias\..*").unwrap();
+    assert_eq!(result.get("alias.st"), Some(&"status".to_string()));
+    assert!(!result.contains_key("myalias.co"));
+
+    // Alternatives are anchored as a group
+    let result = git_ai_repo
+        .config_get_regexp_exact(r"alias\.st|myalias\.co")
+        .unwrap();
+    assert_eq!(result.len(), 2);
+}
+
+#[test]
+fn test_config_keys_with_dots_in_subsection() {
+    let repo = TestRepo::new();
+    // Only the first and last dots delimit section, subsection and name
+    let key = "url.https://github.example.com/org.name/.insteadOf";
+    repo.git(&["config", "--add", key, "gh:"]).unwrap();
+    repo.git(&["config", "--add", key, "github:"]).unwrap();
+    repo.git(&["config", "url.https://github.com/.insteadOf", "plain:"])
+        .unwrap();
+
+    let git_ai_repo =
+        GitAiRepository::find_repository_in_path(repo.path().to_str().unwrap()).unwrap();
+
+    assert_eq!(
+        git_ai_repo.config_get_str(key).unwrap().as_deref(),
+        Some("github:")
+    );
+    assert_eq!(
+        git_ai_repo.config_get_all(key).unwrap(),
+        vec!["gh:".to_string(), "github:".to_string()]
+    );
+    assert_eq!(
+        git_ai_repo
+            .config_get_str_with_scope(key)
+            .unwrap()
+            .map(|(_, scope)| scope),
+        Some(ConfigScope::Local)
+    );
+    assert_eq!(
+        git_ai_repo
+            .config_get_str("url.https://github.com/.insteadOf")
+            .unwrap()
+            .as_deref(),
+        Some("plain:")
+    );
+    // A prefix of the subsection is a different key
+    assert_eq!(
+        git_ai_repo
+            .config_get_str("url.https://github.example.com/.insteadOf")
+            .unwrap(),
+        None
+    );
+
+    let pattern = r"^url\..*\.insteadof$";
+    assert_eq!(
+        git_ai_repo.config_get_regexp(pattern).unwrap(),
+        git_config_cli_regexp(&repo, "--get-regexp", pattern).unwrap()
+    );
+
+    // Writes route to the same subsection git reads
+    let other = "url.ssh://git@git.example.co.uk:2222/.pushInsteadOf";
+    git_ai_repo
+        .config_set_str(other, "uk:", ConfigScope::Local)
+        .unwrap();
+    assert_eq!(
+        get_git_config_cli(&repo, "--get", other).unwrap().trim(),
+        "uk:"
+    );
+    assert!(
+        git_ai_repo
+            .config_unset_all(key, ConfigScope::Local)
+            .unwrap()
+    );
+    assert!(get_git_config_cli(&repo, "--get", key).is_err());
+    assert_eq!(
+        get_git_config_cli(&repo, "--get", "url.https://github.com/.insteadOf")
+            .unwrap()
+            .trim(),
+        "plain:"
+    );
+}
+
+#[test]
+fn test_config_values_with_embedded_newlines_round_trip() {
+    let repo = TestRepo::new();
+    let key = "git-ai.multiline";
+    let value = "first line\n\tsecond line with \"quotes\"\n\nlast line";
+    repo.git(&["config", key, value]).unwrap();
+
+    let git_ai_repo =
+        GitAiRepository::find_repository_in_path(repo.path().to_str().unwrap()).unwrap();
+
+    assert_eq!(
+        git_ai_repo.config_get_str(key).unwrap().as_deref(),
+        Some(value)
+    );
+    assert_eq!(
+        git_ai_repo.config_get_all(key).unwrap(),
+        vec![value.to_string()]
+    );
+    assert_eq!(
+        git_ai_repo
+            .config_get_regexp_exact(r"git-ai\.multiline")
+            .unwrap()
+            .get(key)
+            .map(String::as_str),
+        Some(value)
+    );
+    assert_eq!(
+        git_ai_repo.config_get_regexp_multi(r"^git-ai\.").unwrap(),
+        vec![(key.to_string(), value.to_string())]
+    );
+
+    // Values written by git-ai come back unchanged too, for git and for us
+    let other = "git-ai.template";
+    let template = "Subject\n\n# Body line; with a comment char\n";
+    git_ai_repo
+        .config_set_str(other, template, ConfigScope::Local)
+        .unwrap();
+    assert_eq!(
+        git_ai_repo.config_get_str(other).unwrap().as_deref(),
+        Some(template)
+    );
+    assert_eq!(
+        repo.git_og(&["config", "-z", "--get", other]).unwrap(),
+        format!("{}\0", template)
+    );
+}
+
+#[test]
+fn test_config_get_regexp_multi_preserves_multivar_entries() {
+    let repo = TestRepo::new();
+    let key = "remote.origin.fetch";
+
+    repo.git(&[
+        "config",
+        "--add",
+        key,
+        "+refs/heads/*:refs/remotes/origin/*",
+    ])
+    .unwrap();
+    repo.git(&["config", "--add", key, "+refs/notes/ai:refs/notes/ai"])
+        .unwrap();
+    repo.git(&[
+        "config",
+        "remote.origin.url",
+        "https://github.com/test/repo.git",
+    ])
+    .unwrap();
+
+    let git_ai_repo =
+        GitAiRepository::find_repository_in_path(repo.path().to_str().unwrap()).unwrap();
+    let pattern = r"^remote\.origin\.fetch$";
+    let result = git_ai_repo.config_get_regexp_multi(pattern).unwrap();
+
+    assert_eq!(
+        result,
+        vec![
+            (
+                key.to_string(),
+                "+refs/heads/*:refs/remotes/origin/*".to_string()
+            ),
+            (key.to_string(), "+refs/notes/ai:refs/notes/ai".to_string()),
+        ]
+    );
+
+    // compare with git config --get-regexp output, which prints every occurrence in order
+    let git_config_result: Vec<(String, String)> = repo
+        .git_og(&["config", "--get-regexp", pattern])
+        .unwrap()
+        .lines()
+        .filter_map(|line| line.split_once(' '))
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+    assert_eq!(result, git_config_result);
+
+    // The map-based variant keeps only the last value
+    let map_result = git_ai_repo.config_get_regexp(pattern).unwrap();
+    assert_eq!(
+        map_result.get(key),
+        Some(&"+refs/notes/ai:refs/notes/ai".to_string())
+    );
+}
+
+// ============================================================================
+// config_get_regexp_filtered tests
+// ============================================================================
+
+fn add_three_remotes(repo: &TestRepo) {
+    repo.git(&[
+        "config",
+        "remote.origin.url",
+        "https://github.com/test/repo.git",
+    ])
+    .unwrap();
+    repo.git(&[
+        "config",
+        "remote.mirror.url",
+        "git@gitlab.com:test/repo.git",
+    ])
+    .unwrap();
+    repo.git(&[
+        "config",
+        "remote.upstream.url",
+        "https://github.com/upstream/repo.git",
+    ])
+    .unwrap();
+}
+
+fn git_config_cli_regexp_filtered(
+    repo: &TestRepo,
+    key_pattern: &str,
+    value_pattern: &str,
+) -> Vec<(String, String)> {
+    repo.git_og(&["config", "--get-regexp", key_pattern, value_pattern])
+        .unwrap()
+        .lines()
+        .filter_map(|line| line.split_once(' '))
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+#[test]
+fn test_config_get_regexp_filtered_by_host() {
+    let repo = TestRepo::new();
+    add_three_remotes(&repo);
+
+    let git_ai_repo =
+        GitAiRepository::find_repository_in_path(repo.path().to_str().unwrap()).unwrap();
+    let key_pattern = r"^remote\..*\.url$";
+    let value_pattern = r"github\.com";
+    let result = git_ai_repo
+        .config_get_regexp_filtered(key_pattern, Some(value_pattern))
+        .unwrap();
+
+    assert_eq!(
+        result,
+        vec![
+            (
+                "remote.origin.url".to_string(),
+                "https://github.com/test/repo.git".to_string()
+            ),
+            (
+                "remote.upstream.url".to_string(),
+                "https://github.com/upstream/repo.git".to_string()
+            ),
+        ]
+    );
+    assert_eq!(
+        result,
+        git_config_cli_regexp_filtered(&repo, key_pattern, value_pattern)
+    );
+}
+
+#[test]
+fn test_config_get_regexp_filtered_negated_value_pattern() {
+    let repo = TestRepo::new();
+    add_three_remotes(&repo);
+
+    let git_ai_repo =
+        GitAiRepository::find_repository_in_path(repo.path().to_str().unwrap()).unwrap();
+    let key_pattern = r"^remote\..*\.url$";
+    let value_pattern = r"!github\.com";
+    let result = git_ai_repo
+        .config_get_regexp_filtered(key_pattern, Some(value_pattern))
+        .unwrap();
+
+    assert_eq!(
+        result,
+        vec![(
+            "remote.mirror.url".to_string(),
+            "git@gitlab.com:test/repo.git".to_string()
+        )]
+    );
+    assert_eq!(
+        result,
+        git_config_cli_regexp_filtered(&repo, key_pattern, value_pattern)
+    );
+}
+
+#[test]
+fn test_config_get_regexp_filtered_without_value_pattern_matches_all() {
+    let repo = TestRepo::new();
+    add_three_remotes(&repo);
+
+    let git_ai_repo =
+        GitAiRepository::find_repository_in_path(repo.path().to_str().unwrap()).unwrap();
+    let key_pattern = r"^remote\..*\.url$";
+    let result = git_ai_repo
+        .config_get_regexp_filtered(key_pattern, None)
+        .unwrap();
+
+    assert_eq!(result.len(), 3);
+    assert_eq!(
+        result,
+        git_ai_repo.config_get_regexp_multi(key_pattern).unwrap()
+    );
+}
+
+#[test]
+fn test_config_get_regexp_filtered_invalid_value_pattern_errors() {
+    let repo = TestRepo::new();
+    let git_ai_repo =
+        GitAiRepository::find_repository_in_path(repo.path().to_str().unwrap()).unwrap();
+
+    let result = git_ai_repo.config_get_regexp_filtered(r"^remote\.", Some("("));
+    assert!(matches!(result, Err(GitAiError::Generic(_))));
+}
+
+// ============================================================================
+// include / includeIf tests
+// ============================================================================
+
+/// Write `contents` to a config file next to the repository and return its absolute path
+fn write_included_config(repo: &TestRepo, name: &str, contents: &str) -> String {
+    let path = repo
+        .path()
+        .parent()
+        .expect("test repo should have a parent dir")
+        .join(format!(
+            "{}-{}.inc",
+            repo.path().file_name().unwrap().to_string_lossy(),
+            name
+        ));
+    std::fs::write(&path, contents).expect("failed to write included config");
+    path.to_string_lossy().replace('\\', "/")
+}
+
+#[test]
+fn test_config_get_str_follows_include_path() {
+    let repo = TestRepo::new();
+    let included = write_included_config(&repo, "plain", "[custom]\n\tincluded = from-include\n");
+    repo.git(&["config", "include.path", &included]).unwrap();
+
+    let git_ai_repo =
+        GitAiRepository::find_repository_in_path(repo.path().to_str().unwrap()).unwrap();
+    let result = git_ai_repo.config_get_str("custom.included").unwrap();
+
+    assert_eq!(result, Some("from-include".to_string()));
+    assert_eq!(
+        result.unwrap(),
+        get_git_config_cli(&repo, "--get", "custom.included")
+            .unwrap()
+            .trim()
+    );
+}
+
+#[test]
+fn test_config_get_str_follows_include_if_gitdir() {
+    let repo = TestRepo::new();
+    let included = write_included_config(&repo, "gitdir", "[user]\n\temail = work@example.com\n");
+    // Mirror the common `gitdir:~/work/` setup by matching the directory containing the git dir
+    let git_dir = repo.git_og(&["rev-parse", "--absolute-git-dir"]).unwrap();
+    let work_dir = std::path::Path::new(git_dir.trim())
+        .parent()
+        .unwrap()
+        .to_string_lossy()
+        .replace('\\', "/");
+    repo.git(&[
+        "config",
+        &format!("includeIf.gitdir:{}/.path", work_dir),
+        &included,
+    ])
+    .unwrap();
+    let unmatched = write_included_config(&repo, "other", "[user]\n\temail = other@example.com\n");
+    repo.git(&[
+        "config",
+        "includeIf.gitdir:/definitely/not/this/repo/.path",
+        &unmatched,
+    ])
+    .unwrap();
+
+    let git_ai_repo =
+        GitAiRepository::find_repository_in_path(repo.path().to_str().unwrap()).unwrap();
+    let result = git_ai_repo.config_get_str("user.email").unwrap();
+
+    assert_eq!(result, Some("work@example.com".to_string()));
+    assert_eq!(
+        result.unwrap(),
+        get_git_config_cli(&repo, "--get", "user.email")
+            .unwrap()
+            .trim()
+    );
+}
+
+#[test]
+fn test_config_get_str_follows_include_if_onbranch() {
+    let repo = TestRepo::new();
+    let branch = repo.current_branch();
+    let included = write_included_config(&repo, "onbranch", "[custom]\n\tbranchy = yes-branch\n");
+    repo.git(&[
+        "config",
+        &format!("includeIf.onbranch:{}.path", branch),
+        &included,
+    ])
+    .unwrap();
+    let unmatched = write_included_config(
+        &repo,
+        "otherbranch",
+        "[custom]\n\tother = should-not-appear\n",
+    );
+    repo.git(&[
+        "config",
+        "includeIf.onbranch:no-such-branch.path",
+        &unmatched,
+    ])
+    .unwrap();
+
+    let git_ai_repo =
+        GitAiRepository::find_repository_in_path(repo.path().to_str().unwrap()).unwrap();
+
+    assert_eq!(
+        git_ai_repo.config_get_str("custom.branchy").unwrap(),
+        Some("yes-branch".to_string())
+    );
+    assert_eq!(git_ai_repo.config_get_str("custom.other").unwrap(), None);
+    assert!(get_git_config_cli(&repo, "--get", "custom.other").is_err());
+}
+
+#[test]
+fn test_config_get_regexp_follows_include_if_hasconfig() {
+    let repo = TestRepo::new();
+    repo.git(&[
+        "remote",
+        "add",
+        "origin",
+        "https://github.com/work-org/repo.git",
+    ])
+    .unwrap();
+    let included = write_included_config(
+        &repo,
+        "hasconfig",
+        "[workorg]\n\tname = work-org\n\tsigning = true\n",
+    );
+    repo.git(&[
+        "config",
+        "includeIf.hasconfig:remote.*.url:https://github.com/work-org/**.path",
+        &included,
+    ])
+    .unwrap();
+
+    let git_ai_repo =
+        GitAiRepository::find_repository_in_path(repo.path().to_str().unwrap()).unwrap();
+    let result = git_ai_repo.config_get_regexp(r"^workorg\.").unwrap();
+
+    assert_eq!(result.get("workorg.name"), Some(&"work-org".to_string()));
+    assert_eq!(result.get("workorg.signing"), Some(&"true".to_string()));
+    assert_eq!(
+        result,
+        git_config_cli_regexp(&repo, "", r"^workorg\.").unwrap()
+    );
+}
+
+// ============================================================================
+// Global config fallback tests
+// ============================================================================
+
+#[test]
+#[ignore] // Temporarily ignored: Permission denied on global git config
+fn test_config_falls_back_to_global() {
+    let repo = TestRepo::new();
+
+    // Use a unique key to avoid conflicts with real config
+    let test_key = "gitaici.globalcheck";
+    let global_value = "GLOBAL_CI_VALUE_12345";
+
+    // Set a global value for our test key
+    repo.git_og(&["config", "--global", test_key, global_value])
+        .expect("Failed to set global config");
+
+    // Ensure no local value exists
+    let _ = repo.git(&["config", "--local", "--unset", test_key]);
+
+    let git_ai_repo =
+        GitAiRepository::find_repository_in_path(repo.path().to_str().unwrap()).unwrap();
+    let result = git_ai_repo.config_get_str(test_key).unwrap();
+
+    // Clean up global config
+    let _ = repo.git_og(&["config", "--global", "--unset", test_key]);
+
+    assert_eq!(result, Some(global_value.to_string()));
+}
+
+#[test]
+fn test_config_local_overrides_global() {
+    let repo = TestRepo::new();
+
+    // Get global value (may or may not exist)
+    let global_value = repo
+        .git_og(&["config", "--global", "--get", "user.name"])
+        .ok()
+        .map(|s| s.trim().to_string());
+
+    let local_value = "TEST_LOCAL_USER_12345";
+
+    // Test is invalid if local happens to match global
+    if global_value.as_deref() == Some(local_value) {
+        panic!("Test invalid: local value matches global");
+    }
+
+    repo.git(&["config", "--local", "user.name", local_value])
+        .unwrap();
+
+    let git_ai_repo =
+        GitAiRepository::find_repository_in_path(repo.path().to_str().unwrap()).unwrap();
+    let result = git_ai_repo.config_get_str("user.name").unwrap();
+
+    assert_eq!(result, Some(local_value.to_string()));
+}
+
+// ============================================================================
+// config_get_str_with_scope tests
+// ============================================================================
+
+/// Point `GIT_CONFIG_GLOBAL` at a fresh file next to the repository containing `contents`
+fn isolated_global_config(repo: &TestRepo, contents: &str) -> EnvVarGuard {
+    let path = repo.path().parent().unwrap().join(format!(
+        "{}-global.gitconfig",
+        repo.path().file_name().unwrap().to_string_lossy()
+    ));
+    std::fs::write(&path, contents).expect("failed to write global config");
+    EnvVarGuard::set("GIT_CONFIG_GLOBAL", path.to_str().unwrap())
+}
+
+#[test]
+#[serial]
+fn test_config_get_str_with_scope_local_overrides_global() {
+    let repo = TestRepo::new();
+    let key = "custom.scoped";
+    let _global = isolated_global_config(&repo, "[custom]\n\tscoped = from-global\n");
+
+    repo.git(&["config", "--local", key, "from-local"]).unwrap();
+
+    let git_ai_repo =
+        GitAiRepository::find_repository_in_path(repo.path().to_str().unwrap()).unwrap();
+    let result = git_ai_repo.config_get_str_with_scope(key).unwrap();
+
+    assert_eq!(result, Some(("from-local".to_string(), ConfigScope::Local)));
+    assert_eq!(
+        repo.git_og(&["config", "--show-scope", "--get", key])
+            .unwrap()
+            .trim(),
+        "local\tfrom-local"
+    );
+}
+
+#[test]
+#[serial]
+fn test_config_get_str_with_scope_reports_global() {
+    let repo = TestRepo::new();
+    let key = "custom.scoped";
+    let _global = isolated_global_config(&repo, "[custom]\n\tscoped = from-global\n");
+
+    let git_ai_repo =
+        GitAiRepository::find_repository_in_path(repo.path().to_str().unwrap()).unwrap();
+    let result = git_ai_repo.config_get_str_with_scope(key).unwrap();
+
+    assert_eq!(
+        result,
+        Some(("from-global".to_string(), ConfigScope::Global))
+    );
+}
+
+#[test]
+fn test_config_get_str_with_scope_missing_key_returns_none() {
+    let repo = TestRepo::new();
+
+    let git_ai_repo =
+        GitAiRepository::find_repository_in_path(repo.path().to_str().unwrap()).unwrap();
+
+    assert_eq!(
+        git_ai_repo
+            .config_get_str_with_scope("custom.neverset")
+            .unwrap(),
+        None
+    );
+}
+
+// ============================================================================
+// Explicit config environment tests
+// ============================================================================
+
+/// Open `repo` with an explicit environment whose global config holds `contents`
+fn repo_with_explicit_global(repo: &TestRepo, contents: &str) -> GitAiRepository::Repository {
+    let global = repo.path().parent().unwrap().join(format!(
+        "{}-explicit-global.gitconfig",
+        repo.path().file_name().unwrap().to_string_lossy()
+    ));
+    std::fs::write(&global, contents).expect("failed to write global config");
+
+    GitAiRepository::find_repository_in_path(repo.path().to_str().unwrap())
+        .unwrap()
+        .with_config_env([
+            ("GIT_CONFIG_GLOBAL", global.into_os_string()),
+            ("HOME", repo.path().parent().unwrap().as_os_str().to_owned()),
+        ])
+}
+
+#[test]
+fn test_with_config_env_reads_distinct_globals_concurrently() {
+    let repo_one = TestRepo::new();
+    let repo_two = TestRepo::new();
+    let git_ai_one = repo_with_explicit_global(&repo_one, "[custom]\n\tmarker = one\n");
+    let git_ai_two = repo_with_explicit_global(&repo_two, "[custom]\n\tmarker = two\n");
+
+    std::thread::scope(|s| {
+        let handles = [(&git_ai_one, "one"), (&git_ai_two, "two")].map(|(repo, expected)| {
+            s.spawn(move || {
+                for _ in 0..20 {
+                    assert_eq!(
+                        repo.config_get_str_with_scope("custom.marker").unwrap(),
+                        Some((expected.to_string(), ConfigScope::Global))
+                    );
+                }
+            })
+        });
+        for handle in handles {
+            handle.join().unwrap();
+        }
+    });
+}
+
+#[test]
+fn test_with_config_env_applies_config_count_overrides() {
+    let repo = TestRepo::new();
+    let git_ai_repo = GitAiRepository::find_repository_in_path(repo.path().to_str().unwrap())
+        .unwrap()
+        .with_config_env([
+            ("GIT_CONFIG_GLOBAL", "/dev/null"),
+            ("GIT_CONFIG_COUNT", "1"),
+            ("GIT_CONFIG_KEY_0", "custom.fromenv"),
+            ("GIT_CONFIG_VALUE_0", "explicit"),
+        ]);
+
+    assert_eq!(
+        git_ai_repo.config_get_str("custom.fromenv").unwrap(),
+        Some("explicit".to_string())
+    );
+}
+
+#[test]
+fn test_process_config_env_snapshot_reads_like_the_process() {
+    let repo = TestRepo::new();
+    repo.git(&["config", "custom.snapshot", "local"]).unwrap();
+    let path = repo.path().to_str().unwrap().to_string();
+    let config_env = GitAiRepository::Repository::process_config_env();
+
+    // Open the repository on another thread with the snapshot, as the background fetch does
+    let value = std::thread::spawn(move || {
+        GitAiRepository::find_repository_in_path(&path)
+            .unwrap()
+            .with_config_env(config_env)
+            .config_get_str("custom.snapshot")
+            .unwrap()
+    })
+    .join()
+    .unwrap();
+
+    assert_eq!(value, Some("local".to_string()));
+}
+
+#[test]
+fn test_global_config_path_follows_git_config_global() {
+    let repo = TestRepo::new();
+    let home = repo.path().parent().unwrap().to_path_buf();
+    let custom = home.join(format!(
+        "{}-custom-global.gitconfig",
+        repo.path().file_name().unwrap().to_string_lossy()
+    ));
+    let open = |vars: Vec<(&str, std::ffi::OsString)>| {
+        GitAiRepository::find_repository_in_path(repo.path().to_str().unwrap())
+            .unwrap()
+            .with_config_env(vars)
+    };
+
+    let default_repo = open(vec![("HOME", home.clone().into_os_string())]);
+    assert_eq!(
+        default_repo.global_config_path(),
+        Some(home.join(".gitconfig"))
+    );
+
+    let overridden = open(vec![
+        ("HOME", home.clone().into_os_string()),
+        ("GIT_CONFIG_GLOBAL", custom.clone().into_os_string()),
+    ]);
+    assert_eq!(overridden.global_config_path(), Some(custom));
+}
+
+#[test]
+#[serial]
+fn test_global_config_path_follows_process_git_config_global() {
+    let repo = TestRepo::new();
+    let _global = isolated_global_config(&repo, "");
+    let expected = std::path::PathBuf::from(std::env::var("GIT_CONFIG_GLOBAL").unwrap());
+
+    let git_ai_repo =
+        GitAiRepository::find_repository_in_path(repo.path().to_str().unwrap()).unwrap();
+
+    assert_eq!(git_ai_repo.global_config_path(), Some(expected));
+}
+
+#[test]
+fn test_system_config_path_honors_overrides() {
+    let repo = TestRepo::new();
+    let system = repo
+        .path()
+        .parent()
+        .unwrap()
+        .join("custom-system.gitconfig");
+    let open = |vars: Vec<(&str, std::ffi::OsString)>| {
+        GitAiRepository::find_repository_in_path(repo.path().to_str().unwrap())
+            .unwrap()
+            .with_config_env(vars)
+    };
+
+    let custom = open(vec![("GIT_CONFIG_SYSTEM", system.clone().into_os_string())]);
+    assert_eq!(custom.system_config_path(), Some(system.clone()));
+
+    let disabled = open(vec![
+        ("GIT_CONFIG_SYSTEM", system.into_os_string()),
+        ("GIT_CONFIG_NOSYSTEM", "1".into()),
+    ]);
+    assert_eq!(disabled.system_config_path(), None);
+}
+
+// ============================================================================
+// Remote helpers
+// ============================================================================
+
+#[test]
+fn test_list_remotes_and_remote_url() {
+    let repo = TestRepo::new();
+    repo.git(&[
+        "remote",
+        "add",
+        "origin",
+        "https://example.com/org/repo.git",
+    ])
+    .unwrap();
+    repo.git(&[
+        "remote",
+        "add",
+        "upstream.mirror",
+        "git@example.com:up/repo.git",
+    ])
+    .unwrap();
+
+    let git_ai_repo =
+        GitAiRepository::find_repository_in_path(repo.path().to_str().unwrap()).unwrap();
+
+    assert_eq!(
+        git_ai_repo.list_remotes().unwrap(),
+        vec!["origin".to_string(), "upstream.mirror".to_string()]
+    );
+    assert_eq!(
+        git_ai_repo.remote_url("origin").unwrap().as_deref(),
+        Some("https://example.com/org/repo.git")
+    );
+    assert_eq!(
+        git_ai_repo
+            .remote_url("upstream.mirror")
+            .unwrap()
+            .as_deref(),
+        Some("git@example.com:up/repo.git")
+    );
+    assert_eq!(git_ai_repo.remote_url("missing").unwrap(), None);
+}
+
+#[test]
+fn test_list_remotes_empty_without_remotes() {
+    let repo = TestRepo::new();
+
+    let git_ai_repo =
+        GitAiRepository::find_repository_in_path(repo.path().to_str().unwrap()).unwrap();
+
+    assert!(git_ai_repo.list_remotes().unwrap().is_empty());
+}
+
+// ============================================================================
+// Bare repository tests
+// ============================================================================
+
+#[test]
+fn test_config_get_str_bare_repo() {
+    let repo = TestRepo::new_bare();
+    let key = "custom.baretest";
+
+    repo.git(&["config", key, "bare_value"]).unwrap();
+
+    let git_ai_repo = GitAiRepository::from_bare_repository(repo.path()).unwrap();
+    let result = git_ai_repo.config_get_str(key).unwrap();
+
+    assert_eq!(result, Some("bare_value".to_string()));
+}
+
+#[test]
+fn test_config_get_regexp_bare_repo() {
+    let repo = TestRepo::new_bare();
+
+    repo.git(&["config", "baretest.key1", "value1"]).unwrap();
+    repo.git(&["config", "baretest.key2", "value2"]).unwrap();
+
+    let git_ai_repo = GitAiRepository::from_bare_repository(repo.path()).unwrap();
+    let result = git_ai_repo.config_get_regexp(r"^baretest\.").unwrap();
+
+    assert_eq!(result.len(), 2);
+    assert_eq!(result.get("baretest.key1"), Some(&"value1".to_string()));
+    assert_eq!(result.get("baretest.key2"), Some(&"value2".to_string()));
+}
+
+crate::reuse_tests_in_worktree!(
+    test_config_get_str_simple_value,
+    test_config_get_str_subsection,
+    test_config_get_str_missing_key_returns_none,
+    test_config_get_str_special_chars,
+    test_config_get_str_or_returns_configured_value,
+    test_config_get_str_or_missing_key_returns_default,
+    test_config_get_str_or_surfaces_read_errors,
+    test_config_get_all_multi_valued_key_in_order,
+    test_config_get_all_missing_key_returns_empty,
+    test_config_get_bool_git_vocabulary,
+    test_config_get_bool_valueless_key_is_true,
+    test_config_get_bool_missing_key_returns_none,
+    test_config_get_bool_invalid_value_errors,
+    test_config_get_bool_or_falls_back_when_missing_or_invalid,
+    test_config_get_int_with_suffixes,
+    test_config_get_int_missing_key_returns_none,
+    test_config_get_int_invalid_value_errors,
+    test_config_get_regexp_subsection,
+    test_config_get_regexp_no_matches,
+    test_config_get_regexp_with_subsections,
+    test_config_get_regexp_case_insensitive_keys,
+    test_config_get_regexp_exact_anchors_pattern,
+    test_config_keys_with_dots_in_subsection,
+    test_config_values_with_embedded_newlines_round_trip,
+    test_config_get_regexp_multi_preserves_multivar_entries,
+    test_config_get_regexp_filtered_by_host,
+    test_config_get_regexp_filtered_negated_value_pattern,
+    test_config_get_regexp_filtered_without_value_pattern_matches_all,
+    test_config_get_regexp_filtered_invalid_value_pattern_errors,
+    test_config_get_str_follows_include_path,
+    test_config_get_str_follows_include_if_gitdir,
+    test_config_get_str_follows_include_if_onbranch,
+    test_config_get_regexp_follows_include_if_hasconfig,
+    test_config_local_overrides_global,
+    test_config_get_str_with_scope_missing_key_returns_none,
+    test_with_config_env_reads_distinct_globals_concurrently,
+    test_with_config_env_applies_config_count_overrides,
+    test_process_config_env_snapshot_reads_like_the_process,
+    test_global_config_path_follows_git_config_global,
+    test_system_config_path_honors_overrides,
+    test_list_remotes_and_remote_url,
+    test_list_remotes_empty_without_remotes,
     test_config_get_str_bare_repo,
     test_config_get_regexp_bare_repo,
+    test_config_set_str_new_key,
+    test_config_set_str_subsection,
+    test_config_set_str_overwrites_existing_value,
+    test_config_set_str_refuses_when_config_is_locked,
+    test_config_set_str_value_with_special_chars,
+    test_config_set_str_bare_repo,
+    test_config_unset_removes_single_value,
+    test_config_unset_missing_key_returns_false,
+    test_config_unset_multi_valued_key_errors,
+    test_config_unset_all_removes_every_value,
+    test_config_unset_subsection_key_after_set,
+);
+
+crate::reuse_tests_in_worktree_with_attrs!(
+    (#[serial_test::serial])
+    test_config_get_str_with_scope_local_overrides_global,
+    test_config_get_str_with_scope_reports_global,
+    test_global_config_path_follows_process_git_config_global,
 );
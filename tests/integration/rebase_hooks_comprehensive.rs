@@ -1,7 +1,7 @@
 use git_ai::git::repository;
 
 use crate::repos::test_repo::TestRepo;
-use git_ai::commands::git_handlers::CommandHooksContext;
+use crate::test_utils::hooks_context;
 use git_ai::commands::hooks::rebase_hooks::{handle_rebase_post_command, pre_rebase_hook};
 use git_ai::git::cli_parser::ParsedGitInvocation;
 use git_ai::git::rewrite_log::RewriteLogEvent;
@@ -54,15 +54,7 @@ fn test_pre_rebase_hook_starts_new_rebase() {
     repo.commit("feature commit").unwrap();
 
     // Prepare context and parsed args
-    let mut context = CommandHooksContext {
-        pre_commit_hook_result: None,
-        rebase_original_head: None,
-        rebase_onto: None,
-        fetch_authorship_handle: None,
-        stash_sha: None,
-        push_authorship_handle: None,
-        stashed_va: None,
-    };
+    let mut context = hooks_context();
     let parsed_args = make_rebase_invocation(&["main"]);
     let mut repository =
         repository::find_repository_in_path(repo.path().to_str().unwrap()).unwrap();
@@ -97,15 +89,7 @@ fn test_pre_rebase_hook_continuing_rebase() {
 
     let mut repository =
         repository::find_repository_in_path(repo.path().to_str().unwrap()).unwrap();
-    let mut context = CommandHooksContext {
-        pre_commit_hook_result: None,
-        rebase_original_head: None,
-        rebase_onto: None,
-        fetch_authorship_handle: None,
-        stash_sha: None,
-        push_authorship_handle: None,
-        stashed_va: None,
-    };
+    let mut context = hooks_context();
     let parsed_args = make_rebase_invocation(&["--continue"]);
 
     // Execute pre-hook for continuing rebase
@@ -133,15 +117,7 @@ fn test_pre_rebase_hook_interactive_mode() {
 
     let mut repository =
         repository::find_repository_in_path(repo.path().to_str().unwrap()).unwrap();
-    let mut context = CommandHooksContext {
-        pre_commit_hook_result: None,
-        rebase_original_head: None,
-        rebase_onto: None,
-        fetch_authorship_handle: None,
-        stash_sha: None,
-        push_authorship_handle: None,
-        stashed_va: None,
-    };
+    let mut context = hooks_context();
     let parsed_args = make_rebase_invocation(&["-i", "main"]);
 
     pre_rebase_hook(&parsed_args, &mut repository, &mut context);
@@ -180,15 +156,7 @@ fn test_pre_rebase_hook_with_onto() {
 
     let mut repository =
         repository::find_repository_in_path(repo.path().to_str().unwrap()).unwrap();
-    let mut context = CommandHooksContext {
-        pre_commit_hook_result: None,
-        rebase_original_head: None,
-        rebase_onto: None,
-        fetch_authorship_handle: None,
-        stash_sha: None,
-        push_authorship_handle: None,
-        stashed_va: None,
-    };
+    let mut context = hooks_context();
     let parsed_args = make_rebase_invocation(&["--onto", &onto_commit.commit_sha, "main"]);
 
     pre_rebase_hook(&parsed_args, &mut repository, &mut context);
@@ -214,15 +182,7 @@ fn test_post_rebase_hook_still_in_progress() {
 
     let mut repository =
         repository::find_repository_in_path(repo.path().to_str().unwrap()).unwrap();
-    let context = CommandHooksContext {
-        pre_commit_hook_result: None,
-        rebase_original_head: None,
-        rebase_onto: None,
-        fetch_authorship_handle: None,
-        stash_sha: None,
-        push_authorship_handle: None,
-        stashed_va: None,
-    };
+    let context = hooks_context();
     let parsed_args = make_rebase_invocation(&["main"]);
     let exit_status = std::process::Command::new("true").status().unwrap();
 
@@ -268,15 +228,7 @@ fn test_post_rebase_hook_aborted() {
         .unwrap();
 
     // Prepare context with original head
-    let mut context = CommandHooksContext {
-        pre_commit_hook_result: None,
-        rebase_original_head: None,
-        rebase_onto: None,
-        fetch_authorship_handle: None,
-        stash_sha: None,
-        push_authorship_handle: None,
-        stashed_va: None,
-    };
+    let mut context = hooks_context();
     context.rebase_original_head = Some(original_commit.commit_sha.clone());
 
     let parsed_args = make_rebase_invocation(&["--abort"]);
@@ -310,15 +262,7 @@ fn test_post_rebase_hook_dry_run() {
 
     let mut repository =
         repository::find_repository_in_path(repo.path().to_str().unwrap()).unwrap();
-    let context = CommandHooksContext {
-        pre_commit_hook_result: None,
-        rebase_original_head: None,
-        rebase_onto: None,
-        fetch_authorship_handle: None,
-        stash_sha: None,
-        push_authorship_handle: None,
-        stashed_va: None,
-    };
+    let context = hooks_context();
     let parsed_args = make_rebase_invocation(&["--dry-run", "main"]);
     let exit_status = std::process::Command::new("true").status().unwrap();
 
@@ -1,7 +1,7 @@
 use git_ai::git::repository;
 
 use crate::repos::test_repo::TestRepo;
-use git_ai::commands::git_handlers::CommandHooksContext;
+use crate::test_utils::hooks_context;
 use git_ai::commands::hooks::switch_hooks::{post_switch_hook, pre_switch_hook};
 use git_ai::git::cli_parser::ParsedGitInvocation;
 
@@ -36,15 +36,7 @@ fn test_pre_switch_hook_normal() {
 
     let mut repository =
         repository::find_repository_in_path(repo.path().to_str().unwrap()).unwrap();
-    let mut context = CommandHooksContext {
-        pre_commit_hook_result: None,
-        rebase_original_head: None,
-        rebase_onto: None,
-        fetch_authorship_handle: None,
-        stash_sha: None,
-        push_authorship_handle: None,
-        stashed_va: None,
-    };
+    let mut context = hooks_context();
     let parsed_args = make_switch_invocation(&["main"]);
 
     pre_switch_hook(&parsed_args, &mut repository, &mut context);
@@ -73,15 +65,7 @@ fn test_pre_switch_hook_with_merge_flag() {
 
     let mut repository =
         repository::find_repository_in_path(repo.path().to_str().unwrap()).unwrap();
-    let mut context = CommandHooksContext {
-        pre_commit_hook_result: None,
-        rebase_original_head: None,
-        rebase_onto: None,
-        fetch_authorship_handle: None,
-        stash_sha: None,
-        push_authorship_handle: None,
-        stashed_va: None,
-    };
+    let mut context = hooks_context();
     let parsed_args = make_switch_invocation(&["--merge", "main"]);
 
     pre_switch_hook(&parsed_args, &mut repository, &mut context);
@@ -104,15 +88,7 @@ fn test_pre_switch_hook_merge_without_changes() {
 
     let mut repository =
         repository::find_repository_in_path(repo.path().to_str().unwrap()).unwrap();
-    let mut context = CommandHooksContext {
-        pre_commit_hook_result: None,
-        rebase_original_head: None,
-        rebase_onto: None,
-        fetch_authorship_handle: None,
-        stash_sha: None,
-        push_authorship_handle: None,
-        stashed_va: None,
-    };
+    let mut context = hooks_context();
     let parsed_args = make_switch_invocation(&["--merge", "main"]);
 
     pre_switch_hook(&parsed_args, &mut repository, &mut context);
@@ -139,15 +115,7 @@ fn test_pre_switch_hook_merge_short_flag() {
 
     let mut repository =
         repository::find_repository_in_path(repo.path().to_str().unwrap()).unwrap();
-    let mut context = CommandHooksContext {
-        pre_commit_hook_result: None,
-        rebase_original_head: None,
-        rebase_onto: None,
-        fetch_authorship_handle: None,
-        stash_sha: None,
-        push_authorship_handle: None,
-        stashed_va: None,
-    };
+    let mut context = hooks_context();
     let parsed_args = make_switch_invocation(&["-m", "main"]);
 
     pre_switch_hook(&parsed_args, &mut repository, &mut context);
@@ -181,15 +149,7 @@ fn test_post_switch_hook_success() {
     repo.git(&["checkout", "main"]).unwrap();
 
     let parsed_args = make_switch_invocation(&["main"]);
-    let mut context = CommandHooksContext {
-        pre_commit_hook_result: None,
-        rebase_original_head: None,
-        rebase_onto: None,
-        fetch_authorship_handle: None,
-        stash_sha: None,
-        push_authorship_handle: None,
-        stashed_va: None,
-    };
+    let mut context = hooks_context();
     let exit_status = std::process::Command::new("true").status().unwrap();
 
     post_switch_hook(&parsed_args, &mut repository, exit_status, &mut context);
@@ -211,15 +171,7 @@ fn test_post_switch_hook_failed() {
     let mut repository =
         repository::find_repository_in_path(repo.path().to_str().unwrap()).unwrap();
     let parsed_args = make_switch_invocation(&["main"]);
-    let mut context = CommandHooksContext {
-        pre_commit_hook_result: None,
-        rebase_original_head: None,
-        rebase_onto: None,
-        fetch_authorship_handle: None,
-        stash_sha: None,
-        push_authorship_handle: None,
-        stashed_va: None,
-    };
+    let mut context = hooks_context();
     let exit_status = std::process::Command::new("false")
         .status()
         .unwrap_or_else(|_| {
@@ -249,15 +201,7 @@ fn test_post_switch_hook_head_unchanged() {
     repository.pre_command_base_commit = Some(commit.commit_sha.clone());
 
     let parsed_args = make_switch_invocation(&["main"]);
-    let mut context = CommandHooksContext {
-        pre_commit_hook_result: None,
-        rebase_original_head: None,
-        rebase_onto: None,
-        fetch_authorship_handle: None,
-        stash_sha: None,
-        push_authorship_handle: None,
-        stashed_va: None,
-    };
+    let mut context = hooks_context();
     let exit_status = std::process::Command::new("true").status().unwrap();
 
     post_switch_hook(&parsed_args, &mut repository, exit_status, &mut context);
@@ -298,15 +242,7 @@ fn test_post_switch_hook_force_switch() {
     repository.pre_command_base_commit = Some(old_head.clone());
 
     let parsed_args = make_switch_invocation(&["--force", "main"]);
-    let mut context = CommandHooksContext {
-        pre_commit_hook_result: None,
-        rebase_original_head: None,
-        rebase_onto: None,
-        fetch_authorship_handle: None,
-        stash_sha: None,
-        push_authorship_handle: None,
-        stashed_va: None,
-    };
+    let mut context = hooks_context();
     let exit_status = std::process::Command::new("true").status().unwrap();
 
     post_switch_hook(&parsed_args, &mut repository, exit_status, &mut context);
@@ -337,15 +273,7 @@ fn test_post_switch_hook_force_short_flag() {
     repository.pre_command_base_commit = Some(old_head.clone());
 
     let parsed_args = make_switch_invocation(&["-f", "main"]);
-    let mut context = CommandHooksContext {
-        pre_commit_hook_result: None,
-        rebase_original_head: None,
-        rebase_onto: None,
-        fetch_authorship_handle: None,
-        stash_sha: None,
-        push_authorship_handle: None,
-        stashed_va: None,
-    };
+    let mut context = hooks_context();
     let exit_status = std::process::Command::new("true").status().unwrap();
 
     post_switch_hook(&parsed_args, &mut repository, exit_status, &mut context);
@@ -376,15 +304,7 @@ fn test_post_switch_hook_discard_changes_flag() {
     repository.pre_command_base_commit = Some(old_head.clone());
 
     let parsed_args = make_switch_invocation(&["--discard-changes", "main"]);
-    let mut context = CommandHooksContext {
-        pre_commit_hook_result: None,
-        rebase_original_head: None,
-        rebase_onto: None,
-        fetch_authorship_handle: None,
-        stash_sha: None,
-        push_authorship_handle: None,
-        stashed_va: None,
-    };
+    let mut context = hooks_context();
     let exit_status = std::process::Command::new("true").status().unwrap();
 
     post_switch_hook(&parsed_args, &mut repository, exit_status, &mut context);
@@ -419,15 +339,7 @@ fn test_post_switch_hook_with_merge() {
     repository.pre_command_base_commit = Some(old_head.clone());
 
     // Create stashed VA
-    let mut context = CommandHooksContext {
-        pre_commit_hook_result: None,
-        rebase_original_head: None,
-        rebase_onto: None,
-        fetch_authorship_handle: None,
-        stash_sha: None,
-        push_authorship_handle: None,
-        stashed_va: None,
-    };
+    let mut context = hooks_context();
     // In real scenario, pre_switch_hook would populate this
     // context.stashed_va = Some(...);
 
@@ -579,15 +491,7 @@ fn test_switch_normal_flow() {
 
     let mut repository =
         repository::find_repository_in_path(repo.path().to_str().unwrap()).unwrap();
-    let mut context = CommandHooksContext {
-        pre_commit_hook_result: None,
-        rebase_original_head: None,
-        rebase_onto: None,
-        fetch_authorship_handle: None,
-        stash_sha: None,
-        push_authorship_handle: None,
-        stashed_va: None,
-    };
+    let mut context = hooks_context();
     let parsed_args = make_switch_invocation(&["main"]);
 
     // Pre-hook
@@ -627,15 +531,7 @@ fn test_switch_force_flow() {
 
     let mut repository =
         repository::find_repository_in_path(repo.path().to_str().unwrap()).unwrap();
-    let mut context = CommandHooksContext {
-        pre_commit_hook_result: None,
-        rebase_original_head: None,
-        rebase_onto: None,
-        fetch_authorship_handle: None,
-        stash_sha: None,
-        push_authorship_handle: None,
-        stashed_va: None,
-    };
+    let mut context = hooks_context();
     let parsed_args = make_switch_invocation(&["--force", "main"]);
 
     // Pre-hook
@@ -664,15 +560,7 @@ fn test_switch_new_branch_creation() {
 
     let mut repository =
         repository::find_repository_in_path(repo.path().to_str().unwrap()).unwrap();
-    let mut context = CommandHooksContext {
-        pre_commit_hook_result: None,
-        rebase_original_head: None,
-        rebase_onto: None,
-        fetch_authorship_handle: None,
-        stash_sha: None,
-        push_authorship_handle: None,
-        stashed_va: None,
-    };
+    let mut context = hooks_context();
     let parsed_args = make_switch_invocation(&["-c", "new-branch"]);
 
     pre_switch_hook(&parsed_args, &mut repository, &mut context);
@@ -711,15 +599,7 @@ fn test_switch_between_multiple_branches() {
     // Switch to branch1
     let mut repository =
         repository::find_repository_in_path(repo.path().to_str().unwrap()).unwrap();
-    let mut context = CommandHooksContext {
-        pre_commit_hook_result: None,
-        rebase_original_head: None,
-        rebase_onto: None,
-        fetch_authorship_handle: None,
-        stash_sha: None,
-        push_authorship_handle: None,
-        stashed_va: None,
-    };
+    let mut context = hooks_context();
     let parsed_args = make_switch_invocation(&["branch1"]);
 
     pre_switch_hook(&parsed_args, &mut repository, &mut context);
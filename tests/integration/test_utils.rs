@@ -1,5 +1,6 @@
 #![allow(dead_code)]
 
+use git_ai::commands::git_handlers::CommandHooksContext;
 use std::path::PathBuf;
 
 /// Get the path to a test fixture file
@@ -31,3 +32,8 @@ pub fn load_fixture(filename: &str) -> String {
     std::fs::read_to_string(fixture_path(filename))
         .unwrap_or_else(|_| panic!("Failed to read fixture: {}", filename))
 }
+
+/// A hooks context with nothing captured yet, as `handle_git` builds for a non-dry-run command
+pub fn hooks_context() -> CommandHooksContext {
+    CommandHooksContext::default()
+}